@@ -0,0 +1,33 @@
+/* C API of rs-merkletree, built with the `ffi` feature. See src/ffi.rs. */
+#ifndef RS_MERKLETREE_H
+#define RS_MERKLETREE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct MerkleTree MerkleTree;
+typedef struct MerkleProof MerkleProof;
+
+MerkleTree *merkle_build(const uint8_t *const *leaves, const size_t *lens, size_t count);
+void merkle_free(MerkleTree *tree);
+size_t merkle_root(const MerkleTree *tree, uint8_t *out, size_t out_len);
+
+MerkleProof *merkle_proof(const MerkleTree *tree, size_t index);
+void merkle_proof_free(MerkleProof *proof);
+size_t merkle_proof_serialize(const MerkleProof *proof, uint8_t *out, size_t out_len);
+MerkleProof *merkle_proof_parse(const uint8_t *data, size_t len);
+
+bool merkle_verify(const MerkleProof *proof,
+                   const uint8_t *root, size_t root_len,
+                   const uint8_t *leaf, size_t leaf_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RS_MERKLETREE_H */
@@ -0,0 +1,70 @@
+//! Hardware-accelerated SHA-256.
+//!
+//! Hashing dominates build time on large trees. Every SHA-256 in the crate goes through [sha2](https://docs.rs/sha2), which picks its compression function at runtime from the CPU's features. On x86 and x86-64 it uses the SHA extensions (SHA-NI) when the CPU has them, and portable code otherwise; the `asm` feature replaces that portable fallback with the hand-written assembly of [sha2-asm](https://docs.rs/sha2-asm), which needs a C toolchain at build time. On AArch64 the SHA-2 instructions are only compiled in with the `asm` feature, which then falls back to portable code; without it AArch64 always hashes in software. Roots are the same whichever backend runs.
+//!
+//! [backend](fn.backend.html) reports the backend chosen on the running machine, for benchmarks and diagnostics.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::accel::{backend, Backend};
+//! let backend = backend();
+//! println!("hashing with {}", backend.name());
+//! assert_eq!(backend.is_hardware(), matches!(backend, Backend::ShaNi | Backend::ArmSha2));
+//! ```
+
+/// SHA-256 implementation used by [sha2](https://docs.rs/sha2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// x86-64 SHA extensions.
+    ShaNi,
+    /// AArch64 SHA-2 instructions, enabled by the `asm` feature.
+    ArmSha2,
+    /// Assembly from [sha2-asm](https://docs.rs/sha2-asm), enabled by the `asm` feature on x86 and x86-64.
+    Asm,
+    /// Portable Rust.
+    Software,
+}
+
+impl Backend {
+    /// Returns a short name for the backend: `sha-ni`, `arm-sha2`, `asm` or `software`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::ShaNi => "sha-ni",
+            Backend::ArmSha2 => "arm-sha2",
+            Backend::Asm => "asm",
+            Backend::Software => "software",
+        }
+    }
+
+    /// Returns `true` if hashing uses dedicated CPU instructions.
+    pub fn is_hardware(&self) -> bool {
+        matches!(self, Backend::ShaNi | Backend::ArmSha2)
+    }
+}
+
+/// Returns the backend [sha2](https://docs.rs/sha2) selects on this CPU, with the same feature checks it makes.
+pub fn backend() -> Backend {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if std::is_x86_feature_detected!("sha")
+        && std::is_x86_feature_detected!("sse2")
+        && std::is_x86_feature_detected!("ssse3")
+        && std::is_x86_feature_detected!("sse4.1")
+    {
+        return Backend::ShaNi;
+    }
+    #[cfg(all(feature = "asm", target_arch = "aarch64"))]
+    if std::arch::is_aarch64_feature_detected!("sha2") {
+        return Backend::ArmSha2;
+    }
+    fallback()
+}
+
+#[cfg(all(feature = "asm", any(target_arch = "x86", target_arch = "x86_64")))]
+fn fallback() -> Backend {
+    Backend::Asm
+}
+
+#[cfg(not(all(feature = "asm", any(target_arch = "x86", target_arch = "x86_64"))))]
+fn fallback() -> Backend {
+    Backend::Software
+}
@@ -0,0 +1,199 @@
+//! Merkle trees over arkworks field elements.
+//!
+//! [FieldMerkleTree](struct.FieldMerkleTree.html) commits to leaves that are elements of an [ark_ff](https://docs.rs/ark-ff) prime field, hashing every pair of children with a [FieldHasher](trait.FieldHasher.html). By default that is a Poseidon sponge ([PoseidonConfig](https://docs.rs/ark-crypto-primitives/latest/ark_crypto_primitives/sponge/poseidon/struct.PoseidonConfig.html) implements the trait): the two children are absorbed and one field element is squeezed. This is the computation arkworks' `PoseidonSpongeVar` performs in-circuit, so a [FieldProof](struct.FieldProof.html) (the sibling path and the index bits) can be fed straight into an R1CS membership gadget.
+//!
+//! Trees have a fixed depth, as circuits do; leaves beyond those given are zero. Only the non-zero part of each level is stored.
+//!
+//! Requires the `arkworks` feature.
+//!
+//! # Examples
+//! ```
+//! use ark_bn254::Fr;
+//! use rs_merkletree::arkworks::{poseidon_config, FieldMerkleTree};
+//! let config = poseidon_config::<Fr>();
+//! let leaves: Vec<Fr> = (1..=5u64).map(Fr::from).collect();
+//! let tree = FieldMerkleTree::new(config.clone(), &leaves, 20).unwrap();
+//!
+//! let proof = tree.prove(3).unwrap();
+//! assert_eq!(proof.index_bits.len(), 20);
+//! assert!(proof.verify(&config, tree.root(), Fr::from(4u64)));
+//! ```
+
+use ark_crypto_primitives::sponge::poseidon::{
+    find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge,
+};
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge, FieldBasedCryptographicSponge};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// Poseidon full rounds for a width-3 permutation over a ~254 bit field.
+pub const FULL_ROUNDS: usize = 8;
+
+/// Poseidon partial rounds for a width-3 permutation over a ~254 bit field.
+pub const PARTIAL_ROUNDS: usize = 57;
+
+/// Returns Poseidon parameters for hashing two field elements: rate 2, capacity 1, `x^5` S-boxes, and round constants and MDS matrix from the reference Grain LFSR.
+pub fn poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+        F::MODULUS_BIT_SIZE as u64,
+        2,
+        FULL_ROUNDS as u64,
+        PARTIAL_ROUNDS as u64,
+        0,
+    );
+    PoseidonConfig::new(FULL_ROUNDS, PARTIAL_ROUNDS, 5, mds, ark, 2, 1)
+}
+
+/// Returns the parent of `left` and `right`: absorb both into a fresh sponge and squeeze one element.
+pub fn hash_children<F: PrimeField + Absorb>(config: &PoseidonConfig<F>, left: F, right: F) -> F {
+    let mut sponge = PoseidonSponge::new(config);
+    sponge.absorb(&[left, right].as_slice());
+    sponge.squeeze_native_field_elements(1)[0]
+}
+
+/// Two-to-one hash used for the inner nodes of a [FieldMerkleTree](struct.FieldMerkleTree.html).
+pub trait FieldHasher<F> {
+    /// Returns the parent of `left` and `right`.
+    fn hash_children(&self, left: F, right: F) -> F;
+}
+
+impl<F: PrimeField + Absorb> FieldHasher<F> for PoseidonConfig<F> {
+    fn hash_children(&self, left: F, right: F) -> F {
+        hash_children(self, left, right)
+    }
+}
+
+/// [FieldMerkleTree](struct.FieldMerkleTree.html) is a fixed-depth tree over field elements.
+///
+/// * `hasher`: Hash used for every inner node.
+///
+/// * `levels`: The non-zero prefix of every level, leaves first; the last level holds only the root.
+///
+/// * `zeros`: `zeros[h]` is the root of an all-zero subtree of height `h`.
+#[derive(Debug, Clone)]
+pub struct FieldMerkleTree<F: PrimeField, H = PoseidonConfig<F>> {
+    hasher: H,
+    levels: Vec<Vec<F>>,
+    zeros: Vec<F>,
+}
+
+/// [FieldProof](struct.FieldProof.html) is a membership proof laid out for circuits.
+///
+/// * `path`: Sibling of the node at every level, from the leaf up.
+///
+/// * `index_bits`: Bits of the leaf index, least significant first; `true` means the node is the right child at that level.
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FieldProof<F: PrimeField> {
+    pub path: Vec<F>,
+    pub index_bits: Vec<bool>,
+}
+
+impl<F: PrimeField, H: FieldHasher<F>> FieldMerkleTree<F, H> {
+    /// Function to create a [FieldMerkleTree](struct.FieldMerkleTree.html) of `depth` levels over `leaves`.
+    ///
+    /// Returns `None` if more than `2^depth` leaves are given.
+    pub fn new(hasher: H, leaves: &[F], depth: usize) -> Option<FieldMerkleTree<F, H>> {
+        if depth < usize::BITS as usize && leaves.len() > 1 << depth {
+            return None;
+        }
+        let mut zeros = vec![F::zero()];
+        for height in 0..depth {
+            zeros.push(hasher.hash_children(zeros[height], zeros[height]));
+        }
+        let mut levels = vec![leaves.to_vec()];
+        for height in 0..depth {
+            let next = levels[height]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hasher.hash_children(*left, *right),
+                    [left] => hasher.hash_children(*left, zeros[height]),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Some(FieldMerkleTree {
+            hasher,
+            levels,
+            zeros,
+        })
+    }
+
+    /// Returns the depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// Returns the root.
+    pub fn root(&self) -> F {
+        self.node(self.depth(), 0)
+    }
+
+    /// Returns the hasher of the tree.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Returns the proof for the leaf at `index`, or `None` if `index` is outside the tree.
+    pub fn prove(&self, index: usize) -> Option<FieldProof<F>> {
+        let depth = self.depth();
+        if depth < usize::BITS as usize && index >> depth != 0 {
+            return None;
+        }
+        let mut path = Vec::with_capacity(depth);
+        let mut index_bits = Vec::with_capacity(depth);
+        for height in 0..depth {
+            let position = index >> height;
+            path.push(self.node(height, position ^ 1));
+            index_bits.push(position & 1 == 1);
+        }
+        Some(FieldProof { path, index_bits })
+    }
+
+    fn node(&self, height: usize, index: usize) -> F {
+        self.levels[height]
+            .get(index)
+            .copied()
+            .unwrap_or(self.zeros[height])
+    }
+}
+
+impl<F: PrimeField> FieldProof<F> {
+    /// Checks that `leaf` is included in the tree with root `root`, hashing with `hasher`.
+    pub fn verify<H: FieldHasher<F>>(&self, hasher: &H, root: F, leaf: F) -> bool {
+        if self.path.len() != self.index_bits.len() {
+            return false;
+        }
+        let computed =
+            self.path
+                .iter()
+                .zip(&self.index_bits)
+                .fold(leaf, |node, (sibling, is_right)| {
+                    if *is_right {
+                        hasher.hash_children(*sibling, node)
+                    } else {
+                        hasher.hash_children(node, *sibling)
+                    }
+                });
+        computed == root
+    }
+
+    /// Same as [verify](struct.FieldProof.html#method.verify), but also rejects proofs for positions at or beyond `tree_size`, the number of leaves actually committed. Those positions hold zero padding, and without the bound a proof for a zero leaf would be accepted at any of them.
+    pub fn verify_sized<H: FieldHasher<F>>(
+        &self,
+        hasher: &H,
+        root: F,
+        leaf: F,
+        tree_size: u64,
+    ) -> bool {
+        self.leaf_index() < tree_size && self.verify(hasher, root, leaf)
+    }
+
+    /// Returns the leaf index encoded by [index_bits](struct.FieldProof.html#structfield.index_bits).
+    pub fn leaf_index(&self) -> u64 {
+        self.index_bits
+            .iter()
+            .rev()
+            .fold(0, |index, bit| index << 1 | *bit as u64)
+    }
+}
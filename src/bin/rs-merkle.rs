@@ -0,0 +1,14 @@
+use rs_merkletree::cli::{run, USAGE};
+use std::{env, io, process};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args, io::stdin().lock(), io::stdout().lock()) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("rs-merkle: {}\n\n{}", error, USAGE);
+            process::exit(2);
+        }
+    }
+}
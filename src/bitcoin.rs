@@ -0,0 +1,65 @@
+//! Bitcoin transaction Merkle roots.
+//!
+//! A block header commits to its transactions with a tree of double SHA-256 hashes over the transaction ids, duplicating the last id of any level with an odd count. Bitcoin displays txids and block hashes byte-reversed relative to the order in which they are hashed; the functions here take and return the displayed hex form so values can be copied from block explorers and `bitcoind` RPC output.
+//!
+//! The duplicate-last rule makes the root ambiguous (CVE-2012-2459): a list ending in a repeated pair has the same root as the list without it. [merkle_root](fn.merkle_root.html) reproduces Bitcoin's behavior exactly; callers that need to detect the mutation can check [has_duplicate_tail](fn.has_duplicate_tail.html).
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::bitcoin::merkle_root;
+//! // Block 100000.
+//! let txids = [
+//!     "8c14f0db3df150123e6f3dbbf30f8b955a8249b62ac1d1ff16284aefa3d06d87",
+//!     "fff2525b8931402dd09222c50775608f75787bd2b87e56995a7bdd30f79702c4",
+//!     "6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4",
+//!     "e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d",
+//! ];
+//! assert_eq!(
+//!     merkle_root(&txids).unwrap(),
+//!     "f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766"
+//! );
+//! ```
+
+use crate::config::TreeConfig;
+use crate::hashing::{from_hex, to_hex};
+
+/// Returns the Merkle root for txids given as displayed (big-endian hex), also as displayed. Returns `None` if there are no txids or one is not 32 bytes of hex.
+pub fn merkle_root<S: AsRef<str>>(txids: &[S]) -> Option<String> {
+    let leaves = txids
+        .iter()
+        .map(|txid| from_display_hex(txid.as_ref()))
+        .collect::<Option<Vec<_>>>()?;
+    merkle_root_internal(&leaves).map(|root| to_display_hex(&root))
+}
+
+/// Returns the Merkle root for txids in internal byte order, as stored in blocks and hashed.
+pub fn merkle_root_internal(txids: &[Vec<u8>]) -> Option<Vec<u8>> {
+    TreeConfig::bitcoin().root(txids)
+}
+
+/// Returns true if some level of the tree ends in two identical nodes, i.e. the txid list is a CVE-2012-2459 mutation of a shorter one.
+pub fn has_duplicate_tail(txids: &[Vec<u8>]) -> bool {
+    let config = TreeConfig::bitcoin();
+    config.build_layers(txids).iter().any(|layer| {
+        layer.len() >= 2
+            && layer.len().is_multiple_of(2)
+            && layer[layer.len() - 1] == layer[layer.len() - 2]
+    })
+}
+
+/// Parses a displayed 32 byte hash into internal byte order.
+pub fn from_display_hex(hex: &str) -> Option<Vec<u8>> {
+    let mut bytes = from_hex(hex)?;
+    if bytes.len() != 32 {
+        return None;
+    }
+    bytes.reverse();
+    Some(bytes)
+}
+
+/// Formats a hash in internal byte order the way Bitcoin displays it.
+pub fn to_display_hex(hash: &[u8]) -> String {
+    let mut bytes = hash.to_vec();
+    bytes.reverse();
+    to_hex(&bytes)
+}
@@ -0,0 +1,159 @@
+//! BitTorrent v2 file trees.
+//!
+//! [BEP 52](https://www.bittorrent.org/beps/bep_0052.html) identifies each file of a v2 torrent by a Merkle root over SHA-256 hashes of its 16 KiB blocks. The tree is binary and balanced: leaf hashes beyond the end of the file are 32 zero bytes, so the leaf count is padded to a power of two. [TorrentFileTree](struct.TorrentFileTree.html) computes the `pieces root` stored in a torrent's file tree and the "piece layers" entry, the layer of the tree whose nodes each cover one piece.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::bittorrent::{verify_piece_layer, TorrentFileTree, BLOCK_SIZE};
+//! let contents = vec![1u8; 5 * BLOCK_SIZE + 100];
+//! let tree = TorrentFileTree::from_reader(&contents[..]).unwrap();
+//! let root = tree.pieces_root().unwrap();
+//!
+//! let piece_length = 2 * BLOCK_SIZE as u64;
+//! let layer = tree.piece_layer(piece_length).unwrap();
+//! assert_eq!(layer.len(), 3);
+//! assert!(verify_piece_layer(&root, &layer, piece_length, tree.len()));
+//! ```
+
+use crate::hashing::{sha256, sha256_concat};
+use std::io::{self, ErrorKind, Read};
+
+/// Size of the blocks hashed into leaves.
+pub const BLOCK_SIZE: usize = 16 * 1024;
+
+/// [TorrentFileTree](struct.TorrentFileTree.html) holds the block hashes of one file.
+///
+/// * `len`: File length in bytes.
+///
+/// * `blocks`: SHA-256 of every 16 KiB block, the last one possibly short.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TorrentFileTree {
+    len: u64,
+    blocks: Vec<Vec<u8>>,
+}
+
+impl TorrentFileTree {
+    /// Function to create a [TorrentFileTree](struct.TorrentFileTree.html) from everything `reader` yields.
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<TorrentFileTree> {
+        let mut blocks = Vec::new();
+        let mut len = 0u64;
+        let mut buffer = vec![0u8; BLOCK_SIZE];
+        loop {
+            let mut filled = 0;
+            while filled < BLOCK_SIZE {
+                match reader.read(&mut buffer[filled..]) {
+                    Ok(0) => break,
+                    Ok(n) => filled += n,
+                    Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                    Err(error) => return Err(error),
+                }
+            }
+            if filled == 0 {
+                break;
+            }
+            blocks.push(sha256(&buffer[..filled]));
+            len += filled as u64;
+            if filled < BLOCK_SIZE {
+                break;
+            }
+        }
+        Ok(TorrentFileTree { len, blocks })
+    }
+
+    /// Returns the file length in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns true if the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the leaf hashes, one per block.
+    pub fn block_hashes(&self) -> &[Vec<u8>] {
+        &self.blocks
+    }
+
+    /// Returns the `pieces root` of the file, or `None` for an empty file, which BEP 52 leaves without one.
+    pub fn pieces_root(&self) -> Option<Vec<u8>> {
+        if self.blocks.is_empty() {
+            return None;
+        }
+        reduce(self.blocks.clone(), 0, 1).pop()
+    }
+
+    /// Returns the piece layer for `piece_length`: one hash per piece, each the root of the subtree over that piece's blocks.
+    ///
+    /// Returns `None` if `piece_length` is not a power of two of at least [BLOCK_SIZE](constant.BLOCK_SIZE.html), or if the file fits in one piece; torrents only carry piece layers for files larger than a piece.
+    pub fn piece_layer(&self, piece_length: u64) -> Option<Vec<Vec<u8>>> {
+        let height = piece_height(piece_length)?;
+        if self.len <= piece_length {
+            return None;
+        }
+        let pieces = self.len.div_ceil(piece_length) as usize;
+        let mut layer = self.blocks.clone();
+        for level in 0..height {
+            layer = upper_layer(&layer, level);
+        }
+        debug_assert_eq!(layer.len(), pieces);
+        Some(layer)
+    }
+
+    /// Returns the piece layer concatenated into the byte string stored under the file's `pieces root` in the torrent's `piece layers` dictionary.
+    pub fn piece_layer_bytes(&self, piece_length: u64) -> Option<Vec<u8>> {
+        self.piece_layer(piece_length).map(|layer| layer.concat())
+    }
+}
+
+/// Checks a piece layer received from a torrent against the file's `pieces root`, so that pieces can then be verified against single hashes of the layer.
+pub fn verify_piece_layer(
+    pieces_root: &[u8],
+    layer: &[Vec<u8>],
+    piece_length: u64,
+    file_len: u64,
+) -> bool {
+    let Some(height) = piece_height(piece_length) else {
+        return false;
+    };
+    if file_len <= piece_length || layer.len() as u64 != file_len.div_ceil(piece_length) {
+        return false;
+    }
+    reduce(layer.to_vec(), height, 1).pop().as_deref() == Some(pieces_root)
+}
+
+/// Returns the tree height of a piece, counted in blocks.
+fn piece_height(piece_length: u64) -> Option<usize> {
+    let blocks = piece_length / BLOCK_SIZE as u64;
+    if !piece_length.is_multiple_of(BLOCK_SIZE as u64) || !blocks.is_power_of_two() {
+        return None;
+    }
+    Some(blocks.trailing_zeros() as usize)
+}
+
+/// Hash of an all-padding subtree of the given height.
+fn pad_hash(height: usize) -> Vec<u8> {
+    let mut hash = vec![0u8; 32];
+    for _ in 0..height {
+        hash = sha256_concat(&[&hash, &hash]);
+    }
+    hash
+}
+
+/// Hashes the layer at `height` into the next one, pairing a lone last node with padding.
+fn upper_layer(layer: &[Vec<u8>], height: usize) -> Vec<Vec<u8>> {
+    let pad = pad_hash(height);
+    layer
+        .chunks(2)
+        .map(|pair| sha256_concat(&[&pair[0], pair.get(1).unwrap_or(&pad)]))
+        .collect()
+}
+
+/// Hashes `layer`, which sits at `height`, upwards until at most `width` nodes remain.
+fn reduce(mut layer: Vec<Vec<u8>>, mut height: usize, width: usize) -> Vec<Vec<u8>> {
+    while layer.len() > width {
+        layer = upper_layer(&layer, height);
+        height += 1;
+    }
+    layer
+}
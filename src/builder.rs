@@ -0,0 +1,121 @@
+//! Background tree building.
+//!
+//! A [BackgroundBuilder](struct.BackgroundBuilder.html) runs a builder thread that consumes leaves from a bounded channel and hashes them as they arrive, so producers can stream data while hashing proceeds concurrently. Only the right edge of the tree (one complete subtree per level) is pending at any time; [finalize](struct.BackgroundBuilder.html#method.finalize) closes the channel and returns the same [MerkleTree](../struct.MerkleTree.html) that [build_tree](../struct.MerkleTree.html#method.build_tree) would build from the leaves in arrival order.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::builder::BackgroundBuilder;
+//! use std::thread;
+//! let builder = BackgroundBuilder::spawn(1024);
+//! let sender = builder.sender();
+//! let producer = thread::spawn(move || {
+//!     for word in ["Hello", "World", "From", "Rust"] {
+//!         sender.send(word.to_string()).unwrap();
+//!     }
+//! });
+//! producer.join().unwrap();
+//! let tree = builder.finalize();
+//! assert_eq!(
+//!     tree.root_node().unwrap().hash().to_string(),
+//!     "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
+//! );
+//! ```
+
+use crate::proof::{leaf_hash, node_hash};
+use crate::{MerkleTree, Node};
+use std::sync::mpsc::{self, Receiver, SendError, SyncSender};
+use std::thread::{self, JoinHandle};
+
+/// [BackgroundBuilder](struct.BackgroundBuilder.html) owns the builder thread and a sender into its channel.
+#[derive(Debug)]
+pub struct BackgroundBuilder {
+    sender: SyncSender<String>,
+    handle: JoinHandle<MerkleTree>,
+}
+
+impl BackgroundBuilder {
+    /// Function to start a builder thread whose channel holds up to `capacity` unhashed leaves; producers block while it is full.
+    pub fn spawn(capacity: usize) -> BackgroundBuilder {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let handle = thread::spawn(move || build(receiver));
+        BackgroundBuilder { sender, handle }
+    }
+
+    /// Returns a sender for a producer thread. Every sender must be dropped before [finalize](struct.BackgroundBuilder.html#method.finalize) can return.
+    pub fn sender(&self) -> SyncSender<String> {
+        self.sender.clone()
+    }
+
+    /// Sends one leaf from the owning thread.
+    pub fn push(&self, leaf: String) -> Result<(), SendError<String>> {
+        self.sender.send(leaf)
+    }
+
+    /// Closes the channel, waits until every sent leaf is hashed and returns the tree.
+    pub fn finalize(self) -> MerkleTree {
+        drop(self.sender);
+        self.handle.join().expect("builder thread panicked")
+    }
+}
+
+fn build(receiver: Receiver<String>) -> MerkleTree {
+    let mut frontier = Frontier::default();
+    for leaf in receiver {
+        frontier.push(Node::new(leaf_hash(leaf.as_bytes()), None, None));
+    }
+    MerkleTree::new(frontier.finish().map(Box::new))
+}
+
+/// Complete subtrees on the right edge of the tree: `levels[h]` holds a subtree of `2^h` leaves if bit `h` of the leaf count is set.
+#[derive(Debug, Default)]
+struct Frontier {
+    levels: Vec<Option<Node>>,
+}
+
+impl Frontier {
+    fn push(&mut self, leaf: Node) {
+        let mut carry = leaf;
+        for level in self.levels.iter_mut() {
+            match level.take() {
+                Some(left) => carry = pair(left, carry),
+                None => {
+                    *level = Some(carry);
+                    return;
+                }
+            }
+        }
+        self.levels.push(Some(carry));
+    }
+
+    /// Folds the right edge the way the layered build does: a node left without a sibling on its layer is hashed with itself.
+    fn finish(mut self) -> Option<Node> {
+        let top = self.levels.iter().rposition(Option::is_some)?;
+        if top == 0 {
+            // The layered build hashes even a single leaf once.
+            return self.levels[0].take().map(lone);
+        }
+        let mut carry: Option<Node> = None;
+        for height in 0..top {
+            carry = match (self.levels[height].take(), carry) {
+                (Some(left), Some(right)) => Some(pair(left, right)),
+                (Some(node), None) | (None, Some(node)) => Some(lone(node)),
+                (None, None) => None,
+            };
+        }
+        let top = self.levels[top].take().expect("top level is set");
+        Some(match carry {
+            Some(right) => pair(top, right),
+            None => top,
+        })
+    }
+}
+
+fn pair(left: Node, right: Node) -> Node {
+    let hash = node_hash(&left.hash, &right.hash);
+    Node::new(hash, Some(Box::new(left)), Some(Box::new(right)))
+}
+
+fn lone(node: Node) -> Node {
+    let hash = node_hash(&node.hash, &node.hash);
+    Node::new(hash, Some(Box::new(node)), None)
+}
@@ -0,0 +1,76 @@
+//! Trees that are known to be built.
+//!
+//! A [MerkleTree](../struct.MerkleTree.html) starts out empty and is filled by [build_tree](../struct.MerkleTree.html#method.build_tree), so its accessors have to cope with a tree that was never built: [root_node](../struct.MerkleTree.html#method.root_node) and [depth](../struct.MerkleTree.html#method.depth) return an `Option`. [MerkleTree::build](../struct.MerkleTree.html#method.build) instead consumes the configured tree and returns a [BuiltTree](struct.BuiltTree.html), which always has at least one leaf, so its root, depth and membership checks cannot fail; the state of the tree is in its type. A [BuiltTree](struct.BuiltTree.html) dereferences to the [MerkleTree](../struct.MerkleTree.html) for everything else.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::{Hash, MerkleTree};
+//! let tree = MerkleTree::new(None)
+//!     .build(vec!["Hello", "World", "From", "Rust"])
+//!     .unwrap();
+//! assert_eq!(
+//!     tree.root().to_string(),
+//!     "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
+//! );
+//! assert_eq!(tree.depth(), 3);
+//! assert!(tree.includes(&Hash::leaf(b"Rust")));
+//! assert!(tree.proof(3).unwrap().verify(&tree.root(), &Hash::leaf(b"Rust")));
+//!
+//! assert!(MerkleTree::new(None).build(Vec::new()).is_err());
+//! ```
+
+use crate::hash::Hash;
+use crate::{MerkleTree, Node};
+use std::ops::Deref;
+
+/// [BuiltTree](struct.BuiltTree.html) is a [MerkleTree](../struct.MerkleTree.html) with at least one leaf.
+///
+/// * `tree`: The tree, whose root is always set.
+#[derive(Debug, Clone)]
+pub struct BuiltTree {
+    tree: MerkleTree,
+}
+
+impl BuiltTree {
+    /// Wraps `tree`, which must have a root built by the tree itself.
+    pub(crate) fn new(tree: MerkleTree) -> BuiltTree {
+        debug_assert!(tree.root_node.is_some());
+        BuiltTree { tree }
+    }
+
+    /// Returns the Root Hash.
+    pub fn root(&self) -> Hash {
+        self.root_node().hash()
+    }
+
+    /// Returns the Root Node.
+    pub fn root_node(&self) -> &Node {
+        self.tree
+            .root_node
+            .as_deref()
+            .expect("built trees have a root")
+    }
+
+    /// Returns the depth of the tree from the Root Node to the leaves, counting both; a single leaf gives 2, as it is still hashed with itself.
+    pub fn depth(&self) -> usize {
+        self.root_node().depth()
+    }
+
+    /// Returns `true` if `leaf` is the hash of one of the leaves, see [MerkleTree::includes](../struct.MerkleTree.html#method.includes).
+    pub fn includes(&self, leaf: &Hash) -> bool {
+        self.tree.includes(leaf)
+    }
+
+    /// Returns the [MerkleTree](../struct.MerkleTree.html), for example to build it again.
+    pub fn into_tree(self) -> MerkleTree {
+        self.tree
+    }
+}
+
+impl Deref for BuiltTree {
+    type Target = MerkleTree;
+
+    fn deref(&self) -> &MerkleTree {
+        &self.tree
+    }
+}
@@ -0,0 +1,115 @@
+//! Leaf hash caching across rebuilds.
+//!
+//! Services that rebuild a tree periodically over a dataset that barely changes hash the same leaves again and again. A [MerkleTree](../struct.MerkleTree.html) created with [with_leaf_cache](../struct.MerkleTree.html#method.with_leaf_cache) keeps a [LeafCache](struct.LeafCache.html) mapping leaf data to its hash, so a rebuild only hashes the leaves that are new; the inner nodes above them are still recomputed. After each build the cache keeps only the leaves of that build, so it never grows past the size of the dataset.
+//!
+//! The cache holds the leaf data itself, so it is as sensitive as the data.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::MerkleTree;
+//! let mut tree = MerkleTree::new(None).with_leaf_cache();
+//! tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+//! tree.build_tree(vec!["Hello", "World", "From", "Go"]);
+//!
+//! let cache = tree.leaf_cache().unwrap();
+//! assert_eq!((cache.hits(), cache.misses()), (3, 5));
+//! assert_eq!(cache.len(), 4);
+//! ```
+
+use crate::Hash;
+use std::collections::HashMap;
+
+/// [LeafCache](struct.LeafCache.html) maps leaf data to its hash.
+///
+/// * `entries`: For every cached leaf, its hash and the last build that used it.
+///
+/// * `generation`: Number of the current build.
+///
+/// * `hits`, `misses`: Lookups answered from the cache and lookups that had to hash, over the cache's lifetime.
+#[derive(Debug, Clone, Default)]
+pub struct LeafCache {
+    entries: HashMap<Vec<u8>, (Hash, u64)>,
+    generation: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// Overwrites the cached data and hashes and empties the cache.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for LeafCache {
+    fn zeroize(&mut self) {
+        // Hashes are wiped in place, as draining would leave their bytes in the table.
+        for (hash, _) in self.entries.values_mut() {
+            hash.zeroize();
+        }
+        for (mut data, _) in self.entries.drain() {
+            data.zeroize();
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for LeafCache {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for LeafCache {}
+
+impl LeafCache {
+    /// Function to create an empty [LeafCache](struct.LeafCache.html)
+    pub fn new() -> LeafCache {
+        LeafCache::default()
+    }
+
+    /// Returns the number of cached leaves.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no leaf is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of leaf hashes taken from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of leaf hashes that had to be computed.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Drops every cached leaf.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Starts a build: leaves not looked up until [finish](struct.LeafCache.html#method.finish) are then evicted.
+    pub(crate) fn start(&mut self) {
+        self.generation += 1;
+    }
+
+    /// Returns the hash of `data`, computing it with `hash` if it is not cached.
+    pub(crate) fn hash<F: FnOnce(&[u8]) -> Hash>(&mut self, data: &[u8], hash: F) -> Hash {
+        if let Some(entry) = self.entries.get_mut(data) {
+            entry.1 = self.generation;
+            self.hits += 1;
+        } else {
+            self.entries
+                .insert(data.to_vec(), (hash(data), self.generation));
+            self.misses += 1;
+        }
+        self.entries[data].0
+    }
+
+    /// Ends a build, evicting the leaves it did not use.
+    pub(crate) fn finish(&mut self) {
+        let generation = self.generation;
+        self.entries.retain(|_, (_, used)| *used == generation);
+    }
+}
@@ -0,0 +1,138 @@
+//! Content-defined chunking.
+//!
+//! Fixed-size chunks shift with every inserted or removed byte, so a one byte edit near the start of a file changes every leaf after it. [FastCdc](struct.FastCdc.html) instead places chunk boundaries where a rolling Gear hash of the content matches a mask (Xia et al., "FastCDC: a Fast and Efficient Content-Defined Chunking Approach for Data Deduplication"), so boundaries move with the content and an edit only changes the chunks around it. Normalized chunking keeps sizes close to the average: a stricter mask is used before the average size and a looser one after it.
+//!
+//! Use [FileTree::from_reader_cdc](../file/struct.FileTree.html#method.from_reader_cdc) to build a file tree over content-defined chunks.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::cdc::FastCdc;
+//! let data: Vec<u8> = (0..100_000u32).map(|i| (i.wrapping_mul(2654435761) >> 13) as u8).collect();
+//! let cdc = FastCdc::new(1024, 4096, 16384);
+//! let chunks: Vec<&[u8]> = cdc.chunks(&data).collect();
+//! assert_eq!(chunks.concat(), data);
+//! assert!(chunks.iter().all(|chunk| chunk.len() <= 16384));
+//! ```
+
+/// [FastCdc](struct.FastCdc.html) holds the chunk size bounds and the masks derived from them.
+///
+/// * `min_size`: No boundary is placed before this many bytes.
+///
+/// * `avg_size`: Target average chunk size, a power of two.
+///
+/// * `max_size`: A boundary is forced after this many bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FastCdc {
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+    mask_small: u64,
+    mask_large: u64,
+}
+
+/// Iterator over the content-defined chunks of a slice, returned by [FastCdc::chunks](struct.FastCdc.html#method.chunks).
+#[derive(Debug, Clone)]
+pub struct Chunks<'a> {
+    cdc: FastCdc,
+    data: &'a [u8],
+}
+
+impl FastCdc {
+    /// Function to create a [FastCdc](struct.FastCdc.html) chunker.
+    ///
+    /// Panics unless `0 < min_size <= avg_size <= max_size` and `avg_size` is a power of two of at least 4.
+    pub fn new(min_size: usize, avg_size: usize, max_size: usize) -> FastCdc {
+        assert!(
+            min_size > 0 && min_size <= avg_size && avg_size <= max_size,
+            "chunk sizes must satisfy 0 < min <= avg <= max"
+        );
+        assert!(
+            avg_size.is_power_of_two() && avg_size >= 4,
+            "average chunk size must be a power of two of at least 4"
+        );
+        let bits = avg_size.trailing_zeros();
+        FastCdc {
+            min_size,
+            avg_size,
+            max_size,
+            mask_small: mask(bits + 1),
+            mask_large: mask(bits - 1),
+        }
+    }
+
+    /// Returns the minimum chunk size.
+    pub fn min_size(&self) -> usize {
+        self.min_size
+    }
+
+    /// Returns the average chunk size.
+    pub fn avg_size(&self) -> usize {
+        self.avg_size
+    }
+
+    /// Returns the maximum chunk size.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Returns the length of the first chunk of `data`, assuming `data` is either at least `max_size` bytes or the rest of the input.
+    pub fn cut(&self, data: &[u8]) -> usize {
+        if data.len() <= self.min_size {
+            return data.len();
+        }
+        let end = data.len().min(self.max_size);
+        let normal = end.min(self.avg_size);
+        let mut hash = 0u64;
+        for (i, byte) in data.iter().enumerate().take(end).skip(self.min_size) {
+            hash = (hash << 1).wrapping_add(GEAR[*byte as usize]);
+            let mask = if i < normal {
+                self.mask_small
+            } else {
+                self.mask_large
+            };
+            if hash & mask == 0 {
+                return i + 1;
+            }
+        }
+        end
+    }
+
+    /// Returns an iterator over the chunks of `data`.
+    pub fn chunks<'a>(&self, data: &'a [u8]) -> Chunks<'a> {
+        Chunks { cdc: *self, data }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let (chunk, rest) = self.data.split_at(self.cdc.cut(self.data));
+        self.data = rest;
+        Some(chunk)
+    }
+}
+
+/// A mask of `bits` one bits at the top of the word, where the Gear hash depends on the most recent bytes.
+const fn mask(bits: u32) -> u64 {
+    !(u64::MAX >> bits)
+}
+
+/// Gear table: one pseudo-random word per byte value, generated with SplitMix64 so chunk boundaries are stable across builds.
+const GEAR: [u64; 256] = {
+    let mut table = [0u64; 256];
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+};
@@ -0,0 +1,102 @@
+//! Proof export for circom Merkle verifier templates.
+//!
+//! circom membership templates (Tornado Cash's `MerkleTreeChecker`, Semaphore, zk-kit's `BinaryMerkleRoot`) take the proof as two signal arrays, `pathElements` (the siblings from the leaf up) and `pathIndices` (`1` where the node is the right child), and hash with circomlib's `Poseidon(2)`. [CircomPoseidon](struct.CircomPoseidon.html) is that hash: the width-3 permutation of [arkworks](../arkworks/index.html) applied to the state `[0, left, right]`, returning the first element. Over the BN254 scalar field it reproduces circomlib's outputs, so a [FieldMerkleTree](../arkworks/struct.FieldMerkleTree.html) built with it has the root the circuit computes, and [input_json](fn.input_json.html) writes an `input.json` snarkjs can prove with directly.
+//!
+//! Empty leaves are zero, as in Semaphore and zk-kit; templates with a different zero value need the tree filled with it.
+//!
+//! Requires the `circom` feature.
+//!
+//! # Examples
+//! ```
+//! use ark_bn254::Fr;
+//! use rs_merkletree::arkworks::FieldMerkleTree;
+//! use rs_merkletree::circom::{input_json, CircomPoseidon};
+//! let leaves: Vec<Fr> = (1..=3u64).map(Fr::from).collect();
+//! let tree = FieldMerkleTree::new(CircomPoseidon::new(), &leaves, 2).unwrap();
+//! let proof = tree.prove(1).unwrap();
+//!
+//! let input = input_json(&proof, tree.root(), leaves[1]);
+//! assert_eq!(input["leaf"], "2");
+//! assert_eq!(input["pathElements"][0], "1");
+//! assert_eq!(input["pathIndices"], serde_json::json!([1, 0]));
+//! ```
+
+use crate::arkworks::{poseidon_config, FieldHasher, FieldProof};
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_ff::PrimeField;
+use serde_json::{json, Value};
+
+/// [CircomPoseidon](struct.CircomPoseidon.html) is circomlib's two-input Poseidon hash.
+///
+/// * `config`: Round constants and MDS matrix; [new](struct.CircomPoseidon.html#method.new) uses those of [poseidon_config](../arkworks/fn.poseidon_config.html), which are circomlib's.
+#[derive(Debug, Clone)]
+pub struct CircomPoseidon<F: PrimeField> {
+    pub config: PoseidonConfig<F>,
+}
+
+impl<F: PrimeField> CircomPoseidon<F> {
+    /// Function to create a [CircomPoseidon](struct.CircomPoseidon.html) with circomlib's parameters.
+    pub fn new() -> CircomPoseidon<F> {
+        CircomPoseidon {
+            config: poseidon_config(),
+        }
+    }
+
+    /// Returns circomlib's `Poseidon(2)` of `left` and `right`.
+    pub fn hash(&self, left: F, right: F) -> F {
+        let config = &self.config;
+        let half_full = config.full_rounds / 2;
+        let rounds = config.full_rounds + config.partial_rounds;
+        let mut state = [F::zero(), left, right];
+        for round in 0..rounds {
+            for (element, constant) in state.iter_mut().zip(&config.ark[round]) {
+                *element += constant;
+            }
+            if round < half_full || round >= rounds - half_full {
+                state
+                    .iter_mut()
+                    .for_each(|element| *element = sbox(*element));
+            } else {
+                state[0] = sbox(state[0]);
+            }
+            let mut mixed = [F::zero(); 3];
+            for (out, row) in mixed.iter_mut().zip(&config.mds) {
+                *out = row.iter().zip(&state).map(|(m, s)| *m * s).sum();
+            }
+            state = mixed;
+        }
+        state[0]
+    }
+}
+
+impl<F: PrimeField> Default for CircomPoseidon<F> {
+    fn default() -> CircomPoseidon<F> {
+        CircomPoseidon::new()
+    }
+}
+
+impl<F: PrimeField> FieldHasher<F> for CircomPoseidon<F> {
+    fn hash_children(&self, left: F, right: F) -> F {
+        self.hash(left, right)
+    }
+}
+
+fn sbox<F: PrimeField>(x: F) -> F {
+    let square = x.square();
+    square.square() * x
+}
+
+/// Returns `element` as the decimal string snarkjs expects for a signal.
+pub fn to_decimal<F: PrimeField>(element: F) -> String {
+    element.into_bigint().to_string()
+}
+
+/// Returns the circuit input for `proof`: `{"root", "leaf", "pathElements", "pathIndices"}`, field elements as decimal strings and indices as `0` or `1`.
+pub fn input_json<F: PrimeField>(proof: &FieldProof<F>, root: F, leaf: F) -> Value {
+    json!({
+        "root": to_decimal(root),
+        "leaf": to_decimal(leaf),
+        "pathElements": proof.path.iter().copied().map(to_decimal).collect::<Vec<_>>(),
+        "pathIndices": proof.index_bits.iter().map(|bit| *bit as u8).collect::<Vec<_>>(),
+    })
+}
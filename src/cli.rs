@@ -0,0 +1,267 @@
+//! The `rs-merkle` command-line tool.
+//!
+//! `rs-merkle` computes roots, emits proofs and verifies them without writing Rust. Input is a file (or standard input) read either as newline-delimited items, one leaf per line, built into a [MerkleTree](../struct.MerkleTree.html), or with `--chunk-size` as fixed-size chunks, built into a [FileTree](../file/struct.FileTree.html):
+//!
+//! ```text
+//! rs-merkle root [--chunk-size N] [FILE]
+//! rs-merkle prove --index N [--chunk-size N] [FILE]
+//! rs-merkle verify --root HEX --proof PROOF (--leaf TEXT | --leaf-file PATH)
+//! rs-merkle diff FILE FILE
+//! ```
+//!
+//! `verify` prints `valid` or `invalid` and exits with status 0 or 1. `diff` builds item trees over both files and prints `<index>\t<line in first>\t<line in second>` for every leaf that differs, descending only into mismatched subtrees (see [MerkleTree::diff](../struct.MerkleTree.html#method.diff)); like `diff(1)` it exits with status 1 if there are differences. Usage errors exit with status 2. Item proofs are the hex of [MerkleProof::to_bytes](../proof/struct.MerkleProof.html#method.to_bytes); chunk proofs are `chunk:<index>:<chunk count>:<hex path, comma separated>`.
+//!
+//! Requires the `cli` feature: `cargo install rs-merkletree --features cli`.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::cli::run;
+//! let args = |line: &str| line.split(' ').map(String::from).collect::<Vec<_>>();
+//! let input = "Hello\nWorld\nFrom\nRust\n";
+//!
+//! let mut out = Vec::new();
+//! run(&args("root"), input.as_bytes(), &mut out).unwrap();
+//! assert_eq!(out, b"a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb\n");
+//! ```
+
+use crate::file::{ChunkProof, FileTree};
+use crate::hashing::{from_hex, to_hex};
+use crate::proof::MerkleProof;
+use crate::{Hash, MerkleTree};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+
+/// Usage text printed on errors.
+pub const USAGE: &str = "usage:
+  rs-merkle root [--chunk-size N] [FILE]
+  rs-merkle prove --index N [--chunk-size N] [FILE]
+  rs-merkle verify --root HEX --proof PROOF (--leaf TEXT | --leaf-file PATH)
+  rs-merkle diff FILE FILE
+
+FILE is read as one leaf per line, or as chunks of N bytes with --chunk-size. Standard input is read if FILE is omitted or -.";
+
+const CHUNK_PREFIX: &str = "chunk:";
+
+/// Options given on the command line.
+#[derive(Debug, Default)]
+struct Options {
+    index: Option<usize>,
+    chunk_size: Option<usize>,
+    root: Option<String>,
+    proof: Option<String>,
+    leaf: Option<Vec<u8>>,
+    inputs: Vec<String>,
+}
+
+/// Runs `rs-merkle` with `args` (without the program name), reading input from `stdin` unless a file is given and writing results to `stdout`.
+///
+/// Returns `Ok(false)` if `verify` rejected the proof or `diff` found differences, and `Err` with a message on usage or I/O errors.
+pub fn run<R: Read, W: Write>(
+    args: &[String],
+    mut stdin: R,
+    mut stdout: W,
+) -> Result<bool, String> {
+    let (command, rest) = args.split_first().ok_or("missing command")?;
+    let options = parse_options(rest)?;
+    match command.as_str() {
+        "root" => {
+            let root = match options.chunk_size {
+                Some(chunk_size) => {
+                    to_hex(&chunk_tree(single_input(&options)?, &mut stdin, chunk_size)?.root())
+                }
+                None => item_root(&item_tree(single_input(&options)?, &mut stdin)?)?,
+            };
+            writeln!(stdout, "{}", root).map_err(|error| error.to_string())?;
+            Ok(true)
+        }
+        "prove" => {
+            let index = options.index.ok_or("prove needs --index")?;
+            let proof = match options.chunk_size {
+                Some(chunk_size) => {
+                    let proof = chunk_tree(single_input(&options)?, &mut stdin, chunk_size)?
+                        .prove(index as u64)
+                        .ok_or("index out of range")?;
+                    format_chunk_proof(&proof)
+                }
+                None => to_hex(
+                    &item_tree(single_input(&options)?, &mut stdin)?
+                        .proof(index)
+                        .ok_or("index out of range")?
+                        .to_bytes(),
+                ),
+            };
+            writeln!(stdout, "{}", proof).map_err(|error| error.to_string())?;
+            Ok(true)
+        }
+        "verify" => {
+            let valid = verify(&options)?;
+            writeln!(stdout, "{}", if valid { "valid" } else { "invalid" })
+                .map_err(|error| error.to_string())?;
+            Ok(valid)
+        }
+        "diff" => {
+            let [first, second] = options.inputs.as_slice() else {
+                return Err("diff needs two files".to_string());
+            };
+            let first = read_items(Some(first), &mut stdin)?;
+            let second = read_items(Some(second), &mut stdin)?;
+            let first: Vec<&str> = first.lines().collect();
+            let second: Vec<&str> = second.lines().collect();
+            let differences = build(&first).diff(&build(&second));
+            for &index in &differences {
+                writeln!(
+                    stdout,
+                    "{}\t{}\t{}",
+                    index,
+                    first.get(index).unwrap_or(&""),
+                    second.get(index).unwrap_or(&"")
+                )
+                .map_err(|error| error.to_string())?;
+            }
+            Ok(differences.is_empty())
+        }
+        other => Err(format!("unknown command {}", other)),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", arg))
+        };
+        match arg.as_str() {
+            "--index" => options.index = Some(parse_number(arg, &value()?)?),
+            "--chunk-size" => match parse_number(arg, &value()?)? {
+                0 => return Err("--chunk-size must be positive".to_string()),
+                size => options.chunk_size = Some(size),
+            },
+            "--root" => options.root = Some(value()?),
+            "--proof" => options.proof = Some(value()?),
+            "--leaf" => options.leaf = Some(value()?.into_bytes()),
+            "--leaf-file" => {
+                let path = value()?;
+                let leaf = fs::read(&path).map_err(|error| format!("{}: {}", path, error))?;
+                options.leaf = Some(leaf);
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+            _ => options.inputs.push(arg.clone()),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a number, got {}", flag, value))
+}
+
+/// Returns the input file of a single-input command; `None` means standard input.
+fn single_input(options: &Options) -> Result<Option<&str>, String> {
+    match options.inputs.as_slice() {
+        [] => Ok(None),
+        [input] => Ok(Some(input)),
+        [_, extra, ..] => Err(format!("unexpected argument {}", extra)),
+    }
+}
+
+/// Reads the whole input, from the named file or `stdin`.
+fn read_input<R: Read>(input: Option<&str>, mut stdin: R) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    match input {
+        None | Some("-") => stdin
+            .read_to_end(&mut data)
+            .map_err(|error| error.to_string())?,
+        Some(path) => File::open(path)
+            .and_then(|mut file| file.read_to_end(&mut data))
+            .map_err(|error| format!("{}: {}", path, error))?,
+    };
+    Ok(data)
+}
+
+fn item_tree<R: Read>(input: Option<&str>, stdin: R) -> Result<MerkleTree, String> {
+    let items = read_items(input, stdin)?;
+    Ok(build(&items.lines().collect::<Vec<_>>()))
+}
+
+fn build(items: &[&str]) -> MerkleTree {
+    let mut tree = MerkleTree::new(None);
+    tree.build_tree(items.to_vec());
+    tree
+}
+
+fn read_items<R: Read>(input: Option<&str>, stdin: R) -> Result<String, String> {
+    String::from_utf8(read_input(input, stdin)?)
+        .map_err(|_| "input is not UTF-8; use --chunk-size".to_string())
+}
+
+fn item_root(tree: &MerkleTree) -> Result<String, String> {
+    let root = tree.root_node().ok_or("input has no items")?;
+    Ok(root.hash().to_string())
+}
+
+fn chunk_tree<R: Read>(
+    input: Option<&str>,
+    stdin: R,
+    chunk_size: usize,
+) -> Result<FileTree, String> {
+    let data = read_input(input, stdin)?;
+    FileTree::from_reader(data.as_slice(), chunk_size).map_err(|error| error.to_string())
+}
+
+fn format_chunk_proof(proof: &ChunkProof) -> String {
+    let path: Vec<String> = proof.path.iter().map(|hash| to_hex(hash)).collect();
+    format!(
+        "{}{}:{}:{}",
+        CHUNK_PREFIX,
+        proof.index,
+        proof.chunk_count,
+        path.join(",")
+    )
+}
+
+fn parse_chunk_proof(text: &str) -> Option<ChunkProof> {
+    let mut fields = text.splitn(3, ':');
+    let index = fields.next()?.parse().ok()?;
+    let chunk_count = fields.next()?.parse().ok()?;
+    let path = match fields.next()? {
+        "" => Vec::new(),
+        path => path.split(',').map(from_hex).collect::<Option<_>>()?,
+    };
+    Some(ChunkProof {
+        index,
+        chunk_count,
+        path,
+    })
+}
+
+fn verify(options: &Options) -> Result<bool, String> {
+    let root = options.root.as_deref().ok_or("verify needs --root")?;
+    let proof = options.proof.as_deref().ok_or("verify needs --proof")?;
+    let leaf = options
+        .leaf
+        .as_deref()
+        .ok_or("verify needs --leaf or --leaf-file")?;
+    match proof.strip_prefix(CHUNK_PREFIX) {
+        Some(proof) => {
+            let proof = parse_chunk_proof(proof).ok_or("malformed chunk proof")?;
+            let root = from_hex(root).ok_or("--root is not hex")?;
+            Ok(proof.verify(&root, leaf))
+        }
+        None => {
+            let proof = from_hex(proof)
+                .as_deref()
+                .and_then(MerkleProof::from_bytes)
+                .ok_or("malformed proof")?;
+            let root: Hash = root
+                .to_ascii_lowercase()
+                .parse()
+                .map_err(|error| format!("--root: {:?}", error))?;
+            Ok(proof.verify_data(&root, leaf))
+        }
+    }
+}
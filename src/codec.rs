@@ -0,0 +1,97 @@
+//! Hex and base64 codecs.
+//!
+//! Hashes, roots and proofs are bytes; moving them through JSON, URLs or config files needs a text encoding. The functions here are the crate's own, so text produced by one consumer decodes in another: lowercase hex, accepting either case on input, and standard, padded base64, accepting missing padding on input. [Hash](../hash/struct.Hash.html) and the proof types of [proof](../proof/index.html) have `to_hex`, `from_hex`, `to_base64` and `from_base64` methods built on them.
+//!
+//! Like [proof](../proof/index.html), this module builds without `std`.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::codec::{from_base64, from_hex, to_base64, to_hex};
+//! assert_eq!(to_hex(b"\x01\xab"), "01ab");
+//! assert_eq!(from_hex("01AB"), Ok(vec![0x01, 0xab]));
+//! assert_eq!(to_base64(b"merkle"), "bWVya2xl");
+//! assert_eq!(from_base64("bWVya2xlcw"), Ok(b"merkles".to_vec()));
+//! ```
+
+use crate::error::MerkleError;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Returns `bytes` as lowercase hex.
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        out.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        out.push(HEX_DIGITS[(byte & 0xf) as usize] as char);
+    }
+    out
+}
+
+/// Parses lowercase or uppercase hex. Fails with [MerkleError::InvalidEncoding](../enum.MerkleError.html) at the first invalid digit, or at the end of odd-length input.
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, MerkleError> {
+    let digit = |position: usize| match hex.as_bytes().get(position) {
+        Some(&byte) => (byte as char)
+            .to_digit(16)
+            .map(|digit| digit as u8)
+            .ok_or(MerkleError::InvalidEncoding { position }),
+        None => Err(MerkleError::InvalidEncoding { position }),
+    };
+    (0..hex.len())
+        .step_by(2)
+        .map(|position| Ok(digit(position)? << 4 | digit(position + 1)?))
+        .collect()
+}
+
+/// Encodes `bytes` as standard, padded base64.
+pub fn to_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let word = group
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, byte)| acc | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= group.len() {
+                out.push(BASE64_ALPHABET[(word >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decodes standard base64, with or without padding. Fails with [MerkleError::InvalidEncoding](../enum.MerkleError.html) at the first invalid character, or at the end of truncated input.
+pub fn from_base64(text: &str) -> Result<Vec<u8>, MerkleError> {
+    let digits = text.trim_end_matches('=').as_bytes();
+    if digits.len() % 4 == 1 {
+        return Err(MerkleError::InvalidEncoding {
+            position: digits.len(),
+        });
+    }
+    if text.len() - digits.len() > 2 {
+        return Err(MerkleError::InvalidEncoding {
+            position: digits.len() + 2,
+        });
+    }
+    let mut out = Vec::with_capacity(digits.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for (position, digit) in digits.iter().enumerate() {
+        let value = BASE64_ALPHABET
+            .iter()
+            .position(|c| c == digit)
+            .ok_or(MerkleError::InvalidEncoding { position })? as u32;
+        buffer = buffer << 6 | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(out)
+}
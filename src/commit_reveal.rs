@@ -0,0 +1,174 @@
+//! Commit–reveal over blinded leaves.
+//!
+//! A [BlindedTree](struct.BlindedTree.html) commits to values without publishing them: every leaf is the commitment `SHA256(value || nonce)`, hex encoded, with a fresh random nonce of [NONCE_LEN](constant.NONCE_LEN.html) bytes. Only the root and commitments are public; the values and nonces stay with the tree. Later, a [Reveal](struct.Reveal.html) of one leaf — its value, nonce and proof — lets anyone check it against the root, while the other values stay hidden.
+//!
+//! The nonce is what keeps low-entropy values, such as bids or votes, from being guessed from their commitment, so nonces must never be reused or disclosed for leaves that are not revealed.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::commit_reveal::BlindedTree;
+//! let bids = vec![b"alice:100".to_vec(), b"bob:250".to_vec(), b"carol:175".to_vec()];
+//! let tree = BlindedTree::new(bids);
+//! let root = tree.root().unwrap();
+//!
+//! // After the bidding closes, bob reveals his bid.
+//! let reveal = tree.reveal(1).unwrap();
+//! assert_eq!(reveal.value, b"bob:250");
+//! assert!(reveal.verify(&root));
+//! ```
+
+use crate::hashing::sha256_hex;
+use crate::proof::MerkleProof;
+use crate::{Hash, MerkleTree};
+use rand_core::{OsRng, RngCore};
+
+/// Length of the nonce blinding each value.
+pub const NONCE_LEN: usize = 32;
+
+/// Returns the commitment to `value` under `nonce`: the lowercase hex SHA-256 of `value` followed by `nonce`.
+pub fn commitment(value: &[u8], nonce: &[u8; NONCE_LEN]) -> Vec<u8> {
+    sha256_hex(&[value, nonce.as_slice()])
+}
+
+/// [BlindedTree](struct.BlindedTree.html) is a tree over commitments to private values.
+///
+/// * `tree`: Tree whose leaf data are the commitments.
+///
+/// * `values`: The committed values, in leaf order.
+///
+/// * `nonces`: The nonce of each value.
+#[derive(Debug, Clone)]
+pub struct BlindedTree {
+    tree: MerkleTree,
+    values: Vec<Vec<u8>>,
+    nonces: Vec<[u8; NONCE_LEN]>,
+}
+
+/// [Reveal](struct.Reveal.html) opens one leaf of a [BlindedTree](struct.BlindedTree.html).
+///
+/// * `index`: Position of the leaf.
+///
+/// * `value`: The committed value.
+///
+/// * `nonce`: The nonce it was committed with.
+///
+/// * `proof`: Proof of the commitment against the root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reveal {
+    pub index: usize,
+    pub value: Vec<u8>,
+    pub nonce: [u8; NONCE_LEN],
+    pub proof: MerkleProof,
+}
+
+/// Overwrites the values and nonces and empties the tree.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for BlindedTree {
+    fn zeroize(&mut self) {
+        self.values.zeroize();
+        self.nonces.zeroize();
+        self.tree.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for BlindedTree {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for BlindedTree {}
+
+impl BlindedTree {
+    /// Function to create a [BlindedTree](struct.BlindedTree.html) committing to `values`, each with a nonce from the operating system's random number generator.
+    pub fn new(values: Vec<Vec<u8>>) -> BlindedTree {
+        BlindedTree::new_with_rng(values, &mut OsRng)
+    }
+
+    /// Same as [new](struct.BlindedTree.html#method.new), drawing the nonces from `rng`, for example a hardware generator, or a seeded one in tests. `rng` must be cryptographically secure outside of tests.
+    pub fn new_with_rng<R: RngCore>(values: Vec<Vec<u8>>, rng: &mut R) -> BlindedTree {
+        let nonces = values
+            .iter()
+            .map(|_| {
+                let mut nonce = [0u8; NONCE_LEN];
+                rng.fill_bytes(&mut nonce);
+                nonce
+            })
+            .collect();
+        BlindedTree::build(values, nonces)
+    }
+
+    /// Function to create a [BlindedTree](struct.BlindedTree.html) with the given nonces, one per value, for example to restore a tree from storage.
+    ///
+    /// Returns `None` if there are not as many nonces as values.
+    pub fn with_nonces(values: Vec<Vec<u8>>, nonces: Vec<[u8; NONCE_LEN]>) -> Option<BlindedTree> {
+        if values.len() != nonces.len() {
+            return None;
+        }
+        Some(BlindedTree::build(values, nonces))
+    }
+
+    fn build(values: Vec<Vec<u8>>, nonces: Vec<[u8; NONCE_LEN]>) -> BlindedTree {
+        let commitments: Vec<Vec<u8>> = values
+            .iter()
+            .zip(&nonces)
+            .map(|(value, nonce)| commitment(value, nonce))
+            .collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree_bytes(commitments.iter().map(Vec::as_slice).collect());
+        BlindedTree {
+            tree,
+            values,
+            nonces,
+        }
+    }
+
+    /// Returns the Root Hash, or `None` if there are no values.
+    pub fn root(&self) -> Option<Hash> {
+        self.tree.root_node.as_ref().map(|root| root.hash)
+    }
+
+    /// Returns the commitment of every leaf, which can be published.
+    pub fn commitments(&self) -> Vec<Vec<u8>> {
+        self.values
+            .iter()
+            .zip(&self.nonces)
+            .map(|(value, nonce)| commitment(value, nonce))
+            .collect()
+    }
+
+    /// Returns the underlying tree.
+    pub fn tree(&self) -> &MerkleTree {
+        &self.tree
+    }
+
+    /// Returns the number of committed values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no values are committed.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Produces the reveal package of leaf `index`.
+    pub fn reveal(&self, index: usize) -> Option<Reveal> {
+        Some(Reveal {
+            index,
+            value: self.values.get(index)?.clone(),
+            nonce: self.nonces[index],
+            proof: self.tree.proof(index)?,
+        })
+    }
+}
+
+impl Reveal {
+    /// Checks that `value` under `nonce` is committed by `root`.
+    pub fn verify(&self, root: &Hash) -> bool {
+        self.proof
+            .verify_data(root, &commitment(&self.value, &self.nonce))
+    }
+}
@@ -0,0 +1,317 @@
+//! Concurrent Merkle tree.
+//!
+//! A [ConcurrentMerkleTree](struct.ConcurrentMerkleTree.html) follows the semantics of Solana's account-compression trees. It stores no leaves: only the current root, the proof of the rightmost leaf (enough to append) and a ring buffer of the most recent changes. When a writer submits a proof generated against an older root that is still in the buffer, the proof is "fast-forwarded" by patching in the nodes changed since, so several writers can build proofs from the same snapshot and have all of their updates accepted in any order.
+//!
+//! Leaves are 32 byte hashes; the empty leaf is all zeros.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::concurrent::{proof_from_leaves, ConcurrentMerkleTree};
+//! let mut tree = ConcurrentMerkleTree::new(3, 8);
+//! let mut leaves = vec![vec![0u8; 32]; 8];
+//! for i in 0..4u8 {
+//!     leaves[i as usize] = vec![i + 1; 32];
+//!     tree.append(&leaves[i as usize]).unwrap();
+//! }
+//!
+//! // Two writers prove against the same snapshot.
+//! let snapshot = tree.root();
+//! let proof_a = proof_from_leaves(&leaves, 1, 3);
+//! let proof_b = proof_from_leaves(&leaves, 2, 3);
+//! tree.replace_leaf(&snapshot, &leaves[1], &[9u8; 32], &proof_a, 1).unwrap();
+//! tree.replace_leaf(&snapshot, &leaves[2], &[8u8; 32], &proof_b, 2).unwrap();
+//! ```
+
+use crate::hashing::sha256_concat;
+use crate::notify::{RootChange, Subscribers};
+use std::collections::VecDeque;
+use std::sync::mpsc::Receiver;
+
+/// Errors returned by [ConcurrentMerkleTree](struct.ConcurrentMerkleTree.html) updates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CmtError {
+    /// Every leaf of the tree is in use.
+    TreeFull,
+    /// The leaf index is not below the number of appended leaves.
+    LeafIndexOutOfBounds,
+    /// The root the proof was generated against is not in the changelog buffer.
+    RootNotFound,
+    /// The leaf was modified after the proof's root, so `previous_leaf` is stale.
+    LeafContentsModified,
+    /// The proof does not lead to the current root.
+    InvalidProof,
+}
+
+/// One entry of the changelog buffer: the path written by an update.
+///
+/// `path[h]` is the new node at height `h` on the path of leaf `index` (`path[0]` is the leaf itself).
+#[derive(Debug, Clone, PartialEq)]
+struct ChangeLog {
+    root: Vec<u8>,
+    path: Vec<Vec<u8>>,
+    index: u64,
+}
+
+/// [ConcurrentMerkleTree](struct.ConcurrentMerkleTree.html) tracks the state needed to accept concurrent updates.
+#[derive(Debug, Clone)]
+pub struct ConcurrentMerkleTree {
+    max_depth: usize,
+    buffer_size: usize,
+    change_logs: VecDeque<ChangeLog>,
+    rightmost_proof: Vec<Vec<u8>>,
+    rightmost_leaf: Vec<u8>,
+    rightmost_index: u64,
+    zero_hashes: Vec<Vec<u8>>,
+    sequence_number: u64,
+    subscribers: Subscribers,
+}
+
+impl ChangeLog {
+    /// Patches `proof` for `index` with the nodes this change wrote, or replaces `leaf` if the change wrote that same leaf.
+    fn fast_forward(&self, index: u64, proof: &mut [Vec<u8>], leaf: &mut Vec<u8>) {
+        if self.index == index {
+            *leaf = self.path[0].clone();
+            return;
+        }
+        // The paths meet just above the highest differing bit; below it the change wrote our sibling.
+        let critbit = (u64::BITS - 1 - (self.index ^ index).leading_zeros()) as usize;
+        proof[critbit] = self.path[critbit].clone();
+    }
+}
+
+impl ConcurrentMerkleTree {
+    /// Function to create an empty [ConcurrentMerkleTree](struct.ConcurrentMerkleTree.html) of depth `max_depth` that remembers the last `buffer_size` changes.
+    ///
+    /// Panics if `max_depth` is 0 or greater than 63, or if `buffer_size` is 0.
+    pub fn new(max_depth: usize, buffer_size: usize) -> ConcurrentMerkleTree {
+        assert!(
+            max_depth > 0 && max_depth < 64,
+            "depth must be between 1 and 63"
+        );
+        assert!(buffer_size > 0, "buffer size must be positive");
+        let mut zero_hashes = vec![vec![0u8; 32]];
+        for height in 0..max_depth {
+            let zero = &zero_hashes[height];
+            let next = sha256_concat(&[zero, zero]);
+            zero_hashes.push(next);
+        }
+        let mut change_logs = VecDeque::with_capacity(buffer_size);
+        change_logs.push_back(ChangeLog {
+            root: zero_hashes[max_depth].clone(),
+            path: zero_hashes[..max_depth].to_vec(),
+            index: 0,
+        });
+        ConcurrentMerkleTree {
+            max_depth,
+            buffer_size,
+            change_logs,
+            rightmost_proof: zero_hashes[..max_depth].to_vec(),
+            rightmost_leaf: vec![0u8; 32],
+            rightmost_index: 0,
+            zero_hashes,
+            sequence_number: 0,
+            subscribers: Subscribers::default(),
+        }
+    }
+
+    /// Calls `callback` with a [RootChange](../notify/struct.RootChange.html) after every append or replacement, with the sequence number as version.
+    pub fn subscribe<F: FnMut(&RootChange) + Send + Sync + 'static>(&mut self, callback: F) {
+        self.subscribers.add(callback);
+    }
+
+    /// Returns a channel receiving a [RootChange](../notify/struct.RootChange.html) after every append or replacement, with the sequence number as version.
+    pub fn subscribe_channel(&mut self) -> Receiver<RootChange> {
+        self.subscribers.channel()
+    }
+
+    /// Returns the current Root Hash.
+    pub fn root(&self) -> Vec<u8> {
+        self.change_logs
+            .back()
+            .expect("buffer is never empty")
+            .root
+            .clone()
+    }
+
+    /// Returns the number of appended leaves.
+    pub fn num_leaves(&self) -> u64 {
+        self.rightmost_index
+    }
+
+    /// Returns the number of updates applied so far.
+    pub fn sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+
+    /// Appends `leaf` at the next free index, returning that index.
+    pub fn append(&mut self, leaf: &[u8]) -> Result<u64, CmtError> {
+        if self.rightmost_index >= 1 << self.max_depth {
+            return Err(CmtError::TreeFull);
+        }
+        let index = self.rightmost_index;
+        if index == 0 {
+            // The empty tree's rightmost proof is all zero hashes.
+            return self
+                .write(
+                    index,
+                    leaf.to_vec(),
+                    self.zero_hashes[..self.max_depth].to_vec(),
+                )
+                .map(|_| index);
+        }
+
+        // Derive the proof of the next leaf from the proof of the previous one.
+        let previous = index - 1;
+        let intersection = index.trailing_zeros() as usize;
+        let mut intersection_node = self.rightmost_leaf.clone();
+        let mut proof = self.rightmost_proof.clone();
+        for (height, node) in proof.iter_mut().enumerate().take(intersection) {
+            intersection_node = parent(&intersection_node, node, previous >> height & 1 == 0);
+            *node = self.zero_hashes[height].clone();
+        }
+        if intersection < self.max_depth {
+            proof[intersection] = intersection_node;
+        }
+        self.write(index, leaf.to_vec(), proof).map(|_| index)
+    }
+
+    /// Replaces `previous_leaf` at `index` with `new_leaf`, returning the new root.
+    ///
+    /// `proof` may have been generated against any `root` still held in the changelog buffer; it is fast-forwarded over the changes made since.
+    pub fn replace_leaf(
+        &mut self,
+        root: &[u8],
+        previous_leaf: &[u8],
+        new_leaf: &[u8],
+        proof: &[Vec<u8>],
+        index: u64,
+    ) -> Result<Vec<u8>, CmtError> {
+        if index >= self.rightmost_index {
+            return Err(CmtError::LeafIndexOutOfBounds);
+        }
+        if proof.len() != self.max_depth {
+            return Err(CmtError::InvalidProof);
+        }
+        let start = self
+            .change_logs
+            .iter()
+            .rposition(|change| change.root == root)
+            .ok_or(CmtError::RootNotFound)?;
+
+        let mut proof = proof.to_vec();
+        let mut leaf = previous_leaf.to_vec();
+        for change in self.change_logs.iter().skip(start + 1) {
+            change.fast_forward(index, &mut proof, &mut leaf);
+        }
+        if leaf != previous_leaf {
+            return Err(CmtError::LeafContentsModified);
+        }
+        if compute_root(&leaf, &proof, index) != Some(self.root()) {
+            return Err(CmtError::InvalidProof);
+        }
+        self.write(index, new_leaf.to_vec(), proof)
+    }
+
+    /// Writes `leaf` at `index` with a proof valid for the current tree, records the change and returns the new root.
+    fn write(
+        &mut self,
+        index: u64,
+        leaf: Vec<u8>,
+        proof: Vec<Vec<u8>>,
+    ) -> Result<Vec<u8>, CmtError> {
+        let mut path = Vec::with_capacity(self.max_depth);
+        let mut node = leaf.clone();
+        for (height, sibling) in proof.iter().enumerate() {
+            path.push(node.clone());
+            node = parent(&node, sibling, index >> height & 1 == 0);
+        }
+        let old_root = (!self.subscribers.is_empty()).then(|| self.root());
+        let change = ChangeLog {
+            root: node.clone(),
+            path,
+            index,
+        };
+
+        if index == self.rightmost_index {
+            self.rightmost_index += 1;
+            self.rightmost_proof = proof;
+            self.rightmost_leaf = leaf;
+        } else if index + 1 == self.rightmost_index {
+            self.rightmost_leaf = leaf;
+        } else {
+            change.fast_forward(
+                self.rightmost_index - 1,
+                &mut self.rightmost_proof,
+                &mut self.rightmost_leaf,
+            );
+        }
+
+        if self.change_logs.len() == self.buffer_size {
+            self.change_logs.pop_front();
+        }
+        self.change_logs.push_back(change);
+        self.sequence_number += 1;
+        #[cfg(feature = "debug-validate")]
+        debug_assert_eq!(
+            compute_root(
+                &self.rightmost_leaf,
+                &self.rightmost_proof,
+                self.rightmost_index - 1
+            ),
+            Some(node.clone()),
+            "rightmost proof no longer matches the root"
+        );
+        if let Some(old_root) = old_root {
+            let change = RootChange {
+                old_root,
+                new_root: node.clone(),
+                version: self.sequence_number,
+            };
+            self.subscribers.notify(change);
+        }
+        Ok(node)
+    }
+}
+
+/// Builds the proof for leaf `index` from the full list of leaves, padding missing leaves with zeros. Intended for indexers that track the leaves off-tree.
+pub fn proof_from_leaves(leaves: &[Vec<u8>], index: usize, depth: usize) -> Vec<Vec<u8>> {
+    let mut layer: Vec<Vec<u8>> = leaves.to_vec();
+    let mut zero = vec![0u8; 32];
+    let mut position = index;
+    let mut proof = Vec::with_capacity(depth);
+    for _ in 0..depth {
+        proof.push(
+            layer
+                .get(position ^ 1)
+                .cloned()
+                .unwrap_or_else(|| zero.clone()),
+        );
+        layer = layer
+            .chunks(2)
+            .map(|pair| sha256_concat(&[&pair[0], pair.get(1).unwrap_or(&zero)]))
+            .collect();
+        zero = sha256_concat(&[&zero, &zero]);
+        position >>= 1;
+    }
+    proof
+}
+
+/// Recomputes the root from `leaf` at `index` and its `proof`, or returns `None` if the proof has more than 64 levels.
+pub fn compute_root(leaf: &[u8], proof: &[Vec<u8>], index: u64) -> Option<Vec<u8>> {
+    if proof.len() > u64::BITS as usize {
+        return None;
+    }
+    let mut node = leaf.to_vec();
+    for (height, sibling) in proof.iter().enumerate() {
+        node = parent(&node, sibling, index >> height & 1 == 0);
+    }
+    Some(node)
+}
+
+fn parent(node: &[u8], sibling: &[u8], node_is_left: bool) -> Vec<u8> {
+    if node_is_left {
+        sha256_concat(&[node, sibling])
+    } else {
+        sha256_concat(&[sibling, node])
+    }
+}
@@ -0,0 +1,350 @@
+//! Configurable tree construction.
+//!
+//! [MerkleTree](../struct.MerkleTree.html) fixes one construction. Other systems differ in the hash function, in whether leaves are hashed before use, and in what happens to the last node of a level with an odd number of nodes. A [TreeConfig](struct.TreeConfig.html) captures these choices, with presets for the ecosystems the crate is compatible with, and builds trees over raw byte leaves.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::config::{DuplicateLeaves, HashFunction, OddNode, TreeConfig};
+//! let config = TreeConfig {
+//!     hash_function: HashFunction::Sha256,
+//!     hash_leaves: true,
+//!     odd_node: OddNode::Promote,
+//!     domain_tags: false,
+//!     sort_leaves: false,
+//!     sort_pairs: false,
+//!     duplicate_leaves: DuplicateLeaves::Allow,
+//! };
+//! let leaves: Vec<&[u8]> = vec![b"a", b"b", b"c"];
+//! let layers = config.build_layers(&leaves);
+//! assert_eq!(layers.len(), 3);
+//! // "c" has no sibling and is carried up unchanged.
+//! assert_eq!(layers[1][1], layers[0][2]);
+//! assert_eq!(config.root(&leaves), Some(layers[2][0].clone()));
+//! ```
+
+use crate::hashing::{keccak256_concat, sha256, sha256_concat};
+#[cfg(feature = "rayon")]
+use crate::parallel::Parallelism;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Hash function applied to leaves and inner nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum HashFunction {
+    /// SHA-256.
+    Sha256,
+    /// SHA-256 applied twice, as in Bitcoin.
+    DoubleSha256,
+    /// Keccak-256, as in Ethereum.
+    Keccak256,
+}
+
+/// Treatment of the last node of a level with an odd number of nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum OddNode {
+    /// Hash the node with a copy of itself.
+    Duplicate,
+    /// Move the node to the next level unchanged.
+    Promote,
+}
+
+/// Treatment of leaves equal to an earlier leaf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum DuplicateLeaves {
+    /// Keep every leaf.
+    Allow,
+    /// Refuse to build, reporting the duplicates as [ConfigError::DuplicateLeaves](enum.ConfigError.html).
+    Reject,
+    /// Drop every leaf equal to an earlier one.
+    Remove,
+}
+
+/// Errors returned when building a tree from a [TreeConfig](struct.TreeConfig.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Leaves at these indices repeat an earlier leaf.
+    DuplicateLeaves(Vec<usize>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::DuplicateLeaves(indices) => {
+                write!(f, "leaves at indices {:?} repeat an earlier leaf", indices)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// [TreeConfig](struct.TreeConfig.html) describes how a tree is built.
+///
+/// * `hash_function`: Hash used for leaves and inner nodes.
+///
+/// * `hash_leaves`: Whether leaf data is hashed first. When false, leaves must already be digests (such as transaction ids).
+///
+/// * `odd_node`: What happens to a node without a sibling.
+///
+/// * `domain_tags`: Whether leaves are hashed as `H(0x00 || data)` and inner nodes as `H(0x01 || left || right)`, so an inner node can never be passed off as a leaf.
+///
+/// * `sort_leaves`: Whether leaf nodes are sorted before building, making the root independent of input order.
+///
+/// * `sort_pairs`: Whether each pair is sorted before hashing, so proofs need no left/right directions.
+///
+/// * `duplicate_leaves`: What happens to leaves equal to an earlier leaf. Duplicates in allowlists are almost always data bugs, so they can be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TreeConfig {
+    pub hash_function: HashFunction,
+    pub hash_leaves: bool,
+    pub odd_node: OddNode,
+    pub domain_tags: bool,
+    pub sort_leaves: bool,
+    pub sort_pairs: bool,
+    pub duplicate_leaves: DuplicateLeaves,
+}
+
+const LEAF_TAG: &[u8] = &[0x00];
+const NODE_TAG: &[u8] = &[0x01];
+
+impl HashFunction {
+    /// Returns the digest of the concatenation of `parts`.
+    pub fn digest(&self, parts: &[&[u8]]) -> Vec<u8> {
+        match self {
+            HashFunction::Sha256 => sha256_concat(parts),
+            HashFunction::DoubleSha256 => sha256(&sha256_concat(parts)),
+            HashFunction::Keccak256 => keccak256_concat(parts),
+        }
+    }
+}
+
+impl TreeConfig {
+    /// Preset for Bitcoin transaction Merkle roots: double SHA-256 over txids in internal byte order, duplicating the last node of odd levels. See the [bitcoin](../bitcoin/index.html) module for helpers taking txids as displayed.
+    pub fn bitcoin() -> TreeConfig {
+        TreeConfig {
+            hash_function: HashFunction::DoubleSha256,
+            hash_leaves: false,
+            odd_node: OddNode::Duplicate,
+            domain_tags: false,
+            sort_leaves: false,
+            sort_pairs: false,
+            duplicate_leaves: DuplicateLeaves::Allow,
+        }
+    }
+
+    /// Preset for RFC 6962 / RFC 9162 (Certificate Transparency) trees: tagged SHA-256 with odd nodes promoted, which is equivalent to splitting at the largest power of two below the size. Roots agree with the [history](../history/index.html) module.
+    pub fn rfc6962() -> TreeConfig {
+        TreeConfig {
+            hash_function: HashFunction::Sha256,
+            hash_leaves: true,
+            odd_node: OddNode::Promote,
+            domain_tags: true,
+            sort_leaves: false,
+            sort_pairs: false,
+            duplicate_leaves: DuplicateLeaves::Allow,
+        }
+    }
+
+    /// Preset for Tendermint / CometBFT simple Merkle trees, which follow RFC 6962. See the [tendermint](../tendermint/index.html) module.
+    pub fn tendermint() -> TreeConfig {
+        TreeConfig::rfc6962()
+    }
+
+    /// Preset matching the defaults of the JavaScript [merkletreejs](https://github.com/merkletreejs/merkletreejs) library with the given hash: leaves used as given, odd nodes promoted, no sorting.
+    ///
+    /// The library's options map onto fields: `hashLeaves` to `hash_leaves`, `sortLeaves` to `sort_leaves`, `sortPairs` to `sort_pairs`, `sort` to both, and `duplicateOdd` to [OddNode::Duplicate](enum.OddNode.html).
+    /// ```
+    /// use rs_merkletree::config::{HashFunction, TreeConfig};
+    /// // new MerkleTree(leaves, keccak256, { hashLeaves: true, sortPairs: true })
+    /// let config = TreeConfig {
+    ///     hash_leaves: true,
+    ///     sort_pairs: true,
+    ///     ..TreeConfig::merkletreejs(HashFunction::Keccak256)
+    /// };
+    /// assert!(config.root(&[b"a", b"b"]).is_some());
+    /// ```
+    pub fn merkletreejs(hash_function: HashFunction) -> TreeConfig {
+        TreeConfig {
+            hash_function,
+            hash_leaves: false,
+            odd_node: OddNode::Promote,
+            domain_tags: false,
+            sort_leaves: false,
+            sort_pairs: false,
+            duplicate_leaves: DuplicateLeaves::Allow,
+        }
+    }
+
+    /// Returns the leaf node for `data`.
+    pub fn hash_leaf(&self, data: &[u8]) -> Vec<u8> {
+        match (self.hash_leaves, self.domain_tags) {
+            (false, _) => data.to_vec(),
+            (true, false) => self.hash_function.digest(&[data]),
+            (true, true) => self.hash_function.digest(&[LEAF_TAG, data]),
+        }
+    }
+
+    /// Returns the parent of `left` and `right`.
+    pub fn hash_nodes(&self, left: &[u8], right: &[u8]) -> Vec<u8> {
+        let (left, right) = if self.sort_pairs && left > right {
+            (right, left)
+        } else {
+            (left, right)
+        };
+        if self.domain_tags {
+            self.hash_function.digest(&[NODE_TAG, left, right])
+        } else {
+            self.hash_function.digest(&[left, right])
+        }
+    }
+
+    /// Builds every level of the tree over `leaves`, leaf nodes first; the last level holds only the root. Returns no levels for no leaves, or if duplicate leaves are rejected; with [DuplicateLeaves::Reject](enum.DuplicateLeaves.html) use [try_build_layers](struct.TreeConfig.html#method.try_build_layers) to tell the two apart.
+    pub fn build_layers<T: AsRef<[u8]>>(&self, leaves: &[T]) -> Vec<Vec<Vec<u8>>> {
+        self.try_build_layers(leaves).unwrap_or_default()
+    }
+
+    /// Same as [build_layers](struct.TreeConfig.html#method.build_layers), but returns [ConfigError::DuplicateLeaves](enum.ConfigError.html) with their indices when duplicates are rejected.
+    pub fn try_build_layers<T: AsRef<[u8]>>(
+        &self,
+        leaves: &[T],
+    ) -> Result<Vec<Vec<Vec<u8>>>, ConfigError> {
+        let leaf_nodes = leaves
+            .iter()
+            .map(|leaf| self.hash_leaf(leaf.as_ref()))
+            .collect();
+        self.layers_from(leaf_nodes, |layer| {
+            layer.chunks(2).map(|pair| self.pair_parent(pair)).collect()
+        })
+    }
+
+    /// Same as [try_build_layers](struct.TreeConfig.html#method.try_build_layers), hashing leaves and each level on all cores with [rayon](https://docs.rs/rayon). Nodes are computed from the same children in the same order, so the layers are bit-identical to the sequential ones. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn try_build_layers_parallel<T: AsRef<[u8]> + Sync>(
+        &self,
+        leaves: &[T],
+    ) -> Result<Vec<Vec<Vec<u8>>>, ConfigError> {
+        use rayon::prelude::*;
+        let leaf_nodes = leaves
+            .par_iter()
+            .map(|leaf| self.hash_leaf(leaf.as_ref()))
+            .collect();
+        self.layers_from(leaf_nodes, |layer| {
+            layer
+                .par_chunks(2)
+                .map(|pair| self.pair_parent(pair))
+                .collect()
+        })
+    }
+
+    /// Same as [try_build_layers_parallel](struct.TreeConfig.html#method.try_build_layers_parallel), on the threads of `parallelism` instead of rayon's global pool.
+    #[cfg(feature = "rayon")]
+    pub fn try_build_layers_parallel_in<T: AsRef<[u8]> + Sync>(
+        &self,
+        leaves: &[T],
+        parallelism: &Parallelism,
+    ) -> Result<Vec<Vec<Vec<u8>>>, ConfigError> {
+        parallelism.install(|| self.try_build_layers_parallel(leaves))
+    }
+
+    /// Same as [root](struct.TreeConfig.html#method.root), built with [try_build_layers_parallel](struct.TreeConfig.html#method.try_build_layers_parallel).
+    #[cfg(feature = "rayon")]
+    pub fn root_parallel<T: AsRef<[u8]> + Sync>(&self, leaves: &[T]) -> Option<Vec<u8>> {
+        self.try_build_layers_parallel(leaves)
+            .ok()?
+            .pop()
+            .and_then(|mut root| root.pop())
+    }
+
+    /// Applies the duplicate and sorting settings to `leaf_nodes` and builds the levels above them with `upper_layer`.
+    fn layers_from<F>(
+        &self,
+        mut leaf_nodes: Vec<Vec<u8>>,
+        upper_layer: F,
+    ) -> Result<Vec<Vec<Vec<u8>>>, ConfigError>
+    where
+        F: Fn(&[Vec<u8>]) -> Vec<Vec<u8>>,
+    {
+        if self.duplicate_leaves != DuplicateLeaves::Allow {
+            let duplicates = duplicate_indices(&leaf_nodes);
+            if self.duplicate_leaves == DuplicateLeaves::Reject && !duplicates.is_empty() {
+                return Err(ConfigError::DuplicateLeaves(duplicates));
+            }
+            let duplicates: HashSet<usize> = duplicates.into_iter().collect();
+            let mut index = 0;
+            leaf_nodes.retain(|_| {
+                index += 1;
+                !duplicates.contains(&(index - 1))
+            });
+        }
+        if leaf_nodes.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.sort_leaves {
+            leaf_nodes.sort();
+        }
+        let mut layers = vec![leaf_nodes];
+        while layers[layers.len() - 1].len() > 1 {
+            let next = upper_layer(&layers[layers.len() - 1]);
+            layers.push(next);
+        }
+        Ok(layers)
+    }
+
+    /// Returns the Root Hash over `leaves`, or `None` for no leaves or if duplicate leaves are rejected; with [DuplicateLeaves::Reject](enum.DuplicateLeaves.html) use [try_root](struct.TreeConfig.html#method.try_root) to tell the two apart.
+    pub fn root<T: AsRef<[u8]>>(&self, leaves: &[T]) -> Option<Vec<u8>> {
+        self.try_root(leaves).ok().flatten()
+    }
+
+    /// Same as [root](struct.TreeConfig.html#method.root), but returns [ConfigError::DuplicateLeaves](enum.ConfigError.html) with their indices when duplicates are rejected, and `Ok(None)` only for no leaves.
+    /// ```
+    /// use rs_merkletree::config::{ConfigError, DuplicateLeaves, TreeConfig};
+    /// let config = TreeConfig {
+    ///     duplicate_leaves: DuplicateLeaves::Reject,
+    ///     ..TreeConfig::rfc6962()
+    /// };
+    /// assert_eq!(config.try_root::<&[u8]>(&[]), Ok(None));
+    /// assert_eq!(
+    ///     config.try_root(&[b"a", b"b", b"a"]),
+    ///     Err(ConfigError::DuplicateLeaves(vec![2]))
+    /// );
+    /// ```
+    pub fn try_root<T: AsRef<[u8]>>(&self, leaves: &[T]) -> Result<Option<Vec<u8>>, ConfigError> {
+        Ok(self
+            .try_build_layers(leaves)?
+            .pop()
+            .and_then(|mut root| root.pop()))
+    }
+
+    /// Returns the indices of leaves whose leaf node equals that of an earlier leaf, whatever the `duplicate_leaves` setting.
+    pub fn find_duplicates<T: AsRef<[u8]>>(&self, leaves: &[T]) -> Vec<usize> {
+        let leaf_nodes: Vec<Vec<u8>> = leaves
+            .iter()
+            .map(|leaf| self.hash_leaf(leaf.as_ref()))
+            .collect();
+        duplicate_indices(&leaf_nodes)
+    }
+
+    fn pair_parent(&self, pair: &[Vec<u8>]) -> Vec<u8> {
+        match (pair, self.odd_node) {
+            ([left, right], _) => self.hash_nodes(left, right),
+            ([single], OddNode::Duplicate) => self.hash_nodes(single, single),
+            ([single], OddNode::Promote) => single.clone(),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Returns the indices of nodes equal to an earlier node, in ascending order.
+fn duplicate_indices(nodes: &[Vec<u8>]) -> Vec<usize> {
+    let mut seen = HashSet::with_capacity(nodes.len());
+    nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| !seen.insert(node.as_slice()))
+        .map(|(index, _)| index)
+        .collect()
+}
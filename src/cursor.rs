@@ -0,0 +1,127 @@
+//! Cursor navigation.
+//!
+//! A [TreeCursor](struct.TreeCursor.html) walks a [MerkleTree](../struct.MerkleTree.html) one edge at a time from its root, for tooling and debuggers that explore the structure interactively. It borrows the tree and never allocates: the way back up is kept in a fixed-size stack.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::proof::Position;
+//! use rs_merkletree::MerkleTree;
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+//! let mut cursor = tree.cursor().unwrap();
+//! assert!(cursor.go_right() && cursor.go_right());
+//! assert!(!cursor.go_left());
+//! assert_eq!(cursor.current_hash().to_string(), "635fd701970453575a3f84cb82631929f1a96dbeb7c45f983ff648698a5ca8f4");
+//! assert!(cursor.path().eq([Position::Right, Position::Right]));
+//! assert!(cursor.go_up());
+//! assert_eq!((cursor.depth(), cursor.index()), (1, 1));
+//! ```
+
+use crate::proof::Position;
+use crate::{Hash, Node};
+
+/// Deepest level a [TreeCursor](struct.TreeCursor.html) can reach; built trees never come close.
+pub const MAX_CURSOR_DEPTH: usize = 64;
+
+/// [TreeCursor](struct.TreeCursor.html) points at one node of a tree.
+///
+/// * `ancestors`: The nodes from the root down to the current one; only the first `depth + 1` are set.
+///
+/// * `depth`: Levels below the root.
+///
+/// * `rights`: Bit `i` is set if the step from depth `i` to `i + 1` went right.
+#[derive(Debug, Clone, Copy)]
+pub struct TreeCursor<'a> {
+    ancestors: [Option<&'a Node>; MAX_CURSOR_DEPTH + 1],
+    depth: usize,
+    rights: u64,
+}
+
+impl<'a> TreeCursor<'a> {
+    /// Function to create a [TreeCursor](struct.TreeCursor.html) at `root`.
+    pub fn new(root: &'a Node) -> TreeCursor<'a> {
+        let mut ancestors = [None; MAX_CURSOR_DEPTH + 1];
+        ancestors[0] = Some(root);
+        TreeCursor {
+            ancestors,
+            depth: 0,
+            rights: 0,
+        }
+    }
+
+    /// Returns the node under the cursor.
+    pub fn current(&self) -> &'a Node {
+        self.ancestors[self.depth].expect("the current node is always set")
+    }
+
+    /// Returns the hash of the node under the cursor.
+    pub fn current_hash(&self) -> &'a Hash {
+        &self.current().hash
+    }
+
+    /// Returns the number of levels below the root.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the position of the current node from the left of its level, as used by [MerkleTree::node](../struct.MerkleTree.html#method.node).
+    pub fn index(&self) -> usize {
+        self.path().fold(0, |index, step| {
+            index << 1 | usize::from(step == Position::Right)
+        })
+    }
+
+    /// Returns the directions taken from the root to the current node.
+    pub fn path(&self) -> impl Iterator<Item = Position> + '_ {
+        (0..self.depth).map(|level| {
+            if self.rights >> level & 1 == 1 {
+                Position::Right
+            } else {
+                Position::Left
+            }
+        })
+    }
+
+    /// Returns `true` if the current node has no children.
+    pub fn is_leaf(&self) -> bool {
+        let node = self.current();
+        node.left_node.is_none() && node.right_node.is_none()
+    }
+
+    /// Moves to the left child. Returns `false`, without moving, if there is none.
+    pub fn go_left(&mut self) -> bool {
+        let child = self.current().left_node.as_deref();
+        self.descend(child, false)
+    }
+
+    /// Moves to the right child. Returns `false`, without moving, if there is none, as for a node whose only child was hashed with itself.
+    pub fn go_right(&mut self) -> bool {
+        let child = self.current().right_node.as_deref();
+        self.descend(child, true)
+    }
+
+    /// Moves to the parent. Returns `false` at the root.
+    pub fn go_up(&mut self) -> bool {
+        if self.depth == 0 {
+            return false;
+        }
+        self.ancestors[self.depth] = None;
+        self.depth -= 1;
+        self.rights &= !(1 << self.depth);
+        true
+    }
+
+    fn descend(&mut self, child: Option<&'a Node>, right: bool) -> bool {
+        match child {
+            Some(child) if self.depth < MAX_CURSOR_DEPTH => {
+                if right {
+                    self.rights |= 1 << self.depth;
+                }
+                self.depth += 1;
+                self.ancestors[self.depth] = Some(child);
+                true
+            }
+            _ => false,
+        }
+    }
+}
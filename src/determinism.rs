@@ -0,0 +1,177 @@
+//! Determinism checks for parallel builds.
+//!
+//! [MerkleTree::build_tree_parallel](../struct.MerkleTree.html#method.build_tree_parallel) and [TreeConfig::try_build_layers_parallel](../config/struct.TreeConfig.html#method.try_build_layers_parallel) hash on all cores, but every node is still computed from the same children and every level keeps its order, so their output is bit-identical to the sequential build. The functions here build both ways and report the first node that differs as a [Divergence](struct.Divergence.html); [check](fn.check.html) does so for any [NodeHasher](../proof/trait.NodeHasher.html), so the guarantee can be checked for custom hashers too, which must not depend on hidden state such as call order.
+//!
+//! Requires the `rayon` feature.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::determinism::{check, check_config, check_tree, config_combinations};
+//! use rs_merkletree::proof::{leaf_hash, TaggedHexSha256};
+//! use rs_merkletree::MerkleTree;
+//! let data = ["Hello", "World", "From", "Rust", "!"];
+//! let root = check_tree(&MerkleTree::new(None), &data).unwrap();
+//!
+//! let leaves: Vec<[u8; 64]> = data.iter().map(|data| leaf_hash(data.as_bytes()).to_hex_bytes()).collect();
+//! let leaves: Vec<&[u8]> = leaves.iter().map(|leaf| &leaf[..]).collect();
+//! assert_eq!(check(&TaggedHexSha256, &leaves).unwrap(), root.map(|root| root.to_hex_bytes().to_vec()));
+//!
+//! for config in config_combinations() {
+//!     check_config(&config, &data).unwrap();
+//! }
+//! ```
+
+use crate::config::{DuplicateLeaves, HashFunction, OddNode, TreeConfig};
+use crate::proof::NodeHasher;
+use crate::{Hash, MerkleTree};
+use rayon::prelude::*;
+
+/// [Divergence](struct.Divergence.html) is the first node at which a parallel build differs from the sequential one.
+///
+/// * `level`: Level of the node, 0 for the leaves.
+///
+/// * `index`: Position of the node in its level. When one build has more levels or nodes than the other, this is the first position missing from the smaller one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Divergence {
+    pub level: usize,
+    pub index: usize,
+}
+
+/// Builds a tree over the leaf hashes `leaves` with `hasher` sequentially and in parallel, hashing a lone node with itself as [MerkleTree](../struct.MerkleTree.html) does, and returns the root, or `None` for no leaves.
+pub fn check<H: NodeHasher + Sync>(
+    hasher: &H,
+    leaves: &[&[u8]],
+) -> Result<Option<Vec<u8>>, Divergence> {
+    let sequential = hasher_layers(leaves, |layer| {
+        layer
+            .chunks(2)
+            .map(|pair| hash_pair(hasher, pair).as_ref().to_vec())
+            .collect()
+    });
+    let parallel = hasher_layers(leaves, |layer| {
+        layer
+            .par_chunks(2)
+            .map(|pair| hash_pair(hasher, pair).as_ref().to_vec())
+            .collect()
+    });
+    compare(&sequential, &parallel)
+}
+
+/// Builds copies of `tree`, keeping its settings, over `data` with [build_tree](../struct.MerkleTree.html#method.build_tree) and [build_tree_parallel](../struct.MerkleTree.html#method.build_tree_parallel), and returns the root, or `None` for no data.
+pub fn check_tree(tree: &MerkleTree, data: &[&str]) -> Result<Option<Hash>, Divergence> {
+    let mut sequential = tree.clone();
+    sequential.build_tree(data.to_vec());
+    let mut parallel = tree.clone();
+    parallel.build_tree_parallel(data.to_vec());
+    compare(&tree_layers(&sequential), &tree_layers(&parallel))?;
+    Ok(sequential.root_hash())
+}
+
+/// Builds `leaves` with [try_build_layers](../config/struct.TreeConfig.html#method.try_build_layers) and [try_build_layers_parallel](../config/struct.TreeConfig.html#method.try_build_layers_parallel), and returns the root, or `None` for no leaves or if duplicates are rejected.
+pub fn check_config<T: AsRef<[u8]> + Sync>(
+    config: &TreeConfig,
+    leaves: &[T],
+) -> Result<Option<Vec<u8>>, Divergence> {
+    let sequential = config.try_build_layers(leaves);
+    let parallel = config.try_build_layers_parallel(leaves);
+    match (sequential, parallel) {
+        (Ok(sequential), Ok(parallel)) => compare(&sequential, &parallel),
+        (Err(sequential), Err(parallel)) if sequential == parallel => Ok(None),
+        _ => Err(Divergence { level: 0, index: 0 }),
+    }
+}
+
+/// Returns every [TreeConfig](../config/struct.TreeConfig.html), one per combination of its settings.
+pub fn config_combinations() -> Vec<TreeConfig> {
+    let mut configs = Vec::new();
+    for hash_function in [
+        HashFunction::Sha256,
+        HashFunction::DoubleSha256,
+        HashFunction::Keccak256,
+    ] {
+        for odd_node in [OddNode::Duplicate, OddNode::Promote] {
+            for duplicate_leaves in [
+                DuplicateLeaves::Allow,
+                DuplicateLeaves::Reject,
+                DuplicateLeaves::Remove,
+            ] {
+                for flags in 0..16u8 {
+                    configs.push(TreeConfig {
+                        hash_function,
+                        hash_leaves: flags & 1 != 0,
+                        odd_node,
+                        domain_tags: flags & 2 != 0,
+                        sort_leaves: flags & 4 != 0,
+                        sort_pairs: flags & 8 != 0,
+                        duplicate_leaves,
+                    });
+                }
+            }
+        }
+    }
+    configs
+}
+
+fn hash_pair<H: NodeHasher>(hasher: &H, pair: &[&[u8]]) -> H::Output {
+    match pair {
+        [left, right] => hasher.hash_nodes(left, right),
+        [lone] => hasher.hash_nodes(lone, lone),
+        _ => unreachable!("chunks of two"),
+    }
+}
+
+/// Builds the levels over `leaves`, leaves first, with `upper_layer`.
+fn hasher_layers<F>(leaves: &[&[u8]], upper_layer: F) -> Vec<Vec<Vec<u8>>>
+where
+    F: Fn(&[&[u8]]) -> Vec<Vec<u8>>,
+{
+    if leaves.is_empty() {
+        return Vec::new();
+    }
+    let mut layers = vec![upper_layer(leaves)];
+    while layers[layers.len() - 1].len() > 1 {
+        let layer = &layers[layers.len() - 1];
+        let refs: Vec<&[u8]> = layer.iter().map(Vec::as_slice).collect();
+        let next = upper_layer(&refs);
+        layers.push(next);
+    }
+    layers.insert(0, leaves.iter().map(|leaf| leaf.to_vec()).collect());
+    layers
+}
+
+/// Returns the hashes of every level of `tree`, leaves first.
+fn tree_layers(tree: &MerkleTree) -> Vec<Vec<Vec<u8>>> {
+    let Some(height) = tree.height() else {
+        return Vec::new();
+    };
+    let size = tree.count_leaves();
+    (0..=height)
+        .map(|level| {
+            (0..((size - 1) >> level) + 1)
+                .filter_map(|index| tree.node_at(level, index))
+                .map(|node| node.hash.to_hex_bytes().to_vec())
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the root of `sequential`, or the first node where `parallel` differs.
+fn compare(
+    sequential: &[Vec<Vec<u8>>],
+    parallel: &[Vec<Vec<u8>>],
+) -> Result<Option<Vec<u8>>, Divergence> {
+    for (level, (expected, actual)) in sequential.iter().zip(parallel).enumerate() {
+        if let Some(index) = (0..expected.len().max(actual.len()))
+            .find(|&index| expected.get(index) != actual.get(index))
+        {
+            return Err(Divergence { level, index });
+        }
+    }
+    if sequential.len() != parallel.len() {
+        return Err(Divergence {
+            level: sequential.len().min(parallel.len()),
+            index: 0,
+        });
+    }
+    Ok(sequential.last().and_then(|root| root.first().cloned()))
+}
@@ -0,0 +1,224 @@
+//! Selective disclosure of documents.
+//!
+//! An issuer commits to a document, such as a credential, with a [DisclosureTree](struct.DisclosureTree.html): one leaf per field, each the commitment `SHA256(salt || name length || name || value)`, hex encoded, with a fresh random salt of [SALT_LEN](constant.SALT_LEN.html) bytes. Only the root is published or signed. The holder later reveals any subset of the fields as [FieldDisclosure](struct.FieldDisclosure.html)s, each with its salt and proof, and a verifier checks them against the root while the other fields stay hidden; the salts keep hidden fields with few possible values, such as a birth year, from being guessed from the proofs' sibling hashes.
+//!
+//! Documents expose their fields through the [Disclosable](trait.Disclosable.html) trait. The field name is part of the leaf, so a value cannot be passed off as another field's.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::disclosure::{verify_disclosures, Disclosable, DisclosureTree};
+//! struct Licence {
+//!     name: String,
+//!     birth_year: u16,
+//!     class: String,
+//! }
+//!
+//! impl Disclosable for Licence {
+//!     fn fields(&self) -> Vec<(String, Vec<u8>)> {
+//!         vec![
+//!             ("name".into(), self.name.as_bytes().to_vec()),
+//!             ("birth_year".into(), self.birth_year.to_be_bytes().to_vec()),
+//!             ("class".into(), self.class.as_bytes().to_vec()),
+//!         ]
+//!     }
+//! }
+//!
+//! let licence = Licence { name: "Alice".into(), birth_year: 1990, class: "B".into() };
+//! let tree = DisclosureTree::new(&licence).unwrap();
+//! let root = tree.root().unwrap();
+//!
+//! // Show the licence class without the name or birth year.
+//! let disclosures = tree.disclose(&["class"]).unwrap();
+//! assert_eq!(disclosures[0].value, b"B");
+//! assert!(verify_disclosures(&root, &disclosures));
+//! ```
+
+use crate::hashing::sha256_hex;
+use crate::proof::MerkleProof;
+use crate::{Hash, MerkleTree};
+use rand_core::{OsRng, RngCore};
+use std::collections::HashSet;
+
+/// Length of the salt blinding each field.
+pub const SALT_LEN: usize = 16;
+
+/// A document whose fields can be disclosed one by one.
+pub trait Disclosable {
+    /// Returns the document's fields as `(name, value)` pairs, in a fixed order. Names must be unique.
+    fn fields(&self) -> Vec<(String, Vec<u8>)>;
+}
+
+/// Returns the commitment to field `name` holding `value` under `salt`: the lowercase hex SHA-256 of the salt, the name's length as a 4 byte big-endian integer, the name and the value.
+pub fn field_commitment(name: &str, value: &[u8], salt: &[u8; SALT_LEN]) -> Vec<u8> {
+    sha256_hex(&[
+        salt.as_slice(),
+        &(name.len() as u32).to_be_bytes(),
+        name.as_bytes(),
+        value,
+    ])
+}
+
+/// [DisclosureTree](struct.DisclosureTree.html) is a tree over the salted fields of a document.
+///
+/// * `tree`: Tree whose leaf data are the field commitments.
+///
+/// * `fields`: The document's fields, in leaf order.
+///
+/// * `salts`: The salt of each field.
+#[derive(Debug, Clone)]
+pub struct DisclosureTree {
+    tree: MerkleTree,
+    fields: Vec<(String, Vec<u8>)>,
+    salts: Vec<[u8; SALT_LEN]>,
+}
+
+/// [FieldDisclosure](struct.FieldDisclosure.html) reveals one field of a [DisclosureTree](struct.DisclosureTree.html).
+///
+/// * `name`: Name of the field.
+///
+/// * `value`: Its value.
+///
+/// * `salt`: The salt it was committed with.
+///
+/// * `proof`: Proof of the commitment against the root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDisclosure {
+    pub name: String,
+    pub value: Vec<u8>,
+    pub salt: [u8; SALT_LEN],
+    pub proof: MerkleProof,
+}
+
+/// Overwrites the fields and salts and empties the tree.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for DisclosureTree {
+    fn zeroize(&mut self) {
+        for (name, value) in &mut self.fields {
+            name.zeroize();
+            value.zeroize();
+        }
+        self.fields.clear();
+        self.salts.zeroize();
+        self.tree.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for DisclosureTree {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for DisclosureTree {}
+
+impl DisclosureTree {
+    /// Function to create a [DisclosureTree](struct.DisclosureTree.html) over the fields of `document`, each with a salt from the operating system's random number generator.
+    ///
+    /// Returns `None` if two fields have the same name.
+    pub fn new<T: Disclosable>(document: &T) -> Option<DisclosureTree> {
+        DisclosureTree::new_with_rng(document, &mut OsRng)
+    }
+
+    /// Same as [new](struct.DisclosureTree.html#method.new), drawing the salts from `rng`. `rng` must be cryptographically secure outside of tests.
+    pub fn new_with_rng<T: Disclosable, R: RngCore>(
+        document: &T,
+        rng: &mut R,
+    ) -> Option<DisclosureTree> {
+        let fields = document.fields();
+        let salts = fields
+            .iter()
+            .map(|_| {
+                let mut salt = [0u8; SALT_LEN];
+                rng.fill_bytes(&mut salt);
+                salt
+            })
+            .collect();
+        DisclosureTree::with_salts(fields, salts)
+    }
+
+    /// Function to create a [DisclosureTree](struct.DisclosureTree.html) over `(name, value)` fields with the given salts, one per field, for example to restore a tree from storage.
+    ///
+    /// Returns `None` if two fields have the same name or there are not as many salts as fields.
+    pub fn with_salts(
+        fields: Vec<(String, Vec<u8>)>,
+        salts: Vec<[u8; SALT_LEN]>,
+    ) -> Option<DisclosureTree> {
+        let mut names = HashSet::with_capacity(fields.len());
+        if fields.len() != salts.len() || !fields.iter().all(|(name, _)| names.insert(name)) {
+            return None;
+        }
+        let commitments: Vec<Vec<u8>> = fields
+            .iter()
+            .zip(&salts)
+            .map(|((name, value), salt)| field_commitment(name, value, salt))
+            .collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree_bytes(commitments.iter().map(Vec::as_slice).collect());
+        Some(DisclosureTree {
+            tree,
+            fields,
+            salts,
+        })
+    }
+
+    /// Returns the Root Hash, or `None` if the document has no fields.
+    pub fn root(&self) -> Option<Hash> {
+        self.tree.root_node.as_ref().map(|root| root.hash)
+    }
+
+    /// Returns the salt of every field, to be stored with the document.
+    pub fn salts(&self) -> &[[u8; SALT_LEN]] {
+        &self.salts
+    }
+
+    /// Returns the underlying tree.
+    pub fn tree(&self) -> &MerkleTree {
+        &self.tree
+    }
+
+    /// Returns the number of fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns `true` if the document has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Produces the disclosure of every field in `names`, in that order.
+    ///
+    /// Returns `None` if a name is not a field of the document.
+    pub fn disclose(&self, names: &[&str]) -> Option<Vec<FieldDisclosure>> {
+        names
+            .iter()
+            .map(|name| {
+                let index = self.fields.iter().position(|(field, _)| field == name)?;
+                Some(FieldDisclosure {
+                    name: name.to_string(),
+                    value: self.fields[index].1.clone(),
+                    salt: self.salts[index],
+                    proof: self.tree.proof(index)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl FieldDisclosure {
+    /// Checks that the field is committed by `root`.
+    pub fn verify(&self, root: &Hash) -> bool {
+        self.proof
+            .verify_data(root, &field_commitment(&self.name, &self.value, &self.salt))
+    }
+}
+
+/// Checks that every disclosure is committed by `root` and that no field is disclosed twice.
+pub fn verify_disclosures(root: &Hash, disclosures: &[FieldDisclosure]) -> bool {
+    let mut names = HashSet::with_capacity(disclosures.len());
+    disclosures
+        .iter()
+        .all(|disclosure| names.insert(&disclosure.name) && disclosure.verify(root))
+}
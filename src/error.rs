@@ -0,0 +1,167 @@
+//! Errors returned by [MerkleTree](../struct.MerkleTree.html) and [MerkleProof](../proof/struct.MerkleProof.html).
+//!
+//! Hashes passed in by callers are validated before use, so a hash of the wrong length or in the wrong encoding is reported instead of simply failing to match.
+//!
+//! Every variant has a stable numeric [code](enum.MerkleError.html#method.code), which never changes between releases and is never reused, so services can map failures to responses, such as HTTP statuses, without matching on the message:
+//!
+//! | Code | Variant |
+//! |------|---------|
+//! | 1 | `InvalidHashLength` |
+//! | 2 | `InvalidEncoding` |
+//! | 3 | `HashMismatch` |
+//! | 4 | `MalformedTree` |
+//! | 5 | `TooManyLeaves` |
+//! | 6 | `LeafTooLarge` |
+//! | 7 | `ProofTooLong` |
+//! | 8 | `MalformedProof` |
+//! | 9 | `LeafNotFound` |
+//! | 10 | `InvalidProof` |
+//! | 11 | `SizeMismatch` |
+//! | 12 | `EmptyTree` |
+//! | 13 | `Io` (with the `std` feature) |
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::{Hash, MerkleError, MerkleTree};
+//! assert_eq!(
+//!     "abc".parse::<Hash>(),
+//!     Err(MerkleError::InvalidHashLength { expected: 64, actual: 3 })
+//! );
+//!
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(vec!["Hello", "World"]);
+//!
+//! let error = tree.try_proof(5).unwrap_err();
+//! assert_eq!(error.code(), 9);
+//! assert_eq!(error.to_string(), "no leaf at index 5 in a tree of 2 leaves");
+//! ```
+
+use core::fmt;
+
+/// Errors of the main tree and its proofs.
+///
+/// New variants may be added, with new codes, so matches need a wildcard arm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MerkleError {
+    /// A hash does not have the length of the tree's hashes.
+    InvalidHashLength { expected: usize, actual: usize },
+    /// A hash is not lowercase hex; `position` is the offset of the first offending byte.
+    InvalidEncoding { position: usize },
+    /// The stored hash of an inner node does not match the hash of its children. `depth` counts levels from the root and `index` positions from the left of that level.
+    HashMismatch { depth: usize, index: usize },
+    /// A node has a right child but no left child, or a leaf is not at the depth of the others.
+    MalformedTree { depth: usize, index: usize },
+    /// More leaves were given than [Limits::max_leaves](../limits/struct.Limits.html) allows.
+    TooManyLeaves { limit: usize, actual: usize },
+    /// The leaf at `index` is larger than [Limits::max_leaf_size](../limits/struct.Limits.html) allows.
+    LeafTooLarge {
+        index: usize,
+        limit: usize,
+        actual: usize,
+    },
+    /// A proof has more steps than [Limits::max_proof_len](../limits/struct.Limits.html) allows.
+    ProofTooLong { limit: usize, actual: usize },
+    /// Serialized proof bytes could not be parsed.
+    MalformedProof,
+    /// A proof was asked for a leaf the tree does not have.
+    LeafNotFound { index: usize, leaves: usize },
+    /// A well-formed proof does not lead from the leaf to the root.
+    InvalidProof,
+    /// A proof was made for a tree of `actual` leaves, not the `expected` trusted size.
+    SizeMismatch { expected: u64, actual: u64 },
+    /// A tree was built from no leaves where at least one is required.
+    EmptyTree,
+    /// Reading the leaves failed; the original `std::io::Error` is kept as its `kind` and message so the error stays comparable.
+    #[cfg(feature = "std")]
+    Io {
+        kind: std::io::ErrorKind,
+        message: std::string::String,
+    },
+}
+
+impl MerkleError {
+    /// Returns the stable code of the variant, see the [module documentation](index.html).
+    pub fn code(&self) -> u16 {
+        match self {
+            MerkleError::InvalidHashLength { .. } => 1,
+            MerkleError::InvalidEncoding { .. } => 2,
+            MerkleError::HashMismatch { .. } => 3,
+            MerkleError::MalformedTree { .. } => 4,
+            MerkleError::TooManyLeaves { .. } => 5,
+            MerkleError::LeafTooLarge { .. } => 6,
+            MerkleError::ProofTooLong { .. } => 7,
+            MerkleError::MalformedProof => 8,
+            MerkleError::LeafNotFound { .. } => 9,
+            MerkleError::InvalidProof => 10,
+            MerkleError::SizeMismatch { .. } => 11,
+            MerkleError::EmptyTree => 12,
+            #[cfg(feature = "std")]
+            MerkleError::Io { .. } => 13,
+        }
+    }
+}
+
+impl fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MerkleError::InvalidHashLength { expected, actual } => {
+                write!(f, "hash is {} bytes long, expected {}", actual, expected)
+            }
+            MerkleError::InvalidEncoding { position } => {
+                write!(f, "invalid encoding at byte {}", position)
+            }
+            MerkleError::HashMismatch { depth, index } => write!(
+                f,
+                "node {} at depth {} does not match its children",
+                index, depth
+            ),
+            MerkleError::MalformedTree { depth, index } => {
+                write!(f, "node {} at depth {} is misplaced", index, depth)
+            }
+            MerkleError::TooManyLeaves { limit, actual } => {
+                write!(f, "{} leaves exceed the limit of {}", actual, limit)
+            }
+            MerkleError::LeafTooLarge {
+                index,
+                limit,
+                actual,
+            } => write!(
+                f,
+                "leaf {} is {} bytes, over the limit of {}",
+                index, actual, limit
+            ),
+            MerkleError::ProofTooLong { limit, actual } => {
+                write!(f, "proof has {} steps, over the limit of {}", actual, limit)
+            }
+            MerkleError::MalformedProof => write!(f, "malformed proof"),
+            MerkleError::LeafNotFound { index, leaves } => write!(
+                f,
+                "no leaf at index {} in a tree of {} leaves",
+                index, leaves
+            ),
+            MerkleError::InvalidProof => write!(f, "proof does not lead to the root"),
+            MerkleError::SizeMismatch { expected, actual } => write!(
+                f,
+                "proof is for a tree of {} leaves, expected {}",
+                actual, expected
+            ),
+            MerkleError::EmptyTree => write!(f, "tree has no leaves"),
+            #[cfg(feature = "std")]
+            MerkleError::Io { message, .. } => write!(f, "I/O error: {}", message),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MerkleError {}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for MerkleError {
+    fn from(error: std::io::Error) -> MerkleError {
+        MerkleError::Io {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! C API.
+//!
+//! A stable `extern "C"` interface for linking the crate from C, C++ or any language with a C FFI. Trees and proofs are opaque handles created by [merkle_build](fn.merkle_build.html) and [merkle_proof](fn.merkle_proof.html) and released with [merkle_free](fn.merkle_free.html) and [merkle_proof_free](fn.merkle_proof_free.html). Functions that return bytes copy them into a caller buffer and return the full length, so a call with a zero-length buffer asks for the size. The declarations are in `include/rs_merkletree.h`.
+//!
+//! Requires the `ffi` feature. Build a shared or static library with
+//!
+//! ```text
+//! cargo rustc --lib --release --features ffi --crate-type cdylib
+//! cargo rustc --lib --release --features ffi --crate-type staticlib
+//! ```
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::ffi::*;
+//! let data: [&[u8]; 3] = [b"a", b"b", b"c"];
+//! let ptrs: Vec<*const u8> = data.iter().map(|leaf| leaf.as_ptr()).collect();
+//! let lens: Vec<usize> = data.iter().map(|leaf| leaf.len()).collect();
+//! unsafe {
+//!     let tree = merkle_build(ptrs.as_ptr(), lens.as_ptr(), data.len());
+//!     let mut root = [0u8; 64];
+//!     assert_eq!(merkle_root(tree, root.as_mut_ptr(), root.len()), 64);
+//!
+//!     let proof = merkle_proof(tree, 1);
+//!     assert!(merkle_verify(proof, root.as_ptr(), root.len(), b"b".as_ptr(), 1));
+//!     merkle_proof_free(proof);
+//!     merkle_free(tree);
+//! }
+//! ```
+
+use crate::proof::MerkleProof;
+use crate::{Hash, MerkleTree};
+use std::{ptr, slice};
+
+/// Builds a tree over `count` leaves; leaf `i` is the UTF-8 string of `lens[i]` bytes at `leaves[i]`.
+///
+/// Returns null if `count` is zero or a leaf is not valid UTF-8.
+///
+/// # Safety
+///
+/// `leaves` and `lens` must point to `count` elements, and every `leaves[i]` to `lens[i]` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn merkle_build(
+    leaves: *const *const u8,
+    lens: *const usize,
+    count: usize,
+) -> *mut MerkleTree {
+    if count == 0 || leaves.is_null() || lens.is_null() {
+        return ptr::null_mut();
+    }
+    let leaves = slice::from_raw_parts(leaves, count);
+    let lens = slice::from_raw_parts(lens, count);
+    let mut data = Vec::with_capacity(count);
+    for (leaf, len) in leaves.iter().zip(lens) {
+        match std::str::from_utf8(bytes(*leaf, *len)) {
+            Ok(leaf) => data.push(leaf),
+            Err(_) => return ptr::null_mut(),
+        }
+    }
+    let mut tree = MerkleTree::new(None);
+    tree.build_tree(data);
+    Box::into_raw(Box::new(tree))
+}
+
+/// Releases a tree returned by [merkle_build](fn.merkle_build.html). Null is ignored.
+///
+/// # Safety
+///
+/// `tree` must be null or a live handle from [merkle_build](fn.merkle_build.html).
+#[no_mangle]
+pub unsafe extern "C" fn merkle_free(tree: *mut MerkleTree) {
+    if !tree.is_null() {
+        drop(Box::from_raw(tree));
+    }
+}
+
+/// Copies the hex root into `out` if it fits in `out_len` bytes and returns its length (64).
+///
+/// # Safety
+///
+/// `tree` must be a live tree handle and `out` must point to `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn merkle_root(
+    tree: *const MerkleTree,
+    out: *mut u8,
+    out_len: usize,
+) -> usize {
+    match tree.as_ref().and_then(MerkleTree::root_node) {
+        Some(root) => copy_out(&root.hash().to_hex_bytes(), out, out_len),
+        None => 0,
+    }
+}
+
+/// Returns the proof for the leaf at `index`, or null if there is no such leaf.
+///
+/// # Safety
+///
+/// `tree` must be a live tree handle.
+#[no_mangle]
+pub unsafe extern "C" fn merkle_proof(tree: *const MerkleTree, index: usize) -> *mut MerkleProof {
+    match tree.as_ref().and_then(|tree| tree.proof(index)) {
+        Some(proof) => Box::into_raw(Box::new(proof)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Releases a proof. Null is ignored.
+///
+/// # Safety
+///
+/// `proof` must be null or a live handle from [merkle_proof](fn.merkle_proof.html) or [merkle_proof_parse](fn.merkle_proof_parse.html).
+#[no_mangle]
+pub unsafe extern "C" fn merkle_proof_free(proof: *mut MerkleProof) {
+    if !proof.is_null() {
+        drop(Box::from_raw(proof));
+    }
+}
+
+/// Copies the serialized proof ([MerkleProof::to_bytes](../proof/struct.MerkleProof.html#method.to_bytes)) into `out` if it fits in `out_len` bytes and returns its length.
+///
+/// # Safety
+///
+/// `proof` must be a live proof handle and `out` must point to `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn merkle_proof_serialize(
+    proof: *const MerkleProof,
+    out: *mut u8,
+    out_len: usize,
+) -> usize {
+    match proof.as_ref() {
+        Some(proof) => copy_out(&proof.to_bytes(), out, out_len),
+        None => 0,
+    }
+}
+
+/// Parses a serialized proof, returning null if it is malformed.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn merkle_proof_parse(data: *const u8, len: usize) -> *mut MerkleProof {
+    match MerkleProof::from_bytes(bytes(data, len)) {
+        Some(proof) => Box::into_raw(Box::new(proof)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Returns `true` if `proof` shows that the leaf with data `leaf` is included in the tree with hex root `root`.
+///
+/// # Safety
+///
+/// `proof` must be a live proof handle, `root` must point to `root_len` readable bytes and `leaf` to `leaf_len`.
+#[no_mangle]
+pub unsafe extern "C" fn merkle_verify(
+    proof: *const MerkleProof,
+    root: *const u8,
+    root_len: usize,
+    leaf: *const u8,
+    leaf_len: usize,
+) -> bool {
+    let Ok(root) = Hash::from_hex_bytes(bytes(root, root_len)) else {
+        return false;
+    };
+    proof
+        .as_ref()
+        .is_some_and(|proof| proof.verify_data(&root, bytes(leaf, leaf_len)))
+}
+
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len)
+    }
+}
+
+unsafe fn copy_out(data: &[u8], out: *mut u8, out_len: usize) -> usize {
+    if !out.is_null() && data.len() <= out_len {
+        ptr::copy_nonoverlapping(data.as_ptr(), out, data.len());
+    }
+    data.len()
+}
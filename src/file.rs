@@ -0,0 +1,432 @@
+//! Merkle trees over file contents.
+//!
+//! A [FileTree](struct.FileTree.html) reads a file (or any [Read](https://doc.rust-lang.org/std/io/trait.Read.html)), splits it into fixed-size chunks and commits to the chunk hashes. A downloader that knows only the root can then accept chunks from untrusted peers one at a time, checking each against its [ChunkProof](struct.ChunkProof.html) before writing it to disk.
+//!
+//! Chunks are hashed and combined as in the [history](../history/index.html) module (RFC 6962), so the tree has no duplicated nodes and the last chunk may be short.
+//!
+//! When only the root is needed, a [FileHasher](struct.FileHasher.html) computes it in constant memory and can export its state mid-stream, so that hashing a very large file can be resumed after an interruption instead of restarting from the first byte.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::file::FileTree;
+//! let contents = vec![7u8; 2500];
+//! let tree = FileTree::from_reader(&contents[..], 1024).unwrap();
+//! assert_eq!(tree.chunk_count(), 3);
+//!
+//! let root = tree.root();
+//! for (proof, chunk) in tree.proofs().iter().zip(contents.chunks(1024)) {
+//!     assert!(proof.verify(&root, chunk));
+//! }
+//! ```
+
+use crate::cdc::FastCdc;
+use crate::hashing::sha256;
+use crate::history::{leaf_hash, node_hash, verify_inclusion, HistoryTree};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, ErrorKind, Read};
+use std::ops::Range;
+use std::path::Path;
+
+/// Chunk size used by [FileTree::open](struct.FileTree.html#method.open) callers that have no preference.
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// [FileTree](struct.FileTree.html) is the tree built over the chunks of one file.
+///
+/// * `chunk_size`: Size of every chunk but the last, or the maximum chunk size for content-defined chunks.
+///
+/// * `len`: Total number of bytes read.
+///
+/// * `offsets`: Offset of the first byte of every chunk.
+///
+/// * `tree`: Tree over the chunk hashes.
+#[derive(Debug, Clone)]
+pub struct FileTree {
+    chunk_size: usize,
+    len: u64,
+    offsets: Vec<u64>,
+    tree: HistoryTree,
+}
+
+/// [ChunkProof](struct.ChunkProof.html) proves that a chunk is chunk `index` of a file with `chunk_count` chunks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkProof {
+    pub index: u64,
+    pub chunk_count: u64,
+    pub path: Vec<Vec<u8>>,
+}
+
+/// [FileHasher](struct.FileHasher.html) computes the same root as [FileTree](struct.FileTree.html) from data fed in pieces, keeping only one hash per level.
+///
+/// * `chunk_size`: Size of every chunk but the last.
+///
+/// * `len`: Number of bytes fed so far.
+///
+/// * `peaks`: Roots of the complete subtrees over the full chunks so far, largest first.
+///
+/// * `buffer`: Bytes of the chunk in progress.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileHasher {
+    chunk_size: usize,
+    len: u64,
+    peaks: Vec<Vec<u8>>,
+    buffer: Vec<u8>,
+}
+
+/// Leading bytes of a serialized [FileHasher](struct.FileHasher.html) state.
+const STATE_MAGIC: &[u8; 8] = b"RSMTFH01";
+
+impl FileTree {
+    /// Function to create a [FileTree](struct.FileTree.html) from everything `reader` yields, in chunks of `chunk_size` bytes.
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn from_reader<R: Read>(mut reader: R, chunk_size: usize) -> io::Result<FileTree> {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        let mut file = FileTree::empty(chunk_size);
+        let mut buffer = vec![0u8; chunk_size];
+        loop {
+            let filled = read_chunk(&mut reader, &mut buffer)?;
+            if filled == 0 {
+                break;
+            }
+            file.push(&buffer[..filled]);
+            if filled < chunk_size {
+                break;
+            }
+        }
+        Ok(file)
+    }
+
+    /// Function to create a [FileTree](struct.FileTree.html) from everything `reader` yields, split into content-defined chunks by `cdc`.
+    pub fn from_reader_cdc<R: Read>(mut reader: R, cdc: &FastCdc) -> io::Result<FileTree> {
+        let mut file = FileTree::empty(cdc.max_size());
+        let mut buffer = Vec::with_capacity(cdc.max_size());
+        let mut eof = false;
+        loop {
+            // The chunker needs a full window of `max_size` bytes unless the input has ended.
+            while !eof && buffer.len() < cdc.max_size() {
+                let start = buffer.len();
+                buffer.resize(cdc.max_size(), 0);
+                match reader.read(&mut buffer[start..]) {
+                    Ok(n) => {
+                        buffer.truncate(start + n);
+                        eof = n == 0;
+                    }
+                    Err(error) if error.kind() == ErrorKind::Interrupted => buffer.truncate(start),
+                    Err(error) => return Err(error),
+                }
+            }
+            if buffer.is_empty() {
+                break;
+            }
+            let cut = cdc.cut(&buffer);
+            file.push(&buffer[..cut]);
+            buffer.drain(..cut);
+        }
+        Ok(file)
+    }
+
+    fn empty(chunk_size: usize) -> FileTree {
+        FileTree {
+            chunk_size,
+            len: 0,
+            offsets: Vec::new(),
+            tree: HistoryTree::new(),
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        self.offsets.push(self.len);
+        self.tree.append(chunk);
+        self.len += chunk.len() as u64;
+    }
+
+    /// Function to create a [FileTree](struct.FileTree.html) from the file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P, chunk_size: usize) -> io::Result<FileTree> {
+        FileTree::from_reader(File::open(path)?, chunk_size)
+    }
+
+    /// Returns the Root Hash. An empty file has the root `SHA256("")`.
+    pub fn root(&self) -> Vec<u8> {
+        self.tree.root()
+    }
+
+    /// Returns the chunk size the tree was built with; for content-defined chunks, the maximum chunk size.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the number of bytes in the file.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns true if the file was empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of chunks.
+    pub fn chunk_count(&self) -> u64 {
+        self.tree.len()
+    }
+
+    /// Returns the hash of chunk `index`, if it exists.
+    pub fn chunk_hash(&self, index: u64) -> Option<Vec<u8>> {
+        self.tree.leaf_hash(index)
+    }
+
+    /// Returns the byte range of chunk `index` within the file, if it exists.
+    pub fn chunk_range(&self, index: u64) -> Option<Range<u64>> {
+        let start = *self.offsets.get(index as usize)?;
+        let end = self
+            .offsets
+            .get(index as usize + 1)
+            .copied()
+            .unwrap_or(self.len);
+        Some(start..end)
+    }
+
+    /// Returns the indices of the chunks of this file whose contents do not appear anywhere in `other`: the chunks a holder of `other` has to fetch to rebuild this file.
+    pub fn changed_chunks(&self, other: &FileTree) -> Vec<u64> {
+        let known: HashSet<Vec<u8>> = (0..other.chunk_count())
+            .filter_map(|index| other.chunk_hash(index))
+            .collect();
+        (0..self.chunk_count())
+            .filter(|index| {
+                self.chunk_hash(*index)
+                    .is_some_and(|hash| !known.contains(&hash))
+            })
+            .collect()
+    }
+
+    /// Returns the proof for chunk `index`, if it exists.
+    pub fn prove(&self, index: u64) -> Option<ChunkProof> {
+        let proof = self.tree.membership_proof(index, self.chunk_count())?;
+        Some(ChunkProof {
+            index,
+            chunk_count: proof.size,
+            path: proof.path,
+        })
+    }
+
+    /// Returns the proofs of every chunk, in order.
+    pub fn proofs(&self) -> Vec<ChunkProof> {
+        (0..self.chunk_count())
+            .filter_map(|index| self.prove(index))
+            .collect()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl FileTree {
+    /// Function to create a [FileTree](struct.FileTree.html) from an asynchronous reader, such as an upload being proxied, in chunks of `chunk_size` bytes.
+    ///
+    /// Each chunk is hashed as soon as it is complete, so the task only holds one chunk at a time. Requires the `tokio` feature.
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub async fn from_async_reader<R>(mut reader: R, chunk_size: usize) -> io::Result<FileTree>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        use tokio::io::AsyncReadExt;
+
+        assert!(chunk_size > 0, "chunk size must be positive");
+        let mut file = FileTree::empty(chunk_size);
+        let mut buffer = vec![0u8; chunk_size];
+        let mut filled = 0;
+        loop {
+            let n = reader.read(&mut buffer[filled..]).await?;
+            filled += n;
+            if filled == chunk_size || (n == 0 && filled > 0) {
+                file.push(&buffer[..filled]);
+                filled = 0;
+            }
+            if n == 0 {
+                return Ok(file);
+            }
+        }
+    }
+
+    /// Function to create a [FileTree](struct.FileTree.html) from a stream of byte buffers of any size, regrouped into chunks of `chunk_size` bytes.
+    ///
+    /// Requires the `tokio` feature.
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub async fn from_stream<S, B>(mut stream: S, chunk_size: usize) -> io::Result<FileTree>
+    where
+        S: futures_core::Stream<Item = io::Result<B>> + Unpin,
+        B: AsRef<[u8]>,
+    {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        let mut file = FileTree::empty(chunk_size);
+        let mut buffer = Vec::with_capacity(chunk_size);
+        while let Some(item) =
+            std::future::poll_fn(|cx| std::pin::Pin::new(&mut stream).poll_next(cx)).await
+        {
+            let item = item?;
+            let mut data = item.as_ref();
+            while !data.is_empty() {
+                let take = (chunk_size - buffer.len()).min(data.len());
+                buffer.extend_from_slice(&data[..take]);
+                data = &data[take..];
+                if buffer.len() == chunk_size {
+                    file.push(&buffer);
+                    buffer.clear();
+                }
+            }
+        }
+        if !buffer.is_empty() {
+            file.push(&buffer);
+        }
+        Ok(file)
+    }
+}
+
+impl ChunkProof {
+    /// Checks that `chunk` is chunk `index` of the file with Root Hash `root`.
+    pub fn verify(&self, root: &[u8], chunk: &[u8]) -> bool {
+        verify_inclusion(
+            &leaf_hash(chunk),
+            self.index,
+            self.chunk_count,
+            &self.path,
+            root,
+        )
+    }
+}
+
+impl FileHasher {
+    /// Function to create a [FileHasher](struct.FileHasher.html) for chunks of `chunk_size` bytes.
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn new(chunk_size: usize) -> FileHasher {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        FileHasher {
+            chunk_size,
+            len: 0,
+            peaks: Vec::new(),
+            buffer: Vec::with_capacity(chunk_size),
+        }
+    }
+
+    /// Returns the number of bytes fed so far, i.e. the offset to resume reading the file from.
+    pub fn position(&self) -> u64 {
+        self.len
+    }
+
+    /// Feeds the next `data` of the file.
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // A full chunk is only hashed once more data shows it is not the last one.
+            if self.buffer.len() == self.chunk_size {
+                self.push_chunk();
+            }
+            let take = (self.chunk_size - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..take]);
+            self.len += take as u64;
+            data = &data[take..];
+        }
+    }
+
+    /// Feeds everything `reader` yields.
+    pub fn update_reader<R: Read>(&mut self, mut reader: R) -> io::Result<()> {
+        let mut buffer = vec![0u8; self.chunk_size];
+        loop {
+            let filled = read_chunk(&mut reader, &mut buffer)?;
+            if filled == 0 {
+                return Ok(());
+            }
+            self.update(&buffer[..filled]);
+        }
+    }
+
+    /// Returns the Root Hash of everything fed so far, equal to [FileTree::root](struct.FileTree.html#method.root) over the same data.
+    pub fn finalize(&self) -> Vec<u8> {
+        if self.len == 0 {
+            return sha256(b"");
+        }
+        let mut peaks = self.peaks.clone();
+        if !self.buffer.is_empty() {
+            peaks.push(leaf_hash(&self.buffer));
+        }
+        let mut root = peaks.pop().expect("non-empty input has a peak");
+        while let Some(peak) = peaks.pop() {
+            root = node_hash(&peak, &root);
+        }
+        root
+    }
+
+    /// Serializes the hasher so that hashing can continue later with [resume](struct.FileHasher.html#method.resume).
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut state = STATE_MAGIC.to_vec();
+        state.extend_from_slice(&(self.chunk_size as u64).to_be_bytes());
+        state.extend_from_slice(&self.len.to_be_bytes());
+        state.extend_from_slice(&(self.peaks.len() as u64).to_be_bytes());
+        for peak in &self.peaks {
+            state.extend_from_slice(peak);
+        }
+        state.extend_from_slice(&self.buffer);
+        state
+    }
+
+    /// Function to create a [FileHasher](struct.FileHasher.html) from the output of [save_state](struct.FileHasher.html#method.save_state), returning `None` if the state is malformed.
+    pub fn resume(state: &[u8]) -> Option<FileHasher> {
+        let rest = state.strip_prefix(STATE_MAGIC)?;
+        let (chunk_size, rest) = read_u64(rest)?;
+        let (len, rest) = read_u64(rest)?;
+        let (peak_count, rest) = read_u64(rest)?;
+        if chunk_size == 0 || peak_count > 64 || rest.len() < peak_count as usize * 32 {
+            return None;
+        }
+        let (peaks, buffer) = rest.split_at(peak_count as usize * 32);
+        let chunk_size = usize::try_from(chunk_size).ok()?;
+        // The peaks are the set bits of the number of hashed chunks.
+        let hashed = len.checked_sub(buffer.len() as u64)?;
+        let consistent = buffer.len() <= chunk_size
+            && (len == 0 || !buffer.is_empty())
+            && hashed.is_multiple_of(chunk_size as u64)
+            && (hashed / chunk_size as u64).count_ones() as u64 == peak_count;
+        if !consistent {
+            return None;
+        }
+        Some(FileHasher {
+            chunk_size,
+            len,
+            peaks: peaks.chunks(32).map(|peak| peak.to_vec()).collect(),
+            buffer: buffer.to_vec(),
+        })
+    }
+
+    /// Hashes the buffered chunk and merges equal-sized subtrees.
+    fn push_chunk(&mut self) {
+        let hashed = (self.len - self.buffer.len() as u64) / self.chunk_size as u64;
+        // `hashed` counts the chunks before this one; each trailing one bit is a subtree it completes.
+        let mut node = leaf_hash(&self.buffer);
+        for _ in 0..hashed.trailing_ones() {
+            let left = self.peaks.pop().expect("one peak per set bit");
+            node = node_hash(&left, &node);
+        }
+        self.peaks.push(node);
+        self.buffer.clear();
+    }
+}
+
+fn read_u64(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let (head, rest) = bytes.split_first_chunk::<8>()?;
+    Some((u64::from_be_bytes(*head), rest))
+}
+
+/// Reads until `buffer` is full or the reader is exhausted, returning the number of bytes read.
+fn read_chunk<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(filled)
+}
@@ -0,0 +1,126 @@
+//! Shard-and-combine construction of a [MerkleTree](../struct.MerkleTree.html).
+//!
+//! Large datasets can be split into shards of `2^shard_height` consecutive leaves, each hashed independently (on different threads or machines). Only the resulting [Shard](struct.Shard.html) summaries — index, leaf count and root — need to be exchanged; [ForestBuilder::combine](struct.ForestBuilder.html#method.combine) then deterministically produces the same Root Hash a monolithic [build_tree](../struct.MerkleTree.html#method.build_tree) over all of the data would.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::forest::ForestBuilder;
+//! use rs_merkletree::MerkleTree;
+//! let data: Vec<&str> = vec!["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"];
+//!
+//! let builder = ForestBuilder::new(2);
+//! let shards: Vec<_> = data
+//!     .chunks(builder.shard_size())
+//!     .enumerate()
+//!     .map(|(index, chunk)| builder.build_shard(index, chunk))
+//!     .collect();
+//! let root = builder.combine(&shards).unwrap();
+//!
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(data);
+//! assert_eq!(root, tree.root_node().unwrap().hash());
+//! ```
+
+use crate::proof::{leaf_hash, node_hash};
+use crate::Hash;
+
+/// [Shard](struct.Shard.html) summarizes one independently built part of the tree.
+///
+/// * `index`: Position of the shard; shard `i` covers leaves `i * shard_size .. (i + 1) * shard_size`.
+///
+/// * `leaf_count`: Number of leaves in the shard. Only the last shard may be partial.
+///
+/// * `root`: Root of the shard's own tree, in the [MerkleTree](../struct.MerkleTree.html) hash format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shard {
+    pub index: usize,
+    pub leaf_count: usize,
+    pub root: Hash,
+}
+
+/// [ForestBuilder](struct.ForestBuilder.html) fixes the shard size shared by every participant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForestBuilder {
+    shard_height: u32,
+}
+
+impl ForestBuilder {
+    /// Function to create a [ForestBuilder](struct.ForestBuilder.html) for shards of `2^shard_height` leaves.
+    ///
+    /// Panics if `shard_height` is 0 or greater than 32.
+    pub fn new(shard_height: u32) -> ForestBuilder {
+        assert!(
+            shard_height > 0 && shard_height <= 32,
+            "shard height must be between 1 and 32"
+        );
+        ForestBuilder { shard_height }
+    }
+
+    /// Returns the number of leaves in a full shard.
+    pub fn shard_size(&self) -> usize {
+        1 << self.shard_height
+    }
+
+    /// Builds shard `index` from its leaves.
+    ///
+    /// Panics if `data` is empty or holds more than [shard_size](struct.ForestBuilder.html#method.shard_size) leaves.
+    pub fn build_shard(&self, index: usize, data: &[&str]) -> Shard {
+        assert!(
+            !data.is_empty() && data.len() <= self.shard_size(),
+            "invalid shard length"
+        );
+        let mut layer = upper_layer(data.iter().map(|item| leaf_hash(item.as_bytes())).collect());
+        while layer.len() > 1 {
+            layer = upper_layer(layer);
+        }
+        Shard {
+            index,
+            leaf_count: data.len(),
+            root: layer.remove(0),
+        }
+    }
+
+    /// Combines shard summaries into the Root Hash of the whole tree.
+    ///
+    /// The shards may be given in any order, but must be contiguous from index 0 with only the last one partial. Returns `None` otherwise.
+    pub fn combine(&self, shards: &[Shard]) -> Option<Hash> {
+        let mut shards: Vec<&Shard> = shards.iter().collect();
+        shards.sort_by_key(|shard| shard.index);
+        let last = shards.len().checked_sub(1)?;
+        for (position, shard) in shards.iter().enumerate() {
+            let full = shard.leaf_count == self.shard_size();
+            if shard.index != position
+                || shard.leaf_count == 0
+                || (position != last && !full)
+                || shard.leaf_count > self.shard_size()
+            {
+                return None;
+            }
+        }
+        if shards.len() == 1 {
+            return Some(shards[0].root);
+        }
+
+        // A partial last shard is the rightmost subtree, so the full build keeps pairing its root with itself up to the shard height.
+        let mut roots: Vec<Hash> = shards.iter().map(|shard| shard.root).collect();
+        let tail = shards[last].leaf_count;
+        let natural_height = usize::BITS - (tail - 1).leading_zeros();
+        for _ in natural_height.max(1)..self.shard_height {
+            roots[last] = node_hash(&roots[last], &roots[last]);
+        }
+
+        let mut layer = roots;
+        while layer.len() > 1 {
+            layer = upper_layer(layer);
+        }
+        layer.pop()
+    }
+}
+
+/// Hashes one layer into the next, pairing a lone last node with itself as [MerkleTree](../struct.MerkleTree.html) does.
+fn upper_layer(layer: Vec<Hash>) -> Vec<Hash> {
+    layer
+        .chunks(2)
+        .map(|pair| node_hash(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
@@ -0,0 +1,56 @@
+//! Fuzzing support.
+//!
+//! With the `arbitrary` feature, [TreeConfig](../config/struct.TreeConfig.html), [MerkleProof](../proof/struct.MerkleProof.html), [SizedProof](../proof/struct.SizedProof.html) and their parts implement [arbitrary::Arbitrary], so projects can fuzz their own code that consumes them with `cargo fuzz`. [LeafSet](struct.LeafSet.html) provides the leaves of a tree in the same way.
+//!
+//! Generated proofs are arbitrary bytes, mostly invalid, which is what a verifier exposed to untrusted input sees. To fuzz with proofs that verify, build a tree from a [LeafSet](struct.LeafSet.html) and take its proofs.
+//!
+//! # Examples
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use rs_merkletree::fuzz::LeafSet;
+//! let input = [3u8, 1, b'a', 1, b'b', 0, 7, 7, 7];
+//! let leaves = LeafSet::arbitrary(&mut Unstructured::new(&input)).unwrap();
+//! let tree = leaves.tree();
+//! for index in 0..leaves.leaves.len() {
+//!     let proof = tree.proof(index).unwrap();
+//!     assert!(proof.verify_data(&tree.root_node().unwrap().hash(), leaves.leaves[index].as_bytes()));
+//! }
+//! ```
+
+use crate::MerkleTree;
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Most leaves in a generated [LeafSet](struct.LeafSet.html), so fuzz iterations stay fast.
+pub const MAX_FUZZ_LEAVES: usize = 1024;
+
+/// [LeafSet](struct.LeafSet.html) is a non-empty list of leaves for building a [MerkleTree](../struct.MerkleTree.html).
+///
+/// * `leaves`: Leaf data, between 1 and [MAX_FUZZ_LEAVES](constant.MAX_FUZZ_LEAVES.html) items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeafSet {
+    pub leaves: Vec<String>,
+}
+
+impl LeafSet {
+    /// Builds a [MerkleTree](../struct.MerkleTree.html) over the leaves.
+    pub fn tree(&self) -> MerkleTree {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(self.leaves.iter().map(String::as_str).collect());
+        tree
+    }
+}
+
+impl<'a> Arbitrary<'a> for LeafSet {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<LeafSet> {
+        let count = u.int_in_range(1..=MAX_FUZZ_LEAVES)?;
+        let mut leaves = Vec::with_capacity(count);
+        for _ in 0..count {
+            leaves.push(String::arbitrary(u)?);
+        }
+        Ok(LeafSet { leaves })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, None)
+    }
+}
@@ -0,0 +1,169 @@
+//! Git object hashing.
+//!
+//! Git addresses every file by the hash of a `blob` object and every directory by the hash of a `tree` object listing its entries' modes, names and object ids. Reproducing these ids lets a directory hashed by this crate be compared with a Git commit's tree (`git rev-parse HEAD^{tree}`) or its files with `git ls-tree -r HEAD`, in either SHA-1 or SHA-256 repositories.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::git::{blob_id, tree_id, GitHash, TreeEntry, FILE_MODE};
+//! let blob = blob_id(GitHash::Sha1, b"hello world\n");
+//! assert_eq!(
+//!     rs_merkletree::git::to_hex(&blob),
+//!     "3b18e512dba79e4c8300dd08aeb37f8e728b8dad"
+//! );
+//! let tree = tree_id(GitHash::Sha1, &[TreeEntry::new(FILE_MODE, "hello.txt", blob)]);
+//! assert_eq!(tree.len(), 20);
+//! ```
+
+use crate::hashing::sha256_concat;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Mode of a regular file.
+pub const FILE_MODE: &str = "100644";
+/// Mode of an executable file.
+pub const EXECUTABLE_MODE: &str = "100755";
+/// Mode of a symbolic link.
+pub const SYMLINK_MODE: &str = "120000";
+/// Mode of a subdirectory.
+pub const TREE_MODE: &str = "40000";
+
+/// Object hash of the repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitHash {
+    /// SHA-1, the default object format.
+    Sha1,
+    /// SHA-256, for repositories created with `--object-format=sha256`.
+    Sha256,
+}
+
+/// [TreeEntry](struct.TreeEntry.html) is one line of a tree object.
+///
+/// * `mode`: Octal mode without leading zeros, such as [FILE_MODE](constant.FILE_MODE.html).
+///
+/// * `name`: File or directory name.
+///
+/// * `id`: Raw object id of the blob or subtree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntry {
+    pub mode: String,
+    pub name: String,
+    pub id: Vec<u8>,
+}
+
+impl GitHash {
+    fn digest(&self, parts: &[&[u8]]) -> Vec<u8> {
+        match self {
+            GitHash::Sha1 => {
+                let mut hasher = Sha1::new();
+                for part in parts {
+                    hasher.update(part);
+                }
+                hasher.finalize().to_vec()
+            }
+            GitHash::Sha256 => sha256_concat(parts),
+        }
+    }
+}
+
+impl TreeEntry {
+    /// Function to create a [TreeEntry](struct.TreeEntry.html)
+    pub fn new(mode: &str, name: &str, id: Vec<u8>) -> TreeEntry {
+        TreeEntry {
+            mode: mode.to_string(),
+            name: name.to_string(),
+            id,
+        }
+    }
+
+    /// Git orders entries by name, comparing directories as if their name ended in `/`.
+    fn sort_key(&self) -> Vec<u8> {
+        let mut key = self.name.as_bytes().to_vec();
+        if self.mode == TREE_MODE {
+            key.push(b'/');
+        }
+        key
+    }
+}
+
+/// Returns the id of an object of type `kind` ("blob", "tree", ...) with the given content: the hash of `"<kind> <len>\0" || content`.
+pub fn object_id(hash: GitHash, kind: &str, content: &[u8]) -> Vec<u8> {
+    let header = format!("{} {}\0", kind, content.len());
+    hash.digest(&[header.as_bytes(), content])
+}
+
+/// Returns the blob id of a file's contents, as `git hash-object` prints it (in raw bytes).
+pub fn blob_id(hash: GitHash, content: &[u8]) -> Vec<u8> {
+    object_id(hash, "blob", content)
+}
+
+/// Returns the id of the tree object listing `entries`, which may be given in any order.
+pub fn tree_id(hash: GitHash, entries: &[TreeEntry]) -> Vec<u8> {
+    let mut entries: Vec<&TreeEntry> = entries.iter().collect();
+    entries.sort_by_key(|entry| entry.sort_key());
+    let mut content = Vec::new();
+    for entry in entries {
+        content.extend_from_slice(entry.mode.as_bytes());
+        content.push(b' ');
+        content.extend_from_slice(entry.name.as_bytes());
+        content.push(0);
+        content.extend_from_slice(&entry.id);
+    }
+    object_id(hash, "tree", &content)
+}
+
+/// Returns the tree id of the directory `dir`, as Git would record it for a commit of its contents.
+///
+/// `.git` directories are skipped and empty directories omitted, since Git does not track them. Returns `None` for a directory with no files.
+pub fn dir_tree_id<P: AsRef<Path>>(hash: GitHash, dir: P) -> io::Result<Option<Vec<u8>>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if name == ".git" {
+                continue;
+            }
+            if let Some(id) = dir_tree_id(hash, entry.path())? {
+                entries.push(TreeEntry::new(TREE_MODE, &name, id));
+            }
+        } else if file_type.is_symlink() {
+            let target = fs::read_link(entry.path())?;
+            let id = blob_id(hash, target.to_string_lossy().as_bytes());
+            entries.push(TreeEntry::new(SYMLINK_MODE, &name, id));
+        } else if file_type.is_file() {
+            let mode = if is_executable(&entry.metadata()?) {
+                EXECUTABLE_MODE
+            } else {
+                FILE_MODE
+            };
+            entries.push(TreeEntry::new(
+                mode,
+                &name,
+                blob_id(hash, &fs::read(entry.path())?),
+            ));
+        }
+    }
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(tree_id(hash, &entries)))
+}
+
+/// Formats an object id as Git prints it.
+pub fn to_hex(id: &[u8]) -> String {
+    crate::hashing::to_hex(id)
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_: &fs::Metadata) -> bool {
+    false
+}
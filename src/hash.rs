@@ -0,0 +1,182 @@
+//! Typed hashes.
+//!
+//! Hashes used to be passed around as byte slices holding 64 lowercase hex digits, which is easy to confuse with the 32 byte digest they encode, or with a hash of the wrong length. [Hash](struct.Hash.html) is the digest itself: always 32 bytes, displayed and parsed as the lowercase hex the tree hashes, and compared in constant time. [Node](../struct.Node.html), [MerkleTree](../struct.MerkleTree.html) and the [proofs](../proof/index.html) store, take and return it, so a hash of the wrong length or encoding is rejected once, where it is parsed, and cannot reach them. [from_hex_bytes](struct.Hash.html#method.from_hex_bytes) and [to_hex_bytes](struct.Hash.html#method.to_hex_bytes) convert to and from the hex form parents are hashed over and proofs are serialized with, and [to_hex](struct.Hash.html#method.to_hex) and [to_base64](struct.Hash.html#method.to_base64) to text.
+//!
+//! Like [proof](../proof/index.html), this module builds without `std`.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::{Hash, MerkleTree};
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+//! let root: Hash = tree.root().unwrap();
+//! assert_eq!(
+//!     root.to_string(),
+//!     "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
+//! );
+//! assert_eq!(root.as_ref().len(), 32);
+//!
+//! let leaf = Hash::leaf(b"Rust");
+//! assert!(tree.proof(3).unwrap().verify(&root, &leaf));
+//! assert_eq!("a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb".parse(), Ok(root));
+//! ```
+
+use crate::codec;
+use crate::error::MerkleError;
+use crate::proof::{leaf_hash, legacy_leaf_hash, validate_hash, HASH_LEN};
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+use subtle::ConstantTimeEq;
+
+/// Length of a digest in bytes.
+pub const DIGEST_LEN: usize = 32;
+
+/// [Hash](struct.Hash.html) is a SHA-256 digest of the tree.
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Hash([u8; DIGEST_LEN]);
+
+impl Hash {
+    /// Function to create a [Hash](struct.Hash.html) from a raw digest.
+    pub const fn new(digest: [u8; DIGEST_LEN]) -> Hash {
+        Hash(digest)
+    }
+
+    /// Returns the leaf hash of `data` in a [MerkleTree](../struct.MerkleTree.html), see [leaf_hash](../proof/fn.leaf_hash.html).
+    pub fn leaf(data: &[u8]) -> Hash {
+        leaf_hash(data)
+    }
+
+    /// Returns the leaf hash of `data` in a legacy [MerkleTree](../struct.MerkleTree.html), see [legacy_leaf_hash](../proof/fn.legacy_leaf_hash.html).
+    pub fn legacy_leaf(data: &[u8]) -> Hash {
+        legacy_leaf_hash(data)
+    }
+
+    /// Parses a hash in the form the tree stores: [HASH_LEN](../proof/constant.HASH_LEN.html) lowercase hex digits as bytes.
+    pub fn from_hex_bytes(hex: &[u8]) -> Result<Hash, MerkleError> {
+        validate_hash(hex)?;
+        let mut hex_digits = [0u8; HASH_LEN];
+        hex_digits.copy_from_slice(hex);
+        Ok(Hash::decode(&hex_digits))
+    }
+
+    /// Returns the hash in the form the tree stores: [HASH_LEN](../proof/constant.HASH_LEN.html) lowercase hex digits as bytes.
+    pub fn to_hex_bytes(&self) -> [u8; HASH_LEN] {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let mut out = [0u8; HASH_LEN];
+        for (i, byte) in self.0.iter().enumerate() {
+            out[2 * i] = DIGITS[(byte >> 4) as usize];
+            out[2 * i + 1] = DIGITS[(byte & 0xf) as usize];
+        }
+        out
+    }
+
+    /// Parses [HASH_LEN](../proof/constant.HASH_LEN.html) lowercase hex digits, the form [Display](struct.Hash.html#impl-Display-for-Hash) writes.
+    pub fn from_hex(hex: &str) -> Result<Hash, MerkleError> {
+        Hash::from_hex_bytes(hex.as_bytes())
+    }
+
+    /// Returns the hash as lowercase hex, the form the tree stores.
+    pub fn to_hex(&self) -> String {
+        codec::to_hex(&self.0)
+    }
+
+    /// Parses the base64 of the raw digest, see [to_base64](struct.Hash.html#method.to_base64).
+    pub fn from_base64(text: &str) -> Result<Hash, MerkleError> {
+        Hash::try_from(codec::from_base64(text)?.as_slice())
+    }
+
+    /// Returns the raw digest as standard, padded base64.
+    pub fn to_base64(&self) -> String {
+        codec::to_base64(&self.0)
+    }
+
+    /// Returns the raw digest.
+    pub fn as_bytes(&self) -> &[u8; DIGEST_LEN] {
+        &self.0
+    }
+
+    /// Decodes hex digits already checked by [validate_hash](../proof/fn.validate_hash.html), or computed by a hex hasher.
+    pub(crate) fn decode(hex: &[u8; HASH_LEN]) -> Hash {
+        let digit = |byte: u8| match byte {
+            b'0'..=b'9' => byte - b'0',
+            _ => byte - b'a' + 10,
+        };
+        let mut digest = [0u8; DIGEST_LEN];
+        for (byte, pair) in digest.iter_mut().zip(hex.chunks_exact(2)) {
+            *byte = digit(pair[0]) << 4 | digit(pair[1]);
+        }
+        Hash(digest)
+    }
+}
+
+impl From<[u8; DIGEST_LEN]> for Hash {
+    fn from(digest: [u8; DIGEST_LEN]) -> Hash {
+        Hash(digest)
+    }
+}
+
+impl TryFrom<&[u8]> for Hash {
+    type Error = MerkleError;
+
+    /// Takes a raw digest of [DIGEST_LEN](constant.DIGEST_LEN.html) bytes; use [from_hex_bytes](struct.Hash.html#method.from_hex_bytes) for the hex form.
+    fn try_from(digest: &[u8]) -> Result<Hash, MerkleError> {
+        <[u8; DIGEST_LEN]>::try_from(digest)
+            .map(Hash)
+            .map_err(|_| MerkleError::InvalidHashLength {
+                expected: DIGEST_LEN,
+                actual: digest.len(),
+            })
+    }
+}
+
+impl AsRef<[u8]> for Hash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Compares in constant time, so comparing against a secret hash does not leak how many bytes match.
+impl PartialEq for Hash {
+    fn eq(&self, other: &Hash) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for Hash {}
+
+impl core::hash::Hash for Hash {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = self.to_hex_bytes();
+        f.write_str(core::str::from_utf8(&hex).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl fmt::Debug for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hash({})", self)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Hash {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl FromStr for Hash {
+    type Err = MerkleError;
+
+    /// Parses [HASH_LEN](../proof/constant.HASH_LEN.html) lowercase hex digits.
+    fn from_str(hex: &str) -> Result<Hash, MerkleError> {
+        Hash::from_hex(hex)
+    }
+}
@@ -0,0 +1,70 @@
+//! Internal hashing helpers shared by the tree variants.
+
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+/// Returns the raw SHA-256 digest of `data`.
+pub(crate) fn sha256(data: &[u8]) -> Vec<u8> {
+    sha256_concat(&[data])
+}
+
+/// Returns the raw SHA-256 digest of the concatenation of `parts`.
+pub(crate) fn sha256_concat(parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// Returns SHA-256 over the concatenation of `parts` as lowercase hex, in bytes.
+///
+/// This is the hash format used by [MerkleTree](../struct.MerkleTree.html) nodes; without a tag it is the legacy construction.
+pub(crate) fn sha256_hex(parts: &[&[u8]]) -> Vec<u8> {
+    to_hex(&sha256_concat(parts)).into_bytes()
+}
+
+/// Returns `bytes` as lowercase hex.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    crate::codec::to_hex(bytes)
+}
+
+/// Parses lowercase or uppercase hex, returning `None` on odd length or invalid digits.
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    crate::codec::from_hex(hex).ok()
+}
+
+/// Returns the Keccak-256 digest of the concatenation of `parts`, as used by Ethereum.
+pub(crate) fn keccak256_concat(parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = Keccak256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// Decodes standard base64, with or without padding. Returns `None` on invalid input.
+#[cfg_attr(not(feature = "rekor"), allow(dead_code))]
+pub(crate) fn from_base64(text: &str) -> Option<Vec<u8>> {
+    crate::codec::from_base64(text).ok()
+}
+
+/// Encodes `bytes` as RFC 4648 base32 without padding, in upper case.
+#[cfg_attr(not(any(feature = "thex", feature = "ipld")), allow(dead_code))]
+pub(crate) fn to_base32(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0u16, 0);
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    out
+}
@@ -0,0 +1,364 @@
+//! Tamper-evident history tree.
+//!
+//! A [HistoryTree](struct.HistoryTree.html) is an append-only log of events (Crosby & Wallach, "Efficient Data Structures for Tamper-Evident Logging"). After every append the log has a new commitment: the root over all events so far. The tree can produce
+//!
+//! * membership proofs showing an event is included in *any* past commitment, and
+//! * incremental proofs showing a later commitment extends an earlier one, i.e. that no past event was altered or removed.
+//!
+//! Commitments are identified by the number of events they cover. Hashing and proof formats follow RFC 6962 / RFC 9162 (Certificate Transparency): leaves are `SHA256(0x00 || data)`, inner nodes `SHA256(0x01 || left || right)`, and the tree is split at the largest power of two below its size. The free functions [verify_inclusion](fn.verify_inclusion.html) and [verify_consistency](fn.verify_consistency.html) therefore also verify proofs from CT-style logs.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::history::HistoryTree;
+//! let mut log = HistoryTree::new();
+//! for event in ["login", "transfer", "logout"] {
+//!     log.append(event.as_bytes());
+//! }
+//! let old = log.commitment(2).unwrap();
+//! log.append(b"login");
+//! let new = log.commitment(4).unwrap();
+//!
+//! let proof = log.membership_proof(1, 2).unwrap();
+//! assert!(proof.verify(&old, b"transfer"));
+//!
+//! let proof = log.incremental_proof(2, 4).unwrap();
+//! assert!(proof.verify(&old, &new));
+//! ```
+
+use crate::hashing::sha256_concat;
+use crate::notify::{RootChange, Subscribers};
+use crate::proof::ct_eq;
+use std::sync::mpsc::Receiver;
+
+const LEAF_PREFIX: &[u8] = &[0x00];
+const NODE_PREFIX: &[u8] = &[0x01];
+
+/// [HistoryTree](struct.HistoryTree.html) stores the hash of every complete, aligned subtree, so any past commitment can be recomputed in `O(log n)`.
+///
+/// `layers[h][k]` is the hash of events `k * 2^h .. (k + 1) * 2^h`.
+///
+/// Subscribers are notified after each append, with the new size as version.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryTree {
+    layers: Vec<Vec<Vec<u8>>>,
+    subscribers: Subscribers,
+}
+
+/// [MembershipProof](struct.MembershipProof.html) proves that event `index` is included in the commitment over the first `size` events.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MembershipProof {
+    pub index: u64,
+    pub size: u64,
+    pub path: Vec<Vec<u8>>,
+}
+
+/// [IncrementalProof](struct.IncrementalProof.html) proves that the commitment over `new_size` events extends the one over `old_size` events.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncrementalProof {
+    pub old_size: u64,
+    pub new_size: u64,
+    pub path: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for HistoryTree {
+    fn zeroize(&mut self) {
+        self.layers.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for HistoryTree {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for HistoryTree {}
+
+impl HistoryTree {
+    /// Function to create an empty [HistoryTree](struct.HistoryTree.html)
+    pub fn new() -> HistoryTree {
+        HistoryTree::default()
+    }
+
+    /// Appends an event and returns the size of the log after the append.
+    pub fn append(&mut self, data: &[u8]) -> u64 {
+        self.append_leaf_hash(leaf_hash(data))
+    }
+
+    /// Appends an already hashed leaf (see [leaf_hash](fn.leaf_hash.html)) and returns the new size.
+    pub fn append_leaf_hash(&mut self, hash: Vec<u8>) -> u64 {
+        let old_root = (!self.subscribers.is_empty()).then(|| self.root());
+        if self.layers.is_empty() {
+            self.layers.push(Vec::new());
+        }
+        self.layers[0].push(hash);
+        let mut height = 0;
+        while self.layers[height].len().is_multiple_of(2) {
+            let layer = &self.layers[height];
+            let parent = node_hash(&layer[layer.len() - 2], &layer[layer.len() - 1]);
+            if self.layers.len() == height + 1 {
+                self.layers.push(Vec::new());
+            }
+            self.layers[height + 1].push(parent);
+            height += 1;
+        }
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(
+            self.last_nodes_are_consistent(),
+            "history tree is inconsistent"
+        );
+        if let Some(old_root) = old_root {
+            let change = RootChange {
+                old_root,
+                new_root: self.root(),
+                version: self.len(),
+            };
+            self.subscribers.notify(change);
+        }
+        self.len()
+    }
+
+    /// Checks that the last node of every layer is the hash of its two children, which is all an append can change.
+    #[cfg(feature = "debug-validate")]
+    fn last_nodes_are_consistent(&self) -> bool {
+        self.layers
+            .windows(2)
+            .all(|pair| match pair[1].len().checked_sub(1) {
+                Some(k) => {
+                    pair[0].len() >= 2 * k + 2
+                        && pair[1][k] == node_hash(&pair[0][2 * k], &pair[0][2 * k + 1])
+                }
+                None => true,
+            })
+    }
+
+    /// Calls `callback` with a [RootChange](../notify/struct.RootChange.html) after every append.
+    pub fn subscribe<F: FnMut(&RootChange) + Send + Sync + 'static>(&mut self, callback: F) {
+        self.subscribers.add(callback);
+    }
+
+    /// Returns a channel receiving a [RootChange](../notify/struct.RootChange.html) after every append.
+    pub fn subscribe_channel(&mut self) -> Receiver<RootChange> {
+        self.subscribers.channel()
+    }
+
+    /// Returns the number of events in the log.
+    pub fn len(&self) -> u64 {
+        self.layers.first().map_or(0, |leaves| leaves.len() as u64)
+    }
+
+    /// Returns `true` if the log has no events.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the leaf hash of event `index`.
+    pub fn leaf_hash(&self, index: u64) -> Option<Vec<u8>> {
+        self.layers.first()?.get(index as usize).cloned()
+    }
+
+    /// Returns the commitment over the first `size` events, or `None` if the log is shorter than `size`.
+    pub fn commitment(&self, size: u64) -> Option<Vec<u8>> {
+        if size > self.len() {
+            return None;
+        }
+        if size == 0 {
+            return Some(sha256_concat(&[]));
+        }
+        Some(self.subtree(0, size))
+    }
+
+    /// Returns the commitment over every event.
+    pub fn root(&self) -> Vec<u8> {
+        self.commitment(self.len()).expect("size is in range")
+    }
+
+    /// Generates a proof that event `index` is included in the commitment over the first `size` events.
+    pub fn membership_proof(&self, index: u64, size: u64) -> Option<MembershipProof> {
+        if index >= size || size > self.len() {
+            return None;
+        }
+        let mut path = Vec::new();
+        self.inclusion_path(index, 0, size, &mut path);
+        Some(MembershipProof { index, size, path })
+    }
+
+    /// Generates a proof that the commitment over `new_size` events extends the one over `old_size` events.
+    pub fn incremental_proof(&self, old_size: u64, new_size: u64) -> Option<IncrementalProof> {
+        if old_size > new_size || new_size > self.len() {
+            return None;
+        }
+        let mut path = Vec::new();
+        if old_size > 0 && old_size < new_size {
+            self.consistency_path(old_size, 0, new_size, true, &mut path);
+        }
+        Some(IncrementalProof {
+            old_size,
+            new_size,
+            path,
+        })
+    }
+
+    /// Hash of events `start .. start + len`, which must be non-empty.
+    fn subtree(&self, start: u64, len: u64) -> Vec<u8> {
+        if len.is_power_of_two() && start.is_multiple_of(len) {
+            let height = len.trailing_zeros() as usize;
+            return self.layers[height][(start / len) as usize].clone();
+        }
+        let split = split_point(len);
+        node_hash(
+            &self.subtree(start, split),
+            &self.subtree(start + split, len - split),
+        )
+    }
+
+    /// RFC 9162 `PATH(m, D[start:start + len])`.
+    fn inclusion_path(&self, index: u64, start: u64, len: u64, path: &mut Vec<Vec<u8>>) {
+        if len == 1 {
+            return;
+        }
+        let split = split_point(len);
+        if index < split {
+            self.inclusion_path(index, start, split, path);
+            path.push(self.subtree(start + split, len - split));
+        } else {
+            self.inclusion_path(index - split, start + split, len - split, path);
+            path.push(self.subtree(start, split));
+        }
+    }
+
+    /// RFC 9162 `SUBPROOF(m, D[start:start + len], complete)`.
+    fn consistency_path(
+        &self,
+        old_size: u64,
+        start: u64,
+        len: u64,
+        complete: bool,
+        path: &mut Vec<Vec<u8>>,
+    ) {
+        if old_size == len {
+            if !complete {
+                path.push(self.subtree(start, len));
+            }
+            return;
+        }
+        let split = split_point(len);
+        if old_size <= split {
+            self.consistency_path(old_size, start, split, complete, path);
+            path.push(self.subtree(start + split, len - split));
+        } else {
+            self.consistency_path(old_size - split, start + split, len - split, false, path);
+            path.push(self.subtree(start, split));
+        }
+    }
+}
+
+impl MembershipProof {
+    /// Checks that `data` is event `index` of the commitment `root`.
+    pub fn verify(&self, root: &[u8], data: &[u8]) -> bool {
+        verify_inclusion(&leaf_hash(data), self.index, self.size, &self.path, root)
+    }
+}
+
+impl IncrementalProof {
+    /// Checks that `new_root` extends `old_root`.
+    pub fn verify(&self, old_root: &[u8], new_root: &[u8]) -> bool {
+        verify_consistency(self.old_size, self.new_size, old_root, new_root, &self.path)
+    }
+}
+
+/// Returns the RFC 6962 leaf hash `SHA256(0x00 || data)`.
+pub fn leaf_hash(data: &[u8]) -> Vec<u8> {
+    sha256_concat(&[LEAF_PREFIX, data])
+}
+
+/// Returns the RFC 6962 inner node hash `SHA256(0x01 || left || right)`.
+pub fn node_hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+    sha256_concat(&[NODE_PREFIX, left, right])
+}
+
+/// Verifies an RFC 9162 inclusion proof for the leaf hash `leaf` at `index` in a tree of `size` leaves with root `root`.
+pub fn verify_inclusion(leaf: &[u8], index: u64, size: u64, path: &[Vec<u8>], root: &[u8]) -> bool {
+    if index >= size {
+        return false;
+    }
+    let (mut fn_, mut sn) = (index, size - 1);
+    let mut r = leaf.to_vec();
+    for p in path {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            r = node_hash(p, &r);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            r = node_hash(&r, p);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    sn == 0 && ct_eq(&r, root)
+}
+
+/// Verifies an RFC 9162 consistency proof between a tree of `old_size` leaves with root `old_root` and a tree of `new_size` leaves with root `new_root`.
+pub fn verify_consistency(
+    old_size: u64,
+    new_size: u64,
+    old_root: &[u8],
+    new_root: &[u8],
+    path: &[Vec<u8>],
+) -> bool {
+    if old_size > new_size {
+        return false;
+    }
+    if old_size == new_size {
+        return path.is_empty() && ct_eq(old_root, new_root);
+    }
+    if old_size == 0 {
+        return path.is_empty();
+    }
+    if path.is_empty() {
+        return false;
+    }
+
+    let mut path = path.to_vec();
+    if old_size.is_power_of_two() {
+        path.insert(0, old_root.to_vec());
+    }
+    let (mut fn_, mut sn) = (old_size - 1, new_size - 1);
+    while fn_ & 1 == 1 {
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    let mut fr = path[0].clone();
+    let mut sr = path[0].clone();
+    for c in &path[1..] {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            fr = node_hash(c, &fr);
+            sr = node_hash(c, &sr);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            sr = node_hash(&sr, c);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    sn == 0 && ct_eq(&fr, old_root) & ct_eq(&sr, new_root)
+}
+
+/// Largest power of two strictly less than `n`, for `n > 1`.
+pub(crate) fn split_point(n: u64) -> u64 {
+    1 << (63 - (n - 1).leading_zeros())
+}
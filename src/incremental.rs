@@ -0,0 +1,233 @@
+//! Fixed-depth incremental Merkle tree.
+//!
+//! An [IncrementalMerkleTree](struct.IncrementalMerkleTree.html) has a fixed depth and is filled from left to right, with unused leaves treated as zero. It only stores one "branch" hash per level, so `insert` and `root` both cost `O(depth)` regardless of how many leaves have been inserted.
+//!
+//! This is the construction used by the Ethereum deposit contract and Tornado-style mixers: with SHA-256 and depth 32, [root](struct.IncrementalMerkleTree.html#method.root) and [deposit_root](struct.IncrementalMerkleTree.html#method.deposit_root) are bit-compatible with the deposit contract.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::incremental::IncrementalMerkleTree;
+//! let mut tree = IncrementalMerkleTree::new(32);
+//! assert_eq!(
+//!     tree.deposit_root(),
+//!     [
+//!         0xd7, 0x0a, 0x23, 0x47, 0x31, 0x28, 0x5c, 0x68, 0x04, 0xc2, 0xa4, 0xf5, 0x67, 0x11, 0xdd, 0xb8,
+//!         0xc8, 0x2c, 0x99, 0x74, 0x0f, 0x20, 0x78, 0x54, 0x89, 0x10, 0x28, 0xaf, 0x34, 0xe2, 0x7e, 0x5e,
+//!     ]
+//! );
+//! tree.insert(&[1u8; 32]);
+//! assert_eq!(tree.len(), 1);
+//! ```
+
+use crate::hashing::sha256_concat;
+use crate::notify::{RootChange, Subscribers};
+use std::sync::mpsc::Receiver;
+
+/// Depth of the Ethereum deposit contract tree.
+pub const DEPOSIT_CONTRACT_TREE_DEPTH: usize = 32;
+
+/// [IncrementalMerkleTree](struct.IncrementalMerkleTree.html) is an append-only Merkle tree of fixed depth.
+///
+/// * `branch`: `branch[h]` is the last left-hand node completed at height `h`.
+///
+/// * `zero_hashes`: `zero_hashes[h]` is the root of an all-zero subtree of height `h`.
+///
+/// * `count`: The number of inserted leaves.
+///
+/// * `subscribers`: Notified of the new root after each insertion, with the leaf count as version.
+#[derive(Debug, Clone)]
+pub struct IncrementalMerkleTree {
+    depth: usize,
+    branch: Vec<Vec<u8>>,
+    zero_hashes: Vec<Vec<u8>>,
+    count: u64,
+    subscribers: Subscribers,
+}
+
+impl IncrementalMerkleTree {
+    /// Function to create an empty [IncrementalMerkleTree](struct.IncrementalMerkleTree.html) of the given `depth`, holding up to `2^depth - 1` leaves.
+    ///
+    /// Panics if `depth` is 0 or greater than 63.
+    pub fn new(depth: usize) -> IncrementalMerkleTree {
+        assert!(depth > 0 && depth < 64, "depth must be between 1 and 63");
+        let mut zero_hashes = vec![vec![0u8; 32]];
+        for height in 0..depth {
+            let zero = &zero_hashes[height];
+            let next = sha256_concat(&[zero, zero]);
+            zero_hashes.push(next);
+        }
+        IncrementalMerkleTree {
+            depth,
+            branch: zero_hashes[..depth].to_vec(),
+            zero_hashes,
+            count: 0,
+            subscribers: Subscribers::default(),
+        }
+    }
+
+    /// Returns the depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the number of inserted leaves.
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns `true` if no leaf has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the precomputed roots of all-zero subtrees, from height 0 up to the full depth.
+    pub fn zero_hashes(&self) -> &[Vec<u8>] {
+        &self.zero_hashes
+    }
+
+    /// Inserts `leaf` (normally a 32 byte hash) at the next free position and returns its index.
+    ///
+    /// Returns `None` if the tree is full. As in the deposit contract, the last position is never used.
+    pub fn insert(&mut self, leaf: &[u8]) -> Option<u64> {
+        if self.count >= (1u64 << self.depth) - 1 {
+            return None;
+        }
+        let old_root = (!self.subscribers.is_empty()).then(|| self.root());
+        let index = self.count;
+        self.count += 1;
+
+        let mut node = leaf.to_vec();
+        let mut size = self.count;
+        for height in 0..self.depth {
+            if size & 1 == 1 {
+                self.branch[height] = node;
+                break;
+            }
+            node = sha256_concat(&[&self.branch[height], &node]);
+            size /= 2;
+        }
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(
+            self.last_branch_is_consistent(leaf),
+            "incremental tree is inconsistent"
+        );
+        if let Some(old_root) = old_root {
+            let change = RootChange {
+                old_root,
+                new_root: self.root(),
+                version: self.count,
+            };
+            self.subscribers.notify(change);
+        }
+        Some(index)
+    }
+
+    /// Appends every leaf of `leaves` in one pass and returns the new root.
+    ///
+    /// Each level is hashed once for the whole batch and its branch updated once, instead of walking up the tree for every leaf, so bulk ingestion costs about one hash per new node. Subscribers are notified once, with the final leaf count, and not at all for an empty batch.
+    ///
+    /// Returns `None`, inserting nothing, if the leaves do not fit in the tree.
+    pub fn extend_leaves<I>(&mut self, leaves: I) -> Option<Vec<u8>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut layer: Vec<Vec<u8>> = leaves
+            .into_iter()
+            .map(|leaf| leaf.as_ref().to_vec())
+            .collect();
+        if layer.is_empty() {
+            return Some(self.root());
+        }
+        #[cfg(feature = "debug-validate")]
+        let last = layer.last().cloned();
+        let count = self.count.checked_add(layer.len() as u64)?;
+        if count >= 1u64 << self.depth {
+            return None;
+        }
+        let old_root = (!self.subscribers.is_empty()).then(|| self.root());
+        // `start` is the position of `layer[0]` in its level.
+        let mut start = self.count;
+        for height in 0..self.depth {
+            if layer.is_empty() {
+                break;
+            }
+            let mut nodes = layer.into_iter();
+            let mut parents = Vec::new();
+            if start & 1 == 1 {
+                let right = nodes.next()?;
+                parents.push(sha256_concat(&[&self.branch[height], &right]));
+            }
+            while let Some(left) = nodes.next() {
+                match nodes.next() {
+                    Some(right) => {
+                        parents.push(sha256_concat(&[&left, &right]));
+                        self.branch[height] = left;
+                    }
+                    None => self.branch[height] = left,
+                }
+            }
+            layer = parents;
+            start /= 2;
+        }
+        self.count = count;
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(
+            last.is_none_or(|leaf| self.last_branch_is_consistent(&leaf)),
+            "incremental tree is inconsistent"
+        );
+        let root = self.root();
+        if let Some(old_root) = old_root {
+            let change = RootChange {
+                old_root,
+                new_root: root.clone(),
+                version: self.count,
+            };
+            self.subscribers.notify(change);
+        }
+        Some(root)
+    }
+
+    /// Checks that the branch node completed by inserting `leaf` last is the hash of the left branches below it and the leaf, which is all an insertion can change.
+    #[cfg(feature = "debug-validate")]
+    fn last_branch_is_consistent(&self, leaf: &[u8]) -> bool {
+        let height = self.count.trailing_zeros() as usize;
+        let node = self.branch[..height]
+            .iter()
+            .fold(leaf.to_vec(), |node, left| sha256_concat(&[left, &node]));
+        self.branch[height] == node
+    }
+
+    /// Calls `callback` with a [RootChange](../notify/struct.RootChange.html) after every insertion.
+    pub fn subscribe<F: FnMut(&RootChange) + Send + Sync + 'static>(&mut self, callback: F) {
+        self.subscribers.add(callback);
+    }
+
+    /// Returns a channel receiving a [RootChange](../notify/struct.RootChange.html) after every insertion.
+    pub fn subscribe_channel(&mut self) -> Receiver<RootChange> {
+        self.subscribers.channel()
+    }
+
+    /// Returns the root of the tree, with every unused leaf set to zero.
+    pub fn root(&self) -> Vec<u8> {
+        let mut node = vec![0u8; 32];
+        let mut size = self.count;
+        for height in 0..self.depth {
+            node = if size & 1 == 1 {
+                sha256_concat(&[&self.branch[height], &node])
+            } else {
+                sha256_concat(&[&node, &self.zero_hashes[height]])
+            };
+            size /= 2;
+        }
+        node
+    }
+
+    /// Returns the root with the leaf count mixed in, as computed by the deposit contract's `get_deposit_root`:
+    /// `SHA256(root || little_endian_64(count) || 24 zero bytes)`.
+    pub fn deposit_root(&self) -> Vec<u8> {
+        let mut length = self.count.to_le_bytes().to_vec();
+        length.resize(32, 0);
+        sha256_concat(&[&self.root(), &length])
+    }
+}
@@ -0,0 +1,280 @@
+//! Indexed Merkle tree.
+//!
+//! An [IndexedMerkleTree](struct.IndexedMerkleTree.html) is a fixed-depth, append-only Merkle tree whose leaves form a sorted linked list: each [IndexedLeaf](struct.IndexedLeaf.html) stores its value together with the index and value of the next larger element. A value is proven absent by showing the "low leaf" whose value is below it and whose next value is above it, so non-membership costs a single Merkle proof. This is how several zk rollups keep their nullifier sets.
+//!
+//! The tree starts with a zero leaf `(0, 0, 0)`; a next value of zero means "no larger element".
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::indexed::IndexedMerkleTree;
+//! let value = |v: u8| { let mut bytes = [0u8; 32]; bytes[31] = v; bytes };
+//!
+//! let mut tree = IndexedMerkleTree::new(8);
+//! tree.insert(value(10)).unwrap();
+//! tree.insert(value(30)).unwrap();
+//! let root = tree.root();
+//!
+//! let proof = tree.prove_non_membership(&value(20)).unwrap();
+//! assert!(proof.verify_non_membership(&root, &value(20)));
+//! let proof = tree.prove_membership(&value(30)).unwrap();
+//! assert!(proof.verify_membership(&root, &value(30)));
+//! ```
+
+use crate::hashing::sha256_concat;
+use std::collections::BTreeMap;
+
+const MAX_DEPTH: usize = 63;
+
+/// A value stored in the tree, compared as a 256-bit big-endian integer.
+pub type Value = [u8; 32];
+
+/// [IndexedLeaf](struct.IndexedLeaf.html) is one element of the sorted linked list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexedLeaf {
+    pub value: Value,
+    pub next_index: u64,
+    pub next_value: Value,
+}
+
+/// [IndexedProof](struct.IndexedProof.html) is a Merkle proof for one [IndexedLeaf](struct.IndexedLeaf.html).
+///
+/// * `leaf`: The proven leaf. For non-membership proofs this is the low leaf.
+///
+/// * `index`: Position of the leaf in the tree.
+///
+/// * `siblings`: Sibling hashes from the leaf up to the root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedProof {
+    pub leaf: IndexedLeaf,
+    pub index: u64,
+    pub siblings: Vec<Vec<u8>>,
+}
+
+/// [IndexedMerkleTree](struct.IndexedMerkleTree.html) holds the leaves, every level of node hashes and a sorted index of the values.
+#[derive(Debug, Clone)]
+pub struct IndexedMerkleTree {
+    depth: usize,
+    leaves: Vec<IndexedLeaf>,
+    layers: Vec<Vec<Vec<u8>>>,
+    zero_hashes: Vec<Vec<u8>>,
+    sorted: BTreeMap<Value, u64>,
+}
+
+impl IndexedLeaf {
+    /// Returns the hash of the leaf, `SHA256(value || next_index || next_value)`.
+    pub fn hash(&self) -> Vec<u8> {
+        sha256_concat(&[
+            &self.value,
+            &self.next_index.to_be_bytes(),
+            &self.next_value,
+        ])
+    }
+
+    /// Returns `true` if `value` falls strictly between this leaf and the next one.
+    pub fn brackets(&self, value: &Value) -> bool {
+        self.value < *value && (self.next_value == [0u8; 32] || *value < self.next_value)
+    }
+}
+
+impl IndexedMerkleTree {
+    /// Function to create an [IndexedMerkleTree](struct.IndexedMerkleTree.html) of the given `depth`, holding the initial zero leaf.
+    ///
+    /// Panics if `depth` is 0 or greater than 63.
+    pub fn new(depth: usize) -> IndexedMerkleTree {
+        assert!(
+            depth > 0 && depth <= MAX_DEPTH,
+            "depth must be between 1 and 63"
+        );
+        let mut zero_hashes = vec![vec![0u8; 32]];
+        for height in 0..depth {
+            let zero = &zero_hashes[height];
+            let next = sha256_concat(&[zero, zero]);
+            zero_hashes.push(next);
+        }
+        let mut tree = IndexedMerkleTree {
+            depth,
+            leaves: Vec::new(),
+            layers: vec![Vec::new(); depth + 1],
+            zero_hashes,
+            sorted: BTreeMap::new(),
+        };
+        tree.append(IndexedLeaf {
+            value: [0u8; 32],
+            next_index: 0,
+            next_value: [0u8; 32],
+        });
+        tree
+    }
+
+    /// Returns the number of leaves, including the initial zero leaf.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Always `false`: the tree holds at least the zero leaf.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Returns `true` if `value` has been inserted.
+    pub fn contains(&self, value: &Value) -> bool {
+        self.sorted.contains_key(value)
+    }
+
+    /// Returns the leaf at `index`.
+    pub fn leaf(&self, index: u64) -> Option<&IndexedLeaf> {
+        self.leaves.get(index as usize)
+    }
+
+    /// Returns the Root Hash of the tree.
+    pub fn root(&self) -> Vec<u8> {
+        self.node(self.depth, 0)
+    }
+
+    /// Inserts `value`, updating its low leaf, and returns the index of the new leaf.
+    ///
+    /// Returns `None` if the value is zero, already present, or the tree is full.
+    pub fn insert(&mut self, value: Value) -> Option<u64> {
+        if value == [0u8; 32] || self.contains(&value) || self.leaves.len() >= 1 << self.depth {
+            return None;
+        }
+        let low_index = self.low_index(&value);
+        let low = self.leaves[low_index as usize];
+        let index = self.leaves.len() as u64;
+
+        self.update(
+            low_index,
+            IndexedLeaf {
+                next_index: index,
+                next_value: value,
+                ..low
+            },
+        );
+        self.append(IndexedLeaf {
+            value,
+            next_index: low.next_index,
+            next_value: low.next_value,
+        });
+        Some(index)
+    }
+
+    /// Generates a proof that `value` is in the tree.
+    pub fn prove_membership(&self, value: &Value) -> Option<IndexedProof> {
+        let index = *self.sorted.get(value)?;
+        Some(self.prove(index))
+    }
+
+    /// Generates a proof that `value` is not in the tree, by proving its low leaf.
+    pub fn prove_non_membership(&self, value: &Value) -> Option<IndexedProof> {
+        if self.contains(value) {
+            return None;
+        }
+        Some(self.prove(self.low_index(value)))
+    }
+
+    /// Generates a Merkle proof for the leaf at `index`.
+    pub fn prove(&self, index: u64) -> IndexedProof {
+        let mut position = index as usize;
+        let siblings = (0..self.depth)
+            .map(|height| {
+                let sibling = self.node(height, position ^ 1);
+                position >>= 1;
+                sibling
+            })
+            .collect();
+        IndexedProof {
+            leaf: self.leaves[index as usize],
+            index,
+            siblings,
+        }
+    }
+
+    /// Index of the leaf with the largest value below `value`.
+    fn low_index(&self, value: &Value) -> u64 {
+        self.sorted
+            .range(..*value)
+            .next_back()
+            .map(|(_, index)| *index)
+            .unwrap_or(0)
+    }
+
+    fn append(&mut self, leaf: IndexedLeaf) {
+        let index = self.leaves.len() as u64;
+        self.leaves.push(leaf);
+        self.layers[0].push(Vec::new());
+        self.sorted.insert(leaf.value, index);
+        self.update(index, leaf);
+    }
+
+    /// Replaces the leaf at `index` and recomputes its path to the root.
+    fn update(&mut self, index: u64, leaf: IndexedLeaf) {
+        let mut position = index as usize;
+        self.leaves[position] = leaf;
+        self.layers[0][position] = leaf.hash();
+        for height in 1..=self.depth {
+            let left = self.node(height - 1, position & !1);
+            let right = self.node(height - 1, position | 1);
+            position >>= 1;
+            let layer = &mut self.layers[height];
+            if layer.len() <= position {
+                layer.resize(position + 1, Vec::new());
+            }
+            layer[position] = sha256_concat(&[&left, &right]);
+        }
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(
+            self.path_is_consistent(index),
+            "indexed tree is inconsistent"
+        );
+    }
+
+    /// Checks that every node on the path of leaf `index` is the hash of its children.
+    #[cfg(feature = "debug-validate")]
+    fn path_is_consistent(&self, index: u64) -> bool {
+        let position = index as usize;
+        self.layers[0][position] == self.leaves[position].hash()
+            && (1..=self.depth).all(|height| {
+                let position = position >> height;
+                self.node(height, position)
+                    == sha256_concat(&[
+                        &self.node(height - 1, 2 * position),
+                        &self.node(height - 1, 2 * position + 1),
+                    ])
+            })
+    }
+
+    fn node(&self, height: usize, position: usize) -> Vec<u8> {
+        match self.layers[height].get(position) {
+            Some(hash) => hash.clone(),
+            None => self.zero_hashes[height].clone(),
+        }
+    }
+}
+
+impl IndexedProof {
+    /// Checks that `value` is in the tree with root `root`.
+    pub fn verify_membership(&self, root: &[u8], value: &Value) -> bool {
+        self.leaf.value == *value && self.compute_root().as_deref() == Some(root)
+    }
+
+    /// Checks that `value` is absent from the tree with root `root`: the proven leaf must bracket it.
+    pub fn verify_non_membership(&self, root: &[u8], value: &Value) -> bool {
+        self.leaf.brackets(value) && self.compute_root().as_deref() == Some(root)
+    }
+
+    /// Folds the siblings over the leaf, or returns `None` if the proof is deeper than any tree or `index` does not fit in it.
+    fn compute_root(&self) -> Option<Vec<u8>> {
+        if self.siblings.len() > MAX_DEPTH || self.index >> self.siblings.len() != 0 {
+            return None;
+        }
+        let mut current = self.leaf.hash();
+        for (height, sibling) in self.siblings.iter().enumerate() {
+            current = if self.index >> height & 1 == 1 {
+                sha256_concat(&[sibling, &current])
+            } else {
+                sha256_concat(&[&current, sibling])
+            };
+        }
+        Some(current)
+    }
+}
@@ -0,0 +1,236 @@
+//! IPLD blocks and CIDs for tree nodes.
+//!
+//! Every node of a [MerkleTree](../struct.MerkleTree.html) can be published to IPFS as a [DAG-CBOR](https://ipld.io/specs/codecs/dag-cbor/spec/) block: a map with the node's `hash` and, for inner nodes, links to the `left` and `right` children. Blocks are identified by CIDv1 (`dag-cbor`, `sha2-256`), so fetching the root CID from any IPFS node and following links retrieves the whole tree, and every fetched block is checked against the CID it was requested by.
+//!
+//! Requires the `ipld` feature.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::ipld::{decode_node, encode_tree, Cid};
+//! use rs_merkletree::MerkleTree;
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(vec!["a", "b", "c"]);
+//! let (root, blocks) = encode_tree(&tree).unwrap();
+//! assert!(root.to_string().starts_with("bafyrei"));
+//!
+//! let block = blocks.iter().find(|block| block.cid == root).unwrap();
+//! let node = decode_node(&block.data).unwrap();
+//! assert_eq!(node.hash, tree.root_node().unwrap().hash());
+//! assert!(node.left.is_some());
+//! ```
+
+use crate::hashing::{sha256, to_base32};
+use crate::proof::HASH_LEN;
+use crate::{Hash, MerkleTree, Node};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Multicodec code of DAG-CBOR.
+pub const DAG_CBOR: u64 = 0x71;
+/// Multihash code of SHA2-256.
+pub const SHA2_256: u64 = 0x12;
+
+/// [Cid](struct.Cid.html) is a binary CIDv1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cid(Vec<u8>);
+
+/// [Block](struct.Block.html) is encoded block data and its CID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub cid: Cid,
+    pub data: Vec<u8>,
+}
+
+/// [IpldNode](struct.IpldNode.html) is a decoded node block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpldNode {
+    pub hash: Hash,
+    pub left: Option<Cid>,
+    pub right: Option<Cid>,
+}
+
+impl Cid {
+    /// Returns the CID of DAG-CBOR `data`.
+    pub fn for_dag_cbor(data: &[u8]) -> Cid {
+        let mut bytes = Vec::with_capacity(36);
+        write_varint(&mut bytes, 1);
+        write_varint(&mut bytes, DAG_CBOR);
+        write_varint(&mut bytes, SHA2_256);
+        write_varint(&mut bytes, 32);
+        bytes.extend(sha256(data));
+        Cid(bytes)
+    }
+
+    /// Returns the binary form of the CID.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns true if `data` is the block this CID names.
+    pub fn verify(&self, data: &[u8]) -> bool {
+        *self == Cid::for_dag_cbor(data)
+    }
+}
+
+impl fmt::Display for Cid {
+    /// Formats the CID in multibase base32, as IPFS shows CIDv1.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "b{}", to_base32(&self.0).to_ascii_lowercase())
+    }
+}
+
+impl IpldNode {
+    /// Returns the DAG-CBOR encoding of the node.
+    pub fn encode(&self) -> Vec<u8> {
+        let links = [("left", &self.left), ("right", &self.right)];
+        let fields = 1 + links.iter().filter(|(_, cid)| cid.is_some()).count();
+        let mut out = Vec::new();
+        write_head(&mut out, 5, fields as u64);
+        // DAG-CBOR orders map keys by length, then bytewise: "hash", "left", "right".
+        write_text(&mut out, "hash");
+        write_head(&mut out, 2, HASH_LEN as u64);
+        out.extend_from_slice(&self.hash.to_hex_bytes());
+        for (name, cid) in links {
+            if let Some(cid) = cid {
+                write_text(&mut out, name);
+                out.extend_from_slice(&[0xd8, 42]);
+                write_head(&mut out, 2, cid.0.len() as u64 + 1);
+                out.push(0);
+                out.extend_from_slice(&cid.0);
+            }
+        }
+        out
+    }
+}
+
+/// Encodes every node of `tree` as a block, children before parents, returning the root CID and the distinct blocks. Returns `None` for a tree without a root.
+pub fn encode_tree(tree: &MerkleTree) -> Option<(Cid, Vec<Block>)> {
+    let root = tree.root_node.as_ref()?;
+    let mut blocks = Vec::new();
+    let mut seen = HashSet::new();
+    let cid = encode_node(root, &mut blocks, &mut seen);
+    Some((cid, blocks))
+}
+
+fn encode_node(node: &Node, blocks: &mut Vec<Block>, seen: &mut HashSet<Cid>) -> Cid {
+    let left = node
+        .left_node
+        .as_ref()
+        .map(|child| encode_node(child, blocks, seen));
+    let right = node
+        .right_node
+        .as_ref()
+        .map(|child| encode_node(child, blocks, seen));
+    let data = IpldNode {
+        hash: node.hash,
+        left,
+        right,
+    }
+    .encode();
+    let cid = Cid::for_dag_cbor(&data);
+    if seen.insert(cid.clone()) {
+        blocks.push(Block {
+            cid: cid.clone(),
+            data,
+        });
+    }
+    cid
+}
+
+/// Decodes a node block produced by [encode_tree](fn.encode_tree.html). Returns `None` for any other data.
+pub fn decode_node(data: &[u8]) -> Option<IpldNode> {
+    let mut input = data;
+    let (major, fields) = read_head(&mut input)?;
+    if major != 5 || !(1..=3).contains(&fields) {
+        return None;
+    }
+    let mut hash = None;
+    let mut left = None;
+    let mut right = None;
+    let mut expected = vec!["hash", "left", "right"].into_iter();
+    for _ in 0..fields {
+        let key = read_string(&mut input, 3)?;
+        let key = expected.find(|name| name.as_bytes() == key)?;
+        if key == "hash" {
+            hash = Some(Hash::from_hex_bytes(read_string(&mut input, 2)?).ok()?);
+            continue;
+        }
+        input = input.strip_prefix(&[0xd8, 42])?;
+        let link = read_string(&mut input, 2)?.strip_prefix(&[0])?;
+        let cid = Some(Cid(link.to_vec()));
+        if key == "left" {
+            left = cid;
+        } else {
+            right = cid;
+        }
+    }
+    Some(IpldNode {
+        hash: hash.filter(|_| input.is_empty())?,
+        left,
+        right,
+    })
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_head(out: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => out.push(major | value as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, value as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+
+fn write_text(out: &mut Vec<u8>, text: &str) {
+    write_head(out, 3, text.len() as u64);
+    out.extend_from_slice(text.as_bytes());
+}
+
+fn read_head(input: &mut &[u8]) -> Option<(u8, u64)> {
+    let (first, rest) = input.split_first()?;
+    let (major, info) = (first >> 5, first & 31);
+    let width = match info {
+        0..=23 => 0,
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        _ => return None,
+    };
+    if rest.len() < width {
+        return None;
+    }
+    let value = if width == 0 {
+        info as u64
+    } else {
+        rest[..width]
+            .iter()
+            .fold(0u64, |acc, byte| acc << 8 | *byte as u64)
+    };
+    *input = &rest[width..];
+    Some((major, value))
+}
+
+fn read_string<'a>(input: &mut &'a [u8], major: u8) -> Option<&'a [u8]> {
+    let (found, len) = read_head(input)?;
+    if found != major || (input.len() as u64) < len {
+        return None;
+    }
+    let (string, rest) = input.split_at(len as usize);
+    *input = rest;
+    Some(string)
+}
@@ -0,0 +1,505 @@
+//! Jellyfish Merkle Tree.
+//!
+//! A [JellyfishMerkleTree](struct.JellyfishMerkleTree.html) is a versioned sparse Merkle tree over 256-bit key hashes, stored as a 16-ary tree indexed by the nibbles of `SHA256(key)`. Unlike a plain [SparseMerkleTree](../smt/struct.SparseMerkleTree.html) it never materializes long chains of empty siblings:
+//!
+//! * a subtree holding a single leaf is replaced by the leaf itself, and
+//! * an empty subtree is represented by a constant placeholder hash.
+//!
+//! Each call to [put_value_set](struct.JellyfishMerkleTree.html#method.put_value_set) commits a new version; the root, values and proofs of every committed version stay available. Versions are persistent: a commit copies only the nodes on the paths of the keys it changes and shares every other subtree with the previous version, so keeping many versions costs memory in proportion to the changes, not to the size of the tree times the number of versions.
+//!
+//! Readers that must not wait on ingestion take a [JmtSnapshot](struct.JmtSnapshot.html) of a version: it shares that version's nodes, so it can serve values and proofs from another thread while the tree keeps committing and pruning.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::jmt::JellyfishMerkleTree;
+//! let mut tree = JellyfishMerkleTree::new();
+//! let root_v1 = tree.put_value_set(1, &[(b"alice", Some(b"100"))]).unwrap();
+//! let root_v2 = tree.put_value_set(2, &[(b"alice", Some(b"90")), (b"bob", Some(b"10"))]).unwrap();
+//!
+//! assert_eq!(tree.get(b"alice", 1), Some(&b"100"[..]));
+//! assert_eq!(tree.get(b"alice", 2), Some(&b"90"[..]));
+//!
+//! let proof = tree.prove(b"bob", 1).unwrap();
+//! assert!(proof.verify_non_membership(&root_v1, b"bob"));
+//! let proof = tree.prove(b"bob", 2).unwrap();
+//! assert!(proof.verify_membership(&root_v2, b"bob", b"10"));
+//! ```
+
+use crate::hashing::{sha256, sha256_concat};
+use crate::notify::{RootChange, Subscribers};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+
+/// Version number of a committed tree.
+pub type Version = u64;
+
+/// Hash of an empty subtree.
+pub const PLACEHOLDER_HASH: [u8; 32] = *b"SPARSE_MERKLE_PLACEHOLDER_HASH__";
+
+const LEAF_PREFIX: &[u8] = &[0x00];
+const INTERNAL_PREFIX: &[u8] = &[0x01];
+const KEY_BITS: usize = 256;
+
+type KeyHash = [u8; 32];
+
+/// A node of the tree. Internal nodes cache their hash. Nodes are immutable once built, so versions share them.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Internal {
+        children: Box<[Option<Arc<Node>>; 16]>,
+        hash: Vec<u8>,
+    },
+    Leaf {
+        key: KeyHash,
+        value: Vec<u8>,
+    },
+}
+
+/// [JellyfishMerkleTree](struct.JellyfishMerkleTree.html) holds the root of every committed version.
+///
+/// Subscribers are notified after each committed version.
+#[derive(Debug, Clone, Default)]
+pub struct JellyfishMerkleTree {
+    versions: BTreeMap<Version, Option<Arc<Node>>>,
+    subscribers: Subscribers,
+}
+
+/// [JmtProof](struct.JmtProof.html) is a sparse Merkle proof for one key at one version.
+///
+/// * `leaf`: The leaf found at the key's position as `(key hash, value hash)`, or `None` if the position is empty. For a non-membership proof this may be the leaf of a different key sharing the same path prefix.
+///
+/// * `siblings`: Sibling hashes from the root down to the leaf's position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JmtProof {
+    pub leaf: Option<(Vec<u8>, Vec<u8>)>,
+    pub siblings: Vec<Vec<u8>>,
+}
+
+/// [JmtSnapshot](struct.JmtSnapshot.html) is a read-only view of one version of a [JellyfishMerkleTree](struct.JellyfishMerkleTree.html).
+///
+/// It holds the version's root node, which versions never modify, so it stays consistent and readable while the tree commits new versions or prunes this one, and readers holding snapshots never block the writer. Snapshots are cheap to clone and can be sent to other threads.
+///
+/// * `version`: The version.
+///
+/// * `root`: Its root node, shared with the tree.
+#[derive(Debug, Clone)]
+pub struct JmtSnapshot {
+    version: Version,
+    root: Option<Arc<Node>>,
+}
+
+impl JellyfishMerkleTree {
+    /// Function to create a new [JellyfishMerkleTree](struct.JellyfishMerkleTree.html) with no committed versions.
+    pub fn new() -> JellyfishMerkleTree {
+        JellyfishMerkleTree::default()
+    }
+
+    /// Applies a batch of updates on top of the latest version and commits the result as `version`.
+    ///
+    /// A value of `None` deletes the key. Returns the new root hash, or `None` if `version` is not greater than the latest committed version.
+    pub fn put_value_set(
+        &mut self,
+        version: Version,
+        values: &[(&[u8], Option<&[u8]>)],
+    ) -> Option<Vec<u8>> {
+        if self
+            .latest_version()
+            .is_some_and(|latest| version <= latest)
+        {
+            return None;
+        }
+        let mut root = self.versions.values().next_back().cloned().flatten();
+        let old_root = node_hash(root.as_deref());
+        for (key, value) in values {
+            let key = key_hash(key);
+            root = match value {
+                Some(value) => Some(insert(root.as_ref(), key, value.to_vec(), 0)),
+                None => remove(root.as_ref(), &key, 0),
+            };
+        }
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(
+            values
+                .iter()
+                .all(|(key, _)| path_is_consistent(root.as_deref(), &key_hash(key))),
+            "jellyfish tree is inconsistent"
+        );
+        let hash = node_hash(root.as_deref());
+        self.versions.insert(version, root);
+        if !self.subscribers.is_empty() {
+            let change = RootChange {
+                old_root,
+                new_root: hash.clone(),
+                version,
+            };
+            self.subscribers.notify(change);
+        }
+        Some(hash)
+    }
+
+    /// Calls `callback` with a [RootChange](../notify/struct.RootChange.html) after every committed version.
+    pub fn subscribe<F: FnMut(&RootChange) + Send + Sync + 'static>(&mut self, callback: F) {
+        self.subscribers.add(callback);
+    }
+
+    /// Returns a channel receiving a [RootChange](../notify/struct.RootChange.html) after every committed version.
+    pub fn subscribe_channel(&mut self) -> Receiver<RootChange> {
+        self.subscribers.channel()
+    }
+
+    /// Returns the latest committed version.
+    pub fn latest_version(&self) -> Option<Version> {
+        self.versions.keys().next_back().copied()
+    }
+
+    /// Drops every version older than `version`, except the latest one, and returns how many were dropped.
+    ///
+    /// Nodes are reference-counted, so the nodes reachable only from dropped versions are freed while those shared with kept versions stay. Long-running services can call this periodically to keep a bounded window of history.
+    pub fn prune_before(&mut self, version: Version) -> usize {
+        let Some(latest) = self.latest_version() else {
+            return 0;
+        };
+        let kept = self.versions.split_off(&version.min(latest));
+        std::mem::replace(&mut self.versions, kept).len()
+    }
+
+    /// Returns the oldest version still held.
+    pub fn oldest_version(&self) -> Option<Version> {
+        self.versions.keys().next().copied()
+    }
+
+    /// Returns the number of distinct nodes held for all committed versions together. Nodes shared between versions are counted once.
+    pub fn node_count(&self) -> usize {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<&Arc<Node>> = self.versions.values().flatten().collect();
+        while let Some(node) = stack.pop() {
+            if !seen.insert(Arc::as_ptr(node)) {
+                continue;
+            }
+            if let Node::Internal { children, .. } = node.as_ref() {
+                stack.extend(children.iter().flatten());
+            }
+        }
+        seen.len()
+    }
+
+    /// Returns the root hash at `version`, or `None` if that version was never committed.
+    pub fn root(&self, version: Version) -> Option<Vec<u8>> {
+        self.versions
+            .get(&version)
+            .map(|root| node_hash(root.as_deref()))
+    }
+
+    /// Returns the value of `key` at `version`.
+    pub fn get(&self, key: &[u8], version: Version) -> Option<&[u8]> {
+        get(self.versions.get(&version)?.as_deref(), key)
+    }
+
+    /// Generates a membership or non-membership proof for `key` at `version`. Returns `None` if the version does not exist.
+    pub fn prove(&self, key: &[u8], version: Version) -> Option<JmtProof> {
+        Some(prove(self.versions.get(&version)?.as_deref(), key))
+    }
+
+    /// Returns a [JmtSnapshot](struct.JmtSnapshot.html) of `version`, or `None` if that version is not held.
+    pub fn snapshot(&self, version: Version) -> Option<JmtSnapshot> {
+        Some(JmtSnapshot {
+            version,
+            root: self.versions.get(&version)?.clone(),
+        })
+    }
+
+    /// Returns a [JmtSnapshot](struct.JmtSnapshot.html) of the latest version.
+    pub fn latest_snapshot(&self) -> Option<JmtSnapshot> {
+        self.snapshot(self.latest_version()?)
+    }
+}
+
+impl JmtSnapshot {
+    /// Returns the version the snapshot was taken of.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the root hash of the snapshot.
+    pub fn root(&self) -> Vec<u8> {
+        node_hash(self.root.as_deref())
+    }
+
+    /// Returns the value of `key` in the snapshot.
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        get(self.root.as_deref(), key)
+    }
+
+    /// Generates a membership or non-membership proof for `key` in the snapshot.
+    pub fn prove(&self, key: &[u8]) -> JmtProof {
+        prove(self.root.as_deref(), key)
+    }
+}
+
+impl JmtProof {
+    /// Checks that `key` maps to `value` in the tree with root `root`.
+    pub fn verify_membership(&self, root: &[u8], key: &[u8], value: &[u8]) -> bool {
+        if self.siblings.len() > KEY_BITS {
+            return false;
+        }
+        let key = key_hash(key);
+        match &self.leaf {
+            Some((leaf_key, value_hash)) if *leaf_key == key && *value_hash == sha256(value) => {
+                self.compute_root(&key, leaf_hash(&key, value_hash)) == root
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks that `key` is absent from the tree with root `root`.
+    pub fn verify_non_membership(&self, root: &[u8], key: &[u8]) -> bool {
+        if self.siblings.len() > KEY_BITS {
+            return false;
+        }
+        let key = key_hash(key);
+        let bottom = match &self.leaf {
+            None => PLACEHOLDER_HASH.to_vec(),
+            Some((leaf_key, value_hash)) => {
+                // The leaf must be another key occupying the same position.
+                if leaf_key.len() != key.len()
+                    || *leaf_key == key
+                    || (0..self.siblings.len()).any(|i| bit(leaf_key, i) != bit(&key, i))
+                {
+                    return false;
+                }
+                leaf_hash(leaf_key, value_hash)
+            }
+        };
+        self.compute_root(&key, bottom) == root
+    }
+
+    fn compute_root(&self, key: &KeyHash, bottom: Vec<u8>) -> Vec<u8> {
+        let mut current = bottom;
+        for (depth, sibling) in self.siblings.iter().enumerate().rev() {
+            current = if bit(key, depth) {
+                sha256_concat(&[INTERNAL_PREFIX, sibling, &current])
+            } else {
+                sha256_concat(&[INTERNAL_PREFIX, &current, sibling])
+            };
+        }
+        current
+    }
+}
+
+/// Returns the value of `key` in the tree below `root`.
+fn get<'a>(root: Option<&'a Node>, key: &[u8]) -> Option<&'a [u8]> {
+    let key = key_hash(key);
+    let mut node = root?;
+    let mut depth = 0;
+    loop {
+        match node {
+            Node::Leaf {
+                key: leaf_key,
+                value,
+            } => return (*leaf_key == key).then_some(value.as_slice()),
+            Node::Internal { children, .. } => {
+                node = children[nibble(&key, depth)].as_deref()?;
+                depth += 1;
+            }
+        }
+    }
+}
+
+/// Generates a membership or non-membership proof for `key` in the tree below `root`.
+fn prove(root: Option<&Node>, key: &[u8]) -> JmtProof {
+    let key = key_hash(key);
+    let mut node = root;
+    let mut siblings = Vec::new();
+    let mut depth = 0;
+
+    loop {
+        let children = match node {
+            None => {
+                return JmtProof {
+                    leaf: None,
+                    siblings,
+                }
+            }
+            Some(leaf @ Node::Leaf { .. }) => {
+                return JmtProof {
+                    leaf: leaf_entry(leaf),
+                    siblings,
+                }
+            }
+            Some(Node::Internal { children, .. }) => children,
+        };
+
+        // Descend through the four binary levels hidden inside the internal node.
+        let target = nibble(&key, depth);
+        let (mut lo, mut width) = (0, 16);
+        while width > 1 {
+            width /= 2;
+            let (ours, theirs) = if target >= lo + width {
+                (lo + width, lo)
+            } else {
+                (lo, lo + width)
+            };
+            siblings.push(range_hash(children, theirs, width));
+            lo = ours;
+
+            let occupied: Vec<&Node> = children[lo..lo + width]
+                .iter()
+                .flatten()
+                .map(|child| child.as_ref())
+                .collect();
+            match occupied.as_slice() {
+                [] => {
+                    return JmtProof {
+                        leaf: None,
+                        siblings,
+                    }
+                }
+                [leaf @ Node::Leaf { .. }] => {
+                    return JmtProof {
+                        leaf: leaf_entry(leaf),
+                        siblings,
+                    }
+                }
+                _ => {}
+            }
+        }
+        node = children[target].as_deref();
+        depth += 1;
+    }
+}
+
+fn insert(node: Option<&Arc<Node>>, key: KeyHash, value: Vec<u8>, depth: usize) -> Arc<Node> {
+    let Some(node) = node else {
+        return Arc::new(Node::Leaf { key, value });
+    };
+    match node.as_ref() {
+        Node::Leaf { key: existing, .. } if *existing == key => Arc::new(Node::Leaf { key, value }),
+        Node::Leaf { key: existing, .. } => {
+            // Split: push the existing leaf one level down, then insert the new one next to it.
+            let mut children: [Option<Arc<Node>>; 16] = Default::default();
+            children[nibble(existing, depth)] = Some(Arc::clone(node));
+            let internal = internal(children);
+            insert(Some(&internal), key, value, depth)
+        }
+        Node::Internal { children, .. } => {
+            let slot = nibble(&key, depth);
+            let mut children = children.as_ref().clone();
+            children[slot] = Some(insert(children[slot].as_ref(), key, value, depth + 1));
+            internal(children)
+        }
+    }
+}
+
+/// Removes `key` below `node`. A subtree that does not hold the key is returned as is, so it stays shared.
+fn remove(node: Option<&Arc<Node>>, key: &KeyHash, depth: usize) -> Option<Arc<Node>> {
+    let node = node?;
+    match node.as_ref() {
+        Node::Leaf { key: existing, .. } if existing == key => None,
+        Node::Leaf { .. } => Some(Arc::clone(node)),
+        Node::Internal { children, .. } => {
+            let slot = nibble(key, depth);
+            let child = remove(children[slot].as_ref(), key, depth + 1);
+            let unchanged = match (&child, &children[slot]) {
+                (Some(new), Some(old)) => Arc::ptr_eq(new, old),
+                (new, old) => new.is_none() && old.is_none(),
+            };
+            if unchanged {
+                return Some(Arc::clone(node));
+            }
+            let mut children = children.as_ref().clone();
+            children[slot] = child;
+
+            // An internal node left with a single leaf collapses into that leaf.
+            let mut occupied = children.iter().flatten();
+            match (occupied.next(), occupied.next()) {
+                (None, _) => None,
+                (Some(only), None) if matches!(only.as_ref(), Node::Leaf { .. }) => {
+                    children.iter_mut().find_map(|child| child.take())
+                }
+                _ => Some(internal(children)),
+            }
+        }
+    }
+}
+
+/// Checks that every internal node on the path of `key` caches the hash of its children, which is all a commit can change.
+#[cfg(feature = "debug-validate")]
+fn path_is_consistent(root: Option<&Node>, key: &KeyHash) -> bool {
+    let mut node = root;
+    let mut depth = 0;
+    while let Some(Node::Internal { children, hash }) = node {
+        if *hash != range_hash(children, 0, 16) {
+            return false;
+        }
+        node = children[nibble(key, depth)].as_deref();
+        depth += 1;
+    }
+    true
+}
+
+fn internal(children: [Option<Arc<Node>>; 16]) -> Arc<Node> {
+    let hash = range_hash(&children, 0, 16);
+    Arc::new(Node::Internal {
+        children: Box::new(children),
+        hash,
+    })
+}
+
+/// Hash of the binary subtree covering `children[lo..lo + width]`.
+///
+/// Empty ranges hash to the placeholder and ranges holding a single leaf hash to that leaf, which is what lets a leaf sit at the shallowest level where it is alone.
+fn range_hash(children: &[Option<Arc<Node>>; 16], lo: usize, width: usize) -> Vec<u8> {
+    let mut occupied = children[lo..lo + width].iter().flatten();
+    match (occupied.next(), occupied.next()) {
+        (None, _) => PLACEHOLDER_HASH.to_vec(),
+        (Some(only), None) if width == 1 || matches!(only.as_ref(), Node::Leaf { .. }) => {
+            node_hash(Some(only))
+        }
+        _ => {
+            let half = width / 2;
+            let left = range_hash(children, lo, half);
+            let right = range_hash(children, lo + half, half);
+            sha256_concat(&[INTERNAL_PREFIX, &left, &right])
+        }
+    }
+}
+
+fn node_hash(node: Option<&Node>) -> Vec<u8> {
+    match node {
+        None => PLACEHOLDER_HASH.to_vec(),
+        Some(Node::Internal { hash, .. }) => hash.clone(),
+        Some(Node::Leaf { key, value }) => leaf_hash(key, &sha256(value)),
+    }
+}
+
+fn leaf_entry(node: &Node) -> Option<(Vec<u8>, Vec<u8>)> {
+    match node {
+        Node::Leaf { key, value } => Some((key.to_vec(), sha256(value))),
+        Node::Internal { .. } => None,
+    }
+}
+
+fn leaf_hash(key: &[u8], value_hash: &[u8]) -> Vec<u8> {
+    sha256_concat(&[LEAF_PREFIX, key, value_hash])
+}
+
+fn key_hash(key: &[u8]) -> KeyHash {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&sha256(key));
+    hash
+}
+
+fn nibble(key: &[u8], depth: usize) -> usize {
+    let byte = key[depth / 2];
+    (if depth.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0x0f
+    }) as usize
+}
+
+/// Bit `index` of `key`, counting from the most significant bit. `true` means the right child.
+fn bit(key: &[u8], index: usize) -> bool {
+    (key[index / 8] >> (7 - index % 8)) & 1 == 1
+}
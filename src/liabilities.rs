@@ -0,0 +1,179 @@
+//! Privacy-preserving proof of liabilities.
+//!
+//! An exchange publishes the root of a [SumTree](../sum/struct.SumTree.html) over its customers' balances; the root's sum is its total liabilities, and each customer checks that their balance is counted in it. Publishing the balances as leaves would leak them, so a [LiabilitiesTree](struct.LiabilitiesTree.html) follows the Maxwell and Chalkias et al. constructions:
+//!
+//! * every balance is split into random parts, each in its own leaf, so no leaf reveals a whole balance,
+//! * leaves are shuffled, so a leaf's position says nothing about whose it is, and
+//! * leaves identify their owner only by `SHA256(user id || salt || part)` with a random per-user salt, so IDs cannot be enumerated.
+//!
+//! A customer receives a [LiabilityProof](struct.LiabilityProof.html) holding their salt and one sum proof per part, and checks it against the published root with their own ID and balance.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::liabilities::LiabilitiesTree;
+//! let accounts = vec![(b"alice".to_vec(), 100), (b"bob".to_vec(), 250), (b"carol".to_vec(), 75)];
+//! let tree = LiabilitiesTree::new(accounts, 3).unwrap();
+//! let root = tree.root().unwrap();
+//! assert_eq!(root.sum, 425);
+//!
+//! let proof = tree.proof(b"bob").unwrap();
+//! assert!(proof.verify(root, b"bob", 250));
+//! assert!(!proof.verify(root, b"bob", 200));
+//! ```
+
+use crate::hashing::sha256_concat;
+use crate::sum::{SumNode, SumProof, SumTree};
+use rand_core::{OsRng, RngCore};
+use std::collections::HashMap;
+
+/// Length of the salt of every user.
+pub const SALT_LEN: usize = 32;
+
+/// Returns the leaf data of part `part` of a user's balance: `SHA256(user id || salt || part)`, with `part` as a 4 byte big-endian integer.
+pub fn part_id(user_id: &[u8], salt: &[u8; SALT_LEN], part: u32) -> Vec<u8> {
+    sha256_concat(&[user_id, salt, &part.to_be_bytes()])
+}
+
+/// [LiabilityProof](struct.LiabilityProof.html) proves that a user's whole balance is counted in the root.
+///
+/// * `salt`: The user's salt.
+///
+/// * `parts`: Value and proof of each part of the balance, in part order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiabilityProof {
+    pub salt: [u8; SALT_LEN],
+    pub parts: Vec<(u64, SumProof)>,
+}
+
+impl LiabilityProof {
+    /// Checks that the parts of `user_id`'s balance are leaves of `root` and add up to `balance`.
+    pub fn verify(&self, root: &SumNode, user_id: &[u8], balance: u64) -> bool {
+        let mut total: u64 = 0;
+        for (part, (value, proof)) in self.parts.iter().enumerate() {
+            let data = part_id(user_id, &self.salt, part as u32);
+            if !proof.verify(root, &data, *value) {
+                return false;
+            }
+            match total.checked_add(*value) {
+                Some(sum) => total = sum,
+                None => return false,
+            }
+        }
+        !self.parts.is_empty() && total == balance
+    }
+}
+
+/// [LiabilitiesTree](struct.LiabilitiesTree.html) is a [SumTree](../sum/struct.SumTree.html) over split, shuffled and salted balances.
+///
+/// * `tree`: The sum tree.
+///
+/// * `users`: For every user ID, its salt and the leaf index of each part.
+#[derive(Debug, Clone)]
+pub struct LiabilitiesTree {
+    tree: SumTree,
+    users: HashMap<Vec<u8>, ([u8; SALT_LEN], Vec<usize>)>,
+}
+
+/// Overwrites every salt, part index and user ID and forgets the users. The sum tree only holds what proofs already publish and is kept.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for LiabilitiesTree {
+    fn zeroize(&mut self) {
+        for (salt, indices) in self.users.values_mut() {
+            salt.zeroize();
+            indices.zeroize();
+        }
+        for (mut user_id, _) in self.users.drain() {
+            user_id.zeroize();
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for LiabilitiesTree {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for LiabilitiesTree {}
+
+impl LiabilitiesTree {
+    /// Function to create a [LiabilitiesTree](struct.LiabilitiesTree.html) over `(user id, balance)` accounts, splitting each balance into `parts` parts, or 1 if `parts` is 0. Salts, splits and the shuffle use the operating system's random number generator.
+    ///
+    /// Returns `None` if a user ID appears twice or the total does not fit in a `u64`.
+    pub fn new(accounts: Vec<(Vec<u8>, u64)>, parts: usize) -> Option<LiabilitiesTree> {
+        LiabilitiesTree::new_with_rng(accounts, parts, &mut OsRng)
+    }
+
+    /// Same as [new](struct.LiabilitiesTree.html#method.new), drawing salts, splits and the shuffle from `rng`. `rng` must be cryptographically secure outside of tests, or the salts and leaf order become predictable.
+    pub fn new_with_rng<R: RngCore>(
+        accounts: Vec<(Vec<u8>, u64)>,
+        parts: usize,
+        rng: &mut R,
+    ) -> Option<LiabilitiesTree> {
+        let parts = parts.max(1);
+        let mut users = HashMap::with_capacity(accounts.len());
+        let mut leaves = Vec::with_capacity(accounts.len() * parts);
+        for (user_id, balance) in accounts {
+            let mut salt = [0u8; SALT_LEN];
+            rng.fill_bytes(&mut salt);
+            for (part, value) in split(balance, parts, rng).into_iter().enumerate() {
+                let data = part_id(&user_id, &salt, part as u32);
+                leaves.push((user_id.clone(), part, SumNode::leaf(&data, value)));
+            }
+            if users.insert(user_id, (salt, vec![0; parts])).is_some() {
+                return None;
+            }
+        }
+        // Fisher–Yates, so every order is equally likely.
+        for i in (1..leaves.len()).rev() {
+            let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+            leaves.swap(i, j);
+        }
+        for (index, (user_id, part, _)) in leaves.iter().enumerate() {
+            users.get_mut(user_id)?.1[*part] = index;
+        }
+        let tree = SumTree::from_nodes(leaves.into_iter().map(|(_, _, leaf)| leaf).collect())?;
+        Some(LiabilitiesTree { tree, users })
+    }
+
+    /// Returns the root, whose sum is the total liabilities, or `None` if there are no accounts.
+    pub fn root(&self) -> Option<&SumNode> {
+        self.tree.root()
+    }
+
+    /// Returns the underlying sum tree.
+    pub fn tree(&self) -> &SumTree {
+        &self.tree
+    }
+
+    /// Generates the proof for `user_id`.
+    pub fn proof(&self, user_id: &[u8]) -> Option<LiabilityProof> {
+        let (salt, indices) = self.users.get(user_id)?;
+        let parts = indices
+            .iter()
+            .map(|&index| Some((self.tree.leaf(index)?.sum, self.tree.proof(index)?)))
+            .collect::<Option<Vec<_>>>()?;
+        Some(LiabilityProof { salt: *salt, parts })
+    }
+}
+
+/// Splits `balance` into `parts` random values adding up to it.
+fn split<R: RngCore>(balance: u64, parts: usize, rng: &mut R) -> Vec<u64> {
+    let mut cuts: Vec<u64> = (1..parts)
+        .map(|_| match balance.checked_add(1) {
+            Some(bound) => rng.next_u64() % bound,
+            None => rng.next_u64(),
+        })
+        .collect();
+    cuts.sort_unstable();
+    let mut values = Vec::with_capacity(parts);
+    let mut previous = 0;
+    for cut in cuts {
+        values.push(cut - previous);
+        previous = cut;
+    }
+    values.push(balance - previous);
+    values
+}
@@ -18,10 +18,8 @@
 //! let rootNode = tree.build_tree(data);
 //! let root_hash = rootNode.root_node().unwrap().hash();
 //! assert_eq!(
-//!     String::from_utf8(root_hash),
-//!     Ok(String::from(
-//!         "725367a8cee028cf3360c19d20c175733191562b01e60d093e81d8570e865f81"
-//!     ))
+//!     root_hash.to_string(),
+//!     "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
 //! );
 //! ```
 //! 
@@ -33,21 +31,160 @@
 //! let rootNode = tree.build_tree(data);
 //! let root_hash = rootNode.root_node().unwrap().hash();
 //! assert_eq!(
-//!     String::from_utf8(root_hash),
-//!     Ok(String::from(
-//!         "725367a8cee028cf3360c19d20c175733191562b01e60d093e81d8570e865f81"
-//!     ))
+//!     root_hash.to_string(),
+//!     "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
 //! );
 //! let path = tree.includes(
-//! "d9aa89fdd15ad5c41d9c128feffe9e07dc828b83f85296f7f42bda506821300e".as_bytes(),
+//! &"635fd701970453575a3f84cb82631929f1a96dbeb7c45f983ff648698a5ca8f4".parse().unwrap(),
 //! );
 //! println!("{}",path);
 //! ```
 
 #![allow(non_snake_case)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use crypto::{digest::Digest, sha2::Sha256};
+extern crate alloc;
+
+#[cfg(feature = "std")]
 use std::collections::VecDeque;
+#[cfg(feature = "std")]
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "std")]
+mod hashing;
+#[cfg(feature = "std")]
+pub mod accel;
+#[cfg(feature = "arkworks")]
+pub mod arkworks;
+#[cfg(feature = "std")]
+pub mod bitcoin;
+#[cfg(feature = "std")]
+pub mod bittorrent;
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod built;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod cdc;
+#[cfg(feature = "circom")]
+pub mod circom;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod codec;
+#[cfg(feature = "std")]
+pub mod commit_reveal;
+#[cfg(feature = "std")]
+pub mod concurrent;
+#[cfg(feature = "std")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod cursor;
+#[cfg(feature = "rayon")]
+pub mod determinism;
+#[cfg(feature = "std")]
+pub mod disclosure;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod file;
+#[cfg(feature = "std")]
+pub mod forest;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+#[cfg(feature = "std")]
+pub mod git;
+pub mod hash;
+#[cfg(feature = "std")]
+pub mod history;
+#[cfg(feature = "std")]
+pub mod incremental;
+#[cfg(feature = "std")]
+pub mod indexed;
+#[cfg(feature = "ipld")]
+pub mod ipld;
+#[cfg(feature = "std")]
+pub mod jmt;
+#[cfg(feature = "std")]
+pub mod liabilities;
+pub mod limits;
+#[cfg(feature = "std")]
+pub mod log;
+#[cfg(feature = "std")]
+pub mod manifest;
+#[cfg(feature = "std")]
+pub mod map;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod mmr;
+#[cfg(feature = "std")]
+pub mod mst;
+#[cfg(feature = "std")]
+pub mod multiproof;
+#[cfg(feature = "std")]
+pub mod nmt;
+#[cfg(feature = "std")]
+pub mod notify;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod progress;
+pub mod proof;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "rekor")]
+pub mod rekor;
+#[cfg(feature = "mpt")]
+pub mod mpt;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod root_chain;
+#[cfg(feature = "std")]
+pub mod root_history;
+#[cfg(feature = "std")]
+pub mod rotation;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "std")]
+pub mod signed;
+#[cfg(feature = "std")]
+pub mod smt;
+#[cfg(feature = "std")]
+pub mod ssz;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod store;
+#[cfg(feature = "std")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod sum;
+#[cfg(feature = "std")]
+pub mod sync;
+#[cfg(feature = "std")]
+pub mod tendermint;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(feature = "thex")]
+pub mod thex;
+#[cfg(feature = "std")]
+pub mod transcript;
+#[cfg(feature = "std")]
+pub mod trillian;
+#[cfg(feature = "std")]
+pub mod typed;
+#[cfg(feature = "std")]
+pub mod utreexo;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod zkvm;
+
+pub use error::MerkleError;
+pub use hash::Hash;
 
 
 
@@ -65,13 +202,20 @@ use std::collections::VecDeque;
 /// let rootNode = tree.build_tree(data);
 /// let root_hash = rootNode.root_node().unwrap().hash();
 /// 
-/// assert_eq!(String::from_utf8(root_hash), 
-///        Ok(String::from("725367a8cee028cf3360c19d20c175733191562b01e60d093e81d8570e865f81"))
+/// assert_eq!(root_hash.to_string(), 
+///        "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
 ///   );
 /// ```
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 pub struct MerkleTree {
     root_node: Option<Box<Node>>,
+    legacy: bool,
+    sort_leaves: bool,
+    limits: limits::Limits,
+    metrics: Option<metrics::MetricsHandle>,
+    leaf_meta: std::collections::HashMap<usize, Vec<u8>>,
+    leaf_cache: Option<cache::LeafCache>,
 }
 
 /// [Node](struct.Node.html) is the struct to hold each node of the Merkle Tree.
@@ -81,13 +225,15 @@ pub struct MerkleTree {
 /// * `right_node`: Holds node of the right child. Can be `None` if it does not have a left child or is a leaf node.
 ///
 /// * `hash`: Contains the hash content of the node. Formulated as Hash(left_node.hash,right_node.hash)
+#[cfg(feature = "std")]
 #[derive(Debug, Clone,PartialEq)]
 pub struct Node {
     left_node: Option<Box<Node>>,
     right_node: Option<Box<Node>>,
-    hash: Vec<u8>,
+    hash: Hash,
 }
 
+#[cfg(feature = "std")]
 impl Node {
 
     /// Function to create new instance of [Node](struct.Node.html).
@@ -95,35 +241,26 @@ impl Node {
     /// Accepts: Hash, Optional leftNode, Optional rightNode.
     /// 
     /// Return type [Node](struct.Node.html)
-    pub fn new(hash: Vec<u8>, leftNode: Option<Box<Node>>, rightNode: Option<Box<Node>>) -> Node {
-        return Node {
+    pub fn new(hash: Hash, leftNode: Option<Box<Node>>, rightNode: Option<Box<Node>>) -> Node {
+        Node {
             left_node: leftNode,
             right_node: rightNode,
             hash,
-        };
+        }
     }
     /// Returns the Left Child of the Current Node which is of type [Node](struct.Node.html). Returns `None` if left child does note exist.
     pub fn left_node(&self) -> Option<Node> {
-        let root_node = &self.left_node;
-        match root_node {
-            Some(node) => return Some(*node.left_node.clone().unwrap()),
-            None => return None,
-        }
+        self.left_node.as_ref().map(|node| *node.clone())
     }
     
     /// Returns the Right Child of the Current Node which is of type [Node](struct.Node.html). Returns `None` if right child does note exist.
     pub fn right_node(&self) -> Option<Node> {
-        let root_node = &self.right_node;
-        match root_node {
-            Some(node) => return Some(*node.right_node.clone().unwrap()),
-            None => return None,
-        }
+        self.right_node.as_ref().map(|node| *node.clone())
     }
 
-    /// Return the Hash Value of the current [Node](struct.Node.html) as type `Vec<u8>`
-    pub fn hash(&self) -> Vec<u8> {
-        let hash = &self.hash;
-        return hash.to_vec();
+    /// Return the Hash Value of the current [Node](struct.Node.html) as a [Hash](hash/struct.Hash.html)
+    pub fn hash(&self) -> Hash {
+        self.hash
     }
 
 
@@ -137,6 +274,99 @@ impl Node {
     }
 }
 
+/// Free list of boxed nodes for a build to reuse before allocating, chained through their left children.
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct NodePool {
+    free: Option<Box<Node>>,
+}
+
+#[cfg(feature = "std")]
+impl NodePool {
+    /// Takes apart the tree below `root`, keeping every node for reuse.
+    fn recycle(root: Option<Box<Node>>) -> NodePool {
+        let mut pool = NodePool::default();
+        let mut pending: Vec<Box<Node>> = root.into_iter().collect();
+        while let Some(mut node) = pending.pop() {
+            pending.extend(node.left_node.take());
+            pending.extend(node.right_node.take());
+            node.left_node = pool.free.take();
+            pool.free = Some(node);
+        }
+        pool
+    }
+
+    /// Moves `node` into a pooled box, or a new one if the pool is empty.
+    fn boxed(&mut self, node: Node) -> Box<Node> {
+        match self.free.take() {
+            Some(mut free) => {
+                self.free = free.left_node.take();
+                *free = node;
+                free
+            }
+            None => Box::new(node),
+        }
+    }
+}
+
+/// Unlinks the free list one node at a time, as dropping it whole would recurse once per node. Nodes left over from a larger tree still hold its hashes, and are wiped with the `zeroize` feature.
+#[cfg(feature = "std")]
+impl Drop for NodePool {
+    fn drop(&mut self) {
+        while let Some(mut node) = self.free.take() {
+            self.free = node.left_node.take();
+            #[cfg(feature = "zeroize")]
+            zeroize::Zeroize::zeroize(&mut node.hash);
+        }
+    }
+}
+
+/// Overwrites every node hash and empties the tree. Hashes of low-entropy leaves can be brute-forced back to the leaves, so they are as sensitive as the data.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for MerkleTree {
+    fn zeroize(&mut self) {
+        let mut stack: Vec<&mut Node> = self.root_node.as_deref_mut().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            node.hash.zeroize();
+            stack.extend(node.left_node.as_deref_mut());
+            stack.extend(node.right_node.as_deref_mut());
+        }
+        self.root_node = None;
+        self.leaf_meta.values_mut().for_each(|meta| meta.zeroize());
+        self.leaf_meta.clear();
+        if let Some(cache) = &mut self.leaf_cache {
+            cache.zeroize();
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for MerkleTree {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for MerkleTree {}
+
+/// Builds a tree from the newline-delimited records of the file at a path, for quick commitments to datasets on disk; [from_path_delimited](struct.MerkleTree.html#method.from_path_delimited) takes any delimiter.
+/// ```no_run
+/// use rs_merkletree::MerkleTree;
+/// use std::path::Path;
+/// let tree = MerkleTree::try_from(Path::new("dataset.txt")).unwrap();
+/// println!("{}", tree.root().unwrap());
+/// ```
+#[cfg(feature = "std")]
+impl TryFrom<&std::path::Path> for MerkleTree {
+    type Error = MerkleError;
+
+    fn try_from(path: &std::path::Path) -> Result<MerkleTree, MerkleError> {
+        MerkleTree::from_path_delimited(path, b'\n')
+    }
+}
+
+#[cfg(feature = "std")]
 impl MerkleTree {
     /// Function to build a new instance of [MerkleTree](struct.MerkleTree.html)
     /// ```
@@ -144,32 +374,98 @@ impl MerkleTree {
     /// let mut tree = MerkleTree::new(None);
     /// ```
     pub fn new(rootNode: Option<Box<Node>>) -> MerkleTree {
-        println!("Building Merkle Tree");
-        return MerkleTree {
+        MerkleTree {
             root_node: rootNode,
-        };
+            legacy: false,
+            sort_leaves: false,
+            limits: limits::Limits::UNLIMITED,
+            metrics: None,
+            leaf_meta: std::collections::HashMap::new(),
+            leaf_cache: None,
+        }
+    }
+
+    /// Function to build a new instance of [MerkleTree](struct.MerkleTree.html) using the legacy construction.
+    ///
+    /// Trees created with [new](struct.MerkleTree.html#method.new) prefix leaf data with [LEAF_TAG](proof/constant.LEAF_TAG.html) and inner nodes with [NODE_TAG](proof/constant.NODE_TAG.html) before hashing, so an inner node can never be presented as a leaf. Legacy trees hash both the same way, as releases before domain separation did; use them only to reproduce old roots.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new_legacy(None);
+    /// tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+    /// assert_eq!(
+    ///     tree.root_node().unwrap().hash().to_string(),
+    ///     "725367a8cee028cf3360c19d20c175733191562b01e60d093e81d8570e865f81"
+    /// );
+    /// ```
+    pub fn new_legacy(rootNode: Option<Box<Node>>) -> MerkleTree {
+        MerkleTree {
+            root_node: rootNode,
+            legacy: true,
+            sort_leaves: false,
+            limits: limits::Limits::UNLIMITED,
+            metrics: None,
+            leaf_meta: std::collections::HashMap::new(),
+            leaf_cache: None,
+        }
+    }
+
+    /// Returns `true` if the tree uses the legacy construction without domain tags.
+    pub fn is_legacy(&self) -> bool {
+        self.legacy
+    }
+
+    /// Function to make [build_tree](struct.MerkleTree.html#method.build_tree) sort leaf hashes before building.
+    ///
+    /// The root then depends only on the set of leaves, not on their order, giving a canonical commitment to a set. Proof indices refer to the sorted order; use [leaf_index](struct.MerkleTree.html#method.leaf_index) to find a leaf.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut first = MerkleTree::new(None).with_sorted_leaves();
+    /// let mut second = MerkleTree::new(None).with_sorted_leaves();
+    /// first.build_tree(vec!["Hello", "World", "From", "Rust"]);
+    /// second.build_tree(vec!["Rust", "From", "World", "Hello"]);
+    /// assert_eq!(first.root_node(), second.root_node());
+    /// ```
+    pub fn with_sorted_leaves(mut self) -> MerkleTree {
+        self.sort_leaves = true;
+        self
+    }
+
+    /// Returns `true` if leaf hashes are sorted before building.
+    pub fn sorts_leaves(&self) -> bool {
+        self.sort_leaves
     }
 
     /// Returns the `RootNode` which is of type [Node](struct.Node.html)
     /// 
     /// Returns `None` if the `RootNode` does not exist
     pub fn root_node(&self) -> Option<Node> {
-        let root = &self.root_node;
-        match root {
-            Some(node) => return Some(*node.clone()),
-            None => return None,
-        }
+        self.root_node.as_ref().map(|node| *node.clone())
     }
 
     /// Helper function to build the first layer of nodes.
     /// 
     /// This involves taking in the data provided by user and converting it to the respective hashes and form the leaf nodes of the merkle tree
-    fn build_leaves(&self, data: Vec<&str>) -> Vec<Node> {
+    fn build_leaves(
+        &mut self,
+        data: Vec<&[u8]>,
+        recorder: &mut Option<&mut dyn transcript::HashRecorder>,
+    ) -> Vec<Node> {
         let size = data.len();
+        let legacy = self.legacy;
         let mut ground_layer: Vec<Node> = Vec::new();
         let mut i = 0;
         while i < size {
-            let current_hash = self.hasher_leaf(data[i]);
+            // A transcript must see every leaf hashed, so the cache is bypassed while recording.
+            let current_hash = match &mut self.leaf_cache {
+                Some(cache) if recorder.is_none() => cache.hash(data[i], |data| {
+                    if legacy {
+                        proof::legacy_leaf_hash(data)
+                    } else {
+                        proof::leaf_hash(data)
+                    }
+                }),
+                _ => self.hasher_leaf(data[i], recorder),
+            };
             let current_node = Node::new(current_hash, None, None);
             ground_layer.push(current_node);
             i += 1;
@@ -179,75 +475,98 @@ impl MerkleTree {
 
     ///Function to hash leaf data.
     /// Specific to leaf nodes as they are always singluar data hashes.
-    fn hasher_leaf(&self, data: &str) -> Vec<u8> {
-        let mut hasher = Sha256::new();
-        hasher.input(data.as_bytes());
-        let hash: Vec<u8> = hasher.result_str().as_bytes().to_vec();
-        return hash;
+    fn hasher_leaf(
+        &self,
+        data: &[u8],
+        recorder: &mut Option<&mut dyn transcript::HashRecorder>,
+    ) -> Hash {
+        let hash = if self.legacy {
+            proof::legacy_leaf_hash(data)
+        } else {
+            proof::leaf_hash(data)
+        };
+        if let Some(recorder) = recorder {
+            let tag: &[u8] = if self.legacy { &[] } else { &[proof::LEAF_TAG] };
+            recorder.record(transcript::HashKind::Leaf, &[tag, data].concat(), &hash.to_hex_bytes());
+        }
+        hash
     }
 
     ///Function to hash any level other than the leaf.
-    fn hasher_nodes(&self, left_data: Vec<u8>, right_data: Vec<u8>) -> Vec<u8> {
-        let mut hasher = Sha256::new();
-        hasher.input(left_data.as_slice());
-        hasher.input(right_data.as_slice());
-        let hash = hasher.result_str().as_bytes().to_vec();
+    fn hasher_nodes(
+        &self,
+        left_data: &Hash,
+        right_data: &Hash,
+        recorder: &mut Option<&mut dyn transcript::HashRecorder>,
+    ) -> Hash {
+        let hash = if self.legacy {
+            proof::legacy_node_hash(left_data, right_data)
+        } else {
+            proof::node_hash(left_data, right_data)
+        };
+        if let Some(recorder) = recorder {
+            let tag: &[u8] = if self.legacy { &[] } else { &[proof::NODE_TAG] };
+            let input = [tag, &left_data.to_hex_bytes(), &right_data.to_hex_bytes()].concat();
+            recorder.record(transcript::HashKind::Node, &input, &hash.to_hex_bytes());
+        }
         hash
     }
 
     ///Helper function to build the intermediate levels between the root and the leaves
-    fn build_upper_layer(&self, leaves: Vec<Node>) -> Vec<Node> {
-        let level = leaves.len();
-        let mut layer: Vec<Node> = Vec::new();
-
-        let mut i = 0;
-        while i < level {
-            if i + 1 >= level {
-                let current_hash =
-                    self.hasher_nodes(leaves[i].hash.clone(), leaves[i].hash.clone());
-
-                let current_node = Node::new(current_hash, Some(Box::new(leaves[i].clone())), None);
-                layer.push(current_node);
-                i = i + 1;
-            } else {
-                let current_hash =
-                    self.hasher_nodes(leaves[i].hash.clone(), leaves[i + 1].hash.clone());
-
-                let current_node = Node::new(
-                    current_hash,
-                    Some(Box::new(leaves[i].clone())),
-                    Some(Box::new(leaves[i + 1].clone())),
-                );
-                layer.push(current_node);
-                i = i + 2;
-            }
-        }
-        let size = layer.len();
-        for j in 0..size {
-            println!(
-                "After build_upper_layer: {:?}",
-                String::from_utf8(layer[j].hash.clone())
-            );
+    ///
+    /// The children are moved into boxes from `pool`, never copied.
+    fn build_upper_layer(
+        &self,
+        leaves: Vec<Node>,
+        recorder: &mut Option<&mut dyn transcript::HashRecorder>,
+        pool: &mut NodePool,
+    ) -> Vec<Node> {
+        let mut layer: Vec<Node> = Vec::with_capacity(leaves.len().div_ceil(2));
+        let mut nodes = leaves.into_iter();
+        while let Some(left) = nodes.next() {
+            let current_node = match nodes.next() {
+                Some(right) => {
+                    let current_hash = self.hasher_nodes(&left.hash, &right.hash, recorder);
+                    Node::new(current_hash, Some(pool.boxed(left)), Some(pool.boxed(right)))
+                }
+                None => {
+                    let current_hash = self.hasher_nodes(&left.hash, &left.hash, recorder);
+                    Node::new(current_hash, Some(pool.boxed(left)), None)
+                }
+            };
+            layer.push(current_node);
         }
         layer
     }
 
-    ///Helper function to build the root node
-    fn build_root(&self, leftNode: Node, rightNode: Node) -> Node {
-        println!(
-            "Root left values being hashed:{:?}",
-            String::from_utf8(leftNode.clone().hash)
-        );
-        println!(
-            "Root left values being hashed:{:?}",
-            String::from_utf8(rightNode.clone().hash)
-        );
-        let hash = self.hasher_nodes(leftNode.clone().hash, rightNode.clone().hash);
-        return Node {
-            left_node: Some(Box::new(leftNode)),
-            right_node: Some(Box::new(rightNode)),
-            hash: hash,
-        };
+    /// Function to set the [Limits](limits/struct.Limits.html) enforced by [try_build_tree](struct.MerkleTree.html#method.try_build_tree).
+    pub fn with_limits(mut self, limits: limits::Limits) -> MerkleTree {
+        self.limits = limits;
+        self
+    }
+
+    /// Function to attach [Metrics](metrics/trait.Metrics.html) that are told of every build and proof of this tree and its clones.
+    pub fn with_metrics(mut self, metrics: std::sync::Arc<dyn metrics::Metrics>) -> MerkleTree {
+        self.metrics = Some(metrics::MetricsHandle(metrics));
+        self
+    }
+
+    /// Function to keep leaf hashes in a [LeafCache](cache/struct.LeafCache.html) across builds, so rebuilding over mostly unchanged data only hashes the new leaves.
+    ///
+    /// [build_tree_parallel](struct.MerkleTree.html#method.build_tree_parallel) and recorded builds do not use the cache.
+    pub fn with_leaf_cache(mut self) -> MerkleTree {
+        self.leaf_cache = Some(cache::LeafCache::new());
+        self
+    }
+
+    /// Returns the leaf cache, if the tree was created [with_leaf_cache](struct.MerkleTree.html#method.with_leaf_cache).
+    pub fn leaf_cache(&self) -> Option<&cache::LeafCache> {
+        self.leaf_cache.as_ref()
+    }
+
+    /// Returns the limits enforced by [try_build_tree](struct.MerkleTree.html#method.try_build_tree); unlimited unless set.
+    pub fn limits(&self) -> &limits::Limits {
+        &self.limits
     }
 
     ///Main Function to build the Merkle Tree
@@ -255,47 +574,377 @@ impl MerkleTree {
     /// Parameters are the direct data provided by user. currently accepts `Vec<&str>` as input.
     /// Returns type [MerkleTree](struct.MerkleTree.html)
     pub fn build_tree(&mut self, data: Vec<&str>) -> &MerkleTree {
-        let mut leaves: Vec<Node> = self.build_leaves(data);
+        let never = progress::CancellationToken::new();
+        self.build_tree_with_progress(data, |_| {}, &never)
+            .expect("build without cancellation cannot be cancelled")
+    }
+
+    ///Function to build the Merkle Tree into a [BuiltTree](built/struct.BuiltTree.html), whose root and depth are always available
+    ///
+    /// Consumes the tree with its settings, checks `data` against its [limits](struct.MerkleTree.html#method.with_limits) like [try_build_tree](struct.MerkleTree.html#method.try_build_tree), and fails with [MerkleError::EmptyTree](enum.MerkleError.html) if `data` is empty.
+    pub fn build(mut self, data: Vec<&str>) -> Result<built::BuiltTree, MerkleError> {
+        if data.is_empty() {
+            return Err(MerkleError::EmptyTree);
+        }
+        self.try_build_tree(data)?;
+        Ok(built::BuiltTree::new(self))
+    }
+
+    ///Function to build the Merkle Tree from untrusted data
+    ///
+    /// Same as [build_tree](struct.MerkleTree.html#method.build_tree), but checks `data` against the tree's [limits](struct.MerkleTree.html#method.with_limits) before hashing anything and leaves the tree unchanged if they are exceeded.
+    pub fn try_build_tree(&mut self, data: Vec<&str>) -> Result<&MerkleTree, MerkleError> {
+        self.limits.check_leaves(&data)?;
+        Ok(self.build_tree(data))
+    }
+
+    ///Function to compute the Root Hash over `data` without building the tree
+    ///
+    /// Returns the root [build_tree](struct.MerkleTree.html#method.build_tree) would give with this tree's settings, or `None` for no data; the tree itself is left unchanged. No nodes are created: up to [SMALL_TREE_LEAVES](proof/constant.SMALL_TREE_LEAVES.html) leaves are hashed in a stack buffer without allocating, which suits trees built per request.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let tree = MerkleTree::new(None);
+    /// let root = tree.root_of(&["Hello", "World", "From"]).unwrap();
+    ///
+    /// let mut built = MerkleTree::new(None);
+    /// built.build_tree(vec!["Hello", "World", "From"]);
+    /// assert_eq!(root, built.root_node().unwrap().hash());
+    /// ```
+    pub fn root_of(&self, data: &[&str]) -> Option<Hash> {
+        let mut small = [[0u8; 64]; proof::SMALL_TREE_LEAVES];
+        let mut large = Vec::new();
+        let nodes: &mut [[u8; 64]] = if data.len() <= proof::SMALL_TREE_LEAVES {
+            &mut small[..data.len()]
+        } else {
+            large.resize(data.len(), [0u8; 64]);
+            &mut large
+        };
+        for (node, data) in nodes.iter_mut().zip(data) {
+            *node = if self.legacy {
+                proof::legacy_leaf_hash(data.as_bytes())
+            } else {
+                proof::leaf_hash(data.as_bytes())
+            }
+            .to_hex_bytes();
+        }
+        if self.sort_leaves {
+            nodes.sort_unstable();
+        }
+        let root = if self.legacy {
+            proof::fold_root(&proof::HexSha256, nodes)
+        } else {
+            proof::fold_root(&proof::TaggedHexSha256, nodes)
+        };
+        root.map(|root| Hash::decode(&root))
+    }
+
+    ///Function to rebuild the Merkle Tree from new data, reusing the nodes of the current tree
+    ///
+    /// Produces the same tree as [build_tree](struct.MerkleTree.html#method.build_tree), but the current nodes are recycled for the new ones instead of being freed and allocated again. Services recomputing a tree of about the same size every few seconds then barely touch the allocator.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+    /// tree.rebuild(vec!["Hello", "World", "From", "Go"]);
+    ///
+    /// let mut fresh = MerkleTree::new(None);
+    /// fresh.build_tree(vec!["Hello", "World", "From", "Go"]);
+    /// assert_eq!(tree.root_node(), fresh.root_node());
+    /// ```
+    pub fn rebuild(&mut self, data: Vec<&str>) -> &MerkleTree {
+        let mut pool = NodePool::recycle(self.root_node.take());
+        let never = progress::CancellationToken::new();
+        let data = data.iter().map(|leaf| leaf.as_bytes()).collect();
+        self.build_tree_with(data, |_| {}, &never, None, &mut pool)
+            .expect("build without cancellation cannot be cancelled")
+    }
 
-        for i in 0..leaves.len() {
-            println!("Leaf Value:{:?}", String::from_utf8(leaves[i].hash.clone()));
+    ///Function to build the Merkle Tree with metadata attached to each leaf
+    ///
+    /// Each item is the leaf data and its metadata. The metadata is stored next to the leaf but never hashed, so it does not affect the root or any proof; read it back with [leaf_meta](struct.MerkleTree.html#method.leaf_meta). In sorted trees it follows its leaf to the sorted position.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree_with_meta(vec![("Hello", b"id-1".to_vec()), ("World", b"id-2".to_vec())]);
+    /// assert_eq!(tree.leaf_meta(1), Some(&b"id-2"[..]));
+    ///
+    /// let mut plain = MerkleTree::new(None);
+    /// plain.build_tree(vec!["Hello", "World"]);
+    /// assert_eq!(tree.root_node(), plain.root_node());
+    /// ```
+    pub fn build_tree_with_meta(&mut self, data: Vec<(&str, Vec<u8>)>) -> &MerkleTree {
+        let (data, mut meta): (Vec<&str>, Vec<Vec<u8>>) = data.into_iter().unzip();
+        if self.sort_leaves {
+            // Same stable sort by leaf hash as the build, so equal leaves keep their metadata in order.
+            let mut hashes: Vec<(Hash, Vec<u8>)> = data
+                .iter()
+                .map(|leaf| self.hasher_leaf(leaf.as_bytes(), &mut None))
+                .zip(meta)
+                .collect();
+            hashes.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            meta = hashes.into_iter().map(|(_, meta)| meta).collect();
+        }
+        self.build_tree(data);
+        self.leaf_meta = meta.into_iter().enumerate().collect();
+        self
+    }
+
+    ///Function to attach metadata to the leaf at `index`, replacing any it had
+    ///
+    /// The metadata is not hashed. Returns `false`, attaching nothing, if there is no leaf at `index`. Rebuilding the tree drops all metadata.
+    pub fn set_leaf_meta(&mut self, index: usize, meta: Vec<u8>) -> bool {
+        if self.node_at(0, index).is_none() {
+            return false;
         }
+        self.leaf_meta.insert(index, meta);
+        true
+    }
+
+    ///Function to get the metadata of the leaf at `index`
+    ///
+    /// Returns `None` if the leaf has no metadata or does not exist.
+    pub fn leaf_meta(&self, index: usize) -> Option<&[u8]> {
+        self.leaf_meta.get(&index).map(Vec::as_slice)
+    }
+
+    /// Builds the tree over raw leaf data, for trees whose leaves are not strings.
+    fn build_tree_bytes(&mut self, data: Vec<&[u8]>) -> &MerkleTree {
+        let never = progress::CancellationToken::new();
+        self.build_tree_with(data, |_| {}, &never, None, &mut NodePool::default())
+            .expect("build without cancellation cannot be cancelled")
+    }
+
+    ///Function to build the Merkle Tree while reporting progress
+    ///
+    /// `on_progress` is called after every layer (and periodically while hashing leaves) with a [Progress](progress/struct.Progress.html). `cancel` is checked at the same points; once it is cancelled the build stops, the tree is left unchanged and [Cancelled](progress/struct.Cancelled.html) is returned.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// use rs_merkletree::progress::CancellationToken;
+    /// let mut levels = Vec::new();
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree_with_progress(vec!["a", "b", "c"], |p| levels.push(p.level), &CancellationToken::new())
+    ///     .unwrap();
+    /// assert_eq!(levels, vec![0, 1, 2]);
+    /// ```
+    pub fn build_tree_with_progress<F: FnMut(progress::Progress)>(
+        &mut self,
+        data: Vec<&str>,
+        on_progress: F,
+        cancel: &progress::CancellationToken,
+    ) -> Result<&MerkleTree, progress::Cancelled> {
+        let data = data.iter().map(|leaf| leaf.as_bytes()).collect();
+        self.build_tree_with(data, on_progress, cancel, None, &mut NodePool::default())
+    }
+
+    ///Function to build the Merkle Tree while recording every hash
+    ///
+    /// Same as [build_tree](struct.MerkleTree.html#method.build_tree), but writes each hash input and output, then the root, to `transcript` so the root can be independently replayed. See [transcript](transcript/index.html).
+    pub fn build_tree_recorded<W: std::io::Write>(
+        &mut self,
+        data: Vec<&str>,
+        transcript: &mut transcript::Transcript<W>,
+    ) -> std::io::Result<&MerkleTree> {
+        let never = progress::CancellationToken::new();
+        let data = data.iter().map(|leaf| leaf.as_bytes()).collect();
+        self.build_tree_with(data, |_| {}, &never, Some(&mut *transcript), &mut NodePool::default())
+            .expect("build without cancellation cannot be cancelled");
+        if let Some(root) = &self.root_node {
+            transcript.finish(&root.hash.to_hex_bytes())?;
+        }
+        Ok(self)
+    }
+
+    ///Function to build the tree like [build_tree](struct.MerkleTree.html#method.build_tree), hashing leaves and each level on all cores with [rayon](https://docs.rs/rayon)
+    ///
+    /// Every node is computed from the same children as in the sequential build and the levels keep their order, so the root is bit-identical to that of [build_tree](struct.MerkleTree.html#method.build_tree); [determinism](determinism/index.html) checks this. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn build_tree_parallel(&mut self, data: Vec<&str>) -> &MerkleTree {
+        use rayon::prelude::*;
+        let started = self.metrics.as_ref().map(|_| std::time::Instant::now());
+        let total_leaves = data.len();
+        let mut leaves: Vec<Node> = data
+            .par_iter()
+            .map(|data| Node::new(self.hasher_leaf(data.as_bytes(), &mut None), None, None))
+            .collect();
+        if self.sort_leaves {
+            leaves.sort_by(|a, b| a.hash.as_bytes().cmp(b.hash.as_bytes()));
+        }
+        // Each layer is consumed and its nodes are moved into their parents, so no subtree is ever copied.
+        let upper_layer = |layer: Vec<Node>| -> Vec<Node> {
+            layer
+                .into_par_iter()
+                .chunks(2)
+                .map(|mut pair| {
+                    let right = (pair.len() == 2).then(|| pair.pop()).flatten();
+                    let left = pair.pop().expect("chunks are not empty");
+                    let hash = match &right {
+                        Some(right) => self.hasher_nodes(&left.hash, &right.hash, &mut None),
+                        None => self.hasher_nodes(&left.hash, &left.hash, &mut None),
+                    };
+                    Node::new(hash, Some(Box::new(left)), right.map(Box::new))
+                })
+                .collect()
+        };
+
+        // Same shape as the sequential build: a lone leaf is still hashed once, with itself.
+        let mut layer = upper_layer(leaves);
+        let mut hashes = total_leaves + layer.len();
+        while layer.len() > 1 {
+            layer = upper_layer(layer);
+            hashes += layer.len();
+        }
+        self.root_node = layer.pop().map(Box::new);
+        self.leaf_meta.clear();
+        #[cfg(feature = "debug-validate")]
+        debug_assert_eq!(self.validate(), Ok(()), "built tree is inconsistent");
+        if let (Some(metrics), Some(started)) = (&self.metrics, started) {
+            metrics.0.hashes_computed(hashes as u64);
+            metrics.0.build_completed(total_leaves, started.elapsed());
+        }
+        self
+    }
+
+    ///Function to build the tree like [build_tree_parallel](struct.MerkleTree.html#method.build_tree_parallel), on the threads of `parallelism` instead of rayon's global pool
+    ///
+    /// Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn build_tree_parallel_in(
+        &mut self,
+        data: Vec<&str>,
+        parallelism: &parallel::Parallelism,
+    ) -> &MerkleTree {
+        parallelism.install(|| {
+            self.build_tree_parallel(data);
+        });
+        self
+    }
+
+    ///Function to build the Merkle Tree from the records of `reader`, separated by `delimiter`
+    ///
+    /// Each record is hashed into its leaf as soon as it is read, so only the leaf hashes are held in memory, never the whole input. A trailing delimiter does not start an empty last record; records are otherwise taken exactly as read, so a `\r` before a `\n` delimiter stays part of the record. The tree's [limits](struct.MerkleTree.html#method.with_limits) are checked as records arrive, and no more than `max_leaf_size + 1` bytes of a record are ever buffered, so the `actual` size of a [LeafTooLarge](enum.MerkleError.html) error is only a lower bound. The [leaf cache](struct.MerkleTree.html#method.with_leaf_cache) is bypassed, and on error the tree is left unchanged.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree_from_reader(&b"Hello,World,From,Rust"[..], b',').unwrap();
+    ///
+    /// let mut expected = MerkleTree::new(None);
+    /// expected.build_tree(vec!["Hello", "World", "From", "Rust"]);
+    /// assert_eq!(tree.root(), expected.root());
+    /// ```
+    pub fn build_tree_from_reader<R: std::io::BufRead>(
+        &mut self,
+        mut reader: R,
+        delimiter: u8,
+    ) -> Result<&MerkleTree, MerkleError> {
+        use std::io::{BufRead, Read};
+        let started = self.metrics.as_ref().map(|_| std::time::Instant::now());
+        let mut leaves: Vec<Node> = Vec::new();
+        let mut record = Vec::new();
+        // One byte past the limit is enough to tell that a record is too large.
+        let max_read = (self.limits.max_leaf_size as u64).saturating_add(1);
+        loop {
+            record.clear();
+            if (&mut reader).take(max_read).read_until(delimiter, &mut record)? == 0 {
+                break;
+            }
+            if record.last() == Some(&delimiter) {
+                record.pop();
+            }
+            self.limits.check_leaf_count(leaves.len() + 1)?;
+            self.limits.check_leaf(leaves.len(), &record)?;
+            leaves.push(Node::new(self.hasher_leaf(&record, &mut None), None, None));
+        }
+        let never = progress::CancellationToken::new();
+        Ok(self
+            .build_from_leaves(leaves, |_| {}, &never, None, &mut NodePool::default(), started)
+            .expect("build without cancellation cannot be cancelled"))
+    }
 
-        let upper_layer = self.build_upper_layer(leaves.clone());
-        let mut size = upper_layer.len();
-        leaves.extend(upper_layer.clone());
-        // println!("Size:{}", size);
+    ///Function to build a Merkle Tree from the records of the file at `path`, separated by `delimiter`; see [build_tree_from_reader](struct.MerkleTree.html#method.build_tree_from_reader).
+    ///
+    /// [TryFrom](struct.MerkleTree.html#impl-TryFrom%3C%26Path%3E-for-MerkleTree) does the same with newline-delimited records.
+    pub fn from_path_delimited<P: AsRef<std::path::Path>>(path: P, delimiter: u8) -> Result<MerkleTree, MerkleError> {
+        let file = std::fs::File::open(path)?;
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree_from_reader(std::io::BufReader::new(file), delimiter)?;
+        Ok(tree)
+    }
+
+    fn build_tree_with<F: FnMut(progress::Progress)>(
+        &mut self,
+        data: Vec<&[u8]>,
+        mut on_progress: F,
+        cancel: &progress::CancellationToken,
+        mut recorder: Option<&mut dyn transcript::HashRecorder>,
+        pool: &mut NodePool,
+    ) -> Result<&MerkleTree, progress::Cancelled> {
+        let started = self.metrics.as_ref().map(|_| std::time::Instant::now());
+        let total_leaves = data.len();
+        let mut leaves: Vec<Node> = Vec::with_capacity(total_leaves);
+        let cached = recorder.is_none() && self.leaf_cache.is_some();
+        if let Some(cache) = self.leaf_cache.as_mut().filter(|_| cached) {
+            cache.start();
+        }
+        for batch in data.chunks(progress::LEAF_BATCH) {
+            cancel.check()?;
+            leaves.extend(self.build_leaves(batch.to_vec(), &mut recorder));
+            if leaves.len() < total_leaves {
+                on_progress(progress::Progress::new(leaves.len(), total_leaves, 0));
+            }
+        }
+        if let Some(cache) = self.leaf_cache.as_mut().filter(|_| cached) {
+            cache.finish();
+        }
+        on_progress(progress::Progress::new(total_leaves, total_leaves, 0));
+        self.build_from_leaves(leaves, on_progress, cancel, recorder, pool, started)
+    }
 
-        if size == 1 {
-            let root_node = leaves.pop().unwrap();
-            let root = Node::new(root_node.hash, root_node.left_node, root_node.right_node);
-            self.root_node = Some(Box::new(root));
-            return self;
+    /// Builds the layers above the hashed `leaves` and sets the root, boxing nodes from `pool` before allocating. `started` is when hashing the leaves began, for the metrics.
+    fn build_from_leaves<F: FnMut(progress::Progress)>(
+        &mut self,
+        mut leaves: Vec<Node>,
+        mut on_progress: F,
+        cancel: &progress::CancellationToken,
+        mut recorder: Option<&mut dyn transcript::HashRecorder>,
+        pool: &mut NodePool,
+        started: Option<std::time::Instant>,
+    ) -> Result<&MerkleTree, progress::Cancelled> {
+        let total_leaves = leaves.len();
+        let mut hashes = total_leaves;
+        if self.sort_leaves {
+            leaves.sort_by(|a, b| a.hash.as_bytes().cmp(b.hash.as_bytes()));
         }
-        while size > 2 {
-            let upper_layer = self.build_upper_layer(upper_layer.clone());
-            size = upper_layer.len();
-            leaves.extend(upper_layer);
+
+        // Hash pairs upward until a single node remains. A lone leaf is still hashed once, with itself.
+        let mut level = 1;
+        cancel.check()?;
+        let mut layer = self.build_upper_layer(leaves, &mut recorder, pool);
+        hashes += layer.len();
+        on_progress(progress::Progress::new(total_leaves, total_leaves, level));
+        while layer.len() > 1 {
+            cancel.check()?;
+            layer = self.build_upper_layer(layer, &mut recorder, pool);
+            hashes += layer.len();
+            level += 1;
+            on_progress(progress::Progress::new(total_leaves, total_leaves, level));
         }
-        let root = self.build_root(
-            leaves[leaves.len() - 2].clone(),
-            leaves[leaves.len() - 1].clone(),
-        );
-        // leaves.push(root);
-        println!("Final Tree: ");
-        for j in 0..leaves.len() {
-            println!("{:?}", String::from_utf8(leaves[j].clone().hash));
+        self.root_node = layer.pop().map(|root| pool.boxed(root));
+        self.leaf_meta.clear();
+        #[cfg(feature = "debug-validate")]
+        debug_assert_eq!(self.validate(), Ok(()), "built tree is inconsistent");
+        if let (Some(metrics), Some(started)) = (&self.metrics, started) {
+            metrics.0.hashes_computed(hashes as u64);
+            metrics.0.build_completed(total_leaves, started.elapsed());
         }
-        self.root_node = Some(Box::new(root));
-        return self;
+        Ok(self)
     }
 
     
     //Function to get the depth of the Tree
     /// 
-    /// Returns the depth of the tree from the Root Node to the leaf as [usize](https://doc.rust-lang.org/std/primitive.usize.html)
-    pub fn depth(&self)->usize{
-        self.root_node().unwrap().depth()
+    /// Returns the depth of the tree from the Root Node to the leaf as [usize](https://doc.rust-lang.org/std/primitive.usize.html), or `None` if the tree is empty; a [BuiltTree](built/struct.BuiltTree.html) from [build](struct.MerkleTree.html#method.build) cannot be, and returns it directly.
+    pub fn depth(&self)->Option<usize>{
+        self.root_node.as_deref().map(Node::depth)
     }
 
     ///Function to get the number of leaves in the tree
@@ -328,28 +977,556 @@ impl MerkleTree {
         count
     }
     
+    ///Function to get statistics of the tree
+    ///
+    /// Returns a [TreeStats](stats/struct.TreeStats.html) with node, leaf and padding counts, depth, hash algorithm and approximate memory footprint.
+    pub fn stats(&self) -> stats::TreeStats {
+        let mut stats = stats::TreeStats {
+            nodes: 0,
+            leaves: 0,
+            depth: self.height().map_or(0, |height| height + 1),
+            padding_nodes: 0,
+            hash_algorithm: if self.legacy { "sha256-legacy" } else { "sha256" },
+            memory_bytes: std::mem::size_of::<MerkleTree>(),
+        };
+        let mut stack: Vec<&Node> = self.root_node.as_deref().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            stats.nodes += 1;
+            stats.memory_bytes += std::mem::size_of::<Node>();
+            match (node.left_node.as_deref(), node.right_node.as_deref()) {
+                (None, None) => stats.leaves += 1,
+                (Some(left), Some(right)) => stack.extend([left, right]),
+                (Some(child), None) | (None, Some(child)) => {
+                    stats.padding_nodes += 1;
+                    stack.push(child);
+                }
+            }
+        }
+        stats
+    }
+
+    ///Function to export the tree as a Mermaid flowchart
+    ///
+    /// Returns a `flowchart TD` diagram, ready to paste into a fenced `mermaid` block of a Markdown document. Each node is labelled with the first [MERMAID_HASH_DIGITS](render/constant.MERMAID_HASH_DIGITS.html) hex digits of its hash. See [render](render/index.html).
+    pub fn to_mermaid(&self) -> String {
+        render::mermaid(self.root_node.as_deref())
+    }
+
+    ///Function to draw the tree as indented text
+    ///
+    /// [RenderOptions](render/struct.RenderOptions.html) set how much of each hash is shown, how deep to draw and the leaf data to annotate leaves with. Readable for trees of up to a few hundred leaves.
+    pub fn render(&self, options: &render::RenderOptions) -> String {
+        let leaves: std::collections::HashMap<Hash, &str> = options
+            .leaf_data
+            .unwrap_or_default()
+            .iter()
+            .map(|data| (self.hasher_leaf(data.as_bytes(), &mut None), *data))
+            .collect();
+        render::text(self.root_node.as_deref(), options, &leaves)
+    }
+
     ///Function to check whether a specififc hash is present in the tree.  Returns `True` if hash is present, else `False`.
     ///
-    /// Note: Input parameter should be a hash; not the actual string
-    pub fn includes(&self, data: &[u8]) -> bool {
+    /// Note: Input parameter should be a leaf hash (see [leaf_hash](proof/fn.leaf_hash.html)); not the actual string. Only leaves match, except in legacy trees where any node does.
+    pub fn includes(&self, data: &Hash) -> bool {
+        // Every candidate is compared in constant time and the walk never stops early, so the running time reveals neither whether nor where the hash was found.
+        let mut found = subtle::Choice::from(0);
         let mut data_array = VecDeque::new();
-        data_array.push_front(self.root_node.clone());
-        while data_array.len() > 0 {
-            let element = data_array.pop_front().unwrap();
-            // println!("ELement in include func:{:?}", element);
+        data_array.extend(self.root_node.as_deref());
+        while let Some(element) = data_array.pop_front() {
+            if element.left_node.is_none() || self.legacy {
+                found |= element.hash.as_bytes().ct_eq(data.as_bytes());
+            }
+            data_array.extend(element.left_node.as_deref());
+            data_array.extend(element.right_node.as_deref());
+        }
+        found.into()
+    }
 
-            if element.clone().unwrap().hash == data {
-                return true;
-            } else {
-                if element.clone().unwrap().right_node.is_some() {
-                    data_array.push_front(element.clone().unwrap().right_node);
+    ///Function to check the integrity of the tree.
+    ///
+    /// Recomputes every inner hash from the stored leaf hashes and checks it against the stored value, and checks the shape [build_tree](struct.MerkleTree.html#method.build_tree) produces: left children always present, every leaf at the same depth, and no more than [MAX_MULTIPROOF_HEIGHT](proof/constant.MAX_MULTIPROOF_HEIGHT.html) levels above the leaves. Nodes are visited with an explicit stack, as in [audit](struct.MerkleTree.html#method.audit), so even a forged, very deep tree cannot overflow the call stack. Run it on trees assembled with [Node::new](struct.Node.html#method.new) from disk or the network before serving proofs from them.
+    /// ```
+    /// use rs_merkletree::{Hash, MerkleError, MerkleTree, Node};
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["a", "b"]);
+    /// assert_eq!(tree.validate(), Ok(()));
+    ///
+    /// let root = tree.root_node().unwrap();
+    /// let forged = Node::new(
+    ///     root.hash(),
+    ///     root.left_node().map(Box::new),
+    ///     Some(Box::new(Node::new(Hash::leaf(b"forged"), None, None))),
+    /// );
+    /// let forged = MerkleTree::new(Some(Box::new(forged)));
+    /// assert_eq!(forged.validate(), Err(MerkleError::HashMismatch { depth: 0, index: 0 }));
+    /// ```
+    pub fn validate(&self) -> Result<(), MerkleError> {
+        self.audit().find_map(Result::err).map_or(Ok(()), Err)
+    }
+
+    ///Function to audit the tree: iterates over the leaves as `(index, leaf hash)` while checking every node above them, like [validate](struct.MerkleTree.html#method.validate).
+    ///
+    /// A leaf is only yielded once every node on its path has been recomputed from its children and found to match, so when the iterator ends without an error, the stored root is confirmed to be the root of the leaves yielded. On the first mismatch it yields the [MerkleError](enum.MerkleError.html) and stops. Nodes are checked as the iteration reaches them, so a long-lived tree can be audited without holding a second copy of its hashes.
+    /// ```
+    /// use rs_merkletree::{Hash, MerkleTree};
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["Hello", "World", "From"]);
+    /// let leaves: Vec<(usize, Hash)> = tree.audit().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(leaves.len(), 3);
+    /// assert_eq!(leaves[2].1, Hash::leaf(b"From"));
+    /// ```
+    pub fn audit(&self) -> impl Iterator<Item = Result<(usize, Hash), MerkleError>> + '_ {
+        let leaf_depth = self.height().unwrap_or(0);
+        let mut stack: Vec<(&Node, usize, usize)> =
+            self.root_node.as_deref().map(|root| (root, 0, 0)).into_iter().collect();
+        // Positions of leaves deeper than this no longer fit in a `usize`.
+        let too_tall = (leaf_depth > proof::MAX_MULTIPROOF_HEIGHT).then(|| {
+            stack.clear();
+            Err(MerkleError::MalformedTree { depth: proof::MAX_MULTIPROOF_HEIGHT + 1, index: 0 })
+        });
+        let mut failed = false;
+        too_tall.into_iter().chain(std::iter::from_fn(move || loop {
+            if failed {
+                return None;
+            }
+            let (node, depth, index) = stack.pop()?;
+            let malformed = MerkleError::MalformedTree { depth, index };
+            let (left, right) = match (node.left_node.as_deref(), node.right_node.as_deref()) {
+                (None, None) if depth == leaf_depth => return Some(Ok((index, node.hash))),
+                (Some(left), right) if depth < leaf_depth => (left, right),
+                _ => {
+                    failed = true;
+                    return Some(Err(malformed));
+                }
+            };
+            let expected = self.hasher_nodes(&left.hash, &right.unwrap_or(left).hash, &mut None);
+            if expected != node.hash {
+                failed = true;
+                return Some(Err(MerkleError::HashMismatch { depth, index }));
+            }
+            stack.extend(right.map(|right| (right, depth + 1, 2 * index + 1)));
+            stack.push((left, depth + 1, 2 * index));
+        }))
+    }
+
+    ///Function to find the position of the leaf with hash `hash`.
+    ///
+    /// Returns the index of the first such leaf, for use with [proof](struct.MerkleTree.html#method.proof), or `None` if no leaf has that hash.
+    pub fn leaf_index(&self, hash: &Hash) -> Option<usize> {
+        let mut stack: Vec<&Node> = self.root_node.as_deref().into_iter().collect();
+        let mut index = 0;
+        while let Some(node) = stack.pop() {
+            match (node.left_node.as_deref(), node.right_node.as_deref()) {
+                (None, _) if node.hash == *hash => return Some(index),
+                (None, _) => index += 1,
+                (Some(left), right) => {
+                    stack.extend(right);
+                    stack.push(left);
+                }
+            }
+        }
+        None
+    }
+
+    ///Function to generate a [MerkleProof](proof/struct.MerkleProof.html) for the leaf at `index`.
+    ///
+    /// Returns `None` if the tree is empty or has no leaf at `index`.
+    pub fn proof(&self, index: usize) -> Option<proof::MerkleProof> {
+        let Some(metrics) = &self.metrics else {
+            return self.build_proof(index);
+        };
+        let started = std::time::Instant::now();
+        let proof = self.build_proof(index);
+        metrics.0.proof_generated(started.elapsed());
+        proof
+    }
+
+    ///Function to generate a [MerkleProof](proof/struct.MerkleProof.html) for the leaf at `index`, failing with [MerkleError::LeafNotFound](enum.MerkleError.html) if there is no such leaf.
+    pub fn try_proof(&self, index: usize) -> Result<proof::MerkleProof, MerkleError> {
+        self.proof(index).ok_or_else(|| MerkleError::LeafNotFound { index, leaves: self.size() })
+    }
+
+    ///Function to generate a [MerkleProof](proof/struct.MerkleProof.html) for the leaf at `index` on tokio's blocking pool
+    ///
+    /// Walking a deep tree takes long enough to stall other tasks, so async handlers should await this rather than call [proof](struct.MerkleTree.html#method.proof). The tree is shared with the blocking task through the `Arc`. Requires the `tokio` feature and must be awaited inside a tokio runtime.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// use std::sync::Arc;
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+    /// let tree = Arc::new(tree);
+    /// assert_eq!(tree.proof_async(2).await, tree.proof(2));
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn proof_async(self: &std::sync::Arc<Self>, index: usize) -> Option<proof::MerkleProof> {
+        let tree = std::sync::Arc::clone(self);
+        match tokio::task::spawn_blocking(move || tree.proof(index)).await {
+            Ok(proof) => proof,
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            Err(_) => None,
+        }
+    }
+
+    ///Function to generate a [MerkleProof](proof/struct.MerkleProof.html) for each leaf in `indices` on the threads of `parallelism`
+    ///
+    /// Returns the proofs in the order of `indices`, with `None` where there is no leaf. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn proofs_parallel(
+        &self,
+        indices: &[usize],
+        parallelism: &parallel::Parallelism,
+    ) -> Vec<Option<proof::MerkleProof>> {
+        use rayon::prelude::*;
+        parallelism.install(|| indices.par_iter().map(|&index| self.proof(index)).collect())
+    }
+
+    fn build_proof(&self, index: usize) -> Option<proof::MerkleProof> {
+        let mut node = self.root_node.as_deref()?;
+        // Every leaf sits at the same depth, so the left child of a full node covers exactly half of its leaves.
+        // Trees too tall to count their leaves in a `usize` can only be assembled by hand and have no proofs.
+        let mut half = 1usize.checked_shl(self.height()? as u32)?;
+        let mut index = index;
+        let mut steps = Vec::new();
+        while let Some(left) = node.left_node.as_deref() {
+            half >>= 1;
+            match node.right_node.as_deref() {
+                Some(right) if index < half => {
+                    steps.push(proof::ProofStep {
+                        hash: right.hash,
+                        position: proof::Position::Right,
+                    });
+                    node = left;
+                }
+                Some(right) => {
+                    steps.push(proof::ProofStep {
+                        hash: left.hash,
+                        position: proof::Position::Left,
+                    });
+                    index -= half;
+                    node = right;
+                }
+                None if index < half => {
+                    steps.push(proof::ProofStep {
+                        hash: left.hash,
+                        position: proof::Position::Right,
+                    });
+                    node = left;
+                }
+                None => return None,
+            }
+        }
+        if index != 0 {
+            return None;
+        }
+        steps.reverse();
+        Some(proof::MerkleProof { steps })
+    }
+
+    ///Function to iterate over a range of leaves with their proofs
+    ///
+    /// Yields `(index, leaf hash, proof)` for each leaf in `range`, generating each proof only when it is reached, so slices of a huge tree can be streamed out. Stops early at the last leaf if the range extends past it.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+    /// let root = tree.root_node().unwrap().hash();
+    /// let slice: Vec<_> = tree.iter_range_with_proofs(2..10).collect();
+    /// assert_eq!(slice.len(), 2);
+    /// for (index, leaf, proof) in slice {
+    ///     assert!(proof.verify(&root, &leaf));
+    ///     assert_eq!(tree.proof(index), Some(proof));
+    /// }
+    /// ```
+    pub fn iter_range_with_proofs(
+        &self,
+        range: std::ops::Range<usize>,
+    ) -> impl Iterator<Item = (usize, Hash, proof::MerkleProof)> + '_ {
+        range.map_while(move |index| {
+            let leaf = self.node_at(0, index)?;
+            Some((index, leaf.hash, self.proof(index)?))
+        })
+    }
+
+    ///Function to stream a multiproof of the leaves at `indices` in frames of at most `max_frame_len` bytes
+    ///
+    /// Frames are produced lazily, so the whole proof is never held in memory; verify them with a [FrameDecoder](multiproof/struct.FrameDecoder.html). Returns `None` if the tree is empty, `indices` is empty or any index has no leaf. See [multiproof](multiproof/index.html).
+    ///
+    /// Panics if `max_frame_len` is less than [MIN_FRAME_LEN](multiproof/constant.MIN_FRAME_LEN.html).
+    pub fn multiproof_frames(
+        &self,
+        indices: &[usize],
+        max_frame_len: usize,
+    ) -> Option<multiproof::FrameEncoder<'_>> {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        let last = *indices.last()?;
+        self.node_at(0, last)?;
+        Some(multiproof::FrameEncoder::new(
+            self.root_node.as_deref()?,
+            self.height()?,
+            indices,
+            max_frame_len,
+        ))
+    }
+
+    ///Function to generate a [MultiProof](proof/struct.MultiProof.html) for the leaves at `indices`, which may be in any order and contain duplicates.
+    ///
+    /// Returns `None` if `indices` is empty or has an index past the last leaf.
+    pub fn multiproof(&self, indices: &[usize]) -> Option<proof::MultiProof> {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        self.node_at(0, *indices.last()?)?;
+        let top = self.height()?;
+        let tree_size = self.count_leaves();
+        let width = |height: usize| ((tree_size - 1) >> height) + 1;
+        // Same fold as proof::verify_multiproof, recording the hashes it consumes.
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+        let mut hashes = Vec::new();
+        for (position, &index) in indices.iter().enumerate() {
+            stack.push((0, index));
+            let next = indices.get(position + 1);
+            while let Some(&(height, index)) = stack.last() {
+                let sibling = index ^ 1;
+                let lone = index % 2 == 0 && sibling >= width(height);
+                if height == top
+                    || (index % 2 == 0 && !lone && next.is_some_and(|next| next >> height == sibling))
+                {
+                    break;
                 }
-                if element.clone().unwrap().left_node.is_some() {
-                    data_array.push_front(element.clone().unwrap().left_node);
+                stack.pop();
+                if index % 2 == 1 && stack.last() == Some(&(height, sibling)) {
+                    stack.pop();
+                } else if !lone {
+                    hashes.push(self.node_at(height, sibling)?.hash);
                 }
+                stack.push((height + 1, index / 2));
+            }
+        }
+        Some(proof::MultiProof {
+            tree_size: tree_size as u64,
+            indices: indices.iter().map(|&index| index as u64).collect(),
+            hashes,
+        })
+    }
+
+    ///Function to generate a [SizedProof](proof/struct.SizedProof.html) for the leaf at `index`, which also records the index and the number of leaves so it can be verified against a trusted tree size.
+    ///
+    /// Returns `None` if the tree is empty or has no leaf at `index`.
+    pub fn sized_proof(&self, index: usize) -> Option<proof::SizedProof> {
+        Some(proof::SizedProof {
+            index: index as u64,
+            tree_size: self.count_leaves() as u64,
+            proof: self.proof(index)?,
+        })
+    }
+
+    ///Function to generate an [IndexedProof](proof/struct.IndexedProof.html) for the leaf at `index`, which only verifies for that index.
+    ///
+    /// Returns `None` if the tree is empty or has no leaf at `index`.
+    /// ```
+    /// use rs_merkletree::proof::leaf_hash;
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+    /// let root = tree.root_node().unwrap().hash();
+    ///
+    /// let proof = tree.indexed_proof(2).unwrap();
+    /// assert!(proof.verify(&root, &leaf_hash(b"From"), 2, 4));
+    /// assert!(!proof.verify(&root, &leaf_hash(b"From"), 3, 4));
+    /// ```
+    pub fn indexed_proof(&self, index: usize) -> Option<proof::IndexedProof> {
+        self.proof(index).map(proof::IndexedProof::from)
+    }
+
+    ///Function to get the Root Hash as a [Hash](hash/struct.Hash.html)
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn root(&self) -> Option<Hash> {
+        self.root_hash()
+    }
+
+    ///Function to check if this tree and `other` hold the same leaves under the same construction.
+    ///
+    /// Only the roots, leaf counts and settings are compared, without walking the trees, so this is the cheap check to make before a [diff](struct.MerkleTree.html#method.diff) or a sync when most trees are expected to match.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["Hello", "World", "From"]);
+    /// let mut other = MerkleTree::new(None);
+    /// other.build_tree(vec!["Hello", "World", "From"]);
+    /// assert!(tree.same_as(&other));
+    ///
+    /// // The lone leaf is hashed with itself, so this root is the same, but not the leaves.
+    /// other.build_tree(vec!["Hello", "World", "From", "From"]);
+    /// assert!(!tree.same_as(&other));
+    /// ```
+    pub fn same_as(&self, other: &MerkleTree) -> bool {
+        self.legacy == other.legacy
+            && self.sort_leaves == other.sort_leaves
+            && self.root_hash() == other.root_hash()
+            && self.size() == other.size()
+    }
+
+    ///Function to find the leaves that differ between this tree and `other`.
+    ///
+    /// Returns the indices, in ascending order, of leaves that differ or exist in only one tree. Both trees are walked from the root and only mismatched subtrees are descended into, so the cost grows with the number of differences rather than with the size of the trees.
+    pub fn diff(&self, other: &MerkleTree) -> Vec<usize> {
+        let sizes = (self.size(), other.size());
+        if sizes.0 == sizes.1 && self.root_hash() == other.root_hash() {
+            return Vec::new();
+        }
+        let height = usize::max(self.height().unwrap_or(0), other.height().unwrap_or(0));
+        let mut differences = Vec::new();
+        self.diff_subtree(other, sizes, height, 0, &mut differences);
+        differences
+    }
+
+    /// Collects differing leaves below the nodes at `height` and `index` of both trees.
+    fn diff_subtree(
+        &self,
+        other: &MerkleTree,
+        sizes: (usize, usize),
+        height: usize,
+        index: usize,
+        differences: &mut Vec<usize>,
+    ) {
+        let start = index << height;
+        if start >= usize::max(sizes.0, sizes.1) {
+            return;
+        }
+        // Equal hashes only prove equal leaves if they cover as many leaves: a lone node is hashed with itself, like a pair of equal nodes.
+        let covered = |size: usize| size.min((index + 1) << height).saturating_sub(start);
+        let nodes = (self.node_at(height, index), other.node_at(height, index));
+        if let (Some(left), Some(right)) = nodes {
+            if left.hash == right.hash && covered(sizes.0) == covered(sizes.1) {
+                return;
             }
         }
+        if height == 0 {
+            differences.push(index);
+            return;
+        }
+        self.diff_subtree(other, sizes, height - 1, 2 * index, differences);
+        self.diff_subtree(other, sizes, height - 1, 2 * index + 1, differences);
+    }
 
-        false
+    ///Function to get a [TreeCursor](cursor/struct.TreeCursor.html) at the root
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn cursor(&self) -> Option<cursor::TreeCursor<'_>> {
+        self.root_node.as_deref().map(cursor::TreeCursor::new)
+    }
+
+    ///Function to get the node at `depth` and `index`
+    ///
+    /// `depth` counts levels from the root, which is at depth 0, and `index` positions from the left of that level; the leaves are at depth [depth](struct.MerkleTree.html#method.depth) - 1. Returns `None` if there is no such node.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["Hello", "World", "From"]);
+    /// assert_eq!(tree.node(0, 0), tree.root_node().as_ref());
+    /// assert_eq!(tree.node(1, 1).unwrap().left_node().as_ref(), tree.node(2, 2));
+    /// // "From" has no right neighbour.
+    /// assert_eq!(tree.node(2, 3), None);
+    /// ```
+    pub fn node(&self, depth: usize, index: usize) -> Option<&Node> {
+        let height = self.height()?.checked_sub(depth)?;
+        self.node_at(height, index)
+    }
+
+    ///Function to get the sibling of the node at `depth` and `index`
+    ///
+    /// Returns `None` for the root, for missing nodes, and for a node without a sibling, which its parent hashed with itself.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["Hello", "World", "From"]);
+    /// assert_eq!(tree.sibling_of(2, 0), tree.node(2, 1));
+    /// assert_eq!(tree.sibling_of(2, 2), None);
+    /// ```
+    pub fn sibling_of(&self, depth: usize, index: usize) -> Option<&Node> {
+        if depth == 0 {
+            return None;
+        }
+        self.node(depth, index)?;
+        self.node(depth, index ^ 1)
+    }
+
+    ///Function to get the parent of the node at `depth` and `index`
+    ///
+    /// Returns `None` for the root and for missing nodes.
+    /// ```
+    /// use rs_merkletree::MerkleTree;
+    /// let mut tree = MerkleTree::new(None);
+    /// tree.build_tree(vec!["Hello", "World", "From"]);
+    /// assert_eq!(tree.parent_of(1, 1), tree.root_node().as_ref());
+    /// assert_eq!(tree.parent_of(0, 0), None);
+    /// ```
+    pub fn parent_of(&self, depth: usize, index: usize) -> Option<&Node> {
+        if depth == 0 {
+            return None;
+        }
+        self.node(depth, index)?;
+        self.node(depth - 1, index / 2)
+    }
+
+    /// Returns the height of the root above the leaves, or `None` for an empty tree.
+    fn height(&self) -> Option<usize> {
+        let mut node = self.root_node.as_deref()?;
+        let mut height = 0;
+        while let Some(left) = node.left_node.as_deref() {
+            node = left;
+            height += 1;
+        }
+        Some(height)
+    }
+
+    /// Returns the root hash, `None` for an empty tree.
+    fn root_hash(&self) -> Option<Hash> {
+        self.root_node.as_ref().map(|root| root.hash)
+    }
+
+    /// Returns the number of leaves, read from the shape of the right edge of the tree rather than by visiting every leaf. Trees too tall for their size to fit in a `usize` can only be assembled by hand, and saturate.
+    fn size(&self) -> usize {
+        let (Some(mut node), Some(mut height)) = (self.root_node.as_deref(), self.height()) else {
+            return 0;
+        };
+        let mut size: usize = 1;
+        while height > 0 {
+            height -= 1;
+            node = match (&node.left_node, &node.right_node) {
+                (_, Some(right)) => {
+                    size = size.saturating_add(1usize.checked_shl(height as u32).unwrap_or(usize::MAX));
+                    right
+                }
+                (Some(left), None) => left,
+                (None, None) => break,
+            };
+        }
+        size
+    }
+
+    /// Returns the node `height` levels above the leaves covering leaves from `index << height`.
+    fn node_at(&self, height: usize, index: usize) -> Option<&Node> {
+        let top = self.height()?;
+        // Shifting a `usize` by 64 or more would overflow; every bit of `index` is then already shifted out.
+        let bit = |shift: usize| index.checked_shr(shift as u32).unwrap_or(0);
+        if height > top || bit(top - height) != 0 {
+            return None;
+        }
+        let mut node = self.root_node.as_deref()?;
+        for level in (height..top).rev() {
+            node = if bit(level - height) & 1 == 0 {
+                node.left_node.as_deref()?
+            } else {
+                node.right_node.as_deref()?
+            };
+        }
+        Some(node)
     }
 }
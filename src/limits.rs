@@ -0,0 +1,98 @@
+//! Resource limits for untrusted input.
+//!
+//! A service building trees from uploaded datasets or verifying proofs sent by clients should not let one request exhaust its memory. [Limits](struct.Limits.html) caps the number of leaves, the size of each leaf and the length of proofs; [MerkleTree::try_build_tree](../struct.MerkleTree.html#method.try_build_tree) and [MerkleProof::from_bytes_limited](../proof/struct.MerkleProof.html#method.from_bytes_limited) check them before allocating and report a [MerkleError](../enum.MerkleError.html) when one is exceeded.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::limits::Limits;
+//! use rs_merkletree::{MerkleError, MerkleTree};
+//! let limits = Limits { max_leaves: 2, ..Limits::default() };
+//! let mut tree = MerkleTree::new(None).with_limits(limits);
+//! assert!(tree.try_build_tree(vec!["a", "b"]).is_ok());
+//! assert_eq!(
+//!     tree.try_build_tree(vec!["a", "b", "c"]).err(),
+//!     Some(MerkleError::TooManyLeaves { limit: 2, actual: 3 })
+//! );
+//! ```
+
+use crate::error::MerkleError;
+use crate::proof::MerkleProof;
+
+/// [Limits](struct.Limits.html) bounds the work done on untrusted input.
+///
+/// * `max_leaves`: Most leaves a tree may be built from.
+///
+/// * `max_leaf_size`: Largest leaf, in bytes.
+///
+/// * `max_proof_len`: Most steps a proof may have; a tree of `n` leaves needs about `log2(n)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_leaves: usize,
+    pub max_leaf_size: usize,
+    pub max_proof_len: usize,
+}
+
+impl Limits {
+    /// No limits at all, the behaviour of trees that were not given any.
+    pub const UNLIMITED: Limits = Limits {
+        max_leaves: usize::MAX,
+        max_leaf_size: usize::MAX,
+        max_proof_len: usize::MAX,
+    };
+
+    /// Checks the number and size of `leaves`.
+    pub fn check_leaves<T: AsRef<[u8]>>(&self, leaves: &[T]) -> Result<(), MerkleError> {
+        self.check_leaf_count(leaves.len())?;
+        leaves
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, leaf)| self.check_leaf(index, leaf.as_ref()))
+    }
+
+    pub(crate) fn check_leaf_count(&self, count: usize) -> Result<(), MerkleError> {
+        if count > self.max_leaves {
+            return Err(MerkleError::TooManyLeaves {
+                limit: self.max_leaves,
+                actual: count,
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_leaf(&self, index: usize, leaf: &[u8]) -> Result<(), MerkleError> {
+        if leaf.len() > self.max_leaf_size {
+            return Err(MerkleError::LeafTooLarge {
+                index,
+                limit: self.max_leaf_size,
+                actual: leaf.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks the length of `proof`.
+    pub fn check_proof(&self, proof: &MerkleProof) -> Result<(), MerkleError> {
+        self.check_proof_len(proof.steps.len())
+    }
+
+    pub(crate) fn check_proof_len(&self, len: usize) -> Result<(), MerkleError> {
+        if len > self.max_proof_len {
+            return Err(MerkleError::ProofTooLong {
+                limit: self.max_proof_len,
+                actual: len,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for Limits {
+    /// 16 million leaves of at most 1 MiB each, and proofs of at most 64 steps.
+    fn default() -> Limits {
+        Limits {
+            max_leaves: 1 << 24,
+            max_leaf_size: 1 << 20,
+            max_proof_len: 64,
+        }
+    }
+}
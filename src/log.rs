@@ -0,0 +1,210 @@
+//! Transparency log with signed tree heads.
+//!
+//! [TransparencyLog](struct.TransparencyLog.html) is an embeddable, Certificate-Transparency-style log built on the [HistoryTree](../history/struct.HistoryTree.html). Entries are appended to the log and, periodically, the log emits a [SignedTreeHead](struct.SignedTreeHead.html): the tree size, root hash and timestamp, signed with the log's ed25519 key. Clients holding the log's public key can then check inclusion proofs for entries and consistency proofs between any two tree heads.
+//!
+//! The log also folds the root of every tree head it signs into a [RootChain](../root_chain/struct.RootChain.html), so the history of published heads can be checked against the latest [chain_head](struct.TransparencyLog.html#method.chain_head).
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::log::TransparencyLog;
+//! let mut log = TransparencyLog::new(&[7u8; 32], 60_000);
+//! log.append(b"certificate-1");
+//! log.append(b"certificate-2");
+//! let sth = log.sign_tree_head(1_700_000_000_000);
+//! assert!(sth.verify(log.public_key()));
+//!
+//! let proof = log.inclusion_proof(1, sth.tree_size).unwrap();
+//! assert!(proof.verify(&sth.root_hash, b"certificate-2"));
+//!
+//! let roots = log.tree_heads().iter().map(|head| &head.root_hash);
+//! assert!(rs_merkletree::root_chain::RootChain::verify(roots, log.chain_head()));
+//! ```
+
+use crate::history::{HistoryTree, IncrementalProof, MembershipProof};
+use crate::root_chain::RootChain;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+/// [SignedTreeHead](struct.SignedTreeHead.html) is a signed commitment to the log's contents at a point in time.
+///
+/// * `tree_size`: Number of entries covered.
+///
+/// * `timestamp`: Milliseconds since the Unix epoch at which the head was signed.
+///
+/// * `root_hash`: Root over the first `tree_size` entries.
+///
+/// * `signature`: ed25519 signature over [signed_data](struct.SignedTreeHead.html#method.signed_data).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedTreeHead {
+    pub tree_size: u64,
+    pub timestamp: u64,
+    pub root_hash: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+/// [TransparencyLog](struct.TransparencyLog.html) holds the entries, their tree, the signing key, every emitted tree head and the chain over their roots.
+#[derive(Clone)]
+pub struct TransparencyLog {
+    tree: HistoryTree,
+    entries: Vec<Vec<u8>>,
+    signing_key: SigningKey,
+    public_key: Vec<u8>,
+    interval: u64,
+    heads: Vec<SignedTreeHead>,
+    chain: RootChain,
+}
+
+impl std::fmt::Debug for TransparencyLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransparencyLog")
+            .field("tree_size", &self.tree.len())
+            .field("public_key", &self.public_key)
+            .field("interval", &self.interval)
+            .field("heads", &self.heads.len())
+            .field("chain", &self.chain)
+            .finish()
+    }
+}
+
+/// Overwrites the stored entries and their tree. The signing key is zeroized on drop by `ed25519-dalek` itself.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for TransparencyLog {
+    fn zeroize(&mut self) {
+        self.entries.zeroize();
+        self.tree.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for TransparencyLog {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for TransparencyLog {}
+
+impl SignedTreeHead {
+    /// Returns the bytes covered by the signature, laid out as an RFC 6962 `TreeHeadSignature`:
+    /// version `0`, signature type `1` (tree hash), then timestamp, tree size and root hash.
+    pub fn signed_data(&self) -> Vec<u8> {
+        let mut data = vec![0u8, 1u8];
+        data.extend(self.timestamp.to_be_bytes());
+        data.extend(self.tree_size.to_be_bytes());
+        data.extend(&self.root_hash);
+        data
+    }
+
+    /// Checks the signature with the log's ed25519 `public_key`.
+    pub fn verify(&self, public_key: &[u8]) -> bool {
+        let (Ok(public_key), Ok(signature)) = (
+            <[u8; 32]>::try_from(public_key),
+            <[u8; 64]>::try_from(self.signature.as_slice()),
+        ) else {
+            return false;
+        };
+        VerifyingKey::from_bytes(&public_key).is_ok_and(|key| {
+            key.verify(&self.signed_data(), &Signature::from_bytes(&signature))
+                .is_ok()
+        })
+    }
+}
+
+impl TransparencyLog {
+    /// Function to create an empty [TransparencyLog](struct.TransparencyLog.html).
+    ///
+    /// `seed` derives the ed25519 signing key. `interval` is the maximum time, in milliseconds, between tree heads when using [tick](struct.TransparencyLog.html#method.tick).
+    pub fn new(seed: &[u8; 32], interval: u64) -> TransparencyLog {
+        let signing_key = SigningKey::from_bytes(seed);
+        TransparencyLog {
+            tree: HistoryTree::new(),
+            entries: Vec::new(),
+            public_key: signing_key.verifying_key().to_bytes().to_vec(),
+            signing_key,
+            interval,
+            heads: Vec::new(),
+            chain: RootChain::new(),
+        }
+    }
+
+    /// Returns the log's ed25519 public key.
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /// Appends an entry and returns its index.
+    pub fn append(&mut self, entry: &[u8]) -> u64 {
+        self.entries.push(entry.to_vec());
+        self.tree.append(entry) - 1
+    }
+
+    /// Returns the entry at `index`.
+    pub fn entry(&self, index: u64) -> Option<&[u8]> {
+        self.entries
+            .get(index as usize)
+            .map(|entry| entry.as_slice())
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> u64 {
+        self.tree.len()
+    }
+
+    /// Returns `true` if the log has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Returns the underlying [HistoryTree](../history/struct.HistoryTree.html).
+    pub fn tree(&self) -> &HistoryTree {
+        &self.tree
+    }
+
+    /// Signs a tree head over every entry, with the given `timestamp` in milliseconds.
+    pub fn sign_tree_head(&mut self, timestamp: u64) -> SignedTreeHead {
+        let mut head = SignedTreeHead {
+            tree_size: self.tree.len(),
+            timestamp,
+            root_hash: self.tree.root(),
+            signature: Vec::new(),
+        };
+        head.signature = self.signing_key.sign(&head.signed_data()).to_vec();
+        self.chain.push(&head.root_hash);
+        self.heads.push(head.clone());
+        head
+    }
+
+    /// Emits a new tree head if the interval has elapsed since the last one and entries were added since. `now` is in milliseconds.
+    pub fn tick(&mut self, now: u64) -> Option<SignedTreeHead> {
+        match self.heads.last() {
+            Some(last) if last.tree_size == self.tree.len() => None,
+            Some(last) if now < last.timestamp.saturating_add(self.interval) => None,
+            _ => Some(self.sign_tree_head(now)),
+        }
+    }
+
+    /// Returns the most recent tree head.
+    pub fn latest_tree_head(&self) -> Option<&SignedTreeHead> {
+        self.heads.last()
+    }
+
+    /// Returns every tree head emitted so far, oldest first.
+    pub fn tree_heads(&self) -> &[SignedTreeHead] {
+        &self.heads
+    }
+
+    /// Returns the head of the [RootChain](../root_chain/struct.RootChain.html) over the roots of every tree head emitted so far.
+    pub fn chain_head(&self) -> &[u8] {
+        self.chain.head()
+    }
+
+    /// Generates a proof that entry `index` is included in the tree of size `tree_size`.
+    pub fn inclusion_proof(&self, index: u64, tree_size: u64) -> Option<MembershipProof> {
+        self.tree.membership_proof(index, tree_size)
+    }
+
+    /// Generates a proof that the tree of size `new_size` extends the tree of size `old_size`.
+    pub fn consistency_proof(&self, old_size: u64, new_size: u64) -> Option<IncrementalProof> {
+        self.tree.incremental_proof(old_size, new_size)
+    }
+}
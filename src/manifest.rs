@@ -0,0 +1,209 @@
+//! Directory manifests.
+//!
+//! A [Manifest](struct.Manifest.html) walks a directory, builds a [FileTree](../file/struct.FileTree.html) for every regular file and commits to the sorted list of `(path, length, file root)` entries with a top-level tree. The manifest can be written out as text, shipped alongside an archive, and later used to check an extracted copy file by file, naming exactly which files are missing, modified or unexpected.
+//!
+//! Paths are relative to the directory and use `/` as separator on every platform. Symbolic links are not followed.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::manifest::{Manifest, Mismatch};
+//! let dir = std::env::temp_dir().join("rs_merkletree_manifest_doc");
+//! std::fs::create_dir_all(dir.join("sub")).unwrap();
+//! std::fs::write(dir.join("a.txt"), b"alpha").unwrap();
+//! std::fs::write(dir.join("sub/b.txt"), b"beta").unwrap();
+//!
+//! let manifest = Manifest::build(&dir, 1024).unwrap();
+//! let parsed = Manifest::parse(&manifest.to_text()).unwrap();
+//! assert_eq!(parsed.root(), manifest.root());
+//!
+//! std::fs::write(dir.join("a.txt"), b"ALPHA").unwrap();
+//! let mismatches = parsed.verify(&dir).unwrap();
+//! assert_eq!(mismatches, vec![Mismatch::Modified("a.txt".to_string())]);
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use crate::file::FileTree;
+use crate::hashing::{from_hex, to_hex};
+use crate::history::HistoryTree;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// [ManifestEntry](struct.ManifestEntry.html) describes one file of the directory.
+///
+/// * `path`: Path relative to the directory, `/` separated.
+///
+/// * `len`: File length in bytes.
+///
+/// * `root`: Root of the file's [FileTree](../file/struct.FileTree.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub len: u64,
+    pub root: Vec<u8>,
+}
+
+/// [Manifest](struct.Manifest.html) is the sorted list of file entries and the tree over them.
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    chunk_size: usize,
+    entries: Vec<ManifestEntry>,
+    tree: HistoryTree,
+}
+
+/// A difference found by [Manifest::verify](struct.Manifest.html#method.verify).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The file is listed in the manifest but absent from the directory.
+    Missing(String),
+    /// The file's length or contents differ from the manifest.
+    Modified(String),
+    /// The file exists in the directory but is not listed in the manifest.
+    Unexpected(String),
+}
+
+impl ManifestEntry {
+    /// Returns the leaf data committed to by the top-level tree: the length-prefixed path, the file length and the file root.
+    pub fn leaf_data(&self) -> Vec<u8> {
+        let mut data = (self.path.len() as u64).to_be_bytes().to_vec();
+        data.extend_from_slice(self.path.as_bytes());
+        data.extend_from_slice(&self.len.to_be_bytes());
+        data.extend_from_slice(&self.root);
+        data
+    }
+}
+
+impl Manifest {
+    /// Function to create a [Manifest](struct.Manifest.html) of every file below `dir`, hashing files in chunks of `chunk_size` bytes.
+    pub fn build<P: AsRef<Path>>(dir: P, chunk_size: usize) -> io::Result<Manifest> {
+        let mut entries = Vec::new();
+        for (path, full) in walk(dir.as_ref())? {
+            let tree = FileTree::open(&full, chunk_size)?;
+            entries.push(ManifestEntry {
+                path,
+                len: tree.len(),
+                root: tree.root(),
+            });
+        }
+        Ok(Manifest::from_entries(chunk_size, entries))
+    }
+
+    /// Function to create a [Manifest](struct.Manifest.html) from entries, which are sorted by path.
+    pub fn from_entries(chunk_size: usize, mut entries: Vec<ManifestEntry>) -> Manifest {
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut tree = HistoryTree::new();
+        for entry in &entries {
+            tree.append(&entry.leaf_data());
+        }
+        Manifest {
+            chunk_size,
+            entries,
+            tree,
+        }
+    }
+
+    /// Returns the Root Hash over all entries.
+    pub fn root(&self) -> Vec<u8> {
+        self.tree.root()
+    }
+
+    /// Returns the chunk size used for the file trees.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the entries, sorted by path.
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    /// Returns the entry for `path`, if listed.
+    pub fn entry(&self, path: &str) -> Option<&ManifestEntry> {
+        self.entries
+            .binary_search_by(|entry| entry.path.as_str().cmp(path))
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    /// Checks the directory `dir` against the manifest, returning every difference sorted by path. An empty result means the copy is intact.
+    pub fn verify<P: AsRef<Path>>(&self, dir: P) -> io::Result<Vec<Mismatch>> {
+        let mut found: BTreeMap<String, PathBuf> = walk(dir.as_ref())?.into_iter().collect();
+        let mut mismatches = Vec::new();
+        for entry in &self.entries {
+            match found.remove(&entry.path) {
+                None => mismatches.push(Mismatch::Missing(entry.path.clone())),
+                Some(full) => {
+                    let intact = fs::metadata(&full)?.len() == entry.len
+                        && FileTree::open(&full, self.chunk_size)?.root() == entry.root;
+                    if !intact {
+                        mismatches.push(Mismatch::Modified(entry.path.clone()));
+                    }
+                }
+            }
+        }
+        mismatches.extend(found.into_keys().map(Mismatch::Unexpected));
+        mismatches.sort_by(|a, b| mismatch_path(a).cmp(mismatch_path(b)));
+        Ok(mismatches)
+    }
+
+    /// Serializes the manifest: a `chunk_size <n>` line followed by one `<hex root> <len> <path>` line per entry.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("chunk_size {}\n", self.chunk_size);
+        for entry in &self.entries {
+            text.push_str(&format!(
+                "{} {} {}\n",
+                to_hex(&entry.root),
+                entry.len,
+                entry.path
+            ));
+        }
+        text
+    }
+
+    /// Parses the output of [to_text](struct.Manifest.html#method.to_text), returning `None` if it is malformed.
+    pub fn parse(text: &str) -> Option<Manifest> {
+        let mut lines = text.lines();
+        let chunk_size = lines.next()?.strip_prefix("chunk_size ")?.parse().ok()?;
+        let mut entries = Vec::new();
+        for line in lines.filter(|line| !line.is_empty()) {
+            let mut fields = line.splitn(3, ' ');
+            let root = from_hex(fields.next()?)?;
+            let len = fields.next()?.parse().ok()?;
+            let path = fields.next()?.to_string();
+            entries.push(ManifestEntry { path, len, root });
+        }
+        Some(Manifest::from_entries(chunk_size, entries))
+    }
+}
+
+fn mismatch_path(mismatch: &Mismatch) -> &str {
+    match mismatch {
+        Mismatch::Missing(path) | Mismatch::Modified(path) | Mismatch::Unexpected(path) => path,
+    }
+}
+
+/// Lists the regular files below `root` as `(relative path, full path)` pairs.
+fn walk(root: &Path) -> io::Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![(String::new(), root.to_path_buf())];
+    while let Some((prefix, dir)) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let relative = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push((relative, entry.path()));
+            } else if file_type.is_file() {
+                files.push((relative, entry.path()));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
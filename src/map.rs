@@ -0,0 +1,208 @@
+//! Authenticated map.
+//!
+//! [MerkleMap](struct.MerkleMap.html) offers a familiar map interface (`insert`, `get`, `remove`) whose contents are committed to by a single root hash. Keys and values are turned into bytes with the [Encode](trait.Encode.html) trait and stored in a [SparseMerkleTree](../smt/struct.SparseMerkleTree.html), so every key, present or not, can be proven against the root.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::map::MerkleMap;
+//! let mut balances: MerkleMap<String, u64> = MerkleMap::new();
+//! balances.insert("alice".to_string(), 100);
+//! balances.insert("bob".to_string(), 42);
+//! assert_eq!(balances.get(&"alice".to_string()), Some(&100));
+//!
+//! let root = balances.root();
+//! let proof = balances.prove(&"bob".to_string());
+//! assert!(proof.verify(&root, &"bob".to_string(), Some(&42u64)));
+//!
+//! let proof = balances.prove(&"carol".to_string());
+//! assert!(proof.verify::<String, u64>(&root, &"carol".to_string(), None));
+//! ```
+
+use crate::smt::{SparseMerkleProof, SparseMerkleTree};
+use std::collections::{btree_map, BTreeMap};
+
+/// Canonical byte encoding used to hash keys and values of a [MerkleMap](struct.MerkleMap.html).
+///
+/// Integers are encoded big-endian, strings as UTF-8 and byte containers as-is. `usize` and `isize` are encoded as `u64` and `i64`, so roots do not depend on the target's pointer width.
+pub trait Encode {
+    fn encode(&self) -> Vec<u8>;
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self) -> Vec<u8> {
+        (**self).encode()
+    }
+}
+
+impl Encode for [u8] {
+    fn encode(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: Encode + zeroize::Zeroize> Encode for zeroize::Zeroizing<T> {
+    fn encode(&self) -> Vec<u8> {
+        (**self).encode()
+    }
+}
+
+impl Encode for Vec<u8> {
+    fn encode(&self) -> Vec<u8> {
+        self.clone()
+    }
+}
+
+impl<const N: usize> Encode for [u8; N] {
+    fn encode(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl Encode for str {
+    fn encode(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl Encode for String {
+    fn encode(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self) -> Vec<u8> {
+        vec![*self as u8]
+    }
+}
+
+macro_rules! encode_int {
+    ($($t:ty),*) => {
+        $(impl Encode for $t {
+            fn encode(&self) -> Vec<u8> {
+                self.to_be_bytes().to_vec()
+            }
+        })*
+    };
+}
+
+encode_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl Encode for usize {
+    fn encode(&self) -> Vec<u8> {
+        (*self as u64).encode()
+    }
+}
+
+impl Encode for isize {
+    fn encode(&self) -> Vec<u8> {
+        (*self as i64).encode()
+    }
+}
+
+/// [MerkleMap](struct.MerkleMap.html) is a map from `K` to `V` committed to by a sparse Merkle root.
+#[derive(Debug, Clone)]
+pub struct MerkleMap<K, V> {
+    entries: BTreeMap<K, V>,
+    tree: SparseMerkleTree,
+}
+
+/// [MerkleMapProof](struct.MerkleMapProof.html) proves the value of one key of a [MerkleMap](struct.MerkleMap.html), or its absence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleMapProof {
+    proof: SparseMerkleProof,
+}
+
+impl<K: Ord + Encode, V: Encode> Default for MerkleMap<K, V> {
+    fn default() -> Self {
+        MerkleMap::new()
+    }
+}
+
+/// Overwrites every value and empties the map. Keys cannot be overwritten in place and are only dropped.
+#[cfg(feature = "zeroize")]
+impl<K, V: zeroize::Zeroize> zeroize::Zeroize for MerkleMap<K, V> {
+    fn zeroize(&mut self) {
+        self.entries
+            .values_mut()
+            .for_each(zeroize::Zeroize::zeroize);
+        self.entries.clear();
+        self.tree.zeroize();
+    }
+}
+
+/// The tree wipes itself on drop, and so do values such as [Zeroizing](https://docs.rs/zeroize/latest/zeroize/struct.Zeroizing.html) buffers. A `Drop` impl cannot require `V: Zeroize`, so maps of other values leave them to their own `Drop`. Keys are identifiers and are not wiped.
+#[cfg(feature = "zeroize")]
+impl<K, V: zeroize::ZeroizeOnDrop> zeroize::ZeroizeOnDrop for MerkleMap<K, V> {}
+
+impl<K: Ord + Encode, V: Encode> MerkleMap<K, V> {
+    /// Function to create a new, empty [MerkleMap](struct.MerkleMap.html)
+    pub fn new() -> MerkleMap<K, V> {
+        MerkleMap {
+            entries: BTreeMap::new(),
+            tree: SparseMerkleTree::new(),
+        }
+    }
+
+    /// Inserts `value` under `key`, returning the previous value.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.tree.insert(&key.encode(), &value.encode());
+        self.entries.insert(key, value)
+    }
+
+    /// Returns the value stored under `key`.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key)
+    }
+
+    /// Removes `key`, returning its value.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.tree.remove(&key.encode());
+        self.entries.remove(key)
+    }
+
+    /// Returns `true` if `key` is present.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the entries, ordered by key.
+    pub fn iter(&self) -> btree_map::Iter<'_, K, V> {
+        self.entries.iter()
+    }
+
+    /// Returns the Root Hash committing to every entry.
+    pub fn root(&self) -> Vec<u8> {
+        self.tree.root()
+    }
+
+    /// Generates a proof for `key`: of its value if present, of its absence otherwise.
+    pub fn prove(&self, key: &K) -> MerkleMapProof {
+        MerkleMapProof {
+            proof: self.tree.prove(&key.encode()),
+        }
+    }
+}
+
+impl MerkleMapProof {
+    /// Checks that `key` maps to `value` (or is absent, when `value` is `None`) in the map with root `root`.
+    pub fn verify<K: Encode, V: Encode>(&self, root: &[u8], key: &K, value: Option<&V>) -> bool {
+        match value {
+            Some(value) => self
+                .proof
+                .verify_membership(root, &key.encode(), &value.encode()),
+            None => self.proof.verify_non_membership(root, &key.encode()),
+        }
+    }
+}
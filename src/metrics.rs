@@ -0,0 +1,52 @@
+//! Metrics hooks.
+//!
+//! Implement [Metrics](trait.Metrics.html) to forward hash counts, build durations and proof generation latency to Prometheus, StatsD or any other backend, and attach it to a tree with [MerkleTree::with_metrics](../struct.MerkleTree.html#method.with_metrics). Every method has an empty default, so only the measurements of interest need implementing. Trees without metrics do not read the clock.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::metrics::Metrics;
+//! use rs_merkletree::MerkleTree;
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//! use std::sync::Arc;
+//!
+//! #[derive(Default)]
+//! struct HashCounter(AtomicU64);
+//!
+//! impl Metrics for HashCounter {
+//!     fn hashes_computed(&self, count: u64) {
+//!         self.0.fetch_add(count, Ordering::Relaxed);
+//!     }
+//! }
+//!
+//! let counter = Arc::new(HashCounter::default());
+//! let mut tree = MerkleTree::new(None).with_metrics(counter.clone());
+//! tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+//! // Four leaves, two parents and the root.
+//! assert_eq!(counter.0.load(Ordering::Relaxed), 7);
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Receives measurements from a [MerkleTree](../struct.MerkleTree.html). Implementations are shared between clones of the tree, possibly across threads.
+pub trait Metrics: Send + Sync {
+    /// Called once per build with the number of hashes it computed.
+    fn hashes_computed(&self, _count: u64) {}
+
+    /// Called when a build of `leaves` leaves completes, with the time it took.
+    fn build_completed(&self, _leaves: usize, _duration: Duration) {}
+
+    /// Called after each proof is generated, with the time it took.
+    fn proof_generated(&self, _duration: Duration) {}
+}
+
+/// Shared [Metrics](trait.Metrics.html) held by a tree.
+#[derive(Clone)]
+pub(crate) struct MetricsHandle(pub(crate) Arc<dyn Metrics>);
+
+impl fmt::Debug for MetricsHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetricsHandle")
+    }
+}
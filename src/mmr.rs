@@ -0,0 +1,234 @@
+//! Merkle Mountain Range.
+//!
+//! A [MerkleMountainRange](struct.MerkleMountainRange.html) is an append-only list of perfect binary Merkle trees ("mountains") of strictly decreasing height. Appending a leaf never rewrites existing nodes: it only adds the leaf and merges equal-height mountains, so the cost of an append is `O(log n)` instead of a full rebuild. This makes it a good fit for append-only histories such as block headers or log entries.
+//!
+//! Nodes are stored in post-order. The root is obtained by "bagging the peaks": folding the mountain roots together from right to left.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::mmr::MerkleMountainRange;
+//! let mut mmr = MerkleMountainRange::new();
+//! for entry in ["a", "b", "c", "d", "e"] {
+//!     mmr.append(entry.as_bytes());
+//! }
+//! assert_eq!(mmr.peaks().len(), 2);
+//!
+//! let root = mmr.root().unwrap();
+//! let proof = mmr.prove(2).unwrap();
+//! assert!(proof.verify(&root, b"c"));
+//! ```
+
+use crate::hashing::{sha256, sha256_concat};
+
+/// [MerkleMountainRange](struct.MerkleMountainRange.html) holds every node of the range in post-order.
+///
+/// * `nodes`: All leaf and parent hashes, in the order they were created.
+///
+/// * `leaf_count`: The number of appended leaves.
+#[derive(Debug, Clone, Default)]
+pub struct MerkleMountainRange {
+    nodes: Vec<Vec<u8>>,
+    leaf_count: usize,
+}
+
+/// [MmrProof](struct.MmrProof.html) proves that a leaf is included in a [MerkleMountainRange](struct.MerkleMountainRange.html) of a given size.
+///
+/// * `leaf_index`: Index of the proven leaf.
+///
+/// * `leaf_count`: Number of leaves in the range when the proof was generated.
+///
+/// * `siblings`: Sibling hashes from the leaf up to its mountain's peak.
+///
+/// * `peaks`: The peaks of every other mountain, from left to right.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MmrProof {
+    pub leaf_index: usize,
+    pub leaf_count: usize,
+    pub siblings: Vec<Vec<u8>>,
+    pub peaks: Vec<Vec<u8>>,
+}
+
+/// Position of one mountain within the range.
+struct Mountain {
+    height: u32,
+    first_leaf: usize,
+    first_pos: usize,
+}
+
+impl Mountain {
+    fn leaves(&self) -> usize {
+        1 << self.height
+    }
+
+    fn peak_pos(&self) -> usize {
+        self.first_pos + (2 << self.height) - 2
+    }
+}
+
+impl MerkleMountainRange {
+    /// Function to create a new, empty [MerkleMountainRange](struct.MerkleMountainRange.html)
+    pub fn new() -> MerkleMountainRange {
+        MerkleMountainRange::default()
+    }
+
+    /// Appends `data` as a new leaf and returns its leaf index.
+    pub fn append(&mut self, data: &[u8]) -> usize {
+        let index = self.leaf_count;
+        self.nodes.push(sha256(data));
+        self.leaf_count += 1;
+
+        // Every trailing zero of the new leaf count is a pair of equal-height mountains to merge.
+        for height in 0..self.leaf_count.trailing_zeros() {
+            let right = self.nodes.len() - 1;
+            let left = right - ((2 << height) - 1);
+            let parent = sha256_concat(&[&self.nodes[left], &self.nodes[right]]);
+            self.nodes.push(parent);
+        }
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(
+            self.last_append_is_consistent(data),
+            "mountain range is inconsistent"
+        );
+        index
+    }
+
+    /// Checks that the last leaf is the hash of `data` and every parent merged above it is the hash of its children, which is all an append can change.
+    #[cfg(feature = "debug-validate")]
+    fn last_append_is_consistent(&self, data: &[u8]) -> bool {
+        let merges = self.leaf_count.trailing_zeros() as usize;
+        let leaf = self.nodes.len() - 1 - merges;
+        self.nodes[leaf] == sha256(data)
+            && (1..=merges).all(|height| {
+                let parent = leaf + height;
+                let left = parent - (2 << (height - 1));
+                self.nodes[parent] == sha256_concat(&[&self.nodes[left], &self.nodes[parent - 1]])
+            })
+    }
+
+    /// Returns the number of appended leaves.
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Returns the total number of stored nodes, leaves included.
+    pub fn size(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the peak hashes of all mountains, from the tallest (leftmost) to the shortest.
+    pub fn peaks(&self) -> Vec<Vec<u8>> {
+        mountains(self.leaf_count)
+            .iter()
+            .map(|mountain| self.nodes[mountain.peak_pos()].clone())
+            .collect()
+    }
+
+    /// Returns the root obtained by bagging the peaks, or `None` if the range is empty.
+    pub fn root(&self) -> Option<Vec<u8>> {
+        bag_peaks(&self.peaks())
+    }
+
+    /// Generates an inclusion proof for the leaf at `leaf_index`. Returns `None` if the index is out of range.
+    pub fn prove(&self, leaf_index: usize) -> Option<MmrProof> {
+        if leaf_index >= self.leaf_count {
+            return None;
+        }
+        let layout = mountains(self.leaf_count);
+        let mut siblings = Vec::new();
+        let mut peaks = Vec::new();
+
+        for mountain in &layout {
+            if leaf_index < mountain.first_leaf
+                || leaf_index >= mountain.first_leaf + mountain.leaves()
+            {
+                peaks.push(self.nodes[mountain.peak_pos()].clone());
+                continue;
+            }
+            // Walk down from the peak, collecting the sibling at each level.
+            let offset = leaf_index - mountain.first_leaf;
+            let mut pos = mountain.peak_pos();
+            for height in (1..=mountain.height).rev() {
+                let right_child = pos - 1;
+                let left_child = pos - (1 << height);
+                if offset >> (height - 1) & 1 == 1 {
+                    siblings.push(self.nodes[left_child].clone());
+                    pos = right_child;
+                } else {
+                    siblings.push(self.nodes[right_child].clone());
+                    pos = left_child;
+                }
+            }
+            siblings.reverse();
+        }
+
+        Some(MmrProof {
+            leaf_index,
+            leaf_count: self.leaf_count,
+            siblings,
+            peaks,
+        })
+    }
+}
+
+impl MmrProof {
+    /// Checks that `data` is the leaf at `leaf_index` of the range with root `root`.
+    pub fn verify(&self, root: &[u8], data: &[u8]) -> bool {
+        if self.leaf_index >= self.leaf_count {
+            return false;
+        }
+        let layout = mountains(self.leaf_count);
+        let Some(slot) = layout.iter().position(|mountain| {
+            self.leaf_index >= mountain.first_leaf
+                && self.leaf_index < mountain.first_leaf + mountain.leaves()
+        }) else {
+            return false;
+        };
+        let mountain = &layout[slot];
+        if self.siblings.len() != mountain.height as usize || self.peaks.len() + 1 != layout.len() {
+            return false;
+        }
+
+        let offset = self.leaf_index - mountain.first_leaf;
+        let mut current = sha256(data);
+        for (height, sibling) in self.siblings.iter().enumerate() {
+            current = if offset >> height & 1 == 1 {
+                sha256_concat(&[sibling, &current])
+            } else {
+                sha256_concat(&[&current, sibling])
+            };
+        }
+
+        let mut peaks = self.peaks.clone();
+        peaks.insert(slot, current);
+        bag_peaks(&peaks).as_deref() == Some(root)
+    }
+}
+
+/// Helper function to describe the mountains of a range with `leaf_count` leaves, from left to right.
+fn mountains(leaf_count: usize) -> Vec<Mountain> {
+    let mut layout = Vec::new();
+    let mut first_leaf = 0;
+    let mut first_pos = 0;
+    for height in (0..usize::BITS).rev() {
+        if leaf_count >> height & 1 == 1 {
+            layout.push(Mountain {
+                height,
+                first_leaf,
+                first_pos,
+            });
+            first_leaf += 1 << height;
+            first_pos += (2 << height) - 1;
+        }
+    }
+    layout
+}
+
+/// Folds the peaks from right to left: `H(p0 || H(p1 || ... H(pn-1 || pn)))`.
+fn bag_peaks(peaks: &[Vec<u8>]) -> Option<Vec<u8>> {
+    let mut iter = peaks.iter().rev();
+    let mut root = iter.next()?.clone();
+    for peak in iter {
+        root = sha256_concat(&[peak, &root]);
+    }
+    Some(root)
+}
@@ -0,0 +1,147 @@
+//! Verification of `eth_getProof` responses.
+//!
+//! The `eth_getProof` RPC method returns an account's fields together with a proof in the state trie, and for each requested storage slot a value with a proof in the account's storage trie. [AccountProof::verify](struct.AccountProof.html#method.verify) checks all of them against a trusted state root, so a service only has to trust the block header it got the root from, not the RPC node.
+//!
+//! Both tries are secure tries: the state trie is keyed by `keccak256(address)` and holds RLP `[nonce, balance, storage_root, code_hash]`; storage tries are keyed by `keccak256(slot)` and hold the RLP of the value without leading zeros. Accounts and slots that are absent prove as empty accounts and zero values.
+
+use super::{keccak256, rlp, verify_proof, MptError, EMPTY_ROOT};
+use rlp::Item;
+
+/// [Account](struct.Account.html) is the state of an Ethereum account. Quantities are big-endian 256-bit integers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub nonce: u64,
+    pub balance: [u8; 32],
+    pub storage_root: [u8; 32],
+    pub code_hash: [u8; 32],
+}
+
+/// [StorageProof](struct.StorageProof.html) is one entry of the `storageProof` array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageProof {
+    pub key: [u8; 32],
+    pub value: [u8; 32],
+    pub proof: Vec<Vec<u8>>,
+}
+
+/// [AccountProof](struct.AccountProof.html) is an `eth_getProof` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountProof {
+    pub address: [u8; 20],
+    pub account: Account,
+    pub account_proof: Vec<Vec<u8>>,
+    pub storage_proofs: Vec<StorageProof>,
+}
+
+impl Account {
+    /// Returns the account of an address that was never used: no nonce, balance, storage or code.
+    pub fn empty() -> Account {
+        let mut code_hash = [0u8; 32];
+        code_hash.copy_from_slice(&keccak256(&[]));
+        Account {
+            nonce: 0,
+            balance: [0; 32],
+            storage_root: EMPTY_ROOT,
+            code_hash,
+        }
+    }
+
+    /// Returns the RLP encoding stored in the state trie.
+    pub fn encode(&self) -> Vec<u8> {
+        rlp::encode_list(&[
+            rlp::encode_u64(self.nonce),
+            rlp::encode_bytes(strip_zeros(&self.balance)),
+            rlp::encode_bytes(&self.storage_root),
+            rlp::encode_bytes(&self.code_hash),
+        ])
+    }
+
+    /// Decodes an account from its state trie encoding.
+    pub fn decode(encoded: &[u8]) -> Option<Account> {
+        let Item::List(fields) = rlp::decode(encoded)? else {
+            return None;
+        };
+        let [Item::Bytes(nonce), Item::Bytes(balance), Item::Bytes(storage_root), Item::Bytes(code_hash)] =
+            fields.as_slice()
+        else {
+            return None;
+        };
+        if nonce.len() > 8 {
+            return None;
+        }
+        let nonce = nonce.iter().fold(0u64, |acc, byte| acc << 8 | *byte as u64);
+        Some(Account {
+            nonce,
+            balance: left_pad(balance)?,
+            storage_root: storage_root.as_slice().try_into().ok()?,
+            code_hash: code_hash.as_slice().try_into().ok()?,
+        })
+    }
+}
+
+impl AccountProof {
+    /// Checks the account and every storage slot against `state_root`.
+    ///
+    /// Returns [MptError::ValueMismatch](enum.MptError.html) if a proof is valid but shows a different account or value than claimed.
+    pub fn verify(&self, state_root: &[u8]) -> Result<(), MptError> {
+        let proven = verify_account(state_root, &self.address, &self.account_proof)?;
+        if proven.unwrap_or_else(Account::empty) != self.account {
+            return Err(MptError::ValueMismatch);
+        }
+        for slot in &self.storage_proofs {
+            let value = verify_storage(&self.account.storage_root, &slot.key, &slot.proof)?;
+            if value != slot.value {
+                return Err(MptError::ValueMismatch);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Verifies a state trie proof for `address`, returning the proven account or `None` if it does not exist.
+pub fn verify_account(
+    state_root: &[u8],
+    address: &[u8; 20],
+    proof: &[Vec<u8>],
+) -> Result<Option<Account>, MptError> {
+    match verify_proof(state_root, &keccak256(address), proof)? {
+        Some(encoded) => Account::decode(&encoded)
+            .map(Some)
+            .ok_or(MptError::InvalidNode),
+        None => Ok(None),
+    }
+}
+
+/// Verifies a storage trie proof for `slot`, returning the proven value (zero if absent).
+pub fn verify_storage(
+    storage_root: &[u8],
+    slot: &[u8; 32],
+    proof: &[Vec<u8>],
+) -> Result<[u8; 32], MptError> {
+    match verify_proof(storage_root, &keccak256(slot), proof)? {
+        Some(encoded) => match rlp::decode(&encoded) {
+            Some(Item::Bytes(value)) => left_pad(&value).ok_or(MptError::InvalidNode),
+            _ => Err(MptError::InvalidNode),
+        },
+        None => Ok([0; 32]),
+    }
+}
+
+/// Returns the storage trie encoding of a slot value; zero values are not stored.
+pub fn encode_storage_value(value: &[u8; 32]) -> Vec<u8> {
+    rlp::encode_bytes(strip_zeros(value))
+}
+
+fn strip_zeros(bytes: &[u8]) -> &[u8] {
+    let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    &bytes[first..]
+}
+
+fn left_pad(bytes: &[u8]) -> Option<[u8; 32]> {
+    if bytes.len() > 32 {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    out[32 - bytes.len()..].copy_from_slice(bytes);
+    Some(out)
+}
@@ -0,0 +1,404 @@
+//! Ethereum-style Merkle Patricia Trie.
+//!
+//! [MerklePatriciaTrie](struct.MerklePatriciaTrie.html) is the hexary trie Ethereum uses for its state, storage, transaction and receipt tries. Nodes are serialized with [RLP](rlp/index.html) and referenced by their Keccak-256 hash, except for nodes whose encoding is shorter than 32 bytes, which are embedded in their parent.
+//!
+//! Proofs are lists of RLP-encoded nodes from the root down to the key, which is the format returned by the `eth_getProof` RPC method, so [verify_proof](fn.verify_proof.html) can check proofs produced by an Ethereum node as well as by this trie. The [account](account/index.html) module checks complete `eth_getProof` responses, account fields and storage slots included.
+//!
+//! Keys are used as given. Ethereum's state and storage tries are "secure" tries whose keys are `keccak256(key)`; hash keys with [keccak256](fn.keccak256.html) before inserting to reproduce them.
+//!
+//! Only available with the `mpt` feature.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::mpt::{verify_proof, MerklePatriciaTrie};
+//! let mut trie = MerklePatriciaTrie::new();
+//! trie.insert(b"doe", b"reindeer");
+//! trie.insert(b"dog", b"puppy");
+//! let root = trie.root();
+//!
+//! let proof = trie.prove(b"dog");
+//! assert_eq!(verify_proof(&root, b"dog", &proof), Ok(Some(b"puppy".to_vec())));
+//! assert_eq!(verify_proof(&root, b"cat", &trie.prove(b"cat")), Ok(None));
+//! ```
+
+pub mod account;
+pub mod rlp;
+
+use sha3::{Digest, Keccak256};
+use rlp::Item;
+
+/// Root hash of an empty trie, `keccak256(rlp(""))`.
+pub const EMPTY_ROOT: [u8; 32] = [
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+];
+
+/// Errors returned when verifying a Merkle Patricia proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MptError {
+    /// A proof node is not valid RLP or not a valid trie node.
+    InvalidNode,
+    /// A proof node does not hash to the reference held by its parent (or to the root).
+    HashMismatch,
+    /// The proof ends before the key's path is resolved.
+    IncompleteProof,
+    /// The proof contains nodes that are not on the key's path.
+    UnusedNodes,
+    /// The proof is valid but proves a different value than the one claimed.
+    ValueMismatch,
+}
+
+/// A node of the trie. Paths are stored as nibbles.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Empty,
+    Leaf {
+        path: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Extension {
+        path: Vec<u8>,
+        child: Box<Node>,
+    },
+    Branch {
+        children: Box<[Node; 16]>,
+        value: Option<Vec<u8>>,
+    },
+}
+
+/// [MerklePatriciaTrie](struct.MerklePatriciaTrie.html) is an in-memory Ethereum Merkle Patricia Trie.
+#[derive(Debug, Clone)]
+pub struct MerklePatriciaTrie {
+    root: Node,
+}
+
+impl Default for MerklePatriciaTrie {
+    fn default() -> Self {
+        MerklePatriciaTrie::new()
+    }
+}
+
+impl MerklePatriciaTrie {
+    /// Function to create a new, empty [MerklePatriciaTrie](struct.MerklePatriciaTrie.html)
+    pub fn new() -> MerklePatriciaTrie {
+        MerklePatriciaTrie { root: Node::Empty }
+    }
+
+    /// Inserts `value` under `key`, replacing any previous value.
+    ///
+    /// Empty values are not stored in Ethereum tries, so inserting an empty value is ignored.
+    pub fn insert(&mut self, key: &[u8], value: &[u8]) {
+        if value.is_empty() {
+            return;
+        }
+        let root = std::mem::replace(&mut self.root, Node::Empty);
+        self.root = insert(root, &nibbles(key), value.to_vec());
+    }
+
+    /// Returns the value stored under `key`.
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        let path = nibbles(key);
+        let mut path = path.as_slice();
+        let mut node = &self.root;
+        loop {
+            match node {
+                Node::Empty => return None,
+                Node::Leaf { path: leaf, value } => {
+                    return (leaf.as_slice() == path).then_some(value.as_slice());
+                }
+                Node::Extension { path: ext, child } => {
+                    path = path.strip_prefix(ext.as_slice())?;
+                    node = child;
+                }
+                Node::Branch { children, value } => match path.split_first() {
+                    None => return value.as_deref(),
+                    Some((nibble, rest)) => {
+                        node = &children[*nibble as usize];
+                        path = rest;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Returns the Keccak-256 root hash of the trie.
+    pub fn root(&self) -> Vec<u8> {
+        keccak256(&self.root.encode())
+    }
+
+    /// Generates a proof for `key`: the RLP encoding of every hash-referenced node on its path, starting with the root.
+    ///
+    /// If the key is absent the proof shows where its path ends, and [verify_proof](fn.verify_proof.html) returns `Ok(None)` for it.
+    pub fn prove(&self, key: &[u8]) -> Vec<Vec<u8>> {
+        let path = nibbles(key);
+        let mut path = path.as_slice();
+        let mut node = &self.root;
+        let mut proof = vec![node.encode()];
+        loop {
+            let next = match node {
+                Node::Empty | Node::Leaf { .. } => None,
+                Node::Extension { path: ext, child } => {
+                    path.strip_prefix(ext.as_slice()).map(|rest| {
+                        path = rest;
+                        child.as_ref()
+                    })
+                }
+                Node::Branch { children, .. } => path.split_first().map(|(nibble, rest)| {
+                    path = rest;
+                    &children[*nibble as usize]
+                }),
+            };
+            match next {
+                Some(child) => {
+                    let encoded = child.encode();
+                    if encoded.len() >= 32 {
+                        proof.push(encoded);
+                    }
+                    node = child;
+                }
+                None => return proof,
+            }
+        }
+    }
+}
+
+/// Verifies a proof for `key` against `root`.
+///
+/// Returns `Ok(Some(value))` if the proof shows `key` maps to `value`, `Ok(None)` if it shows `key` is absent, and an [MptError](enum.MptError.html) if the proof is invalid.
+pub fn verify_proof(
+    root: &[u8],
+    key: &[u8],
+    proof: &[Vec<u8>],
+) -> Result<Option<Vec<u8>>, MptError> {
+    let path = nibbles(key);
+    let mut path = path.as_slice();
+    let mut nodes = proof.iter();
+    let mut expected = root.to_vec();
+
+    'fetch: loop {
+        let encoded = match nodes.next() {
+            Some(encoded) => encoded,
+            None if expected == EMPTY_ROOT => return Ok(None),
+            None => return Err(MptError::IncompleteProof),
+        };
+        if keccak256(encoded) != expected {
+            return Err(MptError::HashMismatch);
+        }
+        let mut item = rlp::decode(encoded).ok_or(MptError::InvalidNode)?;
+
+        loop {
+            let child = match &item {
+                Item::Bytes(bytes) if bytes.is_empty() => None,
+                Item::List(fields) if fields.len() == 17 => match path.split_first() {
+                    None => {
+                        let value = bytes(&fields[16])?;
+                        return finish(nodes, (!value.is_empty()).then(|| value.to_vec()));
+                    }
+                    Some((nibble, rest)) => {
+                        path = rest;
+                        Some(fields[*nibble as usize].clone())
+                    }
+                },
+                Item::List(fields) if fields.len() == 2 => {
+                    let (is_leaf, node_path) = decode_hex_prefix(bytes(&fields[0])?)?;
+                    if is_leaf {
+                        let value =
+                            (node_path == path).then(|| bytes(&fields[1]).map(|v| v.to_vec()));
+                        return finish(nodes, value.transpose()?);
+                    }
+                    match path.strip_prefix(node_path.as_slice()) {
+                        Some(rest) => {
+                            path = rest;
+                            Some(fields[1].clone())
+                        }
+                        None => None,
+                    }
+                }
+                _ => return Err(MptError::InvalidNode),
+            };
+
+            match child {
+                None => return finish(nodes, None),
+                Some(Item::Bytes(reference)) if reference.is_empty() => return finish(nodes, None),
+                Some(Item::Bytes(reference)) if reference.len() == 32 => {
+                    expected = reference;
+                    continue 'fetch;
+                }
+                Some(embedded @ Item::List(_)) => item = embedded,
+                Some(_) => return Err(MptError::InvalidNode),
+            }
+        }
+    }
+}
+
+/// Returns the Keccak-256 hash of `data`, as used by Ethereum.
+pub fn keccak256(data: &[u8]) -> Vec<u8> {
+    Keccak256::digest(data).to_vec()
+}
+
+fn finish<'a>(
+    mut remaining: impl Iterator<Item = &'a Vec<u8>>,
+    value: Option<Vec<u8>>,
+) -> Result<Option<Vec<u8>>, MptError> {
+    match remaining.next() {
+        Some(_) => Err(MptError::UnusedNodes),
+        None => Ok(value),
+    }
+}
+
+fn bytes(item: &Item) -> Result<&[u8], MptError> {
+    match item {
+        Item::Bytes(bytes) => Ok(bytes),
+        Item::List(_) => Err(MptError::InvalidNode),
+    }
+}
+
+impl Node {
+    /// RLP encoding of the node.
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            Node::Empty => rlp::encode_bytes(&[]),
+            Node::Leaf { path, value } => rlp::encode_list(&[
+                rlp::encode_bytes(&encode_hex_prefix(path, true)),
+                rlp::encode_bytes(value),
+            ]),
+            Node::Extension { path, child } => rlp::encode_list(&[
+                rlp::encode_bytes(&encode_hex_prefix(path, false)),
+                child.reference(),
+            ]),
+            Node::Branch { children, value } => {
+                let mut items: Vec<Vec<u8>> = children.iter().map(Node::reference).collect();
+                items.push(rlp::encode_bytes(value.as_deref().unwrap_or(&[])));
+                rlp::encode_list(&items)
+            }
+        }
+    }
+
+    /// How the node appears inside its parent: embedded if its encoding is shorter than 32 bytes, otherwise by hash.
+    fn reference(&self) -> Vec<u8> {
+        let encoded = self.encode();
+        if encoded.len() < 32 {
+            encoded
+        } else {
+            rlp::encode_bytes(&keccak256(&encoded))
+        }
+    }
+}
+
+fn insert(node: Node, path: &[u8], value: Vec<u8>) -> Node {
+    match node {
+        Node::Empty => Node::Leaf {
+            path: path.to_vec(),
+            value,
+        },
+        Node::Leaf {
+            path: leaf_path,
+            value: leaf_value,
+        } => {
+            if leaf_path == path {
+                return Node::Leaf {
+                    path: leaf_path,
+                    value,
+                };
+            }
+            let common = common_prefix(&leaf_path, path);
+            let branch = insert(empty_branch(), &leaf_path[common..], leaf_value);
+            let branch = insert(branch, &path[common..], value);
+            wrap(&path[..common], branch)
+        }
+        Node::Extension {
+            path: ext_path,
+            child,
+        } => {
+            let common = common_prefix(&ext_path, path);
+            if common == ext_path.len() {
+                return Node::Extension {
+                    child: Box::new(insert(*child, &path[common..], value)),
+                    path: ext_path,
+                };
+            }
+            let mut branch = empty_branch();
+            if let Node::Branch { children, .. } = &mut branch {
+                let rest = &ext_path[common + 1..];
+                children[ext_path[common] as usize] = wrap(rest, *child);
+            }
+            let branch = insert(branch, &path[common..], value);
+            wrap(&path[..common], branch)
+        }
+        Node::Branch {
+            mut children,
+            value: branch_value,
+        } => match path.split_first() {
+            None => Node::Branch {
+                children,
+                value: Some(value),
+            },
+            Some((nibble, rest)) => {
+                let slot = std::mem::replace(&mut children[*nibble as usize], Node::Empty);
+                children[*nibble as usize] = insert(slot, rest, value);
+                Node::Branch {
+                    children,
+                    value: branch_value,
+                }
+            }
+        },
+    }
+}
+
+fn empty_branch() -> Node {
+    Node::Branch {
+        children: Box::new(std::array::from_fn(|_| Node::Empty)),
+        value: None,
+    }
+}
+
+/// Wraps `node` in an extension for `path`, unless the path is empty.
+fn wrap(path: &[u8], node: Node) -> Node {
+    if path.is_empty() {
+        node
+    } else {
+        Node::Extension {
+            path: path.to_vec(),
+            child: Box::new(node),
+        }
+    }
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+fn nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Hex-prefix encoding of a nibble path, flagging leaves and odd lengths in the first nibble.
+fn encode_hex_prefix(path: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let mut out = Vec::with_capacity(path.len() / 2 + 1);
+    let rest = if path.len() % 2 == 1 {
+        out.push(((flag + 1) << 4) | path[0]);
+        &path[1..]
+    } else {
+        out.push(flag << 4);
+        path
+    };
+    for pair in rest.chunks(2) {
+        out.push((pair[0] << 4) | pair[1]);
+    }
+    out
+}
+
+fn decode_hex_prefix(encoded: &[u8]) -> Result<(bool, Vec<u8>), MptError> {
+    let first = *encoded.first().ok_or(MptError::InvalidNode)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(MptError::InvalidNode);
+    }
+    let mut path = Vec::new();
+    if flag & 1 == 1 {
+        path.push(first & 0x0f);
+    }
+    path.extend(nibbles(&encoded[1..]));
+    Ok((flag & 2 == 2, path))
+}
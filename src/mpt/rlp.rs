@@ -0,0 +1,96 @@
+//! Minimal Recursive Length Prefix (RLP) encoding, as used by Ethereum to serialize trie nodes.
+
+/// A decoded RLP item: either a byte string or a list of items.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Bytes(Vec<u8>),
+    List(Vec<Item>),
+}
+
+/// Encodes `data` as an RLP byte string.
+pub fn encode_bytes(data: &[u8]) -> Vec<u8> {
+    if data.len() == 1 && data[0] < 0x80 {
+        return data.to_vec();
+    }
+    let mut out = encode_length(data.len(), 0x80);
+    out.extend_from_slice(data);
+    out
+}
+
+/// Encodes a list whose elements are already RLP encoded.
+pub fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload: Vec<u8> = items.concat();
+    let mut out = encode_length(payload.len(), 0xc0);
+    out.extend(payload);
+    out
+}
+
+/// Encodes an unsigned integer as a big-endian byte string without leading zeros.
+pub fn encode_u64(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    encode_bytes(&bytes[first..])
+}
+
+fn encode_length(len: usize, offset: u8) -> Vec<u8> {
+    if len <= 55 {
+        return vec![offset + len as u8];
+    }
+    let bytes = len.to_be_bytes();
+    let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    let mut out = vec![offset + 55 + (bytes.len() - first) as u8];
+    out.extend_from_slice(&bytes[first..]);
+    out
+}
+
+/// Decodes a single RLP item spanning the whole of `data`. Returns `None` if the encoding is invalid or has trailing bytes.
+pub fn decode(data: &[u8]) -> Option<Item> {
+    let (item, rest) = decode_item(data)?;
+    if !rest.is_empty() {
+        return None;
+    }
+    Some(item)
+}
+
+/// Decodes the first RLP item of `data`, returning it with the remaining bytes.
+fn decode_item(data: &[u8]) -> Option<(Item, &[u8])> {
+    let prefix = *data.first()?;
+    match prefix {
+        0x00..=0x7f => Some((Item::Bytes(vec![prefix]), &data[1..])),
+        0x80..=0xbf => {
+            let (offset, len) = decode_length(data, 0x80)?;
+            let end = offset.checked_add(len)?;
+            let payload = data.get(offset..end)?;
+            Some((Item::Bytes(payload.to_vec()), &data[end..]))
+        }
+        _ => {
+            let (offset, len) = decode_length(data, 0xc0)?;
+            let end = offset.checked_add(len)?;
+            let mut payload = data.get(offset..end)?;
+            let mut items = Vec::new();
+            while !payload.is_empty() {
+                let (item, rest) = decode_item(payload)?;
+                items.push(item);
+                payload = rest;
+            }
+            Some((Item::List(items), &data[end..]))
+        }
+    }
+}
+
+/// Returns the payload offset and payload length of the item at the start of `data`.
+fn decode_length(data: &[u8], offset: u8) -> Option<(usize, usize)> {
+    let short = (data[0] - offset) as usize;
+    if short <= 55 {
+        return Some((1, short));
+    }
+    let len_of_len = short - 55;
+    let len_bytes = data.get(1..1 + len_of_len)?;
+    if len_of_len > std::mem::size_of::<usize>() || len_bytes[0] == 0 {
+        return None;
+    }
+    let len = len_bytes
+        .iter()
+        .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+    Some((1 + len_of_len, len))
+}
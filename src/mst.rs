@@ -0,0 +1,402 @@
+//! Merkle Search Tree.
+//!
+//! A [MerkleSearchTree](struct.MerkleSearchTree.html) is a search tree over a set of keys whose shape depends only on the set's contents, never on insertion order. Each key is assigned a level from the number of leading zero nibbles of `SHA256(key)`; a node at level `L` holds the level-`L` keys of its range in order, with subtrees of lower-level keys between them. On average every level is 16 times smaller than the one below, so the tree is balanced with high probability.
+//!
+//! Since equal sets always produce identical trees, two replicas can find their differences by exchanging root hashes and then only descending into subtrees whose hashes they do not already have (see [reconcile](struct.MerkleSearchTree.html#method.reconcile)).
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::mst::MerkleSearchTree;
+//! let mut a = MerkleSearchTree::new();
+//! let mut b = MerkleSearchTree::new();
+//! a.extend(["apple", "banana", "cherry"].iter().map(|k| k.as_bytes()));
+//! b.extend(["cherry", "banana", "date"].iter().map(|k| k.as_bytes()));
+//!
+//! let (only_a, only_b) = a.diff(&b);
+//! assert_eq!(only_a, vec![b"apple".to_vec()]);
+//! assert_eq!(only_b, vec![b"date".to_vec()]);
+//! ```
+
+use crate::hashing::{sha256, sha256_concat};
+use std::collections::{BTreeSet, HashMap};
+
+/// [MstNode](struct.MstNode.html) is the content of one node, as exchanged between replicas.
+///
+/// * `level`: The level of every key in the node.
+///
+/// * `keys`: The node's keys in ascending order.
+///
+/// * `children`: Hashes of the subtrees before, between and after the keys (`keys.len() + 1` entries). `None` marks an empty subtree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MstNode {
+    pub level: u32,
+    pub keys: Vec<Vec<u8>>,
+    pub children: Vec<Option<Vec<u8>>>,
+}
+
+/// [MerkleSearchTree](struct.MerkleSearchTree.html) holds a set of keys and the content-addressed nodes of its tree.
+#[derive(Debug, Clone)]
+pub struct MerkleSearchTree {
+    keys: BTreeSet<Vec<u8>>,
+    nodes: HashMap<Vec<u8>, MstNode>,
+    root: Vec<u8>,
+}
+
+impl Default for MerkleSearchTree {
+    fn default() -> Self {
+        MerkleSearchTree::new()
+    }
+}
+
+impl MstNode {
+    /// Returns the hash of the node.
+    pub fn hash(&self) -> Vec<u8> {
+        let mut parts: Vec<Vec<u8>> = vec![self.level.to_be_bytes().to_vec()];
+        for (i, child) in self.children.iter().enumerate() {
+            parts.push(child.clone().unwrap_or_else(|| vec![0u8; 32]));
+            if let Some(key) = self.keys.get(i) {
+                parts.push((key.len() as u64).to_be_bytes().to_vec());
+                parts.push(key.clone());
+            }
+        }
+        let parts: Vec<&[u8]> = parts.iter().map(|part| part.as_slice()).collect();
+        sha256_concat(&parts)
+    }
+}
+
+impl MerkleSearchTree {
+    /// Function to create a new, empty [MerkleSearchTree](struct.MerkleSearchTree.html)
+    pub fn new() -> MerkleSearchTree {
+        let mut tree = MerkleSearchTree {
+            keys: BTreeSet::new(),
+            nodes: HashMap::new(),
+            root: Vec::new(),
+        };
+        tree.rebuild();
+        tree
+    }
+
+    /// Inserts `key`, rehashing only the nodes on its path. Returns `false` if it was already present.
+    pub fn insert(&mut self, key: &[u8]) -> bool {
+        if self.keys.contains(key) {
+            return false;
+        }
+        let root = self.root_subtree();
+        if root.is_none() {
+            self.nodes.remove(&self.root);
+        }
+        self.keys.insert(key.to_vec());
+        let root = self.put(root, key, level(key));
+        self.set_root(Some(root));
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(self.path_is_consistent(key), "search tree is inconsistent");
+        true
+    }
+
+    /// Inserts every key of `keys`, rebuilding the tree only once.
+    pub fn extend<'a>(&mut self, keys: impl IntoIterator<Item = &'a [u8]>) {
+        self.keys.extend(keys.into_iter().map(|key| key.to_vec()));
+        self.rebuild();
+    }
+
+    /// Removes `key`, rehashing only the nodes on its path. Returns `false` if it was not present.
+    pub fn remove(&mut self, key: &[u8]) -> bool {
+        if !self.keys.remove(key) {
+            return false;
+        }
+        let root = self.delete(self.root.clone(), key, level(key));
+        self.set_root(root);
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(self.path_is_consistent(key), "search tree is inconsistent");
+        true
+    }
+
+    /// Returns `true` if `key` is in the set.
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns an iterator over the keys in ascending order.
+    pub fn keys(&self) -> impl Iterator<Item = &[u8]> {
+        self.keys.iter().map(|key| key.as_slice())
+    }
+
+    /// Returns the Root Hash of the tree.
+    pub fn root(&self) -> Vec<u8> {
+        self.root.clone()
+    }
+
+    /// Returns the node with hash `hash`, which is what a replica serves to its peers during reconciliation.
+    pub fn node(&self, hash: &[u8]) -> Option<&MstNode> {
+        self.nodes.get(hash)
+    }
+
+    /// Finds the keys of a remote tree with root `remote_root` that are missing locally.
+    ///
+    /// `fetch` is called to obtain a remote node by hash. Subtrees whose hash also exists in this tree are identical and are skipped without being fetched. Returns `None` if `fetch` fails for a node or returns a node that does not have the requested hash, so a peer can neither inject keys nor send the walk in circles.
+    pub fn reconcile(
+        &self,
+        remote_root: &[u8],
+        mut fetch: impl FnMut(&[u8]) -> Option<MstNode>,
+    ) -> Option<Vec<Vec<u8>>> {
+        let mut missing = Vec::new();
+        let mut pending = vec![remote_root.to_vec()];
+        while let Some(hash) = pending.pop() {
+            if self.nodes.contains_key(&hash) {
+                continue;
+            }
+            let node = fetch(&hash).filter(|node| node.hash() == hash)?;
+            missing.extend(node.keys.into_iter().filter(|key| !self.keys.contains(key)));
+            pending.extend(node.children.into_iter().flatten());
+        }
+        missing.sort();
+        Some(missing)
+    }
+
+    /// Computes the symmetric difference with `other`: `(keys only in self, keys only in other)`.
+    pub fn diff(&self, other: &MerkleSearchTree) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let only_other = self
+            .reconcile(&other.root, |hash| other.node(hash).cloned())
+            .unwrap_or_default();
+        let only_self = other
+            .reconcile(&self.root, |hash| self.node(hash).cloned())
+            .unwrap_or_default();
+        (only_self, only_other)
+    }
+
+    /// Checks that every node on the search path of `key` is stored under its own hash, with a child stored for every gap between its keys, which is all an insertion or removal can change.
+    #[cfg(feature = "debug-validate")]
+    fn path_is_consistent(&self, key: &[u8]) -> bool {
+        let mut next = Some(self.root.clone());
+        while let Some(hash) = next {
+            let Some(node) = self.nodes.get(&hash) else {
+                return false;
+            };
+            if node.hash() != hash
+                || node.children.len() != node.keys.len() + 1
+                || !node
+                    .children
+                    .iter()
+                    .flatten()
+                    .all(|child| self.nodes.contains_key(child))
+            {
+                return false;
+            }
+            let i = node.keys.partition_point(|k| k.as_slice() < key);
+            if node.keys.get(i).is_some_and(|k| k == key) {
+                return true;
+            }
+            next = node.children[i].clone();
+        }
+        true
+    }
+
+    /// Helper function returning the root of a non-empty tree, or `None` for the empty tree.
+    fn root_subtree(&self) -> Option<Vec<u8>> {
+        (!self.keys.is_empty()).then(|| self.root.clone())
+    }
+
+    /// Helper function to set the root, storing the placeholder node of the empty tree if `root` is `None`.
+    fn set_root(&mut self, root: Option<Vec<u8>>) {
+        self.root = match root {
+            Some(root) => root,
+            None => {
+                let empty = MstNode {
+                    level: 0,
+                    keys: Vec::new(),
+                    children: vec![None],
+                };
+                let hash = empty.hash();
+                self.nodes.insert(hash.clone(), empty);
+                hash
+            }
+        };
+    }
+
+    /// Helper function to store `node`, returning its hash. A node without keys is never stored: its only child takes its place.
+    fn store(&mut self, mut node: MstNode) -> Option<Vec<u8>> {
+        if node.keys.is_empty() {
+            return node.children.pop().flatten();
+        }
+        let hash = node.hash();
+        self.nodes.insert(hash.clone(), node);
+        Some(hash)
+    }
+
+    /// Helper function to remove the node with hash `hash` from the store. Keys are unique, so no two nodes of a tree share a hash and the node is not referenced anywhere else.
+    fn take(&mut self, hash: &[u8]) -> MstNode {
+        self.nodes.remove(hash).expect("subtree hashes are stored")
+    }
+
+    /// Helper function to insert `key` of level `key_level` into `subtree`, returning the new subtree's hash.
+    fn put(&mut self, subtree: Option<Vec<u8>>, key: &[u8], key_level: u32) -> Vec<u8> {
+        let hash = match subtree {
+            Some(hash) if self.nodes[&hash].level >= key_level => hash,
+            subtree => {
+                // The key is above every key of the subtree, which it splits in two.
+                let (left, right) = self.split(subtree, key);
+                let node = MstNode {
+                    level: key_level,
+                    keys: vec![key.to_vec()],
+                    children: vec![left, right],
+                };
+                return self.store(node).expect("the node has a key");
+            }
+        };
+        let mut node = self.take(&hash);
+        let i = node.keys.partition_point(|k| k.as_slice() < key);
+        if node.level == key_level {
+            let (left, right) = self.split(node.children[i].take(), key);
+            node.keys.insert(i, key.to_vec());
+            node.children[i] = left;
+            node.children.insert(i + 1, right);
+        } else {
+            node.children[i] = Some(self.put(node.children[i].take(), key, key_level));
+        }
+        self.store(node).expect("the node has a key")
+    }
+
+    /// Helper function to split `subtree` into the subtrees of its keys below and above `key`.
+    fn split(
+        &mut self,
+        subtree: Option<Vec<u8>>,
+        key: &[u8],
+    ) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+        let Some(hash) = subtree else {
+            return (None, None);
+        };
+        let mut node = self.take(&hash);
+        let i = node.keys.partition_point(|k| k.as_slice() < key);
+        let (left, right) = self.split(node.children[i].take(), key);
+        let mut left_children = node.children.drain(..=i).collect::<Vec<_>>();
+        left_children[i] = left;
+        node.children.insert(0, right);
+        let left = MstNode {
+            level: node.level,
+            keys: node.keys.drain(..i).collect(),
+            children: left_children,
+        };
+        (self.store(left), self.store(node))
+    }
+
+    /// Helper function to remove `key` of level `key_level` from the subtree `hash` holding it, returning the new subtree's hash.
+    fn delete(&mut self, hash: Vec<u8>, key: &[u8], key_level: u32) -> Option<Vec<u8>> {
+        let mut node = self.take(&hash);
+        let i = node.keys.partition_point(|k| k.as_slice() < key);
+        if node.level == key_level {
+            let right = node.children.remove(i + 1);
+            node.keys.remove(i);
+            node.children[i] = self.merge(node.children[i].take(), right);
+        } else {
+            let child = node.children[i].take().expect("the key is in this subtree");
+            node.children[i] = self.delete(child, key, key_level);
+        }
+        self.store(node)
+    }
+
+    /// Helper function to join two adjacent subtrees, every key of `left` being below every key of `right`.
+    fn merge(&mut self, left: Option<Vec<u8>>, right: Option<Vec<u8>>) -> Option<Vec<u8>> {
+        let (left, right) = match (left, right) {
+            (Some(left), Some(right)) => (left, right),
+            (left, right) => return left.or(right),
+        };
+        let (left_level, right_level) = (self.nodes[&left].level, self.nodes[&right].level);
+        let mut node = if left_level >= right_level {
+            self.take(&left)
+        } else {
+            self.take(&right)
+        };
+        if left_level > right_level {
+            let last = node.children.pop().flatten();
+            let merged = self.merge(last, Some(right));
+            node.children.push(merged);
+        } else if left_level < right_level {
+            let first = node.children[0].take();
+            node.children[0] = self.merge(Some(left), first);
+        } else {
+            let mut right = self.take(&right);
+            let last = node.children.pop().flatten();
+            let merged = self.merge(last, right.children[0].take());
+            node.children.push(merged);
+            node.children.extend(right.children.drain(1..));
+            node.keys.append(&mut right.keys);
+        }
+        self.store(node)
+    }
+
+    /// Helper function to recompute every node from the key set.
+    fn rebuild(&mut self) {
+        self.nodes.clear();
+        let keys: Vec<(u32, &Vec<u8>)> = self.keys.iter().map(|key| (level(key), key)).collect();
+        let mut nodes = Vec::new();
+        self.root = match keys.iter().map(|(level, _)| *level).max() {
+            Some(top) => build(&keys, top, &mut nodes),
+            None => {
+                let empty = MstNode {
+                    level: 0,
+                    keys: Vec::new(),
+                    children: vec![None],
+                };
+                let hash = empty.hash();
+                nodes.push((hash.clone(), empty));
+                hash
+            }
+        };
+        self.nodes.extend(nodes);
+    }
+}
+
+/// Builds the node at `level` for the sorted, non-empty `keys`, pushing it and its descendants to `nodes`. Returns its hash.
+fn build(keys: &[(u32, &Vec<u8>)], level: u32, nodes: &mut Vec<(Vec<u8>, MstNode)>) -> Vec<u8> {
+    let mut node = MstNode {
+        level,
+        keys: Vec::new(),
+        children: Vec::new(),
+    };
+    let mut start = 0;
+    for (i, (key_level, key)) in keys.iter().enumerate() {
+        if *key_level == level {
+            node.children.push(build_child(&keys[start..i], nodes));
+            node.keys.push(key.to_vec());
+            start = i + 1;
+        }
+    }
+    node.children.push(build_child(&keys[start..], nodes));
+
+    let hash = node.hash();
+    nodes.push((hash.clone(), node));
+    hash
+}
+
+/// Builds the subtree between two separator keys, rooted at the highest level among its keys.
+fn build_child(keys: &[(u32, &Vec<u8>)], nodes: &mut Vec<(Vec<u8>, MstNode)>) -> Option<Vec<u8>> {
+    let top = keys.iter().map(|(level, _)| *level).max()?;
+    Some(build(keys, top, nodes))
+}
+
+/// Level of `key`: the number of leading zero nibbles of its hash.
+fn level(key: &[u8]) -> u32 {
+    let hash = sha256(key);
+    let mut zeros = 0;
+    for byte in hash {
+        if byte == 0 {
+            zeros += 2;
+            continue;
+        }
+        if byte >> 4 == 0 {
+            zeros += 1;
+        }
+        break;
+    }
+    zeros
+}
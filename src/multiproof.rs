@@ -0,0 +1,247 @@
+//! Streamed multiproofs.
+//!
+//! A multiproof proves many leaves of a [MerkleTree](../struct.MerkleTree.html) at once. For millions of leaves it is too large to build or hold in one piece, so [MerkleTree::multiproof_frames](../struct.MerkleTree.html#method.multiproof_frames) emits it as a sequence of frames of bounded size, and a [FrameDecoder](struct.FrameDecoder.html) checks each frame as it arrives and returns the leaves it proves. Both sides keep one pending hash per level of the tree, never the whole proof.
+//!
+//! The proof walks the tree top-down in pre-order, visiting only nodes above at least one proven leaf. Each visited node is written as an item: a flags byte telling which children lead to proven leaves (bit 0 the left one, bit 1 the right one), then its children's hashes, each as a 2 byte big-endian length followed by the hash. The right hash is left out when the node has a single child, hashed with itself. The decoder already trusts a node's hash when it reads its item, so both children are trusted as soon as they hash to it, and a leaf is proven as soon as its parent's item is read. Items never span frames.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::multiproof::FrameDecoder;
+//! use rs_merkletree::proof::{leaf_hash, TaggedHexSha256};
+//! use rs_merkletree::MerkleTree;
+//! let data: Vec<String> = (0..1000).map(|i| format!("record {}", i)).collect();
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(data.iter().map(String::as_str).collect());
+//! let root = tree.root_node().unwrap().hash();
+//!
+//! let indices: Vec<usize> = (0..1000).step_by(7).collect();
+//! let mut decoder = FrameDecoder::new(TaggedHexSha256, &root, 1000);
+//! let mut proven = 0;
+//! for frame in tree.multiproof_frames(&indices, 1024).unwrap() {
+//!     assert!(frame.len() <= 1024);
+//!     for (index, leaf) in decoder.push_frame(&frame).unwrap() {
+//!         assert_eq!(leaf, leaf_hash(data[index].as_bytes()));
+//!         proven += 1;
+//!     }
+//! }
+//! decoder.finish().unwrap();
+//! assert_eq!(proven, indices.len());
+//! ```
+
+use crate::proof::{ct_eq, proof_len, NodeHasher, HASH_LEN};
+use crate::{Hash, Node};
+
+/// Smallest frame that holds any item of a [MerkleTree](../struct.MerkleTree.html) proof: a flags byte and two length-prefixed 64 byte hashes.
+pub const MIN_FRAME_LEN: usize = 1 + 2 * (2 + 64);
+
+const DESCEND_LEFT: u8 = 1;
+const DESCEND_RIGHT: u8 = 2;
+
+/// Errors found by a [FrameDecoder](struct.FrameDecoder.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultiproofError {
+    /// An item is truncated, has unknown flags, a hash of the wrong length or descends to a child that does not exist.
+    Malformed,
+    /// A node's children do not hash to the node.
+    HashMismatch,
+    /// A frame continues after every proven leaf was reached.
+    TrailingData,
+    /// The stream ended before every visited node was read.
+    Incomplete,
+}
+
+/// Number of nodes `height` levels above the leaves of a tree of `tree_size` leaves.
+fn width(tree_size: usize, height: usize) -> usize {
+    tree_size.div_ceil(1 << height)
+}
+
+/// [FrameEncoder](struct.FrameEncoder.html) produces the frames of a multiproof, see [MerkleTree::multiproof_frames](../struct.MerkleTree.html#method.multiproof_frames).
+///
+/// * `indices`: Sorted, distinct indices of the proven leaves.
+///
+/// * `stack`: Nodes still to be written with their height and the range of `indices` under them, the next one last.
+///
+/// * `max_frame_len`: Upper bound on the length of a frame.
+#[derive(Debug, Clone)]
+pub struct FrameEncoder<'a> {
+    indices: Vec<usize>,
+    stack: Vec<(&'a Node, usize, usize, usize)>,
+    max_frame_len: usize,
+}
+
+impl<'a> FrameEncoder<'a> {
+    pub(crate) fn new(
+        root: &'a Node,
+        height: usize,
+        indices: Vec<usize>,
+        max_frame_len: usize,
+    ) -> FrameEncoder<'a> {
+        assert!(
+            max_frame_len >= MIN_FRAME_LEN,
+            "frames must hold at least {} bytes",
+            MIN_FRAME_LEN
+        );
+        let stack = vec![(root, height, 0, indices.len())];
+        FrameEncoder {
+            indices,
+            stack,
+            max_frame_len,
+        }
+    }
+
+    /// Appends the item of the next node to `frame` and queues its children on the way to proven leaves.
+    fn write_item(&mut self, frame: &mut Vec<u8>) {
+        let Some((node, height, start, end)) = self.stack.pop() else {
+            return;
+        };
+        let left = node
+            .left_node
+            .as_deref()
+            .expect("visited nodes are inner nodes");
+        let right = node.right_node.as_deref();
+        let half = 1 << (height - 1);
+        let split = start + self.indices[start..end].partition_point(|&index| index & half == 0);
+        let mut flags = 0;
+        if split > start {
+            flags |= DESCEND_LEFT;
+        }
+        if split < end {
+            flags |= DESCEND_RIGHT;
+        }
+        frame.push(flags);
+        for child in [Some(left), right].into_iter().flatten() {
+            frame.extend_from_slice(&(HASH_LEN as u16).to_be_bytes());
+            frame.extend_from_slice(&child.hash.to_hex_bytes());
+        }
+        if height > 1 {
+            if let (Some(right), true) = (right, split < end) {
+                self.stack.push((right, height - 1, split, end));
+            }
+            if split > start {
+                self.stack.push((left, height - 1, start, split));
+            }
+        }
+    }
+}
+
+impl Iterator for FrameEncoder<'_> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        if self.stack.is_empty() {
+            return None;
+        }
+        let mut frame = Vec::new();
+        while self.stack.last().is_some_and(|(node, ..)| {
+            let len = [node.left_node.as_deref(), node.right_node.as_deref()]
+                .into_iter()
+                .flatten()
+                .map(|_| 2 + HASH_LEN)
+                .sum::<usize>();
+            frame.len() + 1 + len <= self.max_frame_len
+        }) {
+            self.write_item(&mut frame);
+        }
+        Some(frame)
+    }
+}
+
+/// [FrameDecoder](struct.FrameDecoder.html) verifies the frames of a multiproof one at a time.
+///
+/// * `hasher`: Hashes the children of every node.
+///
+/// * `tree_size`: Number of leaves of the tree, which decides which nodes have a single child.
+///
+/// * `stack`: Trusted nodes still to be read, as `(height, index, hash)`, the next one last.
+#[derive(Debug, Clone)]
+pub struct FrameDecoder<H: NodeHasher> {
+    hasher: H,
+    tree_size: usize,
+    stack: Vec<(usize, usize, Vec<u8>)>,
+}
+
+impl<H: NodeHasher> FrameDecoder<H> {
+    /// Function to create a [FrameDecoder](struct.FrameDecoder.html) for a tree of `tree_size` leaves with Root Hash `root`.
+    pub fn new(hasher: H, root: &Hash, tree_size: usize) -> FrameDecoder<H> {
+        let height = proof_len(tree_size as u64);
+        FrameDecoder {
+            hasher,
+            tree_size,
+            stack: vec![(height, 0, root.to_hex_bytes().to_vec())],
+        }
+    }
+
+    /// Verifies the next frame, returning the `(index, leaf hash)` of every leaf it proves in increasing order of index.
+    ///
+    /// After an error the decoder should be dropped: the leaves returned by earlier frames remain proven, but nothing after them is.
+    pub fn push_frame(&mut self, mut frame: &[u8]) -> Result<Vec<(usize, Hash)>, MultiproofError> {
+        let mut leaves = Vec::new();
+        while let Some((&flags, rest)) = frame.split_first() {
+            let (height, index, hash) = self.stack.pop().ok_or(MultiproofError::TrailingData)?;
+            frame = rest;
+            let lone = 2 * index + 1 >= width(self.tree_size, height - 1);
+            if flags == 0
+                || flags > DESCEND_LEFT | DESCEND_RIGHT
+                || (lone && flags & DESCEND_RIGHT != 0)
+            {
+                return Err(MultiproofError::Malformed);
+            }
+            let left = read_hash(&mut frame, hash.len())?;
+            let right = if lone {
+                left
+            } else {
+                read_hash(&mut frame, hash.len())?
+            };
+            if !ct_eq(self.hasher.hash_nodes(left, right).as_ref(), &hash) {
+                return Err(MultiproofError::HashMismatch);
+            }
+            let children = [
+                (DESCEND_LEFT, 2 * index, left),
+                (DESCEND_RIGHT, 2 * index + 1, right),
+            ];
+            if height == 1 {
+                for (flag, index, leaf) in children {
+                    if flags & flag != 0 {
+                        let leaf =
+                            Hash::from_hex_bytes(leaf).map_err(|_| MultiproofError::Malformed)?;
+                        leaves.push((index, leaf));
+                    }
+                }
+            } else {
+                for (flag, index, child) in children.into_iter().rev() {
+                    if flags & flag != 0 {
+                        self.stack.push((height - 1, index, child.to_vec()));
+                    }
+                }
+            }
+        }
+        Ok(leaves)
+    }
+
+    /// Returns `true` once every visited node has been read.
+    pub fn is_complete(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Checks that the stream is complete.
+    pub fn finish(self) -> Result<(), MultiproofError> {
+        if self.is_complete() {
+            Ok(())
+        } else {
+            Err(MultiproofError::Incomplete)
+        }
+    }
+}
+
+/// Reads a length-prefixed hash of `len` bytes from the front of `frame`.
+fn read_hash<'a>(frame: &mut &'a [u8], len: usize) -> Result<&'a [u8], MultiproofError> {
+    let (prefix, rest) = frame
+        .split_first_chunk::<2>()
+        .ok_or(MultiproofError::Malformed)?;
+    if u16::from_be_bytes(*prefix) as usize != len || rest.len() < len {
+        return Err(MultiproofError::Malformed);
+    }
+    let (hash, rest) = rest.split_at(len);
+    *frame = rest;
+    Ok(hash)
+}
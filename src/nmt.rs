@@ -0,0 +1,304 @@
+//! Namespaced Merkle Tree.
+//!
+//! A [NamespacedMerkleTree](struct.NamespacedMerkleTree.html) is a Merkle tree whose leaves are tagged with a namespace ID and pushed in namespace order. Every node is labelled with the minimum and maximum namespace found below it (`min || max || digest`), so a proof can show that a set of leaves is *all* of the leaves of a namespace, or that a namespace has no leaves at all. This is the construction used by data-availability layers such as Celestia.
+//!
+//! Leaves hash as `ns || ns || SHA256(0x00 || ns || data)` and inner nodes as `min || max || SHA256(0x01 || left || right)`, with the tree split at the largest power of two below the leaf count. Following Celestia, the reserved all-`0xFF` parity namespace is ignored when computing a parent's maximum namespace.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::nmt::NamespacedMerkleTree;
+//! let mut tree = NamespacedMerkleTree::new(1);
+//! tree.push(&[1], b"a").unwrap();
+//! tree.push(&[2], b"b").unwrap();
+//! tree.push(&[2], b"c").unwrap();
+//! tree.push(&[4], b"d").unwrap();
+//! let root = tree.root();
+//!
+//! let proof = tree.prove_namespace(&[2]);
+//! assert!(proof.verify(&root, &[2], &[b"b".to_vec(), b"c".to_vec()]));
+//!
+//! let proof = tree.prove_namespace(&[3]);
+//! assert!(proof.verify(&root, &[3], &[]));
+//! ```
+
+use crate::hashing::sha256_concat;
+use std::ops::Range;
+
+const LEAF_PREFIX: &[u8] = &[0x00];
+const NODE_PREFIX: &[u8] = &[0x01];
+
+/// Errors returned when pushing leaves to a [NamespacedMerkleTree](struct.NamespacedMerkleTree.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NmtError {
+    /// The namespace ID does not have the tree's namespace size.
+    InvalidNamespaceSize,
+    /// The namespace ID is smaller than the namespace of the previous leaf.
+    OutOfOrder,
+}
+
+/// [NamespacedMerkleTree](struct.NamespacedMerkleTree.html) holds the namespaced leaves and their hashes.
+#[derive(Debug, Clone)]
+pub struct NamespacedMerkleTree {
+    namespace_size: usize,
+    namespaces: Vec<Vec<u8>>,
+    leaf_hashes: Vec<Vec<u8>>,
+}
+
+/// [NamespaceProof](struct.NamespaceProof.html) proves the complete set of leaves of a namespace, or its absence.
+///
+/// * `range`: The leaves covered by the proof. For a presence proof these are the namespace's leaves; for an absence proof it is the single leaf where the namespace would have been, or empty when the namespace lies outside the root's range.
+///
+/// * `nodes`: Hashes of the subtrees outside `range`, from left to right.
+///
+/// * `leaf_hash`: For absence proofs, the hash of the leaf covered by `range`.
+///
+/// * `leaf_count`: Number of leaves in the tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamespaceProof {
+    pub range: Range<usize>,
+    pub nodes: Vec<Vec<u8>>,
+    pub leaf_hash: Option<Vec<u8>>,
+    pub leaf_count: usize,
+}
+
+impl NamespacedMerkleTree {
+    /// Function to create an empty [NamespacedMerkleTree](struct.NamespacedMerkleTree.html) whose namespace IDs are `namespace_size` bytes long.
+    pub fn new(namespace_size: usize) -> NamespacedMerkleTree {
+        NamespacedMerkleTree {
+            namespace_size,
+            namespaces: Vec::new(),
+            leaf_hashes: Vec::new(),
+        }
+    }
+
+    /// Appends a leaf. Namespaces must be pushed in non-decreasing order.
+    pub fn push(&mut self, namespace: &[u8], data: &[u8]) -> Result<(), NmtError> {
+        if namespace.len() != self.namespace_size {
+            return Err(NmtError::InvalidNamespaceSize);
+        }
+        if self
+            .namespaces
+            .last()
+            .is_some_and(|last| last.as_slice() > namespace)
+        {
+            return Err(NmtError::OutOfOrder);
+        }
+        self.namespaces.push(namespace.to_vec());
+        self.leaf_hashes.push(leaf_hash(namespace, data));
+        Ok(())
+    }
+
+    /// Returns the number of leaves.
+    pub fn len(&self) -> usize {
+        self.leaf_hashes.len()
+    }
+
+    /// Returns `true` if the tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.leaf_hashes.is_empty()
+    }
+
+    /// Returns the root as `min namespace || max namespace || digest`.
+    pub fn root(&self) -> Vec<u8> {
+        if self.leaf_hashes.is_empty() {
+            return empty_root(self.namespace_size);
+        }
+        self.subtree(0..self.leaf_hashes.len())
+    }
+
+    /// Generates a proof of the leaves of `namespace`: a presence proof if it has leaves, otherwise an absence proof.
+    pub fn prove_namespace(&self, namespace: &[u8]) -> NamespaceProof {
+        let start = self
+            .namespaces
+            .partition_point(|ns| ns.as_slice() < namespace);
+        let end = self
+            .namespaces
+            .partition_point(|ns| ns.as_slice() <= namespace);
+        let leaf_count = self.leaf_hashes.len();
+
+        if start < end {
+            return self.prove_range(start..end);
+        }
+        if start == 0 || start == leaf_count {
+            // Outside the root's namespace range: the root alone proves absence.
+            return NamespaceProof {
+                range: 0..0,
+                nodes: Vec::new(),
+                leaf_hash: None,
+                leaf_count,
+            };
+        }
+        let mut proof = self.prove_range(start..start + 1);
+        proof.leaf_hash = Some(self.leaf_hashes[start].clone());
+        proof
+    }
+
+    /// Generates a proof for the leaves in `range`, which must be a non-empty range of leaf indices.
+    pub fn prove_range(&self, range: Range<usize>) -> NamespaceProof {
+        let mut nodes = Vec::new();
+        self.collect_outside(0..self.leaf_hashes.len(), &range, &mut nodes);
+        NamespaceProof {
+            range,
+            nodes,
+            leaf_hash: None,
+            leaf_count: self.leaf_hashes.len(),
+        }
+    }
+
+    fn collect_outside(&self, span: Range<usize>, range: &Range<usize>, nodes: &mut Vec<Vec<u8>>) {
+        if span.end <= range.start || span.start >= range.end {
+            nodes.push(self.subtree(span));
+        } else if span.len() > 1 {
+            let split = span.start + split_point(span.len());
+            self.collect_outside(span.start..split, range, nodes);
+            self.collect_outside(split..span.end, range, nodes);
+        }
+    }
+
+    fn subtree(&self, span: Range<usize>) -> Vec<u8> {
+        if span.len() == 1 {
+            return self.leaf_hashes[span.start].clone();
+        }
+        let split = span.start + split_point(span.len());
+        node_hash(
+            &self.subtree(span.start..split),
+            &self.subtree(split..span.end),
+            self.namespace_size,
+        )
+    }
+}
+
+impl NamespaceProof {
+    /// Checks that `leaves` are all of the leaves of `namespace` in the tree with root `root`. Pass an empty slice to verify an absence proof.
+    pub fn verify(&self, root: &[u8], namespace: &[u8], leaves: &[Vec<u8>]) -> bool {
+        let size = namespace.len();
+        if root.len() < 2 * size {
+            return false;
+        }
+        if self.range.is_empty() {
+            // Absence by root range: the namespace is below the minimum or above the maximum.
+            return leaves.is_empty()
+                && self.nodes.is_empty()
+                && (self.leaf_count == 0 && root == empty_root(size).as_slice()
+                    || namespace < &root[..size]
+                    || namespace > &root[size..2 * size]);
+        }
+
+        let leaf_hashes: Vec<Vec<u8>> = match &self.leaf_hash {
+            Some(hash) => {
+                if !leaves.is_empty()
+                    || self.range.len() != 1
+                    || hash.len() != hash_len(size)
+                    || &hash[..size] <= namespace
+                {
+                    return false;
+                }
+                vec![hash.clone()]
+            }
+            None => {
+                if leaves.len() != self.range.len() {
+                    return false;
+                }
+                leaves
+                    .iter()
+                    .map(|data| leaf_hash(namespace, data))
+                    .collect()
+            }
+        };
+        if self.range.end > self.leaf_count {
+            return false;
+        }
+
+        let mut verifier = RangeVerifier {
+            proof: self,
+            namespace,
+            leaves: leaf_hashes.iter(),
+            nodes: self.nodes.iter(),
+            complete: true,
+        };
+        let computed = verifier.compute(0..self.leaf_count);
+        computed.is_some()
+            && verifier.nodes.next().is_none()
+            && verifier.complete
+            && computed.as_deref() == Some(root)
+    }
+}
+
+/// Recomputes the root from a range proof while checking that the namespace cannot have leaves outside the range.
+struct RangeVerifier<'a, I: Iterator<Item = &'a Vec<u8>>> {
+    proof: &'a NamespaceProof,
+    namespace: &'a [u8],
+    leaves: I,
+    nodes: std::slice::Iter<'a, Vec<u8>>,
+    complete: bool,
+}
+
+impl<'a, I: Iterator<Item = &'a Vec<u8>>> RangeVerifier<'a, I> {
+    fn compute(&mut self, span: Range<usize>) -> Option<Vec<u8>> {
+        let size = self.namespace.len();
+        let range = &self.proof.range;
+        if span.end <= range.start || span.start >= range.end {
+            let node = self.nodes.next()?.clone();
+            if node.len() != hash_len(size) {
+                return None;
+            }
+            // Subtrees left of the range must end before the namespace, subtrees right of it must start after.
+            if span.end <= range.start && &node[size..2 * size] >= self.namespace {
+                self.complete = false;
+            }
+            if span.start >= range.end
+                && self.proof.leaf_hash.is_none()
+                && &node[..size] <= self.namespace
+            {
+                self.complete = false;
+            }
+            return Some(node);
+        }
+        if span.len() == 1 {
+            return self.leaves.next().cloned();
+        }
+        let split = span.start + split_point(span.len());
+        let left = self.compute(span.start..split)?;
+        let right = self.compute(split..span.end)?;
+        Some(node_hash(&left, &right, size))
+    }
+}
+
+fn leaf_hash(namespace: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut hash = [namespace, namespace].concat();
+    hash.extend(sha256_concat(&[LEAF_PREFIX, namespace, data]));
+    hash
+}
+
+fn node_hash(left: &[u8], right: &[u8], size: usize) -> Vec<u8> {
+    let parity = vec![0xffu8; size];
+    let min = &left[..size];
+    let max = if right[..size] == parity[..] {
+        &left[size..2 * size]
+    } else {
+        &right[size..2 * size]
+    };
+    let mut hash = [min, max].concat();
+    hash.extend(sha256_concat(&[NODE_PREFIX, left, right]));
+    hash
+}
+
+/// Length of a node hash, `min || max || digest`, for namespaces of `size` bytes.
+fn hash_len(size: usize) -> usize {
+    2 * size + 32
+}
+
+fn empty_root(size: usize) -> Vec<u8> {
+    let mut root = vec![0u8; 2 * size];
+    root.extend(sha256_concat(&[]));
+    root
+}
+
+/// Largest power of two strictly less than `n`.
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
@@ -0,0 +1,85 @@
+//! Root change notifications.
+//!
+//! The trees that change in place, [IncrementalMerkleTree](../incremental/struct.IncrementalMerkleTree.html), [ConcurrentMerkleTree](../concurrent/struct.ConcurrentMerkleTree.html), [HistoryTree](../history/struct.HistoryTree.html) and [JellyfishMerkleTree](../jmt/struct.JellyfishMerkleTree.html), accept subscribers that receive a [RootChange](struct.RootChange.html) after every commit, either as a callback or through a channel. Caches and on-chain submitters can react to new roots without polling.
+//!
+//! Subscribers belong to the tree they were added to: a clone of the tree starts without any. Trees without subscribers do not compute the extra roots.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::history::HistoryTree;
+//! let mut log = HistoryTree::new();
+//! let changes = log.subscribe_channel();
+//! log.append(b"a");
+//! log.append(b"b");
+//! let change = changes.try_iter().last().unwrap();
+//! assert_eq!(change.version, 2);
+//! assert_eq!(change.new_root, log.root());
+//! ```
+
+use std::fmt;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// [RootChange](struct.RootChange.html) describes one commit.
+///
+/// * `old_root`: Root before the commit; the empty tree's root for the first one.
+///
+/// * `new_root`: Root after the commit.
+///
+/// * `version`: The tree's version after the commit: its leaf count, sequence number or committed version, depending on the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootChange {
+    pub old_root: Vec<u8>,
+    pub new_root: Vec<u8>,
+    pub version: u64,
+}
+
+type Callback = Box<dyn FnMut(&RootChange) + Send + Sync>;
+
+/// Callbacks and channels subscribed to a tree.
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    callbacks: Vec<Callback>,
+    channels: Vec<Sender<RootChange>>,
+}
+
+impl Subscribers {
+    /// Returns `true` if nobody needs to be notified.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.callbacks.is_empty() && self.channels.is_empty()
+    }
+
+    pub(crate) fn add<F: FnMut(&RootChange) + Send + Sync + 'static>(&mut self, callback: F) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    pub(crate) fn channel(&mut self) -> Receiver<RootChange> {
+        let (sender, receiver) = channel();
+        self.channels.push(sender);
+        receiver
+    }
+
+    /// Calls every callback, then sends to every channel, forgetting those whose receiver is gone.
+    pub(crate) fn notify(&mut self, change: RootChange) {
+        for callback in &mut self.callbacks {
+            callback(&change);
+        }
+        self.channels
+            .retain(|sender| sender.send(change.clone()).is_ok());
+    }
+}
+
+impl Clone for Subscribers {
+    /// Subscribers are not shared with clones.
+    fn clone(&self) -> Self {
+        Subscribers::default()
+    }
+}
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscribers")
+            .field("callbacks", &self.callbacks.len())
+            .field("channels", &self.channels.len())
+            .finish()
+    }
+}
@@ -0,0 +1,63 @@
+//! Thread budgets for parallel work.
+//!
+//! Parallel builds and proofs run on rayon's global pool by default, which uses every core. Services with their own thread budgets can hand them a [Parallelism](struct.Parallelism.html) instead: a pool capped at a number of threads, or a pool the service already owns. Results do not depend on the pool; see [determinism](../determinism/index.html).
+//!
+//! Requires the `rayon` feature.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::parallel::Parallelism;
+//! use rs_merkletree::MerkleTree;
+//! let two = Parallelism::with_threads(2).unwrap();
+//! assert_eq!(two.threads(), 2);
+//!
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree_parallel_in(vec!["Hello", "World", "From", "Rust"], &two);
+//! let proofs = tree.proofs_parallel(&[0, 3, 4], &two);
+//! assert_eq!(proofs, vec![tree.proof(0), tree.proof(3), None]);
+//! ```
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use std::sync::Arc;
+
+/// [Parallelism](struct.Parallelism.html) is the pool parallel work runs on.
+///
+/// * `pool`: The pool, or `None` for rayon's global pool.
+#[derive(Debug, Clone, Default)]
+pub struct Parallelism {
+    pool: Option<Arc<ThreadPool>>,
+}
+
+impl Parallelism {
+    /// Function to create a [Parallelism](struct.Parallelism.html) using rayon's global pool.
+    pub fn global() -> Parallelism {
+        Parallelism::default()
+    }
+
+    /// Function to create a [Parallelism](struct.Parallelism.html) with a pool of its own, of at most `threads` threads; 0 lets rayon choose, as for the global pool.
+    pub fn with_threads(threads: usize) -> Result<Parallelism, ThreadPoolBuildError> {
+        let pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+        Ok(Parallelism::with_pool(Arc::new(pool)))
+    }
+
+    /// Function to create a [Parallelism](struct.Parallelism.html) running on an existing pool, shared with the rest of the service.
+    pub fn with_pool(pool: Arc<ThreadPool>) -> Parallelism {
+        Parallelism { pool: Some(pool) }
+    }
+
+    /// Returns the number of threads work is spread over.
+    pub fn threads(&self) -> usize {
+        match &self.pool {
+            Some(pool) => pool.current_num_threads(),
+            None => rayon::current_num_threads(),
+        }
+    }
+
+    /// Runs `work` so that the parallel iterators inside it use this pool.
+    pub fn install<R: Send, F: FnOnce() -> R + Send>(&self, work: F) -> R {
+        match &self.pool {
+            Some(pool) => pool.install(work),
+            None => work(),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Progress reporting and cancellation for long builds.
+//!
+//! [MerkleTree::build_tree_with_progress](../struct.MerkleTree.html#method.build_tree_with_progress) reports a [Progress](struct.Progress.html) as it goes and checks a [CancellationToken](struct.CancellationToken.html) between layers, so a GUI or CLI hashing a huge dataset can show a progress bar and abort cleanly from another thread.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::progress::{CancellationToken, Cancelled};
+//! use rs_merkletree::MerkleTree;
+//! let cancel = CancellationToken::new();
+//! let handle = cancel.clone();
+//! let mut tree = MerkleTree::new(None);
+//! let result = tree.build_tree_with_progress(
+//!     vec!["a", "b", "c", "d"],
+//!     |progress| {
+//!         if progress.level == 1 {
+//!             handle.cancel();
+//!         }
+//!     },
+//!     &cancel,
+//! );
+//! assert_eq!(result.err(), Some(Cancelled));
+//! assert!(tree.root_node().is_none());
+//! ```
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Number of leaves hashed between two progress reports and cancellation checks.
+pub(crate) const LEAF_BATCH: usize = 4096;
+
+/// [Progress](struct.Progress.html) is a snapshot of a running build.
+///
+/// * `leaves_processed`: Number of leaves hashed so far.
+///
+/// * `total_leaves`: Number of leaves in the input.
+///
+/// * `level`: Highest level completed; 0 is the leaf layer and the root's level is reached last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub leaves_processed: usize,
+    pub total_leaves: usize,
+    pub level: usize,
+}
+
+/// [CancellationToken](struct.CancellationToken.html) is a cloneable flag shared between a build and whoever may abort it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Error returned when a build was stopped by its [CancellationToken](struct.CancellationToken.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Progress {
+    pub(crate) fn new(leaves_processed: usize, total_leaves: usize, level: usize) -> Progress {
+        Progress {
+            leaves_processed,
+            total_leaves,
+            level,
+        }
+    }
+}
+
+impl CancellationToken {
+    /// Function to create a [CancellationToken](struct.CancellationToken.html) that is not cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Requests cancellation of every build using this token or a clone of it.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true once [cancel](struct.CancellationToken.html#method.cancel) has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "build cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
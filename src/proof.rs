@@ -0,0 +1,721 @@
+//! Membership proofs for [MerkleTree](../struct.MerkleTree.html).
+//!
+//! This module is the verification core of the crate: it builds without `std` (only `alloc` is needed for [MerkleProof](struct.MerkleProof.html), and [verify_steps](fn.verify_steps.html) and [verify_multiproof](fn.verify_multiproof.html) need no allocation at all), so proofs produced by a server can be checked on embedded devices or inside zkVM guests. Disable default features to get only this module:
+//!
+//! ```toml
+//! rs-merkletree = { version = "0.1", default-features = false }
+//! ```
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::proof::leaf_hash;
+//! use rs_merkletree::MerkleTree;
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+//! let root = tree.root_node().unwrap().hash();
+//!
+//! let leaf = leaf_hash(b"Rust");
+//! let proof = tree.proof(3).unwrap();
+//! assert!(proof.verify(&root, &leaf));
+//! assert!(!tree.proof(2).unwrap().verify(&root, &leaf));
+//! ```
+
+use crate::codec;
+use crate::error::MerkleError;
+use crate::hash::Hash;
+use crate::limits::Limits;
+use alloc::string::String;
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// Length of a [MerkleTree](../struct.MerkleTree.html) hash in its hex form: SHA-256 as lowercase hex, which is what parents hash and what proofs carry on the wire.
+pub const HASH_LEN: usize = 64;
+
+/// Byte prepended to leaf data before hashing, so a leaf hash can never equal an inner node hash.
+pub const LEAF_TAG: u8 = 0x00;
+
+/// Byte prepended to the children of an inner node before hashing.
+pub const NODE_TAG: u8 = 0x01;
+
+/// Side of the node a proof step's sibling sits on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Position {
+    Left,
+    Right,
+}
+
+/// [ProofStep](struct.ProofStep.html) is one level of a [MerkleProof](struct.MerkleProof.html).
+///
+/// * `hash`: Hash of the sibling. For a node without a sibling this is the node's own hash, as it is hashed with itself.
+///
+/// * `position`: Whether the sibling is the left or right input of the parent hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ProofStep {
+    pub hash: Hash,
+    pub position: Position,
+}
+
+/// [MerkleProof](struct.MerkleProof.html) proves that a leaf is part of a tree.
+///
+/// * `steps`: Siblings from the leaf up to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MerkleProof {
+    pub steps: Vec<ProofStep>,
+}
+
+/// [SizedProof](struct.SizedProof.html) is a [MerkleProof](struct.MerkleProof.html) together with the shape it was produced for.
+///
+/// A [MerkleTree](../struct.MerkleTree.html) hashes the last node of an odd level with itself, so the trees over `[a, b, c]` and `[a, b, c, c]` share a root and a proof for the padded copy of `c` verifies against both. Verifying against a trusted tree size (for instance from a [SignedRoot](../signed/struct.SignedRoot.html)) rules that out: the index must be inside the tree, the proof must have the tree's height, its directions must follow from the index, and a sibling may only duplicate the node where the tree really has a lone node.
+///
+/// * `index`: Index of the proven leaf.
+///
+/// * `tree_size`: Number of leaves of the tree the proof was produced from.
+///
+/// * `proof`: The proof itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SizedProof {
+    pub index: u64,
+    pub tree_size: u64,
+    pub proof: MerkleProof,
+}
+
+impl SizedProof {
+    /// Checks that `leaf` is leaf [index](struct.SizedProof.html#structfield.index) of the tree of `tree_size` leaves with root `root`. `tree_size` must come from a trusted source; the proof's own [tree_size](struct.SizedProof.html#structfield.tree_size) has to match it.
+    pub fn verify(&self, root: &Hash, leaf: &Hash, tree_size: u64) -> bool {
+        self.tree_size == tree_size
+            && self
+                .proof
+                .verify_sized(root, leaf, self.index, self.tree_size)
+    }
+
+    /// Same as [verify](struct.SizedProof.html#method.verify), but reports why a proof is rejected: [MerkleError::SizeMismatch](../enum.MerkleError.html) if it was made for another tree size, [MerkleError::InvalidProof](../enum.MerkleError.html) otherwise.
+    pub fn check(&self, root: &Hash, leaf: &Hash, tree_size: u64) -> Result<(), MerkleError> {
+        if self.tree_size != tree_size {
+            return Err(MerkleError::SizeMismatch {
+                expected: tree_size,
+                actual: self.tree_size,
+            });
+        }
+        if !self.verify(root, leaf, tree_size) {
+            return Err(MerkleError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    /// Serializes the proof: index and tree size as 8 byte big-endian integers, then [MerkleProof::to_bytes](struct.MerkleProof.html#method.to_bytes).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&self.index.to_be_bytes());
+        out.extend_from_slice(&self.tree_size.to_be_bytes());
+        out.extend_from_slice(&self.proof.to_bytes());
+        out
+    }
+
+    /// Parses the output of [to_bytes](struct.SizedProof.html#method.to_bytes), returning `None` if it is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<SizedProof> {
+        let (index, rest) = bytes.split_first_chunk::<8>()?;
+        let (tree_size, rest) = rest.split_first_chunk::<8>()?;
+        Some(SizedProof {
+            index: u64::from_be_bytes(*index),
+            tree_size: u64::from_be_bytes(*tree_size),
+            proof: MerkleProof::from_bytes(rest)?,
+        })
+    }
+}
+
+/// [IndexedProof](struct.IndexedProof.html) proves that a leaf sits at a given index of a tree.
+///
+/// A [MerkleProof](struct.MerkleProof.html) carries the direction of every step, so it proves that a leaf is somewhere in the tree, and a protocol that cares about positions has to check the directions against the index itself. An indexed proof carries only the sibling hashes: the verifier supplies the index and the trusted tree size, every direction is taken from the bits of the index, and a sibling may only duplicate the node where the tree really has a lone node, so the proof verifies for its own index only and cannot be replayed for another position, not even for the padded copy of a lone last leaf.
+///
+/// * `siblings`: Hashes of the siblings from the leaf up to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct IndexedProof {
+    pub siblings: Vec<Hash>,
+}
+
+impl IndexedProof {
+    /// Checks that the leaf hash `leaf` (see [leaf_hash](fn.leaf_hash.html)) is leaf `index` of the tree of `tree_size` leaves with root hash `root`. `tree_size` must come from a trusted source, as for [SizedProof](struct.SizedProof.html).
+    pub fn verify(&self, root: &Hash, leaf: &Hash, index: u64, tree_size: u64) -> bool {
+        self.verify_with(&TaggedHexSha256, root, leaf, index, tree_size)
+    }
+
+    /// Same as [verify](struct.IndexedProof.html#method.verify) with a custom hasher.
+    pub fn verify_with<H: NodeHasher>(
+        &self,
+        hasher: &H,
+        root: &Hash,
+        leaf: &Hash,
+        index: u64,
+        tree_size: u64,
+    ) -> bool {
+        verify_at(
+            hasher,
+            &root.to_hex_bytes(),
+            &leaf.to_hex_bytes(),
+            index,
+            tree_size,
+            self.siblings.iter().map(Hash::to_hex_bytes),
+        )
+    }
+
+    /// Serializes the proof: for every sibling the hash length as a 2 byte big-endian integer and the hash as [HASH_LEN](constant.HASH_LEN.html) hex digits.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for sibling in &self.siblings {
+            out.extend_from_slice(&(HASH_LEN as u16).to_be_bytes());
+            out.extend_from_slice(&sibling.to_hex_bytes());
+        }
+        out
+    }
+
+    /// Parses the output of [to_bytes](struct.IndexedProof.html#method.to_bytes), returning `None` if it is malformed.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<IndexedProof> {
+        let mut siblings = Vec::new();
+        while let [high, low, rest @ ..] = bytes {
+            let len = u16::from_be_bytes([*high, *low]) as usize;
+            if rest.len() < len {
+                return None;
+            }
+            siblings.push(Hash::from_hex_bytes(&rest[..len]).ok()?);
+            bytes = &rest[len..];
+        }
+        bytes.is_empty().then_some(IndexedProof { siblings })
+    }
+}
+
+impl From<MerkleProof> for IndexedProof {
+    /// Drops the directions of the steps, which the verifier derives from the index instead.
+    fn from(proof: MerkleProof) -> IndexedProof {
+        IndexedProof {
+            siblings: proof.steps.into_iter().map(|step| step.hash).collect(),
+        }
+    }
+}
+
+/// [MultiProof](struct.MultiProof.html) proves several leaves of a tree at once, sending every hash their paths share only once.
+///
+/// * `tree_size`: Number of leaves of the tree the proof was produced from.
+///
+/// * `indices`: Indices of the proven leaves, in ascending order.
+///
+/// * `hashes`: Hashes of the nodes the paths need but cannot compute, in the order [verify_multiproof](fn.verify_multiproof.html) consumes them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MultiProof {
+    pub tree_size: u64,
+    pub indices: Vec<u64>,
+    pub hashes: Vec<Hash>,
+}
+
+impl MultiProof {
+    /// Checks that `leaves`, the leaf hashes (see [leaf_hash](fn.leaf_hash.html)) at [indices](struct.MultiProof.html#structfield.indices), are included in the tree with root hash `root`.
+    pub fn verify(&self, root: &Hash, leaves: &[Hash]) -> bool {
+        self.verify_with(&TaggedHexSha256, root, leaves)
+    }
+
+    /// Same as [verify](struct.MultiProof.html#method.verify) with a custom hasher.
+    pub fn verify_with<H: NodeHasher>(&self, hasher: &H, root: &Hash, leaves: &[Hash]) -> bool {
+        let leaves: Vec<[u8; HASH_LEN]> = leaves.iter().map(Hash::to_hex_bytes).collect();
+        let hashes: Vec<[u8; HASH_LEN]> = self.hashes.iter().map(Hash::to_hex_bytes).collect();
+        leaves.len() == self.indices.len()
+            && verify_multiproof(
+                hasher,
+                &root.to_hex_bytes(),
+                self.tree_size,
+                self.indices
+                    .iter()
+                    .copied()
+                    .zip(leaves.iter().map(|leaf| &leaf[..])),
+                hashes.iter().map(|hash| &hash[..]),
+            )
+    }
+}
+
+macro_rules! text_codecs {
+    ($($ty:ident),*) => {
+        $(impl $ty {
+            #[doc = concat!("Returns [to_bytes](struct.", stringify!($ty), ".html#method.to_bytes) as lowercase hex.")]
+            pub fn to_hex(&self) -> String {
+                codec::to_hex(&self.to_bytes())
+            }
+
+            #[doc = concat!("Parses the output of [to_hex](struct.", stringify!($ty), ".html#method.to_hex), returning `None` if it is malformed.")]
+            pub fn from_hex(hex: &str) -> Option<$ty> {
+                $ty::from_bytes(&codec::from_hex(hex).ok()?)
+            }
+
+            #[doc = concat!("Returns [to_bytes](struct.", stringify!($ty), ".html#method.to_bytes) as standard, padded base64.")]
+            pub fn to_base64(&self) -> String {
+                codec::to_base64(&self.to_bytes())
+            }
+
+            #[doc = concat!("Parses the output of [to_base64](struct.", stringify!($ty), ".html#method.to_base64), returning `None` if it is malformed.")]
+            pub fn from_base64(text: &str) -> Option<$ty> {
+                $ty::from_bytes(&codec::from_base64(text).ok()?)
+            }
+        })*
+    };
+}
+
+text_codecs!(MerkleProof, SizedProof, IndexedProof);
+
+/// Hash function used to recompute parents while verifying a proof.
+pub trait NodeHasher {
+    /// Hash type, usually a fixed-size array so hashing does not allocate.
+    type Output: AsRef<[u8]>;
+
+    /// Returns the parent of `left` and `right`.
+    fn hash_nodes(&self, left: &[u8], right: &[u8]) -> Self::Output;
+}
+
+/// [TaggedHexSha256](struct.TaggedHexSha256.html) is the hasher of [MerkleTree](../struct.MerkleTree.html): nodes are the lowercase hex SHA-256 of [NODE_TAG](constant.NODE_TAG.html) followed by their children's hex hashes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaggedHexSha256;
+
+impl NodeHasher for TaggedHexSha256 {
+    type Output = [u8; 64];
+
+    fn hash_nodes(&self, left: &[u8], right: &[u8]) -> [u8; 64] {
+        let digest = Sha256::new()
+            .chain_update([NODE_TAG])
+            .chain_update(left)
+            .chain_update(right)
+            .finalize();
+        hex_encode(&digest.into())
+    }
+}
+
+/// [HexSha256](struct.HexSha256.html) is the hasher of legacy [MerkleTree](../struct.MerkleTree.html)s (see [new_legacy](../struct.MerkleTree.html#method.new_legacy)): nodes are the lowercase hex SHA-256 of their children's hex hashes concatenated, without a domain tag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HexSha256;
+
+impl NodeHasher for HexSha256 {
+    type Output = [u8; 64];
+
+    fn hash_nodes(&self, left: &[u8], right: &[u8]) -> [u8; 64] {
+        let digest = Sha256::new()
+            .chain_update(left)
+            .chain_update(right)
+            .finalize();
+        hex_encode(&digest.into())
+    }
+}
+
+impl MerkleProof {
+    /// Serializes the proof: for every step a position byte (`0` left, `1` right), the hash length as a 2 byte big-endian integer and the hash.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for step in &self.steps {
+            out.push(match step.position {
+                Position::Left => 0,
+                Position::Right => 1,
+            });
+            out.extend_from_slice(&(HASH_LEN as u16).to_be_bytes());
+            out.extend_from_slice(&step.hash.to_hex_bytes());
+        }
+        out
+    }
+
+    /// Parses the output of [to_bytes](struct.MerkleProof.html#method.to_bytes), returning `None` if it is malformed.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<MerkleProof> {
+        let mut steps = Vec::new();
+        while let [position, high, low, rest @ ..] = bytes {
+            let position = match position {
+                0 => Position::Left,
+                1 => Position::Right,
+                _ => return None,
+            };
+            let len = u16::from_be_bytes([*high, *low]) as usize;
+            if rest.len() < len {
+                return None;
+            }
+            steps.push(ProofStep {
+                hash: Hash::from_hex_bytes(&rest[..len]).ok()?,
+                position,
+            });
+            bytes = &rest[len..];
+        }
+        bytes.is_empty().then_some(MerkleProof { steps })
+    }
+
+    /// Same as [from_bytes](struct.MerkleProof.html#method.from_bytes) for untrusted input: fails with [MerkleError::ProofTooLong](../enum.MerkleError.html) as soon as the proof exceeds `limits.max_proof_len` steps, and with [MerkleError::MalformedProof](../enum.MerkleError.html) if a step is malformed or its hash is not [HASH_LEN](constant.HASH_LEN.html) bytes.
+    pub fn from_bytes_limited(
+        mut bytes: &[u8],
+        limits: &Limits,
+    ) -> Result<MerkleProof, MerkleError> {
+        let mut steps = Vec::new();
+        while !bytes.is_empty() {
+            limits.check_proof_len(steps.len() + 1)?;
+            let [position, high, low, rest @ ..] = bytes else {
+                return Err(MerkleError::MalformedProof);
+            };
+            let position = match position {
+                0 => Position::Left,
+                1 => Position::Right,
+                _ => return Err(MerkleError::MalformedProof),
+            };
+            let len = u16::from_be_bytes([*high, *low]) as usize;
+            if len != HASH_LEN || rest.len() < len {
+                return Err(MerkleError::MalformedProof);
+            }
+            let hash = Hash::from_hex_bytes(&rest[..len]).map_err(|_| MerkleError::MalformedProof)?;
+            steps.push(ProofStep { hash, position });
+            bytes = &rest[len..];
+        }
+        Ok(MerkleProof { steps })
+    }
+
+    /// Checks that the leaf hash `leaf` (see [leaf_hash](fn.leaf_hash.html)) is included in the tree with root hash `root`.
+    pub fn verify(&self, root: &Hash, leaf: &Hash) -> bool {
+        self.verify_with(&TaggedHexSha256, root, leaf)
+    }
+
+    /// Checks that the leaf holding `data` is included in the tree with root hash `root`.
+    ///
+    /// Prefer this to [verify](struct.MerkleProof.html#method.verify) when the data is at hand: hashing it here with the leaf tag guarantees the proof starts at a leaf, never at an inner node.
+    pub fn verify_data(&self, root: &Hash, data: &[u8]) -> bool {
+        self.verify(root, &leaf_hash(data))
+    }
+
+    /// Same as [verify](struct.MerkleProof.html#method.verify) for roots of legacy trees, whose leaf hashes come from [legacy_leaf_hash](fn.legacy_leaf_hash.html).
+    pub fn verify_legacy(&self, root: &Hash, leaf: &Hash) -> bool {
+        self.verify_with(&HexSha256, root, leaf)
+    }
+
+    /// Same as [verify](struct.MerkleProof.html#method.verify), but also checks that the proof has the shape of a proof for leaf `index` of a tree of `tree_size` leaves (see [SizedProof](struct.SizedProof.html)).
+    pub fn verify_sized(&self, root: &Hash, leaf: &Hash, index: u64, tree_size: u64) -> bool {
+        let directions_match = self
+            .steps
+            .iter()
+            .enumerate()
+            .all(|(level, step)| Some(step.position) == position_at(index, level));
+        directions_match
+            && verify_at(
+                &TaggedHexSha256,
+                &root.to_hex_bytes(),
+                &leaf.to_hex_bytes(),
+                index,
+                tree_size,
+                self.steps.iter().map(|step| step.hash.to_hex_bytes()),
+            )
+    }
+
+    /// Same as [verify](struct.MerkleProof.html#method.verify) for a root and leaf hash given in hex form, as read from untrusted input: fails if either is not a well-formed hash (see [validate_hash](fn.validate_hash.html)).
+    pub fn try_verify(&self, root: &[u8], leaf: &[u8]) -> Result<bool, MerkleError> {
+        let root = Hash::from_hex_bytes(root)?;
+        let leaf = Hash::from_hex_bytes(leaf)?;
+        Ok(self.verify(&root, &leaf))
+    }
+
+    /// Same as [verify](struct.MerkleProof.html#method.verify) with a custom hasher, which is given the hex form of the hashes as the tree hashes them.
+    pub fn verify_with<H: NodeHasher>(&self, hasher: &H, root: &Hash, leaf: &Hash) -> bool {
+        verify_steps(
+            hasher,
+            &root.to_hex_bytes(),
+            &leaf.to_hex_bytes(),
+            self.steps
+                .iter()
+                .map(|step| (step.hash.to_hex_bytes(), step.position)),
+        )
+    }
+}
+
+/// Checks that `hash` has the format of [MerkleTree](../struct.MerkleTree.html) hashes: [HASH_LEN](constant.HASH_LEN.html) lowercase hex digits.
+pub fn validate_hash(hash: &[u8]) -> Result<(), MerkleError> {
+    if hash.len() != HASH_LEN {
+        return Err(MerkleError::InvalidHashLength {
+            expected: HASH_LEN,
+            actual: hash.len(),
+        });
+    }
+    match hash
+        .iter()
+        .position(|byte| !matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+    {
+        Some(position) => Err(MerkleError::InvalidEncoding { position }),
+        None => Ok(()),
+    }
+}
+
+/// Returns the leaf hash of `data` in a [MerkleTree](../struct.MerkleTree.html): the SHA-256 of [LEAF_TAG](constant.LEAF_TAG.html) followed by `data`.
+pub fn leaf_hash(data: &[u8]) -> Hash {
+    let digest = Sha256::new()
+        .chain_update([LEAF_TAG])
+        .chain_update(data)
+        .finalize();
+    Hash::new(digest.into())
+}
+
+/// Returns the leaf hash of `data` in a legacy [MerkleTree](../struct.MerkleTree.html): the SHA-256 of `data`.
+pub fn legacy_leaf_hash(data: &[u8]) -> Hash {
+    Hash::new(Sha256::digest(data).into())
+}
+
+/// Returns the hash of the inner node with children `left` and `right` in a [MerkleTree](../struct.MerkleTree.html): the SHA-256 of [NODE_TAG](constant.NODE_TAG.html) followed by the hex forms of the children, as [TaggedHexSha256](struct.TaggedHexSha256.html) computes it.
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let digest = Sha256::new()
+        .chain_update([NODE_TAG])
+        .chain_update(left.to_hex_bytes())
+        .chain_update(right.to_hex_bytes())
+        .finalize();
+    Hash::new(digest.into())
+}
+
+/// Returns the hash of the inner node with children `left` and `right` in a legacy [MerkleTree](../struct.MerkleTree.html): the SHA-256 of the hex forms of the children, as [HexSha256](struct.HexSha256.html) computes it.
+pub fn legacy_node_hash(left: &Hash, right: &Hash) -> Hash {
+    let digest = Sha256::new()
+        .chain_update(left.to_hex_bytes())
+        .chain_update(right.to_hex_bytes())
+        .finalize();
+    Hash::new(digest.into())
+}
+
+/// Returns the number of steps in a proof for a [MerkleTree](../struct.MerkleTree.html) of `tree_size` leaves: the height of the tree, which is 1 for a single leaf since it is still hashed with itself.
+pub fn proof_len(tree_size: u64) -> usize {
+    match tree_size {
+        0 | 1 => 1,
+        size => (u64::BITS - (size - 1).leading_zeros()) as usize,
+    }
+}
+
+/// Returns the position of the sibling at `level` on the path of leaf `index`, or `None` if `level` is past the top of any tree.
+fn position_at(index: u64, level: usize) -> Option<Position> {
+    match index.checked_shr(level as u32)? % 2 {
+        1 => Some(Position::Left),
+        _ => Some(Position::Right),
+    }
+}
+
+/// Verifies the sibling hashes of leaf `index` in a tree of `tree_size` leaves: the index must be inside the tree, there must be one sibling per level, and a sibling may only duplicate the node where the tree has a lone node.
+fn verify_at<H, S, I>(
+    hasher: &H,
+    root: &[u8],
+    leaf: &[u8],
+    index: u64,
+    tree_size: u64,
+    siblings: I,
+) -> bool
+where
+    H: NodeHasher,
+    S: AsRef<[u8]>,
+    I: ExactSizeIterator<Item = S>,
+{
+    if index >= tree_size || siblings.len() != proof_len(tree_size) {
+        return false;
+    }
+    let (mut index, mut width) = (index, tree_size);
+    let mut current: Option<H::Output> = None;
+    for sibling in siblings {
+        let node = current.as_ref().map_or(leaf, |hash| hash.as_ref());
+        let (position, lone) = match index % 2 {
+            1 => (Position::Left, false),
+            _ => (Position::Right, index == width - 1),
+        };
+        if lone && !ct_eq(sibling.as_ref(), node) {
+            return false;
+        }
+        current = Some(parent(hasher, node, sibling.as_ref(), position));
+        index /= 2;
+        width = width.div_ceil(2);
+    }
+    current.is_some_and(|hash| ct_eq(hash.as_ref(), root))
+}
+
+/// Verifies a proof given as `(sibling hash, position)` pairs from the leaf up, without allocating.
+pub fn verify_steps<H, S, I>(hasher: &H, root: &[u8], leaf: &[u8], steps: I) -> bool
+where
+    H: NodeHasher,
+    S: AsRef<[u8]>,
+    I: IntoIterator<Item = (S, Position)>,
+{
+    let mut steps = steps.into_iter();
+    let Some((sibling, position)) = steps.next() else {
+        return ct_eq(leaf, root);
+    };
+    let mut current = parent(hasher, leaf, sibling.as_ref(), position);
+    for (sibling, position) in steps {
+        current = parent(hasher, current.as_ref(), sibling.as_ref(), position);
+    }
+    ct_eq(current.as_ref(), root)
+}
+
+/// Most leaves [small_root](fn.small_root.html) takes, so its buffer fits comfortably on the stack.
+pub const SMALL_TREE_LEAVES: usize = 64;
+
+/// Returns the Root Hash of the [MerkleTree](../struct.MerkleTree.html) over `data` without allocating, or `None` if `data` is empty or has more than [SMALL_TREE_LEAVES](constant.SMALL_TREE_LEAVES.html) items.
+///
+/// Trees built per request usually have a handful of leaves; hashing them into a stack buffer with [fold_root](fn.fold_root.html) skips the nodes a [MerkleTree](../struct.MerkleTree.html) would allocate.
+pub fn small_root<T: AsRef<[u8]>>(data: &[T]) -> Option<Hash> {
+    if data.len() > SMALL_TREE_LEAVES {
+        return None;
+    }
+    let mut nodes = [[0u8; 64]; SMALL_TREE_LEAVES];
+    for (node, data) in nodes.iter_mut().zip(data) {
+        *node = leaf_hash(data.as_ref()).to_hex_bytes();
+    }
+    fold_root(&TaggedHexSha256, &mut nodes[..data.len()]).map(|root| Hash::decode(&root))
+}
+
+/// Folds the leaf hashes `nodes`, in hex form, into the root in place, pairing nodes as a [MerkleTree](../struct.MerkleTree.html) does: a lone node is hashed with itself, and so is a single leaf. Returns `None` if `nodes` is empty.
+pub fn fold_root<H: NodeHasher<Output = [u8; 64]>>(
+    hasher: &H,
+    nodes: &mut [[u8; 64]],
+) -> Option<[u8; 64]> {
+    let mut len = nodes.len();
+    if len == 0 {
+        return None;
+    }
+    loop {
+        for i in 0..len.div_ceil(2) {
+            let left = nodes[2 * i];
+            let right = if 2 * i + 1 < len {
+                nodes[2 * i + 1]
+            } else {
+                left
+            };
+            nodes[i] = hasher.hash_nodes(&left, &right);
+        }
+        len = len.div_ceil(2);
+        if len == 1 {
+            return Some(nodes[0]);
+        }
+    }
+}
+
+/// Tallest tree [verify_multiproof](fn.verify_multiproof.html) accepts, in levels above the leaves: any tree with at most `2^64` leaves.
+pub const MAX_MULTIPROOF_HEIGHT: usize = 64;
+
+/// Verifies a multiproof for leaves given as `(index, leaf hash)` pairs in strictly ascending index order, without allocating.
+///
+/// `hashes` are the nodes the leaves' paths need but cannot compute, in the order of [MultiProof::hashes](struct.MultiProof.html#structfield.hashes). Both iterators are consumed as the tree is folded bottom-up, left to right, on a fixed stack of at most [MAX_MULTIPROOF_HEIGHT](constant.MAX_MULTIPROOF_HEIGHT.html) + 1 pending nodes, so memory stays the same whatever the tree size and number of leaves, and both can be streamed from storage or the network.
+///
+/// Fails if a leaf is out of range or out of order, or if the proof has too few or too many hashes.
+pub fn verify_multiproof<'a, H, L, P>(
+    hasher: &H,
+    root: &[u8],
+    tree_size: u64,
+    leaves: L,
+    hashes: P,
+) -> bool
+where
+    H: NodeHasher,
+    L: IntoIterator<Item = (u64, &'a [u8])>,
+    P: IntoIterator<Item = &'a [u8]>,
+{
+    if tree_size == 0 {
+        return false;
+    }
+    let top = proof_len(tree_size);
+    let width = |height: usize| (tree_size - 1).checked_shr(height as u32).unwrap_or(0) + 1;
+    let mut stack: [Slot<'a, H::Output>; MAX_MULTIPROOF_HEIGHT + 1] =
+        core::array::from_fn(|_| None);
+    let mut len = 0;
+    let mut leaves = leaves.into_iter().peekable();
+    let mut hashes = hashes.into_iter();
+    let mut previous = None;
+    while let Some((index, leaf)) = leaves.next() {
+        if index >= tree_size
+            || previous.is_some_and(|previous| index <= previous)
+            || len == stack.len()
+        {
+            return false;
+        }
+        previous = Some(index);
+        stack[len] = Some((0, index, Pending::Leaf(leaf)));
+        len += 1;
+        let next = leaves.peek().map(|&(next, _)| next);
+        loop {
+            let Some((height, index, _)) = stack[len - 1] else {
+                return false;
+            };
+            if height == top {
+                break;
+            }
+            let sibling = index ^ 1;
+            let lone = index % 2 == 0 && sibling >= width(height);
+            // The right sibling covers the next leaf: fold it first.
+            if index % 2 == 0 && !lone && next.is_some_and(|next| next >> height == sibling) {
+                break;
+            }
+            let Some((_, _, node)) = stack[len - 1].take() else {
+                return false;
+            };
+            len -= 1;
+            let parent = if lone {
+                hasher.hash_nodes(node.as_ref(), node.as_ref())
+            } else if index % 2 == 0 {
+                let Some(right) = hashes.next() else {
+                    return false;
+                };
+                hasher.hash_nodes(node.as_ref(), right)
+            } else if len > 0
+                && matches!(stack[len - 1], Some((h, i, _)) if h == height && i == sibling)
+            {
+                let Some((_, _, left)) = stack[len - 1].take() else {
+                    return false;
+                };
+                len -= 1;
+                hasher.hash_nodes(left.as_ref(), node.as_ref())
+            } else {
+                let Some(left) = hashes.next() else {
+                    return false;
+                };
+                hasher.hash_nodes(left, node.as_ref())
+            };
+            stack[len] = Some((height + 1, index / 2, Pending::Node(parent)));
+            len += 1;
+        }
+    }
+    match (len, &stack[0]) {
+        (1, Some((height, 0, node))) => {
+            *height == top && hashes.next().is_none() && ct_eq(node.as_ref(), root)
+        }
+        _ => false,
+    }
+}
+
+/// Entry of the stack of [verify_multiproof](fn.verify_multiproof.html): height, index and hash of a pending node.
+type Slot<'a, O> = Option<(usize, u64, Pending<'a, O>)>;
+
+/// Node waiting on the stack of [verify_multiproof](fn.verify_multiproof.html): a borrowed leaf hash or a computed parent.
+enum Pending<'a, O> {
+    Leaf(&'a [u8]),
+    Node(O),
+}
+
+impl<O: AsRef<[u8]>> AsRef<[u8]> for Pending<'_, O> {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Pending::Leaf(hash) => hash,
+            Pending::Node(hash) => hash.as_ref(),
+        }
+    }
+}
+
+/// Compares two hashes in time independent of their contents, so a verifier cannot be timed to learn how much of a forged hash was right. Only the lengths, which are public, are compared early.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+fn parent<H: NodeHasher>(hasher: &H, node: &[u8], sibling: &[u8], position: Position) -> H::Output {
+    match position {
+        Position::Left => hasher.hash_nodes(sibling, node),
+        Position::Right => hasher.hash_nodes(node, sibling),
+    }
+}
+
+fn hex_encode(bytes: &[u8; 32]) -> [u8; 64] {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut out = [0u8; 64];
+    for (i, byte) in bytes.iter().enumerate() {
+        out[2 * i] = DIGITS[(byte >> 4) as usize];
+        out[2 * i + 1] = DIGITS[(byte & 0xf) as usize];
+    }
+    out
+}
@@ -0,0 +1,101 @@
+//! Python bindings.
+//!
+//! A PyO3 module named `rs_merkletree` exposing [MerkleTree](../struct.MerkleTree.html), proof generation and verification, so Python pipelines compute exactly the roots the Rust services do:
+//!
+//! ```python
+//! from rs_merkletree import MerkleTree, verify_proof
+//! tree = MerkleTree(["Hello", "World", "From", "Rust"])
+//! proof = tree.proof(1)
+//! assert verify_proof(tree.root(), "World", proof)
+//! ```
+//!
+//! Requires the `python` feature; build and install the extension with `maturin develop --features python`.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::python::{verify_proof, PyMerkleTree};
+//! let tree = PyMerkleTree::new(vec!["Hello".into(), "World".into()]);
+//! let proof = tree.proof(1).unwrap();
+//! assert!(verify_proof(&tree.root().unwrap(), "World", &proof));
+//! ```
+
+use crate::proof::MerkleProof;
+use crate::MerkleTree;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::borrow::Cow;
+
+/// [PyMerkleTree](struct.PyMerkleTree.html) is the Python `MerkleTree` class, built from a list of strings.
+#[pyclass(name = "MerkleTree", module = "rs_merkletree")]
+pub struct PyMerkleTree {
+    tree: MerkleTree,
+}
+
+#[pymethods]
+impl PyMerkleTree {
+    /// Function to create a [PyMerkleTree](struct.PyMerkleTree.html) over `leaves`.
+    #[new]
+    pub fn new(leaves: Vec<String>) -> PyMerkleTree {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(leaves.iter().map(String::as_str).collect());
+        PyMerkleTree { tree }
+    }
+
+    /// Returns the hex root, or `None` for an empty tree.
+    pub fn root(&self) -> Option<String> {
+        Some(self.tree.root_node()?.hash().to_string())
+    }
+
+    /// Returns the depth of the tree, `0` when empty.
+    pub fn depth(&self) -> usize {
+        self.tree.root_node().map_or(0, |root| root.depth())
+    }
+
+    /// Returns the number of leaves.
+    pub fn count_leaves(&self) -> usize {
+        self.tree.count_leaves()
+    }
+
+    /// Returns `True` if the hex hash `hash` is a leaf of the tree; raises `ValueError` if `hash` is not a hex hash.
+    pub fn includes(&self, hash: &str) -> PyResult<bool> {
+        hash.parse()
+            .map(|hash| self.tree.includes(&hash))
+            .map_err(|error| PyValueError::new_err(format!("{:?}", error)))
+    }
+
+    /// Returns the serialized proof for the leaf at `index` as `bytes`, or `None` if there is no such leaf.
+    pub fn proof(&self, index: usize) -> Option<Cow<'static, [u8]>> {
+        self.tree
+            .proof(index)
+            .map(|proof| Cow::Owned(proof.to_bytes()))
+    }
+
+    fn __len__(&self) -> usize {
+        self.count_leaves()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "MerkleTree(leaves={}, root={:?})",
+            self.count_leaves(),
+            self.root()
+        )
+    }
+}
+
+/// Returns `True` if the serialized `proof` shows that the leaf with data `leaf` is included in the tree with hex root `root`.
+#[pyfunction]
+pub fn verify_proof(root: &str, leaf: &str, proof: &[u8]) -> bool {
+    let Ok(root) = root.parse() else {
+        return false;
+    };
+    MerkleProof::from_bytes(proof).is_some_and(|proof| proof.verify_data(&root, leaf.as_bytes()))
+}
+
+/// The `rs_merkletree` Python module.
+#[pymodule]
+pub fn rs_merkletree(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyMerkleTree>()?;
+    module.add_function(wrap_pyfunction!(verify_proof, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,221 @@
+//! Sigstore Rekor inclusion proofs.
+//!
+//! Rekor, Sigstore's transparency log, returns every log entry with a `verification.inclusionProof` object: the entry's index in the log shard, the tree size and root hash the proof is for, the audit path `hashes`, and a signed checkpoint (a note whose body repeats the tree size and base64 root). The entry's leaf is its canonicalized `body`, and the tree is an RFC 6962 tree, so proofs are checked with [verify_inclusion](../history/fn.verify_inclusion.html).
+//!
+//! [LogEntry::verify](struct.LogEntry.html#method.verify) checks that the body is included under the proof's root and that the checkpoint commits to the same tree. The checkpoint signature is exposed but not verified here: Rekor signs with the log's ECDSA or Ed25519 key, which callers should check with their trust root.
+//!
+//! Requires the `rekor` feature.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::rekor::LogEntry;
+//! # let json = include_str!("../tests/data/rekor_entry.json");
+//! let entry = LogEntry::from_json(json).unwrap();
+//! entry.verify().unwrap();
+//! assert_eq!(entry.inclusion_proof.tree_size, 5);
+//! ```
+
+use crate::hashing::{from_base64, from_hex, to_hex};
+use crate::history::{leaf_hash, verify_inclusion};
+use serde_json::Value;
+
+/// Errors returned when parsing or verifying Rekor entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RekorError {
+    /// The input is not JSON.
+    InvalidJson,
+    /// A required field is absent or has the wrong type.
+    MissingField(&'static str),
+    /// A field is not valid hex or base64.
+    InvalidEncoding(&'static str),
+    /// The checkpoint is malformed or does not match the proof's tree size and root.
+    CheckpointMismatch,
+    /// The audit path does not lead from the entry to the root.
+    InvalidProof,
+}
+
+/// [Checkpoint](struct.Checkpoint.html) is a parsed signed note.
+///
+/// * `origin`: Log identity line.
+///
+/// * `tree_size`: Size of the tree the note commits to.
+///
+/// * `root_hash`: Root of that tree.
+///
+/// * `signatures`: `(signer name, signature bytes)` for every signature line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub origin: String,
+    pub tree_size: u64,
+    pub root_hash: Vec<u8>,
+    pub signatures: Vec<(String, Vec<u8>)>,
+}
+
+/// [InclusionProof](struct.InclusionProof.html) is Rekor's `inclusionProof` object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub log_index: u64,
+    pub tree_size: u64,
+    pub root_hash: Vec<u8>,
+    pub hashes: Vec<Vec<u8>>,
+    pub checkpoint: Option<String>,
+}
+
+/// [LogEntry](struct.LogEntry.html) is a Rekor log entry: its canonicalized body and inclusion proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub uuid: Option<String>,
+    pub body: Vec<u8>,
+    pub inclusion_proof: InclusionProof,
+}
+
+impl Checkpoint {
+    /// Parses a signed note: origin, tree size and base64 root lines, optional extension lines, a blank line, then `— <name> <base64 signature>` lines.
+    pub fn parse(note: &str) -> Result<Checkpoint, RekorError> {
+        let (body, signatures) = note
+            .split_once("\n\n")
+            .ok_or(RekorError::CheckpointMismatch)?;
+        let mut lines = body.lines();
+        let origin = lines
+            .next()
+            .ok_or(RekorError::CheckpointMismatch)?
+            .to_string();
+        let tree_size = lines
+            .next()
+            .and_then(|line| line.parse().ok())
+            .ok_or(RekorError::CheckpointMismatch)?;
+        let root_hash = lines
+            .next()
+            .and_then(from_base64)
+            .ok_or(RekorError::InvalidEncoding("checkpoint"))?;
+        let signatures = signatures
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let line = line
+                    .strip_prefix("\u{2014} ")
+                    .ok_or(RekorError::CheckpointMismatch)?;
+                let (name, signature) = line
+                    .rsplit_once(' ')
+                    .ok_or(RekorError::CheckpointMismatch)?;
+                let signature =
+                    from_base64(signature).ok_or(RekorError::InvalidEncoding("checkpoint"))?;
+                Ok((name.to_string(), signature))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Checkpoint {
+            origin,
+            tree_size,
+            root_hash,
+            signatures,
+        })
+    }
+
+    /// Returns the signed part of the note, up to and including the blank line's first newline.
+    pub fn signed_body(note: &str) -> Option<&str> {
+        note.find("\n\n").map(|end| &note[..end + 1])
+    }
+}
+
+impl InclusionProof {
+    /// Parses an `inclusionProof` object.
+    pub fn from_value(value: &Value) -> Result<InclusionProof, RekorError> {
+        let hashes = value
+            .get("hashes")
+            .and_then(Value::as_array)
+            .ok_or(RekorError::MissingField("hashes"))?
+            .iter()
+            .map(|hash| {
+                hash.as_str()
+                    .and_then(from_hex)
+                    .ok_or(RekorError::InvalidEncoding("hashes"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(InclusionProof {
+            log_index: u64_field(value, "logIndex")?,
+            tree_size: u64_field(value, "treeSize")?,
+            root_hash: from_hex(str_field(value, "rootHash")?)
+                .ok_or(RekorError::InvalidEncoding("rootHash"))?,
+            hashes,
+            checkpoint: value
+                .get("checkpoint")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        })
+    }
+
+    /// Checks that `leaf` (the canonicalized entry body) is included under the proof's root, and that the checkpoint, if present, commits to the same tree.
+    pub fn verify(&self, leaf: &[u8]) -> Result<(), RekorError> {
+        if let Some(note) = &self.checkpoint {
+            let checkpoint = Checkpoint::parse(note)?;
+            if checkpoint.tree_size != self.tree_size || checkpoint.root_hash != self.root_hash {
+                return Err(RekorError::CheckpointMismatch);
+            }
+        }
+        if verify_inclusion(
+            &leaf_hash(leaf),
+            self.log_index,
+            self.tree_size,
+            &self.hashes,
+            &self.root_hash,
+        ) {
+            Ok(())
+        } else {
+            Err(RekorError::InvalidProof)
+        }
+    }
+}
+
+impl LogEntry {
+    /// Parses a log entry as returned by Rekor's API: either the entry object itself or a map from the entry UUID to it.
+    pub fn from_json(json: &str) -> Result<LogEntry, RekorError> {
+        let value: Value = serde_json::from_str(json).map_err(|_| RekorError::InvalidJson)?;
+        let (uuid, entry) = match value.as_object() {
+            Some(map) if map.len() == 1 && !map.contains_key("body") => {
+                let (uuid, entry) = map.iter().next().expect("map has one entry");
+                (Some(uuid.clone()), entry)
+            }
+            _ => (None, &value),
+        };
+        let body =
+            from_base64(str_field(entry, "body")?).ok_or(RekorError::InvalidEncoding("body"))?;
+        let proof = entry
+            .get("verification")
+            .and_then(|verification| verification.get("inclusionProof"))
+            .ok_or(RekorError::MissingField("verification.inclusionProof"))?;
+        Ok(LogEntry {
+            uuid,
+            body,
+            inclusion_proof: InclusionProof::from_value(proof)?,
+        })
+    }
+
+    /// Returns the RFC 6962 leaf hash of the entry. Rekor entry UUIDs end with this hash in hex.
+    pub fn leaf_hash(&self) -> Vec<u8> {
+        leaf_hash(&self.body)
+    }
+
+    /// Verifies the entry's inclusion proof, and that the UUID, if known, names this entry.
+    pub fn verify(&self) -> Result<(), RekorError> {
+        if let Some(uuid) = &self.uuid {
+            if !uuid.ends_with(&to_hex(&self.leaf_hash())) {
+                return Err(RekorError::InvalidProof);
+            }
+        }
+        self.inclusion_proof.verify(&self.body)
+    }
+}
+
+fn str_field<'a>(value: &'a Value, name: &'static str) -> Result<&'a str, RekorError> {
+    value
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or(RekorError::MissingField(name))
+}
+
+fn u64_field(value: &Value, name: &'static str) -> Result<u64, RekorError> {
+    value
+        .get(name)
+        .and_then(Value::as_u64)
+        .ok_or(RekorError::MissingField(name))
+}
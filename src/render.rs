@@ -0,0 +1,138 @@
+//! Diagrams of a [MerkleTree](../struct.MerkleTree.html).
+//!
+//! [MerkleTree::to_mermaid](../struct.MerkleTree.html#method.to_mermaid) writes the tree as a [Mermaid](https://mermaid.js.org) flowchart, which GitHub, GitLab and most documentation generators render from a fenced `mermaid` code block in Markdown. [MerkleTree::render](../struct.MerkleTree.html#method.render) draws it as indented text for terminals and logs, shaped by [RenderOptions](struct.RenderOptions.html).
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::render::RenderOptions;
+//! use rs_merkletree::MerkleTree;
+//! let data = vec!["Hello", "World"];
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(data.clone());
+//! let chart = tree.to_mermaid();
+//! assert!(chart.starts_with("flowchart TD\n"));
+//! assert!(chart.contains("n0 --> n1\n"));
+//!
+//! let text = tree.render(&RenderOptions { leaf_data: Some(&data), ..RenderOptions::default() });
+//! assert_eq!(text.lines().count(), 3);
+//! assert!(text.ends_with("\"World\"\n"));
+//! ```
+
+use crate::{Hash, Node};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+
+/// [RenderOptions](struct.RenderOptions.html) controls [MerkleTree::render](../struct.MerkleTree.html#method.render).
+///
+/// * `hash_len`: Hex digits of each hash to show; longer hashes end with `…`.
+///
+/// * `max_depth`: Deepest level drawn, the root being level 0. Subtrees below it are shown as `…`. `None` draws every level.
+///
+/// * `leaf_data`: The data the tree was built from. When given, leaves are annotated with their data, found by hash so sorted trees are labelled correctly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions<'a> {
+    pub hash_len: usize,
+    pub max_depth: Option<usize>,
+    pub leaf_data: Option<&'a [&'a str]>,
+}
+
+impl Default for RenderOptions<'_> {
+    /// Hashes truncated to 8 digits, every level, no leaf data.
+    fn default() -> Self {
+        RenderOptions {
+            hash_len: 8,
+            max_depth: None,
+            leaf_data: None,
+        }
+    }
+}
+
+/// Number of hex digits of each hash shown in a Mermaid label.
+pub const MERMAID_HASH_DIGITS: usize = 8;
+
+/// Writes the tree under `root` as a top-down Mermaid flowchart. Nodes are numbered breadth first from `n0`, the root.
+pub(crate) fn mermaid(root: Option<&Node>) -> String {
+    let mut chart = String::from("flowchart TD\n");
+    let mut queue: VecDeque<&Node> = root.into_iter().collect();
+    let mut next_id = 1;
+    let mut id = 0;
+    while let Some(node) = queue.pop_front() {
+        let hash = node.hash.to_string();
+        let label: String = hash.chars().take(MERMAID_HASH_DIGITS).collect();
+        let _ = writeln!(chart, "    n{}[\"{}\"]", id, label);
+        for child in [node.left_node.as_deref(), node.right_node.as_deref()]
+            .into_iter()
+            .flatten()
+        {
+            let _ = writeln!(chart, "    n{} --> n{}", id, next_id);
+            queue.push_back(child);
+            next_id += 1;
+        }
+        id += 1;
+    }
+    chart
+}
+
+/// Draws the tree under `root` with box-drawing characters, one node per line. `leaves` maps leaf hashes to their data.
+pub(crate) fn text(
+    root: Option<&Node>,
+    options: &RenderOptions,
+    leaves: &HashMap<Hash, &str>,
+) -> String {
+    let mut out = String::new();
+    if let Some(root) = root {
+        text_node(&mut out, root, "", "", 0, options, leaves);
+    }
+    out
+}
+
+fn text_node(
+    out: &mut String,
+    node: &Node,
+    prefix: &str,
+    child_prefix: &str,
+    depth: usize,
+    options: &RenderOptions,
+    leaves: &HashMap<Hash, &str>,
+) {
+    let hash = node.hash.to_string();
+    let _ = write!(
+        out,
+        "{}{}",
+        prefix,
+        hash.chars().take(options.hash_len).collect::<String>()
+    );
+    if hash.chars().count() > options.hash_len {
+        out.push('…');
+    }
+    let children: Vec<&Node> = [node.left_node.as_deref(), node.right_node.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect();
+    if children.is_empty() {
+        if let Some(data) = leaves.get(&node.hash) {
+            let _ = write!(out, " {:?}", data);
+        }
+    } else if options.max_depth.is_some_and(|max| depth >= max) {
+        out.push_str(" …\n");
+        return;
+    }
+    out.push('\n');
+    for (i, child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        text_node(
+            out,
+            child,
+            &format!("{}{}", child_prefix, branch),
+            &format!("{}{}", child_prefix, indent),
+            depth + 1,
+            options,
+            leaves,
+        );
+    }
+}
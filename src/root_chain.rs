@@ -0,0 +1,105 @@
+//! Chained root commitments.
+//!
+//! A signed root vouches for the current state, but says nothing about the roots published before it: a publisher could quietly rewrite an old root and sign it again. A [RootChain](struct.RootChain.html) folds every published root into a running head, `SHA256(previous head || root)` starting from [GENESIS](constant.GENESIS.html), so anyone holding the latest head can check a claimed history of roots against it, and changing, dropping or reordering any historical root changes every head after it. A [TransparencyLog](../log/struct.TransparencyLog.html) extends its chain with every tree head it signs.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::root_chain::RootChain;
+//! use rs_merkletree::MerkleTree;
+//! let mut chain = RootChain::new();
+//! let mut roots = Vec::new();
+//! for data in [vec!["a", "b"], vec!["a", "b", "c"]] {
+//!     let mut tree = MerkleTree::new(None);
+//!     tree.build_tree(data);
+//!     let root = tree.root_node().unwrap().hash().to_hex_bytes();
+//!     chain.push(&root);
+//!     roots.push(root);
+//! }
+//! assert!(RootChain::verify(&roots, chain.head()));
+//!
+//! roots[0][0] ^= 1;
+//! assert!(!RootChain::verify(&roots, chain.head()));
+//! ```
+
+use crate::hashing::sha256_concat;
+
+/// Head of a chain no root has been pushed to.
+pub const GENESIS: [u8; 32] = [0u8; 32];
+
+/// [RootChain](struct.RootChain.html) is a running commitment to a sequence of roots.
+///
+/// * `head`: Hash over every root pushed so far, [GENESIS](constant.GENESIS.html) before the first.
+///
+/// * `len`: Number of roots pushed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootChain {
+    head: Vec<u8>,
+    len: u64,
+}
+
+impl Default for RootChain {
+    fn default() -> RootChain {
+        RootChain::new()
+    }
+}
+
+impl RootChain {
+    /// Function to create an empty [RootChain](struct.RootChain.html).
+    pub fn new() -> RootChain {
+        RootChain::resume(GENESIS.to_vec(), 0)
+    }
+
+    /// Function to create a [RootChain](struct.RootChain.html) continuing from a stored `head` over `len` roots.
+    pub fn resume(head: Vec<u8>, len: u64) -> RootChain {
+        RootChain { head, len }
+    }
+
+    /// Function to create a [RootChain](struct.RootChain.html) over `roots`, oldest first.
+    pub fn from_roots<I>(roots: I) -> RootChain
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut chain = RootChain::new();
+        for root in roots {
+            chain.push(root.as_ref());
+        }
+        chain
+    }
+
+    /// Appends `root` to the chain and returns the new head.
+    pub fn push(&mut self, root: &[u8]) -> &[u8] {
+        self.head = link(&self.head, root);
+        self.len += 1;
+        &self.head
+    }
+
+    /// Returns the head of the chain.
+    pub fn head(&self) -> &[u8] {
+        &self.head
+    }
+
+    /// Returns the number of roots pushed.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no root has been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Checks that `roots`, oldest first, are exactly the history committed to by `head`.
+    pub fn verify<I>(roots: I, head: &[u8]) -> bool
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        RootChain::from_roots(roots).head == head
+    }
+}
+
+/// Returns the head following `previous` once `root` is pushed: `SHA256(previous || root)`.
+pub fn link(previous: &[u8], root: &[u8]) -> Vec<u8> {
+    sha256_concat(&[previous, root])
+}
@@ -0,0 +1,97 @@
+//! Recent-roots window.
+//!
+//! Contracts such as Tornado Cash and Semaphore keep a ring buffer of the last few roots of their incremental tree, because a user's proof is built against the root they saw, and other insertions may land before theirs does. A [RootHistory](struct.RootHistory.html) is the same buffer: it remembers the last `capacity` roots and accepts a proof if it leads to any of them.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::concurrent::proof_from_leaves;
+//! use rs_merkletree::incremental::IncrementalMerkleTree;
+//! use rs_merkletree::root_history::RootHistory;
+//! let mut tree = IncrementalMerkleTree::new(4);
+//! let mut history = RootHistory::new(8);
+//! let mut leaves = Vec::new();
+//! for i in 0..3u8 {
+//!     leaves.push(vec![i + 1; 32]);
+//!     tree.insert(&leaves[i as usize]);
+//!     history.push(tree.root());
+//! }
+//! // A proof made before the third insertion still verifies.
+//! let proof = proof_from_leaves(&leaves[..2], 1, 4);
+//! assert_eq!(history.verify_path(&leaves[1], &proof, 1), Some(1));
+//! ```
+
+use crate::concurrent::compute_root;
+use std::collections::VecDeque;
+
+/// [RootHistory](struct.RootHistory.html) holds the most recent roots of a tree, oldest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootHistory {
+    capacity: usize,
+    roots: VecDeque<Vec<u8>>,
+}
+
+impl RootHistory {
+    /// Function to create an empty [RootHistory](struct.RootHistory.html) that keeps `capacity` roots.
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> RootHistory {
+        assert!(capacity > 0, "capacity must be positive");
+        RootHistory {
+            capacity,
+            roots: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records a new root, forgetting the oldest one if the window is full.
+    pub fn push(&mut self, root: Vec<u8>) {
+        if self.roots.len() == self.capacity {
+            self.roots.pop_front();
+        }
+        self.roots.push_back(root);
+    }
+
+    /// Returns the most recent root.
+    pub fn latest(&self) -> Option<&[u8]> {
+        self.roots.back().map(Vec::as_slice)
+    }
+
+    /// Returns the number of roots held.
+    pub fn len(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// Returns true if no root was recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Returns the window size.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the roots, oldest first.
+    pub fn roots(&self) -> impl Iterator<Item = &[u8]> {
+        self.roots.iter().map(Vec::as_slice)
+    }
+
+    /// Returns how many updates ago `root` was current (0 for the latest), or `None` if it is not in the window.
+    pub fn age(&self, root: &[u8]) -> Option<usize> {
+        self.roots.iter().rev().position(|known| known == root)
+    }
+
+    /// Returns true if `root` is in the window.
+    pub fn contains(&self, root: &[u8]) -> bool {
+        self.age(root).is_some()
+    }
+
+    /// Verifies a proof of any kind: `check` is called with each root in the window, newest first, and the age of the first root it accepts is returned.
+    pub fn verify<F: FnMut(&[u8]) -> bool>(&self, mut check: F) -> Option<usize> {
+        self.roots.iter().rev().position(|root| check(root))
+    }
+
+    /// Verifies a fixed-depth path (as produced for [IncrementalMerkleTree](../incremental/struct.IncrementalMerkleTree.html) or [ConcurrentMerkleTree](../concurrent/struct.ConcurrentMerkleTree.html) leaves) against the window, returning the age of the root it leads to.
+    pub fn verify_path(&self, leaf: &[u8], proof: &[Vec<u8>], index: u64) -> Option<usize> {
+        self.age(&compute_root(leaf, proof, index)?)
+    }
+}
@@ -0,0 +1,227 @@
+//! Epoch-based tree rotation.
+//!
+//! Log systems that publish a root periodically usually start a fresh tree for every period, so each published root covers a bounded batch of entries. A [RotatingTree](struct.RotatingTree.html) does the bookkeeping: entries go into the current [HistoryTree](../history/struct.HistoryTree.html), which is sealed once its [RotationPolicy](struct.RotationPolicy.html) says so, recording the final root as an [Epoch](struct.Epoch.html), and a new tree is started for the next epoch.
+//!
+//! Sealed roots are chained by appending each one, as data, to a meta-tree, itself a [HistoryTree](../history/struct.HistoryTree.html). A [CrossTreeProof](struct.CrossTreeProof.html) composes a leaf's proof in its epoch with the epoch's proof in the meta-tree, so a single meta-tree root vouches for every leaf of every sealed epoch.
+//!
+//! Time is passed in by the caller, in milliseconds, as for [TransparencyLog::tick](../log/struct.TransparencyLog.html#method.tick).
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::rotation::{RotatingTree, RotationPolicy};
+//! let policy = RotationPolicy { interval: Some(60_000), max_leaves: Some(2) };
+//! let mut log = RotatingTree::new(policy, 0);
+//! assert_eq!(log.append(b"a", 1_000), (0, 0));
+//! assert_eq!(log.append(b"b", 2_000), (0, 1));
+//! // The size threshold sealed epoch 0.
+//! assert_eq!(log.append(b"c", 3_000), (1, 0));
+//! assert!(log.tick(30_000).is_none());
+//! let sealed = log.tick(63_000).unwrap();
+//! assert_eq!((sealed.number, sealed.size), (1, 1));
+//! assert_eq!(log.sealed_root(0), log.epoch_tree(0).map(|tree| tree.root()).as_deref());
+//!
+//! let proof = log.cross_tree_proof(0, 1, 2).unwrap();
+//! assert!(proof.verify(&log.meta_root(), b"b"));
+//! ```
+
+use crate::history::{HistoryTree, MembershipProof};
+
+/// [RotationPolicy](struct.RotationPolicy.html) decides when the current epoch is sealed. An epoch is sealed as soon as either limit is reached; a policy without limits only rotates on [seal](struct.RotatingTree.html#method.seal).
+///
+/// * `interval`: Longest time, in milliseconds, an epoch stays open.
+///
+/// * `max_leaves`: Number of leaves after which an epoch is sealed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RotationPolicy {
+    pub interval: Option<u64>,
+    pub max_leaves: Option<u64>,
+}
+
+/// [Epoch](struct.Epoch.html) is the record of a sealed tree.
+///
+/// * `number`: Position of the epoch, from 0.
+///
+/// * `root`: Root of the tree when it was sealed.
+///
+/// * `size`: Number of leaves in the tree.
+///
+/// * `started_at`, `sealed_at`: Times, in milliseconds, at which the epoch was opened and sealed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Epoch {
+    pub number: u64,
+    pub root: Vec<u8>,
+    pub size: u64,
+    pub started_at: u64,
+    pub sealed_at: u64,
+}
+
+/// [CrossTreeProof](struct.CrossTreeProof.html) proves that a leaf belongs to a sealed epoch committed in a meta-tree root.
+///
+/// * `epoch_root`: Root of the leaf's epoch.
+///
+/// * `epoch_proof`: Proof of the leaf in its epoch, against `epoch_root`.
+///
+/// * `meta_proof`: Proof of `epoch_root` in the meta-tree; its index is the epoch number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossTreeProof {
+    pub epoch_root: Vec<u8>,
+    pub epoch_proof: MembershipProof,
+    pub meta_proof: MembershipProof,
+}
+
+impl CrossTreeProof {
+    /// Returns the epoch of the leaf.
+    pub fn epoch(&self) -> u64 {
+        self.meta_proof.index
+    }
+
+    /// Checks that `data` is in the epoch committed at position [epoch](struct.CrossTreeProof.html#method.epoch) of the meta-tree with root `meta_root`.
+    pub fn verify(&self, meta_root: &[u8], data: &[u8]) -> bool {
+        self.epoch_proof.verify(&self.epoch_root, data)
+            && self.meta_proof.verify(meta_root, &self.epoch_root)
+    }
+}
+
+/// [RotatingTree](struct.RotatingTree.html) appends to the tree of the current epoch and keeps the sealed ones.
+///
+/// * `policy`: When to seal.
+///
+/// * `current`: Tree of the open epoch.
+///
+/// * `started_at`: Time at which the open epoch started.
+///
+/// * `sealed`: Records of the sealed epochs, oldest first.
+///
+/// * `trees`: Trees of the sealed epochs, for proofs against their roots, or `None` once pruned.
+///
+/// * `meta`: Meta-tree over the sealed roots.
+#[derive(Debug, Clone)]
+pub struct RotatingTree {
+    policy: RotationPolicy,
+    current: HistoryTree,
+    started_at: u64,
+    sealed: Vec<Epoch>,
+    trees: Vec<Option<HistoryTree>>,
+    meta: HistoryTree,
+}
+
+impl RotatingTree {
+    /// Function to create a [RotatingTree](struct.RotatingTree.html) whose first epoch starts at `now`.
+    pub fn new(policy: RotationPolicy, now: u64) -> RotatingTree {
+        RotatingTree {
+            policy,
+            current: HistoryTree::new(),
+            started_at: now,
+            sealed: Vec::new(),
+            trees: Vec::new(),
+            meta: HistoryTree::new(),
+        }
+    }
+
+    /// Appends a leaf at time `now` and returns its epoch and its index within the epoch.
+    ///
+    /// An epoch whose interval has elapsed is sealed before the leaf is added, and one that reaches `max_leaves` is sealed right after.
+    pub fn append(&mut self, data: &[u8], now: u64) -> (u64, u64) {
+        self.tick(now);
+        let epoch = self.epoch();
+        let index = self.current.append(data) - 1;
+        if self
+            .policy
+            .max_leaves
+            .is_some_and(|max| self.current.len() >= max)
+        {
+            self.seal(now);
+        }
+        (epoch, index)
+    }
+
+    /// Seals the current epoch if its interval has elapsed at `now`, returning its record. Empty epochs are never sealed; they stay open until a leaf arrives.
+    pub fn tick(&mut self, now: u64) -> Option<&Epoch> {
+        let interval = self.policy.interval?;
+        if now < self.started_at.saturating_add(interval) {
+            return None;
+        }
+        self.seal(now)
+    }
+
+    /// Seals the current epoch at `now` regardless of the policy, returning its record, or `None` if it is empty.
+    pub fn seal(&mut self, now: u64) -> Option<&Epoch> {
+        if self.current.is_empty() {
+            return None;
+        }
+        let tree = std::mem::take(&mut self.current);
+        self.meta.append(&tree.root());
+        self.sealed.push(Epoch {
+            number: self.epoch(),
+            root: tree.root(),
+            size: tree.len(),
+            started_at: self.started_at,
+            sealed_at: now,
+        });
+        self.trees.push(Some(tree));
+        self.started_at = now;
+        self.sealed.last()
+    }
+
+    /// Returns the number of the open epoch.
+    pub fn epoch(&self) -> u64 {
+        self.sealed.len() as u64
+    }
+
+    /// Returns the tree of the open epoch.
+    pub fn current(&self) -> &HistoryTree {
+        &self.current
+    }
+
+    /// Returns the records of every sealed epoch, oldest first.
+    pub fn sealed(&self) -> &[Epoch] {
+        &self.sealed
+    }
+
+    /// Returns the root of sealed epoch `number`.
+    pub fn sealed_root(&self, number: u64) -> Option<&[u8]> {
+        self.sealed
+            .get(number as usize)
+            .map(|epoch| epoch.root.as_slice())
+    }
+
+    /// Returns the tree of sealed epoch `number`, or `None` if it was pruned.
+    pub fn epoch_tree(&self, number: u64) -> Option<&HistoryTree> {
+        self.trees.get(number as usize)?.as_ref()
+    }
+
+    /// Drops the trees of the sealed epochs before `epoch`, which a long-running log no longer needs to prove against.
+    ///
+    /// Their records and the meta-tree are kept, so [sealed_root](struct.RotatingTree.html#method.sealed_root) and meta-tree proofs still cover them, but [cross_tree_proof](struct.RotatingTree.html#method.cross_tree_proof) returns `None` for their leaves.
+    pub fn prune_before(&mut self, epoch: u64) {
+        let end = self.trees.len().min(epoch.try_into().unwrap_or(usize::MAX));
+        self.trees[..end].iter_mut().for_each(|tree| *tree = None);
+    }
+
+    /// Returns the meta-tree, whose leaf `n` is the root of sealed epoch `n`.
+    pub fn meta_tree(&self) -> &HistoryTree {
+        &self.meta
+    }
+
+    /// Returns the root of the meta-tree over every sealed epoch.
+    pub fn meta_root(&self) -> Vec<u8> {
+        self.meta.root()
+    }
+
+    /// Generates a proof that leaf `index` of sealed epoch `epoch` is covered by the meta-tree root over the first `meta_size` epochs.
+    ///
+    /// Returns `None` if the epoch is not sealed, was pruned, is not among the first `meta_size`, or has no leaf at `index`.
+    pub fn cross_tree_proof(
+        &self,
+        epoch: u64,
+        index: u64,
+        meta_size: u64,
+    ) -> Option<CrossTreeProof> {
+        let tree = self.epoch_tree(epoch)?;
+        Some(CrossTreeProof {
+            epoch_root: tree.root(),
+            epoch_proof: tree.membership_proof(index, tree.len())?,
+            meta_proof: self.meta.membership_proof(epoch, meta_size)?,
+        })
+    }
+}
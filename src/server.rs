@@ -0,0 +1,204 @@
+//! HTTP proof server.
+//!
+//! [ProofServer](struct.ProofServer.html) serves a [MerkleTree](../struct.MerkleTree.html) over HTTP/1.1 with JSON bodies:
+//!
+//! * `GET /root` returns `{"root": "<hex>", "leaves": <count>}`.
+//! * `GET /proof/{index}` returns `{"index": <index>, "root": "<hex>", "proof": "<hex>"}`, where `proof` is the hex of [MerkleProof::to_bytes](../proof/struct.MerkleProof.html#method.to_bytes).
+//! * `POST /verify` with `{"root": "<hex>", "leaf": "<data>", "proof": "<hex>"}` returns `{"valid": <bool>}`.
+//!
+//! Errors are returned as `{"error": "<message>"}` with a 400 or 404 status. Every connection carries one request. The server has no TLS or authentication; put it behind a reverse proxy when exposing it.
+//!
+//! Requires the `server` feature.
+//!
+//! # Examples
+//! ```no_run
+//! use rs_merkletree::server::ProofServer;
+//! use std::net::TcpListener;
+//! let server = ProofServer::from_snapshot("leaves.txt").unwrap();
+//! server.serve(TcpListener::bind("127.0.0.1:8080").unwrap()).unwrap();
+//! ```
+
+use crate::hashing::{from_hex, to_hex};
+use crate::limits::Limits;
+use crate::proof::MerkleProof;
+use crate::MerkleTree;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::thread;
+
+/// Largest request body accepted, in bytes.
+pub const MAX_BODY: usize = 1 << 20;
+
+/// [ProofServer](struct.ProofServer.html) answers root, proof and verification requests for one tree.
+#[derive(Debug, Clone)]
+pub struct ProofServer {
+    tree: MerkleTree,
+    leaves: usize,
+}
+
+/// [Response](struct.Response.html) is the status and JSON body of a handled request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Response {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+}
+
+impl ProofServer {
+    /// Function to create a [ProofServer](struct.ProofServer.html) serving `tree`.
+    pub fn new(tree: MerkleTree) -> ProofServer {
+        let leaves = if tree.root_node().is_some() {
+            tree.count_leaves()
+        } else {
+            0
+        };
+        ProofServer { tree, leaves }
+    }
+
+    /// Function to create a [ProofServer](struct.ProofServer.html) from a snapshot: a file with one leaf per line.
+    pub fn from_snapshot<P: AsRef<Path>>(path: P) -> io::Result<ProofServer> {
+        let snapshot = fs::read_to_string(path)?;
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(snapshot.lines().collect());
+        Ok(ProofServer::new(tree))
+    }
+
+    /// Routes one request.
+    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> Response {
+        match (method, path) {
+            ("GET", "/root") => Response::ok(json!({ "root": self.root(), "leaves": self.leaves })),
+            ("GET", path) if path.starts_with("/proof/") => {
+                let Ok(index) = path["/proof/".len()..].parse::<usize>() else {
+                    return Response::error(400, "invalid leaf index");
+                };
+                match self.tree.proof(index) {
+                    Some(proof) => Response::ok(json!({
+                        "index": index,
+                        "root": self.root(),
+                        "proof": to_hex(&proof.to_bytes()),
+                    })),
+                    None => Response::error(404, "leaf not found"),
+                }
+            }
+            ("POST", "/verify") => self.verify(body),
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    /// Accepts connections on `listener` until it fails, handling each one on its own thread.
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        thread::scope(|scope| {
+            for stream in listener.incoming() {
+                let stream = stream?;
+                scope.spawn(move || {
+                    // A broken connection only affects its own client.
+                    let _ = self.handle_connection(stream);
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Reads one request from `stream` and writes the response.
+    pub fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let response = match read_request(&mut reader)? {
+            Some((method, path, body)) => self.handle(&method, &path, &body),
+            None => Response::error(400, "malformed request"),
+        };
+        write_response(stream, &response)
+    }
+
+    fn root(&self) -> Option<String> {
+        Some(self.tree.root_node()?.hash().to_string())
+    }
+
+    fn verify(&self, body: &[u8]) -> Response {
+        let Ok(request) = serde_json::from_slice::<Value>(body) else {
+            return Response::error(400, "body is not JSON");
+        };
+        let field = |name| request.get(name).and_then(Value::as_str);
+        let (Some(root), Some(leaf), Some(proof)) = (field("root"), field("leaf"), field("proof"))
+        else {
+            return Response::error(400, "expected root, leaf and proof strings");
+        };
+        let limits = Limits::default();
+        let Some(proof) = from_hex(proof)
+            .as_deref()
+            .and_then(|bytes| MerkleProof::from_bytes_limited(bytes, &limits).ok())
+        else {
+            return Response::error(400, "malformed proof");
+        };
+        let valid = root
+            .parse()
+            .is_ok_and(|root| proof.verify_data(&root, leaf.as_bytes()));
+        Response::ok(json!({ "valid": valid }))
+    }
+}
+
+/// Parses the request line, headers and body; `None` if the request is malformed.
+fn read_request<R: BufRead>(reader: &mut R) -> io::Result<Option<(String, String, Vec<u8>)>> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                match value.trim().parse() {
+                    Ok(len) if len <= MAX_BODY => content_length = len,
+                    _ => return Ok(None),
+                }
+            }
+        }
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Some((method, path, body)))
+}
+
+fn write_response<W: Write>(mut writer: W, response: &Response) -> io::Result<()> {
+    let body = response.body.to_string();
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        _ => "Not Found",
+    };
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        body.len(),
+        body
+    )?;
+    writer.flush()
+}
@@ -0,0 +1,128 @@
+//! Signed roots.
+//!
+//! A root is only useful to a client that trusts it. [SignedRoot](struct.SignedRoot.html) carries a root together with the tree size, a timestamp and a signature over all three, so roots can be distributed out-of-band (mirrors, gossip, a config file) and checked against the publisher's key. Signing goes through the [RootSigner](trait.RootSigner.html) and [RootVerifier](trait.RootVerifier.html) traits; ed25519 keys from `ed25519-dalek` implement them, and other schemes can be plugged in.
+//!
+//! # Examples
+//! ```
+//! use ed25519_dalek::SigningKey;
+//! use rs_merkletree::signed::SignedRoot;
+//! use rs_merkletree::MerkleTree;
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+//!
+//! let key = SigningKey::from_bytes(&[7u8; 32]);
+//! let signed = SignedRoot::for_tree(&tree, &key, 1_700_000_000_000).unwrap();
+//! assert_eq!(signed.tree_size, 4);
+//! assert!(signed.verify(&key.verifying_key()));
+//! assert!(signed.verify_ed25519(key.verifying_key().as_bytes()));
+//! ```
+
+use crate::proof::HASH_LEN;
+use crate::{Hash, MerkleTree};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+/// Prefix of the signed data, so a root signature cannot be replayed as a signature over anything else.
+const DOMAIN: &[u8] = b"rs-merkletree signed root v1\0";
+
+/// Signs the bytes of a [SignedRoot](struct.SignedRoot.html).
+pub trait RootSigner {
+    /// Returns the signature over `message`.
+    fn sign_root(&self, message: &[u8]) -> Vec<u8>;
+}
+
+/// Checks the signature of a [SignedRoot](struct.SignedRoot.html).
+pub trait RootVerifier {
+    /// Returns `true` if `signature` is valid for `message`.
+    fn verify_root(&self, message: &[u8], signature: &[u8]) -> bool;
+}
+
+impl RootSigner for SigningKey {
+    fn sign_root(&self, message: &[u8]) -> Vec<u8> {
+        self.sign(message).to_vec()
+    }
+}
+
+impl RootVerifier for VerifyingKey {
+    fn verify_root(&self, message: &[u8], signature: &[u8]) -> bool {
+        <[u8; 64]>::try_from(signature).is_ok_and(|signature| {
+            self.verify(message, &Signature::from_bytes(&signature))
+                .is_ok()
+        })
+    }
+}
+
+/// [SignedRoot](struct.SignedRoot.html) is a root vouched for by its publisher.
+///
+/// * `root`: Root hash of the tree.
+///
+/// * `tree_size`: Number of leaves in the tree.
+///
+/// * `timestamp`: Milliseconds since the Unix epoch at which the root was signed.
+///
+/// * `signature`: Signature over [signed_data](struct.SignedRoot.html#method.signed_data).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedRoot {
+    pub root: Hash,
+    pub tree_size: u64,
+    pub timestamp: u64,
+    pub signature: Vec<u8>,
+}
+
+impl SignedRoot {
+    /// Function to create a [SignedRoot](struct.SignedRoot.html) by signing `root` with `signer`.
+    pub fn sign<S: RootSigner>(
+        signer: &S,
+        root: Hash,
+        tree_size: u64,
+        timestamp: u64,
+    ) -> SignedRoot {
+        let mut signed = SignedRoot {
+            root,
+            tree_size,
+            timestamp,
+            signature: Vec::new(),
+        };
+        signed.signature = signer.sign_root(&signed.signed_data());
+        signed
+    }
+
+    /// Function to create a [SignedRoot](struct.SignedRoot.html) for the root of `tree`.
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn for_tree<S: RootSigner>(
+        tree: &MerkleTree,
+        signer: &S,
+        timestamp: u64,
+    ) -> Option<SignedRoot> {
+        let root = tree.root_node()?.hash();
+        Some(SignedRoot::sign(
+            signer,
+            root,
+            tree.count_leaves() as u64,
+            timestamp,
+        ))
+    }
+
+    /// Returns the bytes covered by the signature: a fixed domain string, the tree size and timestamp as 8 byte big-endian integers, then the root in hex.
+    pub fn signed_data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(DOMAIN.len() + 16 + HASH_LEN);
+        data.extend_from_slice(DOMAIN);
+        data.extend_from_slice(&self.tree_size.to_be_bytes());
+        data.extend_from_slice(&self.timestamp.to_be_bytes());
+        data.extend_from_slice(&self.root.to_hex_bytes());
+        data
+    }
+
+    /// Checks the signature with `verifier`.
+    pub fn verify<V: RootVerifier>(&self, verifier: &V) -> bool {
+        verifier.verify_root(&self.signed_data(), &self.signature)
+    }
+
+    /// Checks the signature with a raw 32 byte ed25519 `public_key`.
+    pub fn verify_ed25519(&self, public_key: &[u8]) -> bool {
+        <[u8; 32]>::try_from(public_key)
+            .ok()
+            .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+            .is_some_and(|key| self.verify(&key))
+    }
+}
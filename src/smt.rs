@@ -0,0 +1,247 @@
+//! Sparse Merkle Tree.
+//!
+//! A [SparseMerkleTree](struct.SparseMerkleTree.html) is a Merkle tree of fixed depth 256 where every possible key owns exactly one leaf, found by walking the bits of `SHA256(key)` from the root. Almost every leaf is empty, so empty subtrees are never stored: they are represented by precomputed default hashes.
+//!
+//! Because each key has a single fixed position, the tree can prove both that a key is present (a membership proof) and that it is absent (a non-membership proof, showing the leaf at the key's position is empty).
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::smt::SparseMerkleTree;
+//! let mut tree = SparseMerkleTree::new();
+//! tree.insert(b"alice", b"100");
+//! let root = tree.root();
+//!
+//! let proof = tree.prove(b"alice");
+//! assert!(proof.verify_membership(&root, b"alice", b"100"));
+//!
+//! let proof = tree.prove(b"bob");
+//! assert!(proof.verify_non_membership(&root, b"bob"));
+//! ```
+
+use crate::hashing::{sha256, sha256_concat};
+use std::collections::BTreeMap;
+
+/// Depth of the tree, i.e. the number of bits in a key path.
+pub const DEPTH: usize = 256;
+
+type Path = [u8; 32];
+
+/// [SparseMerkleTree](struct.SparseMerkleTree.html) is a key-value store committed to by a single 256-level Merkle root.
+///
+/// * `leaves`: The non-empty leaves, keyed by their path `SHA256(key)`.
+///
+/// * `defaults`: `defaults[h]` is the hash of an empty subtree of height `h`.
+#[derive(Debug, Clone)]
+pub struct SparseMerkleTree {
+    leaves: BTreeMap<Path, Vec<u8>>,
+    defaults: Vec<Vec<u8>>,
+}
+
+/// [SparseMerkleProof](struct.SparseMerkleProof.html) holds the siblings on the path from a key's leaf to the root.
+///
+/// Siblings equal to the default hash of their level are omitted; `bitmap` has bit `h` set when the sibling at height `h` is stored in `siblings`.
+/// The same proof type is used for membership and non-membership.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMerkleProof {
+    bitmap: [u8; 32],
+    siblings: Vec<Vec<u8>>,
+}
+
+impl Default for SparseMerkleTree {
+    fn default() -> Self {
+        SparseMerkleTree::new()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SparseMerkleTree {
+    fn zeroize(&mut self) {
+        self.leaves.values_mut().for_each(zeroize::Zeroize::zeroize);
+        self.leaves.clear();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SparseMerkleTree {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SparseMerkleTree {}
+
+impl SparseMerkleTree {
+    /// Function to create a new, empty [SparseMerkleTree](struct.SparseMerkleTree.html)
+    pub fn new() -> SparseMerkleTree {
+        SparseMerkleTree {
+            leaves: BTreeMap::new(),
+            defaults: default_hashes(),
+        }
+    }
+
+    /// Inserts `value` under `key`. Returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+        let old = self.leaves.insert(key_path(key), value.to_vec());
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(
+            self.prove(key).verify_membership(&self.root(), key, value),
+            "sparse merkle tree is inconsistent"
+        );
+        old
+    }
+
+    /// Removes `key` from the tree, returning its value if it was present.
+    pub fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        let old = self.leaves.remove(&key_path(key));
+        #[cfg(feature = "debug-validate")]
+        debug_assert!(
+            self.prove(key).verify_non_membership(&self.root(), key),
+            "sparse merkle tree is inconsistent"
+        );
+        old
+    }
+
+    /// Returns the value stored under `key`, or `None` if the leaf is empty.
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.leaves
+            .get(&key_path(key))
+            .map(|value| value.as_slice())
+    }
+
+    /// Returns the number of non-empty leaves.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns `true` if no key has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Returns the Root Hash of the tree as raw SHA-256 bytes.
+    pub fn root(&self) -> Vec<u8> {
+        let leaves: Vec<(&Path, &Vec<u8>)> = self.leaves.iter().collect();
+        self.subtree_root(&leaves, 0)
+    }
+
+    /// Generates a proof for `key`.
+    ///
+    /// If the key is present the proof is a membership proof for its current value, otherwise it is a non-membership proof.
+    pub fn prove(&self, key: &[u8]) -> SparseMerkleProof {
+        let path = key_path(key);
+        let mut leaves: Vec<(&Path, &Vec<u8>)> = self.leaves.iter().collect();
+        let mut siblings_from_root = Vec::with_capacity(DEPTH);
+
+        for depth in 0..DEPTH {
+            let split = leaves.partition_point(|(p, _)| !bit(p, depth));
+            let (left, right) = leaves.split_at(split);
+            if bit(&path, depth) {
+                siblings_from_root.push(self.subtree_root(left, depth + 1));
+                leaves = right.to_vec();
+            } else {
+                siblings_from_root.push(self.subtree_root(right, depth + 1));
+                leaves = left.to_vec();
+            }
+        }
+
+        let mut bitmap = [0u8; 32];
+        let mut siblings = Vec::new();
+        for (height, sibling) in siblings_from_root.into_iter().rev().enumerate() {
+            if sibling != self.defaults[height] {
+                bitmap[height / 8] |= 1 << (height % 8);
+                siblings.push(sibling);
+            }
+        }
+        SparseMerkleProof { bitmap, siblings }
+    }
+
+    /// Helper function to compute the root of the subtree at `depth` holding `leaves`, which must be sorted by path.
+    fn subtree_root(&self, leaves: &[(&Path, &Vec<u8>)], depth: usize) -> Vec<u8> {
+        if leaves.is_empty() {
+            return self.defaults[DEPTH - depth].clone();
+        }
+        if depth == DEPTH {
+            let (path, value) = leaves[0];
+            return leaf_hash(path, value);
+        }
+        let split = leaves.partition_point(|(p, _)| !bit(p, depth));
+        let left = self.subtree_root(&leaves[..split], depth + 1);
+        let right = self.subtree_root(&leaves[split..], depth + 1);
+        sha256_concat(&[&left, &right])
+    }
+}
+
+impl SparseMerkleProof {
+    /// Checks that `key` maps to `value` in the tree with root `root`.
+    pub fn verify_membership(&self, root: &[u8], key: &[u8], value: &[u8]) -> bool {
+        let path = key_path(key);
+        self.compute_root(&path, leaf_hash(&path, value)) == Some(root.to_vec())
+    }
+
+    /// Checks that `key` is absent from the tree with root `root`, i.e. that its leaf is empty.
+    pub fn verify_non_membership(&self, root: &[u8], key: &[u8]) -> bool {
+        let path = key_path(key);
+        self.compute_root(&path, vec![0u8; 32]) == Some(root.to_vec())
+    }
+
+    /// Returns the number of non-default siblings carried by the proof.
+    pub fn len(&self) -> usize {
+        self.siblings.len()
+    }
+
+    /// Returns `true` if every sibling on the path is an empty subtree.
+    pub fn is_empty(&self) -> bool {
+        self.siblings.is_empty()
+    }
+
+    /// Helper function to fold the siblings over `leaf`. Returns `None` if the proof is malformed.
+    fn compute_root(&self, path: &Path, leaf: Vec<u8>) -> Option<Vec<u8>> {
+        let defaults = default_hashes();
+        let mut stored = self.siblings.iter();
+        let mut current = leaf;
+        for (height, default) in defaults.iter().enumerate().take(DEPTH) {
+            let sibling = if self.bitmap[height / 8] & (1 << (height % 8)) != 0 {
+                stored.next()?
+            } else {
+                default
+            };
+            current = if bit(path, DEPTH - 1 - height) {
+                sha256_concat(&[sibling, &current])
+            } else {
+                sha256_concat(&[&current, sibling])
+            };
+        }
+        if stored.next().is_some() {
+            return None;
+        }
+        Some(current)
+    }
+}
+
+/// Returns the default hashes of empty subtrees for every height from 0 (an empty leaf) to `DEPTH`.
+fn default_hashes() -> Vec<Vec<u8>> {
+    let mut defaults = Vec::with_capacity(DEPTH + 1);
+    defaults.push(vec![0u8; 32]);
+    for height in 0..DEPTH {
+        let below = &defaults[height];
+        let hash = sha256_concat(&[below, below]);
+        defaults.push(hash);
+    }
+    defaults
+}
+
+fn key_path(key: &[u8]) -> Path {
+    let mut path = [0u8; 32];
+    path.copy_from_slice(&sha256(key));
+    path
+}
+
+/// Returns bit `index` of `path`, counting from the most significant bit. `true` means the right child.
+fn bit(path: &Path, index: usize) -> bool {
+    (path[index / 8] >> (7 - index % 8)) & 1 == 1
+}
+
+fn leaf_hash(path: &Path, value: &[u8]) -> Vec<u8> {
+    sha256_concat(&[path, &sha256(value)])
+}
@@ -0,0 +1,187 @@
+//! SSZ merkleization.
+//!
+//! Ethereum's consensus layer commits to objects with [SimpleSerialize](https://github.com/ethereum/consensus-specs/blob/dev/ssz/simple-serialize.md) `hash_tree_root`: values are serialized and packed into 32 byte chunks, the chunks are padded with zero chunks to the next power of two of the type's limit and hashed pairwise with SHA-256, and the length of lists is mixed into the root. Implementing [HashTreeRoot](trait.HashTreeRoot.html) for a container is a matter of listing the roots of its fields with [container_root](fn.container_root.html).
+//!
+//! Supported types are `bool`, the unsigned integers up to `u128`, fixed-size arrays (SSZ vectors), [List](struct.List.html), [Bitvector](struct.Bitvector.html) and [Bitlist](struct.Bitlist.html).
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::ssz::{container_root, HashTreeRoot};
+//! struct Checkpoint {
+//!     epoch: u64,
+//!     root: [u8; 32],
+//! }
+//! impl HashTreeRoot for Checkpoint {
+//!     fn hash_tree_root(&self) -> [u8; 32] {
+//!         container_root(&[self.epoch.hash_tree_root(), self.root.hash_tree_root()])
+//!     }
+//! }
+//! let checkpoint = Checkpoint { epoch: 0, root: [0; 32] };
+//! assert_eq!(
+//!     checkpoint.hash_tree_root()[..4],
+//!     [0xf5, 0xa5, 0xfd, 0x42]
+//! );
+//! ```
+
+use crate::hashing::sha256_concat;
+
+/// Size of an SSZ chunk.
+pub const BYTES_PER_CHUNK: usize = 32;
+
+/// Types with an SSZ `hash_tree_root`.
+pub trait HashTreeRoot {
+    /// Serialized size of basic types, which are packed several to a chunk; `None` for composite types.
+    const BASIC_SIZE: Option<usize> = None;
+
+    /// Returns the `hash_tree_root` of the value.
+    fn hash_tree_root(&self) -> [u8; 32];
+
+    /// Returns the little-endian serialization of a basic value. Unused for composite types.
+    fn packed(&self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+/// SSZ `List[T, N]`: up to `N` elements, with the length mixed into the root.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct List<T, const N: usize>(pub Vec<T>);
+
+/// SSZ `Bitvector[N]`: exactly `N` bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitvector<const N: usize>(pub Vec<bool>);
+
+/// SSZ `Bitlist[N]`: up to `N` bits, with the length mixed into the root.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Bitlist<const N: usize>(pub Vec<bool>);
+
+macro_rules! basic_type {
+    ($($ty:ty),*) => {
+        $(
+            impl HashTreeRoot for $ty {
+                const BASIC_SIZE: Option<usize> = Some(std::mem::size_of::<$ty>());
+
+                fn hash_tree_root(&self) -> [u8; 32] {
+                    let mut chunk = [0u8; 32];
+                    let bytes = self.to_le_bytes();
+                    chunk[..bytes.len()].copy_from_slice(&bytes);
+                    chunk
+                }
+
+                fn packed(&self) -> Vec<u8> {
+                    self.to_le_bytes().to_vec()
+                }
+            }
+        )*
+    };
+}
+
+basic_type!(u8, u16, u32, u64, u128);
+
+impl HashTreeRoot for bool {
+    const BASIC_SIZE: Option<usize> = Some(1);
+
+    fn hash_tree_root(&self) -> [u8; 32] {
+        (*self as u8).hash_tree_root()
+    }
+
+    fn packed(&self) -> Vec<u8> {
+        vec![*self as u8]
+    }
+}
+
+impl<T: HashTreeRoot, const N: usize> HashTreeRoot for [T; N] {
+    fn hash_tree_root(&self) -> [u8; 32] {
+        let (chunks, limit) = elements_to_chunks(self, N);
+        merkleize(&chunks, limit)
+    }
+}
+
+impl<T: HashTreeRoot, const N: usize> HashTreeRoot for List<T, N> {
+    fn hash_tree_root(&self) -> [u8; 32] {
+        let (chunks, limit) = elements_to_chunks(&self.0, N);
+        mix_in_length(&merkleize(&chunks, limit), self.0.len())
+    }
+}
+
+impl<const N: usize> HashTreeRoot for Bitvector<N> {
+    fn hash_tree_root(&self) -> [u8; 32] {
+        merkleize(&pack(&bits_to_bytes(&self.0)), N.div_ceil(256))
+    }
+}
+
+impl<const N: usize> HashTreeRoot for Bitlist<N> {
+    fn hash_tree_root(&self) -> [u8; 32] {
+        let chunks = pack(&bits_to_bytes(&self.0));
+        mix_in_length(&merkleize(&chunks, N.div_ceil(256)), self.0.len())
+    }
+}
+
+/// Returns the root of a container from the roots of its fields, in declaration order.
+pub fn container_root(field_roots: &[[u8; 32]]) -> [u8; 32] {
+    merkleize(field_roots, field_roots.len())
+}
+
+/// Packs serialized bytes into zero-padded chunks.
+pub fn pack(bytes: &[u8]) -> Vec<[u8; 32]> {
+    bytes
+        .chunks(BYTES_PER_CHUNK)
+        .map(|part| {
+            let mut chunk = [0u8; 32];
+            chunk[..part.len()].copy_from_slice(part);
+            chunk
+        })
+        .collect()
+}
+
+/// Merkleizes `chunks` as a tree with `next_pow_of_two(limit)` leaves, padding with zero chunks.
+///
+/// Panics if there are more chunks than `limit`.
+pub fn merkleize(chunks: &[[u8; 32]], limit: usize) -> [u8; 32] {
+    assert!(chunks.len() <= limit.max(1), "more chunks than the limit");
+    let depth = limit.max(1).next_power_of_two().trailing_zeros() as usize;
+    let mut zero = [0u8; 32];
+    let mut layer = chunks.to_vec();
+    for _ in 0..depth {
+        // Only the populated prefix is hashed; the rest of the level is all zero subtrees.
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&zero)))
+            .collect();
+        zero = hash_pair(&zero, &zero);
+    }
+    layer.first().copied().unwrap_or(zero)
+}
+
+/// Mixes a list length into its root: `hash(root || uint256(length))`.
+pub fn mix_in_length(root: &[u8; 32], length: usize) -> [u8; 32] {
+    hash_pair(root, &(length as u128).hash_tree_root())
+}
+
+fn elements_to_chunks<T: HashTreeRoot>(elements: &[T], capacity: usize) -> (Vec<[u8; 32]>, usize) {
+    match T::BASIC_SIZE {
+        Some(size) => {
+            let bytes: Vec<u8> = elements.iter().flat_map(HashTreeRoot::packed).collect();
+            (pack(&bytes), (capacity * size).div_ceil(BYTES_PER_CHUNK))
+        }
+        None => (
+            elements.iter().map(HashTreeRoot::hash_tree_root).collect(),
+            capacity,
+        ),
+    }
+}
+
+fn bits_to_bytes(bits: &[bool]) -> Vec<u8> {
+    let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+    for (i, bit) in bits.iter().enumerate() {
+        if *bit {
+            bytes[i / 8] |= 1 << (i % 8);
+        }
+    }
+    bytes
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&sha256_concat(&[left, right]));
+    out
+}
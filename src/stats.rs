@@ -0,0 +1,38 @@
+//! Size and shape statistics of a [MerkleTree](../struct.MerkleTree.html).
+//!
+//! [MerkleTree::stats](../struct.MerkleTree.html#method.stats) gathers everything a dashboard tracking tree growth needs in a single walk of the tree.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::MerkleTree;
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(vec!["Hello", "World", "From"]);
+//! let stats = tree.stats();
+//! assert_eq!((stats.leaves, stats.nodes, stats.depth), (3, 6, 3));
+//! // "From" has no sibling, so its parent hashes it with itself.
+//! assert_eq!(stats.padding_nodes, 1);
+//! assert_eq!(stats.hash_algorithm, "sha256");
+//! ```
+
+/// [TreeStats](struct.TreeStats.html) describes a tree at the time it was taken.
+///
+/// * `nodes`: Number of nodes, leaves included.
+///
+/// * `leaves`: Number of leaves.
+///
+/// * `depth`: Number of levels, 0 for an empty tree.
+///
+/// * `padding_nodes`: Inner nodes with a single child, created because a level had an odd number of nodes.
+///
+/// * `hash_algorithm`: `sha256` for domain-separated trees, `sha256-legacy` for [legacy](../struct.MerkleTree.html#method.new_legacy) ones.
+///
+/// * `memory_bytes`: Approximate heap and inline size of the tree, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeStats {
+    pub nodes: usize,
+    pub leaves: usize,
+    pub depth: usize,
+    pub padding_nodes: usize,
+    pub hash_algorithm: &'static str,
+    pub memory_bytes: usize,
+}
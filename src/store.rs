@@ -0,0 +1,196 @@
+//! Deduplicating node store.
+//!
+//! A [NodeStore](struct.NodeStore.html) builds [MerkleTree](../struct.MerkleTree.html)-compatible trees whose nodes are interned by content: every distinct subtree is allocated once and shared by every tree and position that contains it. Datasets with many repeated leaves (log lines, sparse files, padding) produce many identical subtrees, so the store holds far fewer nodes than the trees it represents.
+//!
+//! Nodes are keyed by their hash together with the identity of their interned children, since the duplicate-last rule of [MerkleTree](../struct.MerkleTree.html) gives a node with one child the same hash as one with two identical children.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::store::NodeStore;
+//! use rs_merkletree::MerkleTree;
+//! let data = vec!["same"; 64];
+//! let mut store = NodeStore::new();
+//! let shared = store.build(data.clone());
+//!
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(data);
+//! assert_eq!(shared.root_hash(), Some(tree.root_node().unwrap().hash()));
+//! // 127 logical nodes, but only one per level is distinct.
+//! assert_eq!(shared.count_nodes(), 127);
+//! assert_eq!(store.len(), 7);
+//! ```
+
+use crate::proof::{leaf_hash, node_hash};
+use crate::{Hash, Node};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Interning key: the node hash and the addresses of its interned children, which are unique while the node is stored.
+type NodeKey = (Hash, Option<usize>, Option<usize>);
+
+/// [SharedNode](struct.SharedNode.html) is an immutable node that may be referenced from many parents and trees.
+#[derive(Debug, PartialEq)]
+pub struct SharedNode {
+    hash: Hash,
+    left: Option<Arc<SharedNode>>,
+    right: Option<Arc<SharedNode>>,
+}
+
+/// [SharedTree](struct.SharedTree.html) is a tree built in a [NodeStore](struct.NodeStore.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedTree {
+    root: Option<Arc<SharedNode>>,
+}
+
+/// [NodeStore](struct.NodeStore.html) interns nodes so identical subtrees are stored once.
+#[derive(Debug, Clone, Default)]
+pub struct NodeStore {
+    nodes: HashMap<NodeKey, Arc<SharedNode>>,
+}
+
+impl SharedNode {
+    /// Returns the hash of the node, in the [MerkleTree](../struct.MerkleTree.html) format.
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    /// Returns the left child, if any.
+    pub fn left(&self) -> Option<&SharedNode> {
+        self.left.as_deref()
+    }
+
+    /// Returns the right child, if any.
+    pub fn right(&self) -> Option<&SharedNode> {
+        self.right.as_deref()
+    }
+
+    /// Converts the subtree into an owned [Node](../struct.Node.html), duplicating shared parts.
+    pub fn to_node(&self) -> Node {
+        Node::new(
+            self.hash,
+            self.left.as_ref().map(|node| Box::new(node.to_node())),
+            self.right.as_ref().map(|node| Box::new(node.to_node())),
+        )
+    }
+
+    fn count(&self, leaves_only: bool) -> usize {
+        let children = self.left.iter().chain(self.right.iter());
+        let below: usize = children.map(|child| child.count(leaves_only)).sum();
+        match (&self.left, &self.right) {
+            (None, None) => 1,
+            _ if leaves_only => below,
+            _ => below + 1,
+        }
+    }
+}
+
+impl SharedTree {
+    /// Returns the root node, or `None` for a tree built from no data.
+    pub fn root(&self) -> Option<&SharedNode> {
+        self.root.as_deref()
+    }
+
+    /// Returns the Root Hash, or `None` for a tree built from no data.
+    pub fn root_hash(&self) -> Option<Hash> {
+        self.root.as_ref().map(|node| node.hash)
+    }
+
+    /// Returns the number of leaves the tree represents.
+    pub fn count_leaves(&self) -> usize {
+        self.root.as_ref().map_or(0, |node| node.count(true))
+    }
+
+    /// Returns the number of nodes the tree represents, counting shared nodes at every position.
+    pub fn count_nodes(&self) -> usize {
+        self.root.as_ref().map_or(0, |node| node.count(false))
+    }
+}
+
+impl NodeStore {
+    /// Function to create an empty [NodeStore](struct.NodeStore.html)
+    pub fn new() -> NodeStore {
+        NodeStore::default()
+    }
+
+    /// Returns the number of distinct nodes held.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true if the store holds no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Builds the tree over `data` exactly as [MerkleTree::build_tree](../struct.MerkleTree.html#method.build_tree) does, reusing every subtree already in the store.
+    pub fn build(&mut self, data: Vec<&str>) -> SharedTree {
+        let leaves: Vec<Arc<SharedNode>> = data
+            .iter()
+            .map(|item| self.intern(leaf_hash(item.as_bytes()), None, None))
+            .collect();
+        if leaves.is_empty() {
+            return SharedTree { root: None };
+        }
+        let mut layer = self.upper_layer(leaves);
+        while layer.len() > 1 {
+            layer = self.upper_layer(layer);
+        }
+        SharedTree { root: layer.pop() }
+    }
+
+    /// Interns an existing [Node](../struct.Node.html) and its subtree.
+    pub fn insert(&mut self, node: &Node) -> SharedTree {
+        SharedTree {
+            root: Some(self.insert_node(node)),
+        }
+    }
+
+    /// Drops nodes no longer referenced by any [SharedTree](struct.SharedTree.html) outside the store, returning how many were removed.
+    pub fn collect_garbage(&mut self) -> usize {
+        let before = self.nodes.len();
+        // Parents hold references to their children, so sweep until nothing changes.
+        loop {
+            let count = self.nodes.len();
+            self.nodes.retain(|_, node| Arc::strong_count(node) > 1);
+            if self.nodes.len() == count {
+                return before - count;
+            }
+        }
+    }
+
+    fn insert_node(&mut self, node: &Node) -> Arc<SharedNode> {
+        let left = node.left_node.as_ref().map(|child| self.insert_node(child));
+        let right = node
+            .right_node
+            .as_ref()
+            .map(|child| self.insert_node(child));
+        self.intern(node.hash, left, right)
+    }
+
+    fn upper_layer(&mut self, layer: Vec<Arc<SharedNode>>) -> Vec<Arc<SharedNode>> {
+        layer
+            .chunks(2)
+            .map(|pair| {
+                let right = pair.get(1).cloned();
+                let right_hash = right.as_ref().unwrap_or(&pair[0]).hash;
+                let hash = node_hash(&pair[0].hash, &right_hash);
+                self.intern(hash, Some(pair[0].clone()), right)
+            })
+            .collect()
+    }
+
+    fn intern(
+        &mut self,
+        hash: Hash,
+        left: Option<Arc<SharedNode>>,
+        right: Option<Arc<SharedNode>>,
+    ) -> Arc<SharedNode> {
+        let address =
+            |child: &Option<Arc<SharedNode>>| child.as_ref().map(|node| Arc::as_ptr(node) as usize);
+        let key = (hash, address(&left), address(&right));
+        self.nodes
+            .entry(key)
+            .or_insert_with(|| Arc::new(SharedNode { hash, left, right }))
+            .clone()
+    }
+}
@@ -0,0 +1,200 @@
+//! Verified streaming.
+//!
+//! [encode](fn.encode.html) interleaves file contents with the tree nodes needed to check them, in the style of [bao](https://github.com/oconnor663/bao): an 8 byte little-endian length header, followed by the tree in pre-order where every inner node is written as its two child hashes and every leaf as the raw chunk. A [Decoder](struct.Decoder.html) that knows only the Root Hash reads such a stream and yields each chunk as soon as it has been verified, so a receiver never acts on data that does not belong to the root and stops at the first corrupted chunk.
+//!
+//! Roots are the same as those of [FileTree](../file/struct.FileTree.html) for the same chunk size.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::stream::{encode, Decoder};
+//! use std::io::Read;
+//! let contents = b"hello streaming world".to_vec();
+//! let (root, encoded) = encode(&contents, 4);
+//!
+//! let mut decoded = Vec::new();
+//! Decoder::new(&encoded[..], &root, 4).read_to_end(&mut decoded).unwrap();
+//! assert_eq!(decoded, contents);
+//!
+//! let mut corrupted = encoded.clone();
+//! *corrupted.last_mut().unwrap() ^= 1;
+//! assert!(Decoder::new(&corrupted[..], &root, 4).read_to_end(&mut Vec::new()).is_err());
+//! ```
+
+use crate::hashing::sha256;
+use crate::history::{leaf_hash, node_hash, split_point};
+use std::io::{self, ErrorKind, Read};
+
+/// Encodes `data` in chunks of `chunk_size` bytes, returning the Root Hash and the combined stream.
+///
+/// Panics if `chunk_size` is 0.
+pub fn encode(data: &[u8], chunk_size: usize) -> (Vec<u8>, Vec<u8>) {
+    assert!(chunk_size > 0, "chunk size must be positive");
+    let mut encoded = (data.len() as u64).to_le_bytes().to_vec();
+    if data.is_empty() {
+        return (sha256(b""), encoded);
+    }
+    let chunks: Vec<&[u8]> = data.chunks(chunk_size).collect();
+    let (root, body) = encode_subtree(&chunks);
+    encoded.extend(body);
+    (root, encoded)
+}
+
+/// Returns the hash and pre-order encoding of the subtree over `chunks`.
+fn encode_subtree(chunks: &[&[u8]]) -> (Vec<u8>, Vec<u8>) {
+    if chunks.len() == 1 {
+        return (leaf_hash(chunks[0]), chunks[0].to_vec());
+    }
+    let k = split_point(chunks.len() as u64) as usize;
+    let (left, left_body) = encode_subtree(&chunks[..k]);
+    let (right, right_body) = encode_subtree(&chunks[k..]);
+    let mut body = Vec::with_capacity(64 + left_body.len() + right_body.len());
+    body.extend_from_slice(&left);
+    body.extend_from_slice(&right);
+    body.extend(left_body);
+    body.extend(right_body);
+    (node_hash(&left, &right), body)
+}
+
+/// A subtree still to be read: its expected hash, first chunk and chunk count.
+#[derive(Debug)]
+struct Pending {
+    hash: Vec<u8>,
+    start: u64,
+    count: u64,
+}
+
+/// [Decoder](struct.Decoder.html) reads an encoded stream and yields only verified content.
+///
+/// Reading fails with [ErrorKind::InvalidData](https://doc.rust-lang.org/std/io/enum.ErrorKind.html) as soon as a node or chunk does not match the root; everything returned before that point is authentic.
+#[derive(Debug)]
+pub struct Decoder<R: Read> {
+    reader: R,
+    root: Vec<u8>,
+    chunk_size: usize,
+    len: Option<u64>,
+    stack: Vec<Pending>,
+    chunk: Vec<u8>,
+    position: usize,
+    failed: bool,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Function to create a [Decoder](struct.Decoder.html) checking the stream from `reader` against `root`.
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn new(reader: R, root: &[u8], chunk_size: usize) -> Decoder<R> {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        Decoder {
+            reader,
+            root: root.to_vec(),
+            chunk_size,
+            len: None,
+            stack: Vec::new(),
+            chunk: Vec::new(),
+            position: 0,
+            failed: false,
+        }
+    }
+
+    /// Returns the content length from the header, once it has been read.
+    pub fn len(&self) -> Option<u64> {
+        self.len
+    }
+
+    /// Returns true if the header has been read and announces no content.
+    pub fn is_empty(&self) -> bool {
+        self.len == Some(0)
+    }
+
+    /// Reads and verifies the next chunk, returning false at the end of the content.
+    fn next_chunk(&mut self) -> io::Result<bool> {
+        let len = match self.len {
+            Some(len) => len,
+            None => self.read_header()?,
+        };
+        let Some(mut pending) = self.stack.pop() else {
+            return Ok(false);
+        };
+        while pending.count > 1 {
+            let mut pair = [0u8; 64];
+            self.reader.read_exact(&mut pair)?;
+            let (left, right) = pair.split_at(32);
+            if node_hash(left, right) != pending.hash {
+                return Err(corrupted(pending.start));
+            }
+            let k = split_point(pending.count);
+            self.stack.push(Pending {
+                hash: right.to_vec(),
+                start: pending.start + k,
+                count: pending.count - k,
+            });
+            pending = Pending {
+                hash: left.to_vec(),
+                start: pending.start,
+                count: k,
+            };
+        }
+        let offset = pending.start * self.chunk_size as u64;
+        let size = (len - offset).min(self.chunk_size as u64) as usize;
+        self.chunk.resize(size, 0);
+        self.reader.read_exact(&mut self.chunk)?;
+        if leaf_hash(&self.chunk) != pending.hash {
+            return Err(corrupted(pending.start));
+        }
+        self.position = 0;
+        Ok(true)
+    }
+
+    /// Reads the length header and seeds the stack with the whole tree.
+    fn read_header(&mut self) -> io::Result<u64> {
+        let mut header = [0u8; 8];
+        self.reader.read_exact(&mut header)?;
+        let len = u64::from_le_bytes(header);
+        let count = len.div_ceil(self.chunk_size as u64);
+        if count == 0 {
+            if self.root != sha256(b"") {
+                return Err(corrupted(0));
+            }
+        } else {
+            self.stack.push(Pending {
+                hash: self.root.clone(),
+                start: 0,
+                count,
+            });
+        }
+        self.len = Some(len);
+        Ok(len)
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.failed {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "stream already failed verification",
+            ));
+        }
+        if self.position == self.chunk.len() {
+            match self.next_chunk() {
+                Ok(true) => {}
+                Ok(false) => return Ok(0),
+                Err(error) => {
+                    self.failed = true;
+                    return Err(error);
+                }
+            }
+        }
+        let n = buf.len().min(self.chunk.len() - self.position);
+        buf[..n].copy_from_slice(&self.chunk[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+fn corrupted(chunk: u64) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("chunk {} does not match the root", chunk),
+    )
+}
@@ -0,0 +1,185 @@
+//! Merkle sum tree.
+//!
+//! In a [SumTree](struct.SumTree.html) every node carries, besides its hash, the sum of a numeric value over the leaves below it, and that sum is hashed into the node. The root therefore commits to the total, and a [SumProof](struct.SumProof.html) shows that a leaf's value is counted in it: since the sums along the path are checked to add up, no value can be left out of or subtracted from the total. This is the basis of proof-of-reserves and proof-of-liabilities attestations.
+//!
+//! Leaves are `SHA256(0x00 || value || data)` and inner nodes `SHA256(0x01 || left hash || left sum || right hash || right sum)`, with values and sums as 8 byte big-endian integers. A node without a sibling is carried up a level unchanged rather than paired with itself, which would count it twice.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::sum::SumTree;
+//! let accounts = vec![(b"alice".to_vec(), 100), (b"bob".to_vec(), 250), (b"carol".to_vec(), 75)];
+//! let tree = SumTree::new(accounts).unwrap();
+//! let root = tree.root().unwrap();
+//! assert_eq!(root.sum, 425);
+//!
+//! let proof = tree.proof(1).unwrap();
+//! assert!(proof.verify(root, b"bob", 250));
+//! assert!(!proof.verify(root, b"bob", 25));
+//! ```
+
+use crate::hashing::sha256_concat;
+use crate::proof::{ct_eq, Position};
+
+const LEAF_PREFIX: &[u8] = &[0x00];
+const NODE_PREFIX: &[u8] = &[0x01];
+
+/// [SumNode](struct.SumNode.html) is a node of a [SumTree](struct.SumTree.html).
+///
+/// * `hash`: Hash of the node, committing to `sum`.
+///
+/// * `sum`: Total value of the leaves below the node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumNode {
+    pub hash: Vec<u8>,
+    pub sum: u64,
+}
+
+impl SumNode {
+    /// Function to create the leaf for `data` with `value`.
+    pub fn leaf(data: &[u8], value: u64) -> SumNode {
+        SumNode {
+            hash: sha256_concat(&[LEAF_PREFIX, &value.to_be_bytes(), data]),
+            sum: value,
+        }
+    }
+
+    /// Function to create the parent of `left` and `right`, or `None` if their sums overflow.
+    pub fn parent(left: &SumNode, right: &SumNode) -> Option<SumNode> {
+        let sum = left.sum.checked_add(right.sum)?;
+        let hash = sha256_concat(&[
+            NODE_PREFIX,
+            &left.hash,
+            &left.sum.to_be_bytes(),
+            &right.hash,
+            &right.sum.to_be_bytes(),
+        ]);
+        Some(SumNode { hash, sum })
+    }
+}
+
+/// [SumStep](struct.SumStep.html) is one level of a [SumProof](struct.SumProof.html).
+///
+/// * `sibling`: The sibling on the path to the root.
+///
+/// * `position`: Side of the sibling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumStep {
+    pub sibling: SumNode,
+    pub position: Position,
+}
+
+/// [SumProof](struct.SumProof.html) proves that a leaf and its value are counted in the root of a [SumTree](struct.SumTree.html).
+///
+/// * `steps`: Siblings from the leaf up; levels where the node had no sibling are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumProof {
+    pub steps: Vec<SumStep>,
+}
+
+impl SumProof {
+    /// Checks that `data` with `value` is a leaf of the tree with root `root`, whose hash and sum must both match.
+    pub fn verify(&self, root: &SumNode, data: &[u8], value: u64) -> bool {
+        self.verify_node(root, SumNode::leaf(data, value))
+    }
+
+    /// Same as [verify](struct.SumProof.html#method.verify), starting from an already built leaf.
+    pub fn verify_node(&self, root: &SumNode, leaf: SumNode) -> bool {
+        let mut node = leaf;
+        for step in &self.steps {
+            let parent = match step.position {
+                Position::Left => SumNode::parent(&step.sibling, &node),
+                Position::Right => SumNode::parent(&node, &step.sibling),
+            };
+            match parent {
+                Some(parent) => node = parent,
+                None => return false,
+            }
+        }
+        node.sum == root.sum && ct_eq(&node.hash, &root.hash)
+    }
+}
+
+/// [SumTree](struct.SumTree.html) keeps every level of the tree, leaves first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumTree {
+    layers: Vec<Vec<SumNode>>,
+}
+
+impl SumTree {
+    /// Function to create a [SumTree](struct.SumTree.html) over `(data, value)` leaves.
+    ///
+    /// Returns `None` if the total does not fit in a `u64`.
+    pub fn new(leaves: Vec<(Vec<u8>, u64)>) -> Option<SumTree> {
+        let leaves = leaves
+            .iter()
+            .map(|(data, value)| SumNode::leaf(data, *value))
+            .collect();
+        SumTree::from_nodes(leaves)
+    }
+
+    /// Function to create a [SumTree](struct.SumTree.html) over already built leaves.
+    ///
+    /// Returns `None` if the total does not fit in a `u64`.
+    pub fn from_nodes(leaves: Vec<SumNode>) -> Option<SumTree> {
+        let mut layers = vec![leaves];
+        while layers.last().is_some_and(|layer| layer.len() > 1) {
+            let layer = layers.last()?;
+            let mut parents = Vec::with_capacity(layer.len().div_ceil(2));
+            for pair in layer.chunks(2) {
+                parents.push(match pair {
+                    [left, right] => SumNode::parent(left, right)?,
+                    [lone] => lone.clone(),
+                    _ => unreachable!("chunks of two"),
+                });
+            }
+            layers.push(parents);
+        }
+        Some(SumTree { layers })
+    }
+
+    /// Returns the root, or `None` if the tree has no leaves.
+    pub fn root(&self) -> Option<&SumNode> {
+        self.layers.last()?.first()
+    }
+
+    /// Returns the sum of every value.
+    pub fn total(&self) -> u64 {
+        self.root().map_or(0, |root| root.sum)
+    }
+
+    /// Returns the number of leaves.
+    pub fn len(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    /// Returns `true` if the tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the leaf at `index`.
+    pub fn leaf(&self, index: usize) -> Option<&SumNode> {
+        self.layers[0].get(index)
+    }
+
+    /// Generates a proof for the leaf at `index`.
+    pub fn proof(&self, index: usize) -> Option<SumProof> {
+        self.leaf(index)?;
+        let mut steps = Vec::new();
+        let mut index = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                steps.push(SumStep {
+                    sibling: sibling.clone(),
+                    position: if index & 1 == 0 {
+                        Position::Right
+                    } else {
+                        Position::Left
+                    },
+                });
+            }
+            index /= 2;
+        }
+        Some(SumProof { steps })
+    }
+}
@@ -0,0 +1,564 @@
+//! Anti-entropy synchronization.
+//!
+//! Two peers holding versions of the same dataset converge by exchanging hashes top-down and fetching only the leaves under subtrees whose hashes differ. The local peer drives a [SyncSession](struct.SyncSession.html): it produces a [SyncRequest](enum.SyncRequest.html), the remote peer answers with [respond](fn.respond.html), and the session consumes the [SyncResponse](enum.SyncResponse.html) and produces the next request, until it has the remote leaves and has checked them against the remote root. Messages encode to bytes with `to_bytes` and `from_bytes`, so any transport will do.
+//!
+//! The session plans its queries with a [ReconciliationPlanner](struct.ReconciliationPlanner.html), which can also be driven directly, for example by replica repair jobs with their own transport.
+//!
+//! Nodes are addressed by `(height, index)`: `height` levels above the leaves and `index` from the left, so the same address covers the same leaves in trees of different sizes.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::sync::{respond, SyncSession};
+//! use rs_merkletree::MerkleTree;
+//! let remote_data = vec!["a", "b", "x", "d", "e"];
+//! let mut remote = MerkleTree::new(None);
+//! remote.build_tree(remote_data.clone());
+//!
+//! let mut session = SyncSession::new(vec!["a".into(), "b".into(), "c".into(), "d".into()]);
+//! let mut request = Some(session.start());
+//! while let Some(next) = request {
+//!     let response = respond(&remote, &remote_data, &next);
+//!     request = session.handle(&response).unwrap();
+//! }
+//! assert_eq!(session.leaves(), &["a", "b", "x", "d", "e"]);
+//! // Only the changed and the new leaf were transferred.
+//! assert_eq!(session.fetched(), 2);
+//! ```
+
+use crate::{Hash, MerkleTree};
+use std::ops::Range;
+
+/// Address of a node: `(height, index)`.
+pub type Address = (usize, usize);
+
+/// A node address with its hash, `None` if the node does not exist.
+pub type SubtreeHash = (Address, Option<Hash>);
+
+/// A message from the peer being synchronized to the peer holding the reference data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncRequest {
+    /// Asks for the root, leaf count and height.
+    Root,
+    /// Asks for the hashes of the nodes at these `(height, index)` addresses.
+    Subtrees(Vec<Address>),
+    /// Asks for the leaf data in these ranges of indices.
+    Leaves(Vec<Range<usize>>),
+}
+
+/// The answer to a [SyncRequest](enum.SyncRequest.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncResponse {
+    /// The root hash, `None` for an empty tree, with the number of leaves and the height of the root.
+    Root {
+        root: Option<Hash>,
+        leaves: usize,
+        height: usize,
+    },
+    /// The hash of each requested node, `None` if the node does not exist.
+    Subtrees(Vec<SubtreeHash>),
+    /// The requested leaves as `(index, data)`.
+    Leaves(Vec<(usize, String)>),
+}
+
+/// Errors of a [SyncSession](struct.SyncSession.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncError {
+    /// The response does not answer the last request.
+    UnexpectedResponse,
+    /// The leaves received do not rebuild the remote root.
+    RootMismatch,
+}
+
+/// Answers `request` from `tree`, built from `leaves`.
+pub fn respond(tree: &MerkleTree, leaves: &[&str], request: &SyncRequest) -> SyncResponse {
+    match request {
+        SyncRequest::Root => SyncResponse::Root {
+            root: tree.root_hash(),
+            leaves: leaves.len(),
+            height: tree.height().unwrap_or(0),
+        },
+        SyncRequest::Subtrees(addresses) => SyncResponse::Subtrees(
+            addresses
+                .iter()
+                .map(|&(height, index)| {
+                    let hash = tree.node_at(height, index).map(|node| node.hash);
+                    ((height, index), hash)
+                })
+                .collect(),
+        ),
+        SyncRequest::Leaves(ranges) => SyncResponse::Leaves(
+            ranges
+                .iter()
+                .flat_map(|range| range.start.min(leaves.len())..range.end.min(leaves.len()))
+                .map(|index| (index, leaves[index].to_string()))
+                .collect(),
+        ),
+    }
+}
+
+/// [ReconciliationPlanner](struct.ReconciliationPlanner.html) works out which leaf ranges to fetch to repair a local tree from a remote one, querying as few subtree hashes as possible.
+///
+/// Each round, [next_round](struct.ReconciliationPlanner.html#method.next_round) lists the nodes to ask the remote peer about and [record](struct.ReconciliationPlanner.html#method.record) takes the answers. A differing node is expanded to its descendants `fan_out` levels lower, so a larger fan-out trades more hashes per round for fewer rounds. Subtrees the local tree does not reach at all are fetched without querying. Once [is_complete](struct.ReconciliationPlanner.html#method.is_complete), [ranges](struct.ReconciliationPlanner.html#method.ranges) is the set of leaves to fetch.
+///
+/// * `local_size`, `remote_size`: Leaf counts of both trees.
+///
+/// * `fan_out`: Levels descended per round, at least 1.
+///
+/// * `queries`: Nodes to ask about in the next round.
+///
+/// * `fetch`: Leaf ranges known to differ.
+/// ```
+/// use rs_merkletree::sync::ReconciliationPlanner;
+/// use rs_merkletree::MerkleTree;
+/// let mut local = MerkleTree::new(None);
+/// let mut remote = MerkleTree::new(None);
+/// local.build_tree(vec!["a", "b", "c", "d", "e", "f", "g", "h"]);
+/// remote.build_tree(vec!["a", "b", "c", "X", "e", "f", "g", "h", "i"]);
+///
+/// let root = remote.root_node().unwrap().hash();
+/// let height = remote.depth().unwrap() - 1;
+/// let mut planner = ReconciliationPlanner::new(&local, Some(&root), 9, height, 2);
+/// while !planner.is_complete() {
+///     let answers = planner
+///         .next_round()
+///         .into_iter()
+///         .map(|(h, i)| ((h, i), remote.node(height - h, i).map(|node| node.hash())))
+///         .collect::<Vec<_>>();
+///     planner.record(&local, &answers);
+/// }
+/// assert_eq!(planner.ranges(), vec![3..4, 8..9]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconciliationPlanner {
+    local_size: usize,
+    remote_size: usize,
+    fan_out: usize,
+    queries: Vec<Address>,
+    fetch: Vec<Range<usize>>,
+}
+
+impl ReconciliationPlanner {
+    /// Function to create a [ReconciliationPlanner](struct.ReconciliationPlanner.html) from the remote root, leaf count and root height.
+    ///
+    /// A `fan_out` of 0 is treated as 1.
+    pub fn new(
+        local: &MerkleTree,
+        remote_root: Option<&Hash>,
+        remote_size: usize,
+        remote_height: usize,
+        fan_out: usize,
+    ) -> ReconciliationPlanner {
+        let local_size = local.size();
+        let mut planner = ReconciliationPlanner {
+            local_size,
+            remote_size,
+            fan_out: fan_out.max(1),
+            queries: Vec::new(),
+            fetch: Vec::new(),
+        };
+        // Matching roots and sizes are the common case: nothing to query or fetch.
+        if remote_root.is_some()
+            && (remote_root != local.root_hash().as_ref() || remote_size != local_size)
+        {
+            planner.expand((remote_height, 0));
+        }
+        planner
+    }
+
+    /// Returns the nodes to query in this round, as `(height, index)` addresses.
+    pub fn next_round(&mut self) -> Vec<Address> {
+        std::mem::take(&mut self.queries)
+    }
+
+    /// Takes the remote hashes of the queried nodes and plans the next round.
+    pub fn record(&mut self, local: &MerkleTree, answers: &[SubtreeHash]) {
+        for &((height, index), ref remote) in answers {
+            let start = index << height;
+            if start >= self.remote_size {
+                continue;
+            }
+            // As in MerkleTree::diff, equal hashes only prove equal leaves when they cover as many leaves.
+            let covered = |size: usize| size.min((index + 1) << height).saturating_sub(start);
+            let local_hash = local.node_at(height, index).map(|node| &node.hash);
+            if local_hash.is_some()
+                && local_hash == remote.as_ref()
+                && covered(self.local_size) == covered(self.remote_size)
+            {
+                continue;
+            }
+            if height == 0 {
+                self.fetch.push(start..start + 1);
+            } else {
+                self.expand((height, index));
+            }
+        }
+    }
+
+    /// Returns `true` once no more queries are needed.
+    pub fn is_complete(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Returns the leaf ranges to fetch, sorted and merged.
+    pub fn ranges(&self) -> Vec<Range<usize>> {
+        let mut sorted = self.fetch.clone();
+        sorted.sort_by_key(|range| range.start);
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for range in sorted {
+            match ranges.last_mut() {
+                Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+                _ => ranges.push(range),
+            }
+        }
+        ranges
+    }
+
+    /// Queues the descendants of a differing node `fan_out` levels down, fetching directly those the local tree does not reach.
+    fn expand(&mut self, (height, index): Address) {
+        let levels = self.fan_out.min(height);
+        let height = height - levels;
+        let first = index << levels;
+        for index in first..first + (1 << levels) {
+            let start = index << height;
+            if start >= self.remote_size {
+                break;
+            }
+            if start >= self.local_size {
+                let end = ((index + 1) << height).min(self.remote_size);
+                self.fetch.push(start..end);
+            } else {
+                self.queries.push((height, index));
+            }
+        }
+    }
+}
+
+/// [SyncSession](struct.SyncSession.html) brings local leaves up to date with a remote peer.
+///
+/// * `leaves`: The local data, updated in place when the remote leaves arrive.
+///
+/// * `tree`: Tree over `leaves`.
+///
+/// * `remote`: Root and leaf count of the remote tree, once known.
+///
+/// * `planner`: Plans the subtree queries once the remote root is known.
+///
+/// * `fan_out`: Levels descended per round, see [ReconciliationPlanner](struct.ReconciliationPlanner.html).
+///
+/// * `pending`: The request awaiting a response.
+///
+/// * `fetched`: Number of leaves received.
+#[derive(Debug, Clone)]
+pub struct SyncSession {
+    leaves: Vec<String>,
+    tree: MerkleTree,
+    remote: Option<(Option<Hash>, usize)>,
+    planner: Option<ReconciliationPlanner>,
+    fan_out: usize,
+    pending: Option<SyncRequest>,
+    fetched: usize,
+}
+
+impl SyncSession {
+    /// Function to create a [SyncSession](struct.SyncSession.html) over the local `leaves`, descending one level per round.
+    pub fn new(leaves: Vec<String>) -> SyncSession {
+        SyncSession::with_fan_out(leaves, 1)
+    }
+
+    /// Function to create a [SyncSession](struct.SyncSession.html) descending `fan_out` levels per round.
+    pub fn with_fan_out(leaves: Vec<String>, fan_out: usize) -> SyncSession {
+        let tree = build(&leaves);
+        SyncSession {
+            leaves,
+            tree,
+            remote: None,
+            planner: None,
+            fan_out,
+            pending: None,
+            fetched: 0,
+        }
+    }
+
+    /// Returns the first request, which asks for the remote root.
+    pub fn start(&mut self) -> SyncRequest {
+        self.pending = Some(SyncRequest::Root);
+        SyncRequest::Root
+    }
+
+    /// Consumes the response to the last request and returns the next request, or `None` once the local leaves match the remote ones.
+    pub fn handle(&mut self, response: &SyncResponse) -> Result<Option<SyncRequest>, SyncError> {
+        let next = match (self.pending.take(), response) {
+            (
+                Some(SyncRequest::Root),
+                SyncResponse::Root {
+                    root,
+                    leaves,
+                    height,
+                },
+            ) => {
+                self.remote = Some((*root, *leaves));
+                if *root == self.root() && *leaves == self.leaves.len() {
+                    None
+                } else {
+                    let planner = ReconciliationPlanner::new(
+                        &self.tree,
+                        root.as_ref(),
+                        *leaves,
+                        *height,
+                        self.fan_out,
+                    );
+                    self.planner = Some(planner);
+                    self.plan()?
+                }
+            }
+            (Some(SyncRequest::Subtrees(_)), SyncResponse::Subtrees(hashes)) => {
+                let planner = self.planner.as_mut().ok_or(SyncError::UnexpectedResponse)?;
+                planner.record(&self.tree, hashes);
+                self.plan()?
+            }
+            (Some(SyncRequest::Leaves(_)), SyncResponse::Leaves(leaves)) => {
+                self.apply(leaves.clone())?
+            }
+            _ => return Err(SyncError::UnexpectedResponse),
+        };
+        self.pending = next.clone();
+        Ok(next)
+    }
+
+    /// Returns the local leaves.
+    pub fn leaves(&self) -> &[String] {
+        &self.leaves
+    }
+
+    /// Returns the tree over the local leaves.
+    pub fn tree(&self) -> &MerkleTree {
+        &self.tree
+    }
+
+    /// Returns the number of leaves received so far.
+    pub fn fetched(&self) -> usize {
+        self.fetched
+    }
+
+    fn root(&self) -> Option<Hash> {
+        self.tree.root_hash()
+    }
+
+    /// Turns the planner's state into the next request: more queries, the leaves to fetch, or nothing.
+    fn plan(&mut self) -> Result<Option<SyncRequest>, SyncError> {
+        let planner = self.planner.as_mut().ok_or(SyncError::UnexpectedResponse)?;
+        if !planner.is_complete() {
+            return Ok(Some(SyncRequest::Subtrees(planner.next_round())));
+        }
+        let ranges = planner.ranges();
+        if ranges.is_empty() {
+            // Every leaf matched, so only the leaf count differed.
+            return self.apply(Vec::new());
+        }
+        Ok(Some(SyncRequest::Leaves(ranges)))
+    }
+
+    /// Writes the received leaves, truncates to the remote size and checks the rebuilt root.
+    fn apply(&mut self, leaves: Vec<(usize, String)>) -> Result<Option<SyncRequest>, SyncError> {
+        let (remote_root, remote_size) = self.remote.ok_or(SyncError::UnexpectedResponse)?;
+        self.leaves.resize(remote_size, String::new());
+        self.fetched += leaves.len();
+        for (index, data) in leaves {
+            if index >= remote_size {
+                return Err(SyncError::UnexpectedResponse);
+            }
+            self.leaves[index] = data;
+        }
+        self.tree = build(&self.leaves);
+        if self.root() != remote_root {
+            return Err(SyncError::RootMismatch);
+        }
+        Ok(None)
+    }
+}
+
+fn build(leaves: &[String]) -> MerkleTree {
+    let mut tree = MerkleTree::new(None);
+    tree.build_tree(leaves.iter().map(String::as_str).collect());
+    tree
+}
+
+impl SyncRequest {
+    /// Serializes the request: a tag byte (`0` root, `1` subtrees, `2` leaves), then for subtrees a 4 byte count and a height and index per node, for leaves a 4 byte count and a start and end per range, all as 8 byte big-endian integers.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            SyncRequest::Root => out.push(0),
+            SyncRequest::Subtrees(addresses) => {
+                out.push(1);
+                out.extend_from_slice(&(addresses.len() as u32).to_be_bytes());
+                for &(height, index) in addresses {
+                    put_u64(&mut out, height);
+                    put_u64(&mut out, index);
+                }
+            }
+            SyncRequest::Leaves(ranges) => {
+                out.push(2);
+                out.extend_from_slice(&(ranges.len() as u32).to_be_bytes());
+                for range in ranges {
+                    put_u64(&mut out, range.start);
+                    put_u64(&mut out, range.end);
+                }
+            }
+        }
+        out
+    }
+
+    /// Parses the output of [to_bytes](enum.SyncRequest.html#method.to_bytes), returning `None` if it is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<SyncRequest> {
+        let mut reader = Reader(bytes);
+        let request = match reader.u8()? {
+            0 => SyncRequest::Root,
+            1 => {
+                let count = reader.u32()?;
+                let mut addresses = Vec::new();
+                for _ in 0..count {
+                    addresses.push((reader.u64()?, reader.u64()?));
+                }
+                SyncRequest::Subtrees(addresses)
+            }
+            2 => {
+                let count = reader.u32()?;
+                let mut ranges = Vec::new();
+                for _ in 0..count {
+                    ranges.push(reader.u64()?..reader.u64()?);
+                }
+                SyncRequest::Leaves(ranges)
+            }
+            _ => return None,
+        };
+        reader.0.is_empty().then_some(request)
+    }
+}
+
+impl SyncResponse {
+    /// Serializes the response with the same tags as [SyncRequest::to_bytes](enum.SyncRequest.html#method.to_bytes). Optional hashes are a presence byte followed by a 4 byte length and the hash in hex; leaf data is a 4 byte length and UTF-8 bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            SyncResponse::Root {
+                root,
+                leaves,
+                height,
+            } => {
+                out.push(0);
+                put_u64(&mut out, *leaves);
+                put_u64(&mut out, *height);
+                put_optional(&mut out, root.as_ref());
+            }
+            SyncResponse::Subtrees(hashes) => {
+                out.push(1);
+                out.extend_from_slice(&(hashes.len() as u32).to_be_bytes());
+                for ((height, index), hash) in hashes {
+                    put_u64(&mut out, *height);
+                    put_u64(&mut out, *index);
+                    put_optional(&mut out, hash.as_ref());
+                }
+            }
+            SyncResponse::Leaves(leaves) => {
+                out.push(2);
+                out.extend_from_slice(&(leaves.len() as u32).to_be_bytes());
+                for (index, data) in leaves {
+                    put_u64(&mut out, *index);
+                    put_bytes(&mut out, data.as_bytes());
+                }
+            }
+        }
+        out
+    }
+
+    /// Parses the output of [to_bytes](enum.SyncResponse.html#method.to_bytes), returning `None` if it is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<SyncResponse> {
+        let mut reader = Reader(bytes);
+        let response = match reader.u8()? {
+            0 => SyncResponse::Root {
+                leaves: reader.u64()?,
+                height: reader.u64()?,
+                root: reader.optional()?,
+            },
+            1 => {
+                let count = reader.u32()?;
+                let mut hashes = Vec::new();
+                for _ in 0..count {
+                    let address = (reader.u64()?, reader.u64()?);
+                    hashes.push((address, reader.optional()?));
+                }
+                SyncResponse::Subtrees(hashes)
+            }
+            2 => {
+                let count = reader.u32()?;
+                let mut leaves = Vec::new();
+                for _ in 0..count {
+                    let index = reader.u64()?;
+                    leaves.push((index, String::from_utf8(reader.bytes()?.to_vec()).ok()?));
+                }
+                SyncResponse::Leaves(leaves)
+            }
+            _ => return None,
+        };
+        reader.0.is_empty().then_some(response)
+    }
+}
+
+fn put_u64(out: &mut Vec<u8>, value: usize) {
+    out.extend_from_slice(&(value as u64).to_be_bytes());
+}
+
+fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn put_optional(out: &mut Vec<u8>, hash: Option<&Hash>) {
+    match hash {
+        Some(hash) => {
+            out.push(1);
+            put_bytes(out, &hash.to_hex_bytes());
+        }
+        None => out.push(0),
+    }
+}
+
+/// Cursor over an encoded message.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<usize> {
+        usize::try_from(u64::from_be_bytes(self.take(8)?.try_into().ok()?)).ok()
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    fn optional(&mut self) -> Option<Option<Hash>> {
+        match self.u8()? {
+            0 => Some(None),
+            1 => Some(Some(Hash::from_hex_bytes(self.bytes()?).ok()?)),
+            _ => None,
+        }
+    }
+}
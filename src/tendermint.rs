@@ -0,0 +1,70 @@
+//! Tendermint / CometBFT simple Merkle trees.
+//!
+//! Tendermint block headers commit to transactions, validator sets, evidence and the header fields themselves with a "simple" Merkle tree derived from RFC 6962: leaves are `SHA256(0x00 || item)`, inner nodes `SHA256(0x01 || left || right)`, the tree is split at the largest power of two below its size, and the empty tree hashes to `SHA256("")`. [Proof](struct.Proof.html) mirrors Tendermint's `merkle.Proof`, whose `aunts` are ordered from the leaf up.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::tendermint::{hash_from_byte_slices, prove};
+//! let items: Vec<&[u8]> = vec![b"tx1", b"tx2", b"tx3"];
+//! let root = hash_from_byte_slices(&items);
+//! let proof = prove(&items, 2).unwrap();
+//! assert!(proof.verify(&root, b"tx3"));
+//! ```
+
+use crate::config::TreeConfig;
+use crate::hashing::sha256;
+use crate::history::verify_inclusion;
+
+/// [Proof](struct.Proof.html) is Tendermint's inclusion proof.
+///
+/// * `total`: Number of items in the tree.
+///
+/// * `index`: Index of the proven item.
+///
+/// * `leaf_hash`: Leaf hash of the proven item.
+///
+/// * `aunts`: Sibling hashes from the leaf up to the root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proof {
+    pub total: u64,
+    pub index: u64,
+    pub leaf_hash: Vec<u8>,
+    pub aunts: Vec<Vec<u8>>,
+}
+
+/// Returns the root over `items`, as Tendermint's `merkle.HashFromByteSlices`.
+pub fn hash_from_byte_slices<T: AsRef<[u8]>>(items: &[T]) -> Vec<u8> {
+    TreeConfig::tendermint()
+        .root(items)
+        .unwrap_or_else(|| sha256(b""))
+}
+
+/// Returns the proof for item `index`, as Tendermint's `merkle.ProofsFromByteSlices`, or `None` if out of range.
+pub fn prove<T: AsRef<[u8]>>(items: &[T], index: usize) -> Option<Proof> {
+    let layers = TreeConfig::tendermint().build_layers(items);
+    let leaf_hash = layers.first()?.get(index)?.clone();
+    let mut aunts = Vec::new();
+    let mut position = index;
+    for layer in &layers[..layers.len() - 1] {
+        // A promoted node has no sibling at this level.
+        if let Some(sibling) = layer.get(position ^ 1) {
+            aunts.push(sibling.clone());
+        }
+        position /= 2;
+    }
+    Some(Proof {
+        total: items.len() as u64,
+        index: index as u64,
+        leaf_hash,
+        aunts,
+    })
+}
+
+impl Proof {
+    /// Checks that `item` is item `index` of the tree with root `root`.
+    pub fn verify(&self, root: &[u8], item: &[u8]) -> bool {
+        let leaf_hash = TreeConfig::tendermint().hash_leaf(item);
+        leaf_hash == self.leaf_hash
+            && verify_inclusion(&leaf_hash, self.index, self.total, &self.aunts, root)
+    }
+}
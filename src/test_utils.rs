@@ -0,0 +1,158 @@
+//! Property-testing strategies and known-answer test vectors.
+//!
+//! Requires the `test-utils` feature, which pulls in [proptest](https://docs.rs/proptest). The strategies generate leaf lists, trees built from them and valid or random proofs, for property tests of code built on this crate. [test_vectors](fn.test_vectors.html) computes the root of a dataset under every construction the crate offers, and [vectors_json](fn.vectors_json.html) writes them out so implementations in other languages can check themselves against this one.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::test_utils::{test_vectors, vectors_json};
+//! let vectors = test_vectors(&["Hello", "World", "From", "Rust"]);
+//! let legacy = vectors.iter().find(|vector| vector.preset == "merkletree-legacy").unwrap();
+//! assert_eq!(legacy.root, "725367a8cee028cf3360c19d20c175733191562b01e60d093e81d8570e865f81");
+//! assert!(vectors_json(&vectors).starts_with("[{\"preset\":\"merkletree\""));
+//! ```
+
+use crate::config::{HashFunction, TreeConfig};
+use crate::hashing::to_hex;
+use crate::proof::{MerkleProof, Position, ProofStep};
+use crate::MerkleTree;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Most leaves generated by [leaves](fn.leaves.html).
+pub const MAX_LEAVES: usize = 64;
+
+/// [TestVector](struct.TestVector.html) is the expected root of a dataset under one construction.
+///
+/// * `preset`: Name of the construction, such as `merkletree` or `rfc6962`.
+///
+/// * `leaves`: The dataset.
+///
+/// * `root`: Expected root as lowercase hex. For [MerkleTree](../struct.MerkleTree.html) presets this is the stored hash, which is already hex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    pub preset: &'static str,
+    pub leaves: Vec<String>,
+    pub root: String,
+}
+
+/// Returns a strategy for 1 to [MAX_LEAVES](constant.MAX_LEAVES.html) short strings.
+pub fn leaves() -> impl Strategy<Value = Vec<String>> {
+    vec("[a-z0-9]{0,8}", 1..=MAX_LEAVES)
+}
+
+/// Returns a strategy for leaves together with the tree built from them.
+pub fn tree() -> impl Strategy<Value = (Vec<String>, MerkleTree)> {
+    leaves().prop_map(|leaves| {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(leaves.iter().map(String::as_str).collect());
+        (leaves, tree)
+    })
+}
+
+/// Returns a strategy for a tree, its leaves, a leaf index and the valid proof for that leaf.
+pub fn tree_with_proof() -> impl Strategy<Value = (Vec<String>, MerkleTree, usize, MerkleProof)> {
+    tree().prop_flat_map(|(leaves, tree)| {
+        let count = leaves.len();
+        (Just(leaves), Just(tree), 0..count).prop_map(|(leaves, tree, index)| {
+            let proof = tree.proof(index).expect("index is in range");
+            (leaves, tree, index, proof)
+        })
+    })
+}
+
+/// Returns a strategy for well-formed but random proofs: up to 32 steps of 64 digit lowercase hex hashes.
+pub fn proof() -> impl Strategy<Value = MerkleProof> {
+    let step = ("[0-9a-f]{64}", any::<bool>()).prop_map(|(hash, left)| ProofStep {
+        hash: hash.parse().expect("the pattern matches hex hashes"),
+        position: if left {
+            Position::Left
+        } else {
+            Position::Right
+        },
+    });
+    vec(step, 0..=32).prop_map(|steps| MerkleProof { steps })
+}
+
+/// Returns the root of `dataset` under every preset: `merkletree`, `merkletree-legacy` and `merkletree-sorted` ([MerkleTree](../struct.MerkleTree.html)), and `rfc6962`, `bitcoin`, `tendermint`, `merkletreejs-sha256` and `merkletreejs-keccak256` ([TreeConfig](../config/struct.TreeConfig.html), with leaves used as given where the preset does not hash them).
+///
+/// Returns no vectors for an empty dataset.
+pub fn test_vectors(dataset: &[&str]) -> Vec<TestVector> {
+    if dataset.is_empty() {
+        return Vec::new();
+    }
+    let leaves: Vec<String> = dataset.iter().map(|leaf| leaf.to_string()).collect();
+    let bytes: Vec<&[u8]> = dataset.iter().map(|leaf| leaf.as_bytes()).collect();
+    let mut vectors = Vec::new();
+    let mut push = |preset, root: String| {
+        vectors.push(TestVector {
+            preset,
+            leaves: leaves.clone(),
+            root,
+        })
+    };
+
+    let trees = [
+        ("merkletree", MerkleTree::new(None)),
+        ("merkletree-legacy", MerkleTree::new_legacy(None)),
+        (
+            "merkletree-sorted",
+            MerkleTree::new(None).with_sorted_leaves(),
+        ),
+    ];
+    for (preset, mut tree) in trees {
+        tree.build_tree(dataset.to_vec());
+        let root = tree.root_node().expect("dataset is not empty").hash();
+        push(preset, root.to_string());
+    }
+    let configs = [
+        ("rfc6962", TreeConfig::rfc6962()),
+        ("bitcoin", TreeConfig::bitcoin()),
+        ("tendermint", TreeConfig::tendermint()),
+        (
+            "merkletreejs-sha256",
+            TreeConfig::merkletreejs(HashFunction::Sha256),
+        ),
+        (
+            "merkletreejs-keccak256",
+            TreeConfig::merkletreejs(HashFunction::Keccak256),
+        ),
+    ];
+    for (preset, config) in configs {
+        push(
+            preset,
+            to_hex(&config.root(&bytes).expect("dataset is not empty")),
+        );
+    }
+    vectors
+}
+
+/// Writes `vectors` as a JSON array of `{"preset", "leaves", "root"}` objects.
+pub fn vectors_json(vectors: &[TestVector]) -> String {
+    let objects: Vec<String> = vectors
+        .iter()
+        .map(|vector| {
+            let leaves: Vec<String> = vector.leaves.iter().map(|leaf| json_string(leaf)).collect();
+            format!(
+                "{{\"preset\":{},\"leaves\":[{}],\"root\":{}}}",
+                json_string(vector.preset),
+                leaves.join(","),
+                json_string(&vector.root)
+            )
+        })
+        .collect();
+    format!("[{}]", objects.join(","))
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
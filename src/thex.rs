@@ -0,0 +1,132 @@
+//! Tiger Tree Hash (THEX).
+//!
+//! The [THEX](https://adc.sourceforge.io/draft-jchapweske-thex-02.html) format identifies files in Direct Connect, Gnutella and other file-sharing networks by their Tiger Tree Hash (TTH). Files are split into 1 KiB segments; leaves are `Tiger(0x00 || segment)`, inner nodes `Tiger(0x01 || left || right)`, and a node without a sibling is promoted to the next level unchanged. The root is usually shown in unpadded base32, as in `urn:tree:tiger:<root>`.
+//!
+//! Requires the `thex` feature.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::thex::ThexTree;
+//! let tree = ThexTree::from_reader(&b""[..]).unwrap();
+//! assert_eq!(tree.root_base32(), "LWPNACQDBZRYXW3VHJVCJ64QBZNGHOHHHZWCLNQ");
+//! assert_eq!(tree.urn(), "urn:tree:tiger:LWPNACQDBZRYXW3VHJVCJ64QBZNGHOHHHZWCLNQ");
+//! ```
+
+use crate::hashing::to_base32;
+use std::io::{self, ErrorKind, Read};
+use tiger::{Digest, Tiger};
+
+/// Size of the segments hashed into leaves.
+pub const SEGMENT_SIZE: usize = 1024;
+
+const LEAF_PREFIX: &[u8] = &[0x00];
+const NODE_PREFIX: &[u8] = &[0x01];
+
+/// [ThexTree](struct.ThexTree.html) is the Tiger tree of one file.
+///
+/// * `levels`: Every level of the tree, leaves first; the last level holds only the root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThexTree {
+    levels: Vec<Vec<Vec<u8>>>,
+}
+
+impl ThexTree {
+    /// Function to create a [ThexTree](struct.ThexTree.html) from everything `reader` yields.
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<ThexTree> {
+        let mut leaves = Vec::new();
+        let mut buffer = [0u8; SEGMENT_SIZE];
+        loop {
+            let mut filled = 0;
+            while filled < SEGMENT_SIZE {
+                match reader.read(&mut buffer[filled..]) {
+                    Ok(0) => break,
+                    Ok(n) => filled += n,
+                    Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                    Err(error) => return Err(error),
+                }
+            }
+            // An empty file still has one (empty) segment.
+            if filled > 0 || leaves.is_empty() {
+                leaves.push(leaf_hash(&buffer[..filled]));
+            }
+            if filled < SEGMENT_SIZE {
+                break;
+            }
+        }
+        Ok(ThexTree::from_leaf_hashes(leaves))
+    }
+
+    /// Function to create a [ThexTree](struct.ThexTree.html) from precomputed leaf hashes.
+    ///
+    /// Panics if `leaves` is empty.
+    pub fn from_leaf_hashes(leaves: Vec<Vec<u8>>) -> ThexTree {
+        assert!(!leaves.is_empty(), "a tree needs at least one leaf");
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [single] => single.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        ThexTree { levels }
+    }
+
+    /// Returns the 24 byte root.
+    pub fn root(&self) -> Vec<u8> {
+        self.levels[self.levels.len() - 1][0].clone()
+    }
+
+    /// Returns the root in unpadded base32, the usual TTH notation.
+    pub fn root_base32(&self) -> String {
+        to_base32(&self.root())
+    }
+
+    /// Returns the `urn:tree:tiger:` URN of the file.
+    pub fn urn(&self) -> String {
+        format!("urn:tree:tiger:{}", self.root_base32())
+    }
+
+    /// Returns the number of leaves.
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Returns the levels of the tree, leaves first.
+    pub fn levels(&self) -> &[Vec<Vec<u8>>] {
+        &self.levels
+    }
+
+    /// Returns the THEX breadth-first serialization: the root, then every lower level left to right, down to the leaves.
+    pub fn breadth_first(&self) -> Vec<u8> {
+        self.levels
+            .iter()
+            .rev()
+            .flatten()
+            .flatten()
+            .copied()
+            .collect()
+    }
+}
+
+/// Returns the THEX leaf hash `Tiger(0x00 || segment)`.
+pub fn leaf_hash(segment: &[u8]) -> Vec<u8> {
+    tiger(&[LEAF_PREFIX, segment])
+}
+
+/// Returns the THEX inner node hash `Tiger(0x01 || left || right)`.
+pub fn node_hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+    tiger(&[NODE_PREFIX, left, right])
+}
+
+fn tiger(parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = Tiger::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
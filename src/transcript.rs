@@ -0,0 +1,249 @@
+//! Audit transcripts of hashing operations.
+//!
+//! A [Transcript](struct.Transcript.html) records every hash computed while building a [MerkleTree](../struct.MerkleTree.html) with [build_tree_recorded](../struct.MerkleTree.html#method.build_tree_recorded) or verifying a proof with [verify_recorded](fn.verify_recorded.html), in the order they were computed, and writes them to any [Write](https://doc.rust-lang.org/std/io/trait.Write.html). An auditor can then check a published root with [replay_build](fn.replay_build.html), or with a few lines of code in any language, without trusting this crate.
+//!
+//! The format is line based text:
+//!
+//! ```text
+//! rs-merkletree transcript v1
+//! leaf <hex of the exact SHA-256 input> <output>
+//! node <hex of the exact SHA-256 input> <output>
+//! sibling <hash>
+//! root <output>
+//! ```
+//!
+//! Outputs are written as stored by the tree, i.e. as lowercase hex. Inputs include the domain tag, so a node input is the tag followed by the outputs of its two children. Verification transcripts list the proof's sibling hashes first, as they are given rather than computed; they are checked with [replay](fn.replay.html). A build transcript never has `sibling` lines, and [replay_build](fn.replay_build.html) rejects them: a sibling is trusted as given, so a forged build transcript could otherwise reach any root without deriving it from its leaves.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::transcript::{replay_build, Transcript};
+//! use rs_merkletree::MerkleTree;
+//! let mut transcript = Transcript::new(Vec::new());
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree_recorded(vec!["Hello", "World", "From", "Rust"], &mut transcript)
+//!     .unwrap();
+//! let text = String::from_utf8(transcript.into_inner().unwrap()).unwrap();
+//! assert_eq!(replay_build(&text), Ok(tree.root_node().unwrap().hash()));
+//! ```
+
+use crate::hashing::{sha256_hex, to_hex};
+use crate::proof::{leaf_hash, MerkleProof, NodeHasher, LEAF_TAG, NODE_TAG};
+use crate::Hash;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+/// First line of every transcript.
+pub const HEADER: &str = "rs-merkletree transcript v1";
+
+/// Kind of a recorded hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashKind {
+    /// The hash of leaf data.
+    Leaf,
+    /// The hash of two child hashes.
+    Node,
+}
+
+/// Receives each hash as it is computed.
+pub trait HashRecorder {
+    /// Records that hashing `input` gave `output`.
+    fn record(&mut self, kind: HashKind, input: &[u8], output: &[u8]);
+}
+
+/// Errors found by [replay](fn.replay.html) and [replay_build](fn.replay_build.html). `line` counts from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptError {
+    /// The first line is not [HEADER](constant.HEADER.html).
+    MissingHeader,
+    /// A line is not a `leaf`, `node`, `sibling` or `root` entry, or its hex is invalid.
+    Malformed { line: usize },
+    /// The recorded output is not the hash of the recorded input.
+    HashMismatch { line: usize },
+    /// A node input is not the tag and two outputs or siblings recorded earlier.
+    UnknownChild { line: usize },
+    /// The root is not the last recorded output, or entries follow it.
+    RootMismatch { line: usize },
+    /// The transcript ends without a `root` line.
+    MissingRoot,
+    /// A build transcript has a `sibling` line, which only verification transcripts may have.
+    UnexpectedSibling { line: usize },
+}
+
+/// [Transcript](struct.Transcript.html) writes recorded hashes to a writer.
+///
+/// * `writer`: Destination of the transcript.
+///
+/// * `error`: First write error, reported by [finish](struct.Transcript.html#method.finish) and [into_inner](struct.Transcript.html#method.into_inner); nothing more is written after it.
+#[derive(Debug)]
+pub struct Transcript<W: Write> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Transcript<W> {
+    /// Function to create a [Transcript](struct.Transcript.html) writing to `writer`. The header is written immediately.
+    pub fn new(writer: W) -> Transcript<W> {
+        let mut transcript = Transcript {
+            writer,
+            error: None,
+        };
+        let result = writeln!(transcript.writer, "{}", HEADER);
+        transcript.error = result.err();
+        transcript
+    }
+
+    /// Writes a `sibling` line for a hash taken from a proof rather than computed.
+    pub fn sibling(&mut self, hash: &[u8]) {
+        self.write_line(format_args!("sibling {}", String::from_utf8_lossy(hash)));
+    }
+
+    /// Writes the `root` line for `root`.
+    pub fn finish(&mut self, root: &[u8]) -> io::Result<()> {
+        self.write_line(format_args!("root {}", String::from_utf8_lossy(root)));
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.writer.flush(),
+        }
+    }
+
+    /// Returns the writer, or the first error hit while writing to it.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(self.writer),
+        }
+    }
+
+    fn write_line(&mut self, line: std::fmt::Arguments) {
+        if self.error.is_none() {
+            self.error = writeln!(self.writer, "{}", line).err();
+        }
+    }
+}
+
+impl<W: Write> HashRecorder for Transcript<W> {
+    fn record(&mut self, kind: HashKind, input: &[u8], output: &[u8]) {
+        let name = match kind {
+            HashKind::Leaf => "leaf",
+            HashKind::Node => "node",
+        };
+        self.write_line(format_args!(
+            "{} {} {}",
+            name,
+            to_hex(input),
+            String::from_utf8_lossy(output)
+        ));
+    }
+}
+
+/// Hashes nodes like [TaggedHexSha256](../proof/struct.TaggedHexSha256.html), recording each hash.
+struct RecordingHasher<'a, R: HashRecorder> {
+    recorder: RefCell<&'a mut R>,
+}
+
+impl<R: HashRecorder> NodeHasher for RecordingHasher<'_, R> {
+    type Output = Vec<u8>;
+
+    fn hash_nodes(&self, left: &[u8], right: &[u8]) -> Vec<u8> {
+        let input = [&[NODE_TAG], left, right].concat();
+        let output = sha256_hex(&[&input]);
+        self.recorder
+            .borrow_mut()
+            .record(HashKind::Node, &input, &output);
+        output
+    }
+}
+
+/// Verifies `proof` of `data` against `root` like [MerkleProof::verify_data](../proof/struct.MerkleProof.html#method.verify_data), recording every hash and then `root` in `transcript`.
+///
+/// A proof that does not verify still produces a transcript, which [replay](fn.replay.html) rejects with [RootMismatch](enum.TranscriptError.html).
+pub fn verify_recorded<W: Write>(
+    proof: &MerkleProof,
+    root: &Hash,
+    data: &[u8],
+    transcript: &mut Transcript<W>,
+) -> io::Result<bool> {
+    let input = [&[LEAF_TAG], data].concat();
+    let leaf = leaf_hash(data);
+    for step in &proof.steps {
+        transcript.sibling(&step.hash.to_hex_bytes());
+    }
+    transcript.record(HashKind::Leaf, &input, &leaf.to_hex_bytes());
+    let hasher = RecordingHasher {
+        recorder: RefCell::new(&mut *transcript),
+    };
+    let valid = proof.verify_with(&hasher, root, &leaf);
+    transcript.finish(&root.to_hex_bytes())?;
+    Ok(valid)
+}
+
+/// Checks a verification transcript and returns the root it commits to.
+///
+/// Every output must be the SHA-256 of its input, every node input must be a tag followed by two earlier outputs or siblings, and the root must be the last output. Both the domain-separated and the legacy construction are accepted. Siblings are taken as given, so this shows that the root follows from the leaf and the siblings, not from leaves alone; check build transcripts with [replay_build](fn.replay_build.html).
+pub fn replay(transcript: &str) -> Result<Hash, TranscriptError> {
+    replay_lines(transcript, true)
+}
+
+/// Checks a build transcript, written by [build_tree_recorded](../struct.MerkleTree.html#method.build_tree_recorded), and returns the root it commits to.
+///
+/// Same as [replay](fn.replay.html), but fails with [UnexpectedSibling](enum.TranscriptError.html) on any `sibling` line, so every node is derived from the recorded leaves.
+pub fn replay_build(transcript: &str) -> Result<Hash, TranscriptError> {
+    replay_lines(transcript, false)
+}
+
+fn replay_lines(transcript: &str, siblings: bool) -> Result<Hash, TranscriptError> {
+    let mut lines = transcript.lines().enumerate();
+    match lines.next() {
+        Some((_, HEADER)) => {}
+        _ => return Err(TranscriptError::MissingHeader),
+    }
+    let mut outputs: HashSet<&[u8]> = HashSet::new();
+    let mut last: Option<&[u8]> = None;
+    let mut root: Option<Hash> = None;
+    for (i, text) in lines {
+        let line = i + 1;
+        if root.is_some() {
+            return Err(TranscriptError::RootMismatch { line });
+        }
+        let fields: Vec<&str> = text.split(' ').collect();
+        match fields.as_slice() {
+            ["root", output] => {
+                if last != Some(output.as_bytes()) {
+                    return Err(TranscriptError::RootMismatch { line });
+                }
+                root =
+                    Some(Hash::from_hex(output).map_err(|_| TranscriptError::Malformed { line })?);
+            }
+            ["sibling", _] if !siblings => {
+                return Err(TranscriptError::UnexpectedSibling { line });
+            }
+            ["sibling", hash] => {
+                outputs.insert(hash.as_bytes());
+            }
+            [kind @ ("leaf" | "node"), input, output] => {
+                let input =
+                    crate::hashing::from_hex(input).ok_or(TranscriptError::Malformed { line })?;
+                if sha256_hex(&[&input]) != output.as_bytes() {
+                    return Err(TranscriptError::HashMismatch { line });
+                }
+                if *kind == "node" && !is_node_input(&input, &outputs) {
+                    return Err(TranscriptError::UnknownChild { line });
+                }
+                outputs.insert(output.as_bytes());
+                last = Some(output.as_bytes());
+            }
+            _ => return Err(TranscriptError::Malformed { line }),
+        }
+    }
+    root.ok_or(TranscriptError::MissingRoot)
+}
+
+fn is_node_input(input: &[u8], outputs: &HashSet<&[u8]>) -> bool {
+    let children = match input.split_first() {
+        Some((&NODE_TAG, rest)) if input.len() % 2 == 1 => rest,
+        _ => input,
+    };
+    let (left, right) = children.split_at(children.len() / 2);
+    !left.is_empty() && outputs.contains(left) && outputs.contains(right)
+}
@@ -0,0 +1,273 @@
+//! Trillian-compatible proofs and log roots.
+//!
+//! [Trillian](https://github.com/google/trillian) returns inclusion and consistency proofs as a `Proof { leaf_index, hashes }` message and commits to the log state with a `SignedLogRoot` whose `log_root` field holds a TLS-encoded `LogRootV1`. Trillian's `RFC6962_SHA256` hash strategy is the one used by [history](../history/index.html), so proofs convert losslessly between the two.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::history::HistoryTree;
+//! use rs_merkletree::trillian::{LogRootV1, Proof, SignedLogRoot};
+//! let mut log = HistoryTree::new();
+//! for entry in ["a", "b", "c"] {
+//!     log.append(entry.as_bytes());
+//! }
+//! let signed = SignedLogRoot::from_log_root(&LogRootV1::new(log.len(), log.root())).unwrap();
+//! let root = signed.decode().unwrap();
+//!
+//! let proof = Proof::from(log.membership_proof(1, 3).unwrap());
+//! assert!(proof.verify_inclusion(b"b", root.tree_size, &root.root_hash));
+//! ```
+
+use crate::history::{self, IncrementalProof, MembershipProof};
+
+/// Errors returned when converting from Trillian messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrillianError {
+    /// The encoded log root ends early.
+    Truncated,
+    /// The encoded log root has bytes after its last field.
+    TrailingData,
+    /// The log root version is not `LogRootV1`.
+    UnsupportedVersion(u16),
+    /// A field does not fit its TLS length prefix.
+    FieldTooLong(&'static str),
+    /// The proof's leaf index is negative or outside the tree.
+    InvalidIndex,
+    /// The hash strategy is not one this crate implements.
+    UnsupportedHashStrategy(String),
+}
+
+/// [HashStrategy](enum.HashStrategy.html) mirrors Trillian's `HashStrategy` enum for the strategies this crate can verify.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashStrategy {
+    /// `RFC6962_SHA256`: leaves `SHA256(0x00 || data)`, inner nodes `SHA256(0x01 || left || right)`.
+    Rfc6962Sha256,
+}
+
+impl HashStrategy {
+    /// Returns the strategy for its protobuf enum name, e.g. `RFC6962_SHA256`.
+    pub fn from_name(name: &str) -> Result<HashStrategy, TrillianError> {
+        match name {
+            "RFC6962_SHA256" => Ok(HashStrategy::Rfc6962Sha256),
+            _ => Err(TrillianError::UnsupportedHashStrategy(name.to_string())),
+        }
+    }
+
+    /// Returns the protobuf enum name of the strategy.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashStrategy::Rfc6962Sha256 => "RFC6962_SHA256",
+        }
+    }
+
+    /// Returns the leaf hash of `data`.
+    pub fn hash_leaf(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HashStrategy::Rfc6962Sha256 => history::leaf_hash(data),
+        }
+    }
+
+    /// Returns the hash of an inner node.
+    pub fn hash_children(&self, left: &[u8], right: &[u8]) -> Vec<u8> {
+        match self {
+            HashStrategy::Rfc6962Sha256 => history::node_hash(left, right),
+        }
+    }
+}
+
+/// [Proof](struct.Proof.html) is Trillian's `Proof` message.
+///
+/// * `leaf_index`: Index of the proven leaf; Trillian sets it to `0` for consistency proofs.
+///
+/// * `hashes`: Audit path, closest to the leaf first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof {
+    pub leaf_index: i64,
+    pub hashes: Vec<Vec<u8>>,
+}
+
+impl Proof {
+    /// Converts into a [MembershipProof](../history/struct.MembershipProof.html) against a tree of `tree_size` leaves.
+    pub fn to_membership_proof(&self, tree_size: u64) -> Result<MembershipProof, TrillianError> {
+        let index = u64::try_from(self.leaf_index).map_err(|_| TrillianError::InvalidIndex)?;
+        if index >= tree_size {
+            return Err(TrillianError::InvalidIndex);
+        }
+        Ok(MembershipProof {
+            index,
+            size: tree_size,
+            path: self.hashes.clone(),
+        })
+    }
+
+    /// Converts into an [IncrementalProof](../history/struct.IncrementalProof.html) between trees of `old_size` and `new_size` leaves.
+    pub fn to_incremental_proof(&self, old_size: u64, new_size: u64) -> IncrementalProof {
+        IncrementalProof {
+            old_size,
+            new_size,
+            path: self.hashes.clone(),
+        }
+    }
+
+    /// Checks that `data` is included at `leaf_index` in the tree of `tree_size` leaves with root `root`.
+    pub fn verify_inclusion(&self, data: &[u8], tree_size: u64, root: &[u8]) -> bool {
+        self.to_membership_proof(tree_size)
+            .map(|proof| proof.verify(root, data))
+            .unwrap_or(false)
+    }
+
+    /// Checks that `new_root` extends `old_root`.
+    pub fn verify_consistency(
+        &self,
+        old_size: u64,
+        new_size: u64,
+        old_root: &[u8],
+        new_root: &[u8],
+    ) -> bool {
+        self.to_incremental_proof(old_size, new_size)
+            .verify(old_root, new_root)
+    }
+}
+
+impl From<MembershipProof> for Proof {
+    fn from(proof: MembershipProof) -> Proof {
+        Proof {
+            leaf_index: proof.index as i64,
+            hashes: proof.path,
+        }
+    }
+}
+
+impl From<IncrementalProof> for Proof {
+    fn from(proof: IncrementalProof) -> Proof {
+        Proof {
+            leaf_index: 0,
+            hashes: proof.path,
+        }
+    }
+}
+
+/// [LogRootV1](struct.LogRootV1.html) is the log state a Trillian log commits to.
+///
+/// * `tree_size`: Number of leaves in the tree.
+///
+/// * `root_hash`: Root of the tree, at most 128 bytes.
+///
+/// * `timestamp_nanos`: Time the root was produced, in nanoseconds since the Unix epoch.
+///
+/// * `revision`: Revision of the tree in storage.
+///
+/// * `metadata`: Opaque personality data, at most 65535 bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRootV1 {
+    pub tree_size: u64,
+    pub root_hash: Vec<u8>,
+    pub timestamp_nanos: u64,
+    pub revision: u64,
+    pub metadata: Vec<u8>,
+}
+
+const LOG_ROOT_V1: u16 = 1;
+
+impl LogRootV1 {
+    /// Function to create a [LogRootV1](struct.LogRootV1.html) with zero timestamp and revision and no metadata.
+    pub fn new(tree_size: u64, root_hash: Vec<u8>) -> LogRootV1 {
+        LogRootV1 {
+            tree_size,
+            root_hash,
+            timestamp_nanos: 0,
+            revision: 0,
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Returns the TLS encoding stored in `SignedLogRoot.log_root`.
+    pub fn encode(&self) -> Result<Vec<u8>, TrillianError> {
+        let root_len = u8::try_from(self.root_hash.len())
+            .map_err(|_| TrillianError::FieldTooLong("root_hash"))?;
+        if root_len > 128 {
+            return Err(TrillianError::FieldTooLong("root_hash"));
+        }
+        let metadata_len = u16::try_from(self.metadata.len())
+            .map_err(|_| TrillianError::FieldTooLong("metadata"))?;
+        let mut out = Vec::with_capacity(29 + self.root_hash.len() + self.metadata.len());
+        out.extend_from_slice(&LOG_ROOT_V1.to_be_bytes());
+        out.extend_from_slice(&self.tree_size.to_be_bytes());
+        out.push(root_len);
+        out.extend_from_slice(&self.root_hash);
+        out.extend_from_slice(&self.timestamp_nanos.to_be_bytes());
+        out.extend_from_slice(&self.revision.to_be_bytes());
+        out.extend_from_slice(&metadata_len.to_be_bytes());
+        out.extend_from_slice(&self.metadata);
+        Ok(out)
+    }
+
+    /// Parses the TLS encoding stored in `SignedLogRoot.log_root`.
+    pub fn decode(data: &[u8]) -> Result<LogRootV1, TrillianError> {
+        let mut reader = Reader { data };
+        let version = u16::from_be_bytes(reader.take_array()?);
+        if version != LOG_ROOT_V1 {
+            return Err(TrillianError::UnsupportedVersion(version));
+        }
+        let tree_size = u64::from_be_bytes(reader.take_array()?);
+        let root_len = reader.take(1)?[0] as usize;
+        if root_len > 128 {
+            return Err(TrillianError::FieldTooLong("root_hash"));
+        }
+        let root_hash = reader.take(root_len)?.to_vec();
+        let timestamp_nanos = u64::from_be_bytes(reader.take_array()?);
+        let revision = u64::from_be_bytes(reader.take_array()?);
+        let metadata_len = u16::from_be_bytes(reader.take_array()?) as usize;
+        let metadata = reader.take(metadata_len)?.to_vec();
+        if !reader.data.is_empty() {
+            return Err(TrillianError::TrailingData);
+        }
+        Ok(LogRootV1 {
+            tree_size,
+            root_hash,
+            timestamp_nanos,
+            revision,
+            metadata,
+        })
+    }
+}
+
+/// [SignedLogRoot](struct.SignedLogRoot.html) is Trillian's `SignedLogRoot` message. Signing is left to the personality, as in current Trillian releases.
+///
+/// * `log_root`: TLS-encoded [LogRootV1](struct.LogRootV1.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedLogRoot {
+    pub log_root: Vec<u8>,
+}
+
+impl SignedLogRoot {
+    /// Function to create a [SignedLogRoot](struct.SignedLogRoot.html) holding `root`.
+    pub fn from_log_root(root: &LogRootV1) -> Result<SignedLogRoot, TrillianError> {
+        Ok(SignedLogRoot {
+            log_root: root.encode()?,
+        })
+    }
+
+    /// Parses the contained [LogRootV1](struct.LogRootV1.html).
+    pub fn decode(&self) -> Result<LogRootV1, TrillianError> {
+        LogRootV1::decode(&self.log_root)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], TrillianError> {
+        if self.data.len() < len {
+            return Err(TrillianError::Truncated);
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], TrillianError> {
+        Ok(self.take(N)?.try_into().expect("length checked"))
+    }
+}
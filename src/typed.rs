@@ -0,0 +1,168 @@
+//! Typed trees.
+//!
+//! A [TypedMerkleTree](struct.TypedMerkleTree.html) owns its items and commits to them, so it can serve as the collection itself instead of shadowing one kept elsewhere. Items are turned into leaf data by a [LeafEncoder](trait.LeafEncoder.html): [CanonicalEncoder](struct.CanonicalEncoder.html) uses the [Encode](../map/trait.Encode.html) trait of [MerkleMap](../map/struct.MerkleMap.html), and any `Fn(&T) -> Vec<u8>` works too. Roots and proofs are those of a [MerkleTree](../struct.MerkleTree.html) built over the encoded items.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::typed::{CanonicalEncoder, TypedMerkleTree};
+//! let accounts = vec![String::from("alice"), String::from("bob"), String::from("carol")];
+//! let tree = TypedMerkleTree::new(accounts, CanonicalEncoder);
+//! assert_eq!(tree.get(1).map(String::as_str), Some("bob"));
+//!
+//! let root = tree.root().unwrap();
+//! let (index, proof) = tree.proof_of(&String::from("carol")).unwrap();
+//! assert_eq!(index, 2);
+//! assert!(tree.verify(&root, &tree[index], &proof));
+//!
+//! let by_id = TypedMerkleTree::new(vec![7u32, 9], |id: &u32| id.to_le_bytes().to_vec());
+//! assert_eq!(by_id.position(&9), Some(1));
+//! ```
+
+use crate::map::Encode;
+use crate::proof::MerkleProof;
+use crate::{Hash, MerkleTree};
+use std::ops::Index;
+
+/// Turns an item of a [TypedMerkleTree](struct.TypedMerkleTree.html) into the data of its leaf.
+pub trait LeafEncoder<T> {
+    fn encode(&self, item: &T) -> Vec<u8>;
+}
+
+impl<T, F: Fn(&T) -> Vec<u8>> LeafEncoder<T> for F {
+    fn encode(&self, item: &T) -> Vec<u8> {
+        self(item)
+    }
+}
+
+/// [CanonicalEncoder](struct.CanonicalEncoder.html) encodes items with their [Encode](../map/trait.Encode.html) implementation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CanonicalEncoder;
+
+impl<T: Encode> LeafEncoder<T> for CanonicalEncoder {
+    fn encode(&self, item: &T) -> Vec<u8> {
+        item.encode()
+    }
+}
+
+/// [TypedMerkleTree](struct.TypedMerkleTree.html) holds items of type `T` and a tree over their encodings.
+///
+/// * `items`: The items, in leaf order.
+///
+/// * `encoder`: Encodes each item as leaf data.
+///
+/// * `tree`: Tree over the encoded items.
+#[derive(Debug, Clone)]
+pub struct TypedMerkleTree<T, E> {
+    items: Vec<T>,
+    encoder: E,
+    tree: MerkleTree,
+}
+
+impl<T, E: LeafEncoder<T>> TypedMerkleTree<T, E> {
+    /// Function to create a [TypedMerkleTree](struct.TypedMerkleTree.html) over `items`.
+    pub fn new(items: Vec<T>, encoder: E) -> TypedMerkleTree<T, E> {
+        let mut typed = TypedMerkleTree {
+            items,
+            encoder,
+            tree: MerkleTree::new(None),
+        };
+        typed.rebuild();
+        typed
+    }
+
+    fn rebuild(&mut self) {
+        let data: Vec<Vec<u8>> = self
+            .items
+            .iter()
+            .map(|item| self.encoder.encode(item))
+            .collect();
+        self.tree
+            .build_tree_bytes(data.iter().map(Vec::as_slice).collect());
+    }
+
+    /// Appends an item and rebuilds the tree.
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+        self.rebuild();
+    }
+
+    /// Returns the item at `index`.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.items.get(index)
+    }
+
+    /// Returns every item, in leaf order.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Returns an iterator over the items.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// Returns the number of items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the Root Hash, or `None` if there are no items.
+    pub fn root(&self) -> Option<Hash> {
+        self.tree.root_hash()
+    }
+
+    /// Returns the underlying tree.
+    pub fn tree(&self) -> &MerkleTree {
+        &self.tree
+    }
+
+    /// Generates a proof for the item at `index`.
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        self.tree.proof(index)
+    }
+
+    /// Checks that `item` is committed by `root` according to `proof`, encoding it as this tree does.
+    pub fn verify(&self, root: &Hash, item: &T, proof: &MerkleProof) -> bool {
+        proof.verify_data(root, &self.encoder.encode(item))
+    }
+
+    /// Returns the items, dropping the tree.
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl<T: PartialEq, E: LeafEncoder<T>> TypedMerkleTree<T, E> {
+    /// Returns the index of the first item equal to `item`.
+    pub fn position(&self, item: &T) -> Option<usize> {
+        self.items.iter().position(|candidate| candidate == item)
+    }
+
+    /// Generates a proof for the first item equal to `item`, with its index.
+    pub fn proof_of(&self, item: &T) -> Option<(usize, MerkleProof)> {
+        let index = self.position(item)?;
+        Some((index, self.proof(index)?))
+    }
+}
+
+impl<T, E> Index<usize> for TypedMerkleTree<T, E> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.items[index]
+    }
+}
+
+impl<'a, T, E> IntoIterator for &'a TypedMerkleTree<T, E> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
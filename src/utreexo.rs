@@ -0,0 +1,360 @@
+//! Utreexo-style dynamic accumulator.
+//!
+//! The accumulator is a forest of perfect binary trees, one per set bit of the number of leaves ever added (like the mountains of an [MMR](../mmr/index.html)), but unlike an MMR it also supports deleting elements. When a leaf is deleted its sibling moves up into the parent's place, so the roots change in `O(log n)` and proofs stay short. This suits UTXO-set-like applications where most elements are eventually spent.
+//!
+//! Two views are provided:
+//!
+//! * [Stump](struct.Stump.html) only keeps the roots (a few hundred bytes). It can add elements and verify or apply deletions given a proof.
+//! * [Pollard](struct.Pollard.html) keeps the whole forest so it can generate those proofs.
+//!
+//! Leaves are `SHA256(0x00 || data)` and inner nodes `SHA256(0x01 || left || right)`, so a pair of hashes cannot be passed off as an element. Both views accept the same element more than once; each copy is deleted separately.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::utreexo::{Pollard, Stump};
+//! let mut pollard = Pollard::new();
+//! let mut stump = Stump::new();
+//! for utxo in ["a", "b", "c", "d", "e"] {
+//!     pollard.add(utxo.as_bytes());
+//!     stump.add(utxo.as_bytes());
+//! }
+//!
+//! let proof = pollard.prove(b"c").unwrap();
+//! assert!(stump.verify(&proof, b"c"));
+//! assert!(stump.delete(&proof, b"c"));
+//! pollard.delete(b"c");
+//! assert_eq!(stump.roots(), pollard.roots());
+//! ```
+
+use crate::hashing::sha256_concat;
+use std::collections::HashMap;
+
+const MAX_HEIGHT: usize = 64;
+const LEAF_PREFIX: &[u8] = &[0x00];
+const NODE_PREFIX: &[u8] = &[0x01];
+
+/// [UtreexoProof](struct.UtreexoProof.html) proves that a leaf is in the forest.
+///
+/// * `siblings`: Sibling hashes from the leaf up to its root.
+///
+/// * `directions`: Bit `i` is set when the node at level `i` of the path is a right child.
+///
+/// * `row`: Height of the leaf above the bottom of its tree, non-zero once deletions have moved it up. The proof is only checked against the root of height `row + siblings.len()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UtreexoProof {
+    pub siblings: Vec<Vec<u8>>,
+    pub directions: u64,
+    pub row: usize,
+}
+
+/// [Stump](struct.Stump.html) is the compact form of the accumulator: the root of each tree, by height.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stump {
+    roots: Vec<Option<Vec<u8>>>,
+    num_leaves: u64,
+}
+
+/// A node of the [Pollard](struct.Pollard.html) arena.
+#[derive(Debug, Clone)]
+struct PollardNode {
+    hash: Vec<u8>,
+    parent: Option<usize>,
+    children: Option<(usize, usize)>,
+}
+
+/// [Pollard](struct.Pollard.html) is the full form of the accumulator, holding every node so it can prove any element.
+#[derive(Debug, Clone)]
+pub struct Pollard {
+    nodes: Vec<PollardNode>,
+    roots: Vec<Option<usize>>,
+    leaves: HashMap<Vec<u8>, Vec<usize>>,
+    free: Vec<usize>,
+    num_leaves: u64,
+}
+
+impl Default for Stump {
+    fn default() -> Self {
+        Stump::new()
+    }
+}
+
+impl Default for Pollard {
+    fn default() -> Self {
+        Pollard::new()
+    }
+}
+
+impl UtreexoProof {
+    /// Folds the proof over `leaf_hash`, returning the root it leads to.
+    fn compute_root(&self, leaf_hash: Vec<u8>) -> Vec<u8> {
+        self.fold(leaf_hash, 0)
+    }
+
+    /// Folds siblings from level `from` upward, starting with `current` at that level.
+    fn fold(&self, mut current: Vec<u8>, from: usize) -> Vec<u8> {
+        for (level, sibling) in self.siblings.iter().enumerate().skip(from) {
+            current = if self.directions >> level & 1 == 1 {
+                node_hash(sibling, &current)
+            } else {
+                node_hash(&current, sibling)
+            };
+        }
+        current
+    }
+}
+
+impl Stump {
+    /// Function to create an empty [Stump](struct.Stump.html)
+    pub fn new() -> Stump {
+        Stump {
+            roots: vec![None; MAX_HEIGHT],
+            num_leaves: 0,
+        }
+    }
+
+    /// Returns the number of elements ever added, including deleted ones.
+    pub fn num_leaves(&self) -> u64 {
+        self.num_leaves
+    }
+
+    /// Returns the non-empty roots, from the tallest tree to the shortest.
+    pub fn roots(&self) -> Vec<Vec<u8>> {
+        self.roots.iter().rev().flatten().cloned().collect()
+    }
+
+    /// Adds `data` to the accumulator.
+    pub fn add(&mut self, data: &[u8]) {
+        let mut carry = Some(leaf_hash(data));
+        for height in 0..MAX_HEIGHT {
+            if self.num_leaves >> height & 1 == 0 {
+                self.roots[height] = carry;
+                break;
+            }
+            carry = match (self.roots[height].take(), carry) {
+                (Some(left), Some(right)) => Some(node_hash(&left, &right)),
+                (left, right) => left.or(right),
+            };
+        }
+        self.num_leaves += 1;
+    }
+
+    /// Checks that `data` is in the accumulator.
+    pub fn verify(&self, proof: &UtreexoProof, data: &[u8]) -> bool {
+        self.root_slot(proof, data).is_some()
+    }
+
+    /// Deletes `data` from the accumulator. Returns `false`, leaving the accumulator unchanged, if the proof does not verify.
+    pub fn delete(&mut self, proof: &UtreexoProof, data: &[u8]) -> bool {
+        let Some(slot) = self.root_slot(proof, data) else {
+            return false;
+        };
+        // The sibling takes the parent's place; a deleted root leaves its tree empty.
+        self.roots[slot] = proof
+            .siblings
+            .first()
+            .map(|sibling| proof.fold(sibling.clone(), 1));
+        true
+    }
+
+    /// Height of the tree whose root the proof leads to, which must be the one its row and length point at.
+    fn root_slot(&self, proof: &UtreexoProof, data: &[u8]) -> Option<usize> {
+        if proof.siblings.len() > MAX_HEIGHT {
+            return None;
+        }
+        let slot = proof.row.checked_add(proof.siblings.len())?;
+        let root = self.roots.get(slot)?.as_ref()?;
+        (proof.compute_root(leaf_hash(data)) == *root).then_some(slot)
+    }
+}
+
+impl Pollard {
+    /// Function to create an empty [Pollard](struct.Pollard.html)
+    pub fn new() -> Pollard {
+        Pollard {
+            nodes: Vec::new(),
+            roots: vec![None; MAX_HEIGHT],
+            leaves: HashMap::new(),
+            free: Vec::new(),
+            num_leaves: 0,
+        }
+    }
+
+    /// Returns the number of elements ever added, including deleted ones.
+    pub fn num_leaves(&self) -> u64 {
+        self.num_leaves
+    }
+
+    /// Returns the number of elements currently in the accumulator, counting every copy.
+    pub fn len(&self) -> usize {
+        self.leaves.values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if every element has been deleted.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Returns `true` if `data` is in the accumulator.
+    pub fn contains(&self, data: &[u8]) -> bool {
+        self.leaves.contains_key(&leaf_hash(data))
+    }
+
+    /// Returns the non-empty roots, from the tallest tree to the shortest.
+    pub fn roots(&self) -> Vec<Vec<u8>> {
+        self.roots
+            .iter()
+            .rev()
+            .flatten()
+            .map(|index| self.nodes[*index].hash.clone())
+            .collect()
+    }
+
+    /// Returns the [Stump](struct.Stump.html) with the same roots as this forest.
+    pub fn stump(&self) -> Stump {
+        Stump {
+            roots: self
+                .roots
+                .iter()
+                .map(|root| root.map(|index| self.nodes[index].hash.clone()))
+                .collect(),
+            num_leaves: self.num_leaves,
+        }
+    }
+
+    /// Adds `data` to the accumulator, as another copy if it is already present.
+    pub fn add(&mut self, data: &[u8]) {
+        let hash = leaf_hash(data);
+        let leaf = self.push_node(hash.clone(), None);
+        self.leaves.entry(hash).or_default().push(leaf);
+
+        let mut carry = Some(leaf);
+        for height in 0..MAX_HEIGHT {
+            if self.num_leaves >> height & 1 == 0 {
+                self.roots[height] = carry;
+                break;
+            }
+            carry = match (self.roots[height].take(), carry) {
+                (Some(left), Some(right)) => {
+                    let hash = node_hash(&self.nodes[left].hash, &self.nodes[right].hash);
+                    let parent = self.push_node(hash, Some((left, right)));
+                    self.nodes[left].parent = Some(parent);
+                    self.nodes[right].parent = Some(parent);
+                    Some(parent)
+                }
+                (left, right) => left.or(right),
+            };
+        }
+        self.num_leaves += 1;
+    }
+
+    /// Generates a proof that `data` is in the accumulator, for the copy added last.
+    pub fn prove(&self, data: &[u8]) -> Option<UtreexoProof> {
+        let mut index = *self.leaves.get(&leaf_hash(data))?.last()?;
+        let mut proof = UtreexoProof {
+            siblings: Vec::new(),
+            directions: 0,
+            row: 0,
+        };
+        while let Some(parent) = self.nodes[index].parent {
+            let (left, right) = self.nodes[parent].children?;
+            if right == index {
+                proof.directions |= 1 << proof.siblings.len();
+                proof.siblings.push(self.nodes[left].hash.clone());
+            } else {
+                proof.siblings.push(self.nodes[right].hash.clone());
+            }
+            index = parent;
+        }
+        let slot = self.roots.iter().position(|root| *root == Some(index))?;
+        proof.row = slot - proof.siblings.len();
+        Some(proof)
+    }
+
+    /// Deletes the copy of `data` added last, the one [prove](struct.Pollard.html#method.prove) proves. Returns `false` if it is not present.
+    pub fn delete(&mut self, data: &[u8]) -> bool {
+        let hash = leaf_hash(data);
+        let Some(copies) = self.leaves.get_mut(&hash) else {
+            return false;
+        };
+        let leaf = copies.pop().expect("leaves only holds non-empty lists");
+        if copies.is_empty() {
+            self.leaves.remove(&hash);
+        }
+        self.free.push(leaf);
+        let Some(parent) = self.nodes[leaf].parent else {
+            self.replace_root(leaf, None);
+            return true;
+        };
+        self.free.push(parent);
+        let (left, right) = self.nodes[parent].children.expect("parent has children");
+        let sibling = if left == leaf { right } else { left };
+
+        // Move the sibling into the parent's place.
+        let grandparent = self.nodes[parent].parent;
+        self.nodes[sibling].parent = grandparent;
+        match grandparent {
+            None => self.replace_root(parent, Some(sibling)),
+            Some(grandparent) => {
+                let children = self.nodes[grandparent]
+                    .children
+                    .as_mut()
+                    .expect("grandparent has children");
+                if children.0 == parent {
+                    children.0 = sibling;
+                } else {
+                    children.1 = sibling;
+                }
+                self.rehash_from(grandparent);
+            }
+        }
+        true
+    }
+
+    /// Stores a node, reusing the slot of a deleted one if there is any.
+    fn push_node(&mut self, hash: Vec<u8>, children: Option<(usize, usize)>) -> usize {
+        let node = PollardNode {
+            hash,
+            parent: None,
+            children,
+        };
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn replace_root(&mut self, old: usize, new: Option<usize>) {
+        if let Some(slot) = self.roots.iter_mut().find(|root| **root == Some(old)) {
+            *slot = new;
+        }
+    }
+
+    /// Recomputes the hashes from `index` up to its root.
+    fn rehash_from(&mut self, mut index: usize) {
+        loop {
+            if let Some((left, right)) = self.nodes[index].children {
+                self.nodes[index].hash = node_hash(&self.nodes[left].hash, &self.nodes[right].hash);
+            }
+            match self.nodes[index].parent {
+                Some(parent) => index = parent,
+                None => return,
+            }
+        }
+    }
+}
+
+/// Returns the leaf hash `SHA256(0x00 || data)`.
+fn leaf_hash(data: &[u8]) -> Vec<u8> {
+    sha256_concat(&[LEAF_PREFIX, data])
+}
+
+/// Returns the inner node hash `SHA256(0x01 || left || right)`.
+fn node_hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+    sha256_concat(&[NODE_PREFIX, left, right])
+}
@@ -0,0 +1,53 @@
+//! WebAssembly bindings.
+//!
+//! Exports [build_root](fn.build_root.html), [generate_proof](fn.generate_proof.html) and [verify_proof](fn.verify_proof.html) through `wasm-bindgen`, so a browser can verify proofs produced by a Rust backend with the same code. Leaves are passed as JS strings, roots as hex strings and proofs as `Uint8Array`s in the [MerkleProof::to_bytes](../proof/struct.MerkleProof.html#method.to_bytes) format.
+//!
+//! Requires the `wasm` feature. The crate is not a `cdylib` by default (that would break `no_std` users), so request one when building the module:
+//!
+//! ```text
+//! cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! wasm-bindgen --target web target/wasm32-unknown-unknown/release/rs_merkletree.wasm --out-dir pkg
+//! ```
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::wasm::{build_root, generate_proof, verify_proof};
+//! let leaves: Vec<String> = ["Hello", "World", "From", "Rust"].map(String::from).to_vec();
+//! let root = build_root(leaves.clone()).unwrap();
+//! assert_eq!(root, "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb");
+//!
+//! let proof = generate_proof(leaves, 1).unwrap();
+//! assert!(verify_proof(&root, "World", &proof));
+//! ```
+
+use crate::proof::MerkleProof;
+use crate::MerkleTree;
+use wasm_bindgen::prelude::*;
+
+/// Returns the hex root of the tree over `leaves`, or `undefined` if `leaves` is empty.
+#[wasm_bindgen]
+pub fn build_root(leaves: Vec<String>) -> Option<String> {
+    let tree = build(&leaves);
+    Some(tree.root_node()?.hash().to_string())
+}
+
+/// Returns the serialized proof for the leaf at `index`, or `undefined` if there is no such leaf.
+#[wasm_bindgen]
+pub fn generate_proof(leaves: Vec<String>, index: usize) -> Option<Vec<u8>> {
+    build(&leaves).proof(index).map(|proof| proof.to_bytes())
+}
+
+/// Checks that the leaf with data `leaf` is included in the tree with hex root `root`.
+#[wasm_bindgen]
+pub fn verify_proof(root: &str, leaf: &str, proof: &[u8]) -> bool {
+    let Ok(root) = root.parse() else {
+        return false;
+    };
+    MerkleProof::from_bytes(proof).is_some_and(|proof| proof.verify_data(&root, leaf.as_bytes()))
+}
+
+fn build(leaves: &[String]) -> MerkleTree {
+    let mut tree = MerkleTree::new(None);
+    tree.build_tree(leaves.iter().map(String::as_str).collect());
+    tree
+}
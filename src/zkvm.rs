@@ -0,0 +1,87 @@
+//! Proof verification for zkVM guests.
+//!
+//! Guest programs for RISC Zero, SP1 and similar zkVMs pay for every cycle, cannot print and are best kept free of `std`. [verify_compact](fn.verify_compact.html) checks a [MerkleTree](../struct.MerkleTree.html) proof under those constraints: it takes fixed-size hashes, never allocates, never panics and runs the same sequence of hash calls for every proof of a given length, so its cycle count is deterministic. The host converts a [MerkleProof](../proof/struct.MerkleProof.html) into a [CompactProof](struct.CompactProof.html) and writes its fields to the guest.
+//!
+//! SHA-256 is computed with the `sha2` crate, which both RISC Zero and SP1 ship accelerated forks of; patch it in the guest's `Cargo.toml` (`[patch.crates-io] sha2 = ...`) to have every hash run on the zkVM's SHA-256 precompile. Depend on the crate with `default-features = false` in the guest.
+//!
+//! # Examples
+//! ```
+//! use rs_merkletree::zkvm::{verify_compact, CompactProof};
+//! use rs_merkletree::MerkleTree;
+//! let mut tree = MerkleTree::new(None);
+//! tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+//! let root: [u8; 64] = tree.root_node().unwrap().hash().to_hex_bytes();
+//!
+//! // Host side.
+//! let proof = CompactProof::from_proof(&tree.proof(3).unwrap()).unwrap();
+//!
+//! // Guest side.
+//! let leaf = b"635fd701970453575a3f84cb82631929f1a96dbeb7c45f983ff648698a5ca8f4";
+//! assert!(verify_compact(&root, leaf, &proof.siblings, proof.left_bits));
+//! ```
+
+use crate::proof::{ct_eq, MerkleProof, NodeHasher, Position, TaggedHexSha256};
+use alloc::vec::Vec;
+
+/// Longest proof a [CompactProof](struct.CompactProof.html) can describe.
+pub const MAX_DEPTH: usize = 64;
+
+/// [CompactProof](struct.CompactProof.html) is a [MerkleProof](../proof/struct.MerkleProof.html) laid out for guests.
+///
+/// * `siblings`: Sibling hashes from the leaf up, in the hex form parents are hashed over, so the guest never converts them.
+///
+/// * `left_bits`: Bit `i` is set if `siblings[i]` is the left input of its parent hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactProof {
+    pub siblings: Vec<[u8; 64]>,
+    pub left_bits: u64,
+}
+
+impl CompactProof {
+    /// Converts `proof`, returning `None` if it has more than [MAX_DEPTH](constant.MAX_DEPTH.html) steps.
+    pub fn from_proof(proof: &MerkleProof) -> Option<CompactProof> {
+        if proof.steps.len() > MAX_DEPTH {
+            return None;
+        }
+        let mut left_bits = 0;
+        let mut siblings = Vec::with_capacity(proof.steps.len());
+        for (i, step) in proof.steps.iter().enumerate() {
+            siblings.push(step.hash.to_hex_bytes());
+            if step.position == Position::Left {
+                left_bits |= 1 << i;
+            }
+        }
+        Some(CompactProof {
+            siblings,
+            left_bits,
+        })
+    }
+
+    /// Checks that the leaf hash `leaf` is included in the tree with root hash `root`.
+    pub fn verify(&self, root: &[u8; 64], leaf: &[u8; 64]) -> bool {
+        verify_compact(root, leaf, &self.siblings, self.left_bits)
+    }
+}
+
+/// Verifies a proof of `siblings.len()` steps without allocating; bit `i` of `left_bits` is set if `siblings[i]` is a left sibling.
+///
+/// Returns `false` for proofs longer than [MAX_DEPTH](constant.MAX_DEPTH.html).
+pub fn verify_compact(
+    root: &[u8; 64],
+    leaf: &[u8; 64],
+    siblings: &[[u8; 64]],
+    left_bits: u64,
+) -> bool {
+    if siblings.len() > MAX_DEPTH {
+        return false;
+    }
+    let mut current = *leaf;
+    for (i, sibling) in siblings.iter().enumerate() {
+        current = if left_bits >> i & 1 == 1 {
+            TaggedHexSha256.hash_nodes(sibling, &current)
+        } else {
+            TaggedHexSha256.hash_nodes(&current, sibling)
+        };
+    }
+    ct_eq(&current, root)
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::accel::{backend, Backend};
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn backend_matches_cpu_features() {
+        let backend = backend();
+        assert_eq!(backend, rs_merkletree::accel::backend());
+        #[cfg(target_arch = "x86_64")]
+        assert_eq!(
+            backend == Backend::ShaNi,
+            std::is_x86_feature_detected!("sha") && std::is_x86_feature_detected!("sse4.1")
+        );
+        #[cfg(all(target_arch = "aarch64", not(feature = "asm")))]
+        assert_eq!(backend, Backend::Software);
+        if !backend.is_hardware() {
+            let x86 = cfg!(any(target_arch = "x86", target_arch = "x86_64"));
+            let expected = if cfg!(feature = "asm") && x86 {
+                Backend::Asm
+            } else {
+                Backend::Software
+            };
+            assert_eq!(backend, expected);
+        }
+    }
+
+    #[test]
+    fn roots_do_not_depend_on_backend() {
+        let mut tree = MerkleTree::new_legacy(None);
+        tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+        assert_eq!(
+            tree.root_node().unwrap().hash().to_string(),
+            "725367a8cee028cf3360c19d20c175733191562b01e60d093e81d8570e865f81"
+        );
+        assert!(["sha-ni", "arm-sha2", "asm", "software"].contains(&backend().name()));
+    }
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "arkworks")]
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_crypto_primitives::sponge::constraints::CryptographicSpongeVar;
+    use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_r1cs_std::boolean::Boolean;
+    use ark_r1cs_std::eq::EqGadget;
+    use ark_r1cs_std::fields::fp::FpVar;
+    use ark_r1cs_std::select::CondSelectGadget;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rs_merkletree::arkworks::{hash_children, poseidon_config, FieldMerkleTree, FieldProof};
+
+    #[test]
+    fn matches_a_fully_padded_tree() {
+        let config = poseidon_config::<Fr>();
+        let leaves: Vec<Fr> = (10..15u64).map(Fr::from).collect();
+        let tree = FieldMerkleTree::new(config.clone(), &leaves, 3).unwrap();
+
+        let mut level = leaves.clone();
+        level.resize(8, Fr::from(0u64));
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| hash_children(&config, pair[0], pair[1]))
+                .collect();
+        }
+        assert_eq!(tree.root(), level[0]);
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = tree.prove(index).unwrap();
+            assert_eq!(proof.leaf_index(), index as u64);
+            assert!(proof.verify(&config, tree.root(), *leaf));
+            assert!(!proof.verify(&config, tree.root(), *leaf + Fr::from(1u64)));
+
+            let mut bytes = Vec::new();
+            proof.serialize_compressed(&mut bytes).unwrap();
+            assert_eq!(
+                FieldProof::deserialize_compressed(&bytes[..]).unwrap(),
+                proof
+            );
+        }
+        assert!(tree.prove(8).is_none());
+        // Positions past the 5 committed leaves hold zero padding.
+        let padding = tree.prove(6).unwrap();
+        assert!(padding.verify(&config, tree.root(), Fr::from(0u64)));
+        assert!(!padding.verify_sized(&config, tree.root(), Fr::from(0u64), 5));
+        assert!(tree
+            .prove(4)
+            .unwrap()
+            .verify_sized(&config, tree.root(), leaves[4], 5));
+        assert!(FieldMerkleTree::new(config, &[Fr::from(0u64); 9], 3).is_none());
+    }
+
+    #[test]
+    fn proofs_satisfy_the_poseidon_gadget() {
+        let config = poseidon_config::<Fr>();
+        let leaves: Vec<Fr> = (1..=6u64).map(Fr::from).collect();
+        let tree = FieldMerkleTree::new(config.clone(), &leaves, 4).unwrap();
+        let proof = tree.prove(5).unwrap();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let root = FpVar::new_input(cs.clone(), || Ok(tree.root())).unwrap();
+        let mut node = FpVar::new_witness(cs.clone(), || Ok(leaves[5])).unwrap();
+        for (sibling, is_right) in proof.path.iter().zip(&proof.index_bits) {
+            let sibling = FpVar::new_witness(cs.clone(), || Ok(*sibling)).unwrap();
+            let is_right = Boolean::new_witness(cs.clone(), || Ok(*is_right)).unwrap();
+            let left = FpVar::conditionally_select(&is_right, &sibling, &node).unwrap();
+            let right = FpVar::conditionally_select(&is_right, &node, &sibling).unwrap();
+            let mut sponge = PoseidonSpongeVar::new(cs.clone(), &config);
+            sponge.absorb(&vec![left, right]).unwrap();
+            node = sponge.squeeze_field_elements(1).unwrap().remove(0);
+        }
+        node.enforce_equal(&root).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
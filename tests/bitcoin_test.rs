@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::bitcoin::{
+        from_display_hex, has_duplicate_tail, merkle_root, merkle_root_internal,
+    };
+
+    #[test]
+    fn matches_block_100000() {
+        let txids = [
+            "8c14f0db3df150123e6f3dbbf30f8b955a8249b62ac1d1ff16284aefa3d06d87",
+            "fff2525b8931402dd09222c50775608f75787bd2b87e56995a7bdd30f79702c4",
+            "6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4",
+            "e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d",
+        ];
+        assert_eq!(
+            merkle_root(&txids).unwrap(),
+            "f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766"
+        );
+        // A block with only a coinbase has the coinbase txid as its root.
+        assert_eq!(merkle_root(&txids[..1]).unwrap(), txids[0]);
+        assert!(merkle_root(&["abcd"]).is_none());
+    }
+
+    #[test]
+    fn duplicate_last_rule_and_mutation() {
+        let txids: Vec<Vec<u8>> = (1..=3u8).map(|i| vec![i; 32]).collect();
+        let mut mutated = txids.clone();
+        mutated.push(txids[2].clone());
+        assert_eq!(merkle_root_internal(&txids), merkle_root_internal(&mutated));
+        assert!(!has_duplicate_tail(&txids));
+        assert!(has_duplicate_tail(&mutated));
+        assert_eq!(
+            from_display_hex("00000000000000000000000000000000000000000000000000000000000000ff")
+                .unwrap()[0],
+            0xff
+        );
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::bittorrent::{verify_piece_layer, TorrentFileTree, BLOCK_SIZE};
+
+    fn sha256(data: &[u8]) -> Vec<u8> {
+        use crypto::{digest::Digest, sha2::Sha256};
+        let mut hasher = Sha256::new();
+        hasher.input(data);
+        let mut out = vec![0u8; 32];
+        hasher.result(&mut out);
+        out
+    }
+
+    #[test]
+    fn pieces_root_pads_with_zero_leaves() {
+        let single = TorrentFileTree::from_reader(&b"hello"[..]).unwrap();
+        assert_eq!(single.pieces_root(), Some(sha256(b"hello")));
+        assert_eq!(
+            TorrentFileTree::from_reader(&b""[..])
+                .unwrap()
+                .pieces_root(),
+            None
+        );
+
+        // Three blocks are padded to four with an all-zero leaf hash.
+        let contents = vec![9u8; 2 * BLOCK_SIZE + 1];
+        let tree = TorrentFileTree::from_reader(&contents[..]).unwrap();
+        let a = sha256(&contents[..BLOCK_SIZE]);
+        let c = sha256(&contents[2 * BLOCK_SIZE..]);
+        let left = sha256(&[a.clone(), a].concat());
+        let right = sha256(&[c, vec![0u8; 32]].concat());
+        assert_eq!(tree.pieces_root(), Some(sha256(&[left, right].concat())));
+    }
+
+    #[test]
+    fn piece_layers_verify_against_root() {
+        let contents: Vec<u8> = (0..11 * BLOCK_SIZE + 7).map(|i| (i % 253) as u8).collect();
+        let tree = TorrentFileTree::from_reader(&contents[..]).unwrap();
+        let root = tree.pieces_root().unwrap();
+        for blocks_per_piece in [1u64, 2, 4, 8] {
+            let piece_length = blocks_per_piece * BLOCK_SIZE as u64;
+            let layer = tree.piece_layer(piece_length).unwrap();
+            assert_eq!(layer.len() as u64, tree.len().div_ceil(piece_length));
+            assert!(verify_piece_layer(&root, &layer, piece_length, tree.len()));
+            assert_eq!(
+                tree.piece_layer_bytes(piece_length).unwrap().len(),
+                layer.len() * 32
+            );
+
+            let mut tampered = layer.clone();
+            tampered[0][0] ^= 1;
+            assert!(!verify_piece_layer(
+                &root,
+                &tampered,
+                piece_length,
+                tree.len()
+            ));
+        }
+        // Single-piece files and invalid piece lengths have no layer.
+        assert!(tree.piece_layer(16 * BLOCK_SIZE as u64).is_none());
+        assert!(tree.piece_layer(3 * BLOCK_SIZE as u64).is_none());
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::builder::BackgroundBuilder;
+    use rs_merkletree::MerkleTree;
+    use std::thread;
+
+    #[test]
+    fn matches_build_tree_for_every_size() {
+        for size in 0..=33 {
+            let data: Vec<String> = (0..size).map(|i| format!("leaf {}", i)).collect();
+            let builder = BackgroundBuilder::spawn(4);
+            for leaf in &data {
+                builder.push(leaf.clone()).unwrap();
+            }
+            let built = builder.finalize();
+            let mut expected = MerkleTree::new(None);
+            expected.build_tree(data.iter().map(String::as_str).collect());
+            assert_eq!(built.root_node(), expected.root_node(), "size {}", size);
+        }
+    }
+
+    #[test]
+    fn concurrent_producers() {
+        let builder = BackgroundBuilder::spawn(16);
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let sender = builder.sender();
+                thread::spawn(move || {
+                    for i in 0..250 {
+                        sender.send(format!("{}-{}", p, i)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let tree = builder.finalize();
+        assert_eq!(tree.count_leaves(), 1000);
+        assert_eq!(tree.depth(), Some(11));
+        assert!(tree.proof(999).is_some());
+        assert!(tree.proof(1000).is_none());
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::limits::Limits;
+    use rs_merkletree::{Hash, MerkleError, MerkleTree};
+
+    #[test]
+    fn built_trees_match_build_tree() {
+        let constructors: [fn() -> MerkleTree; 3] = [
+            || MerkleTree::new(None),
+            || MerkleTree::new_legacy(None),
+            || MerkleTree::new(None).with_sorted_leaves(),
+        ];
+        for new in constructors {
+            for size in 1..=9 {
+                let data: Vec<String> = (0..size).map(|i| format!("leaf {}", 9 - i)).collect();
+                let data: Vec<&str> = data.iter().map(String::as_str).collect();
+                let built = new().build(data.clone()).unwrap();
+                let mut tree = new();
+                tree.build_tree(data);
+                assert_eq!(Some(built.root()), tree.root());
+                assert_eq!(Some(built.depth()), tree.depth());
+                assert_eq!(built.root_node(), &tree.root_node().unwrap());
+                assert_eq!(built.count_leaves(), size);
+                assert_eq!(built.is_legacy(), tree.is_legacy());
+            }
+        }
+
+        let built = MerkleTree::new(None).build(vec!["a", "b", "c"]).unwrap();
+        assert!(built.includes(&Hash::leaf(b"b")));
+        assert!(!built.includes(&Hash::leaf(b"d")));
+        let mut tree = built.into_tree();
+        tree.build_tree(vec!["d"]);
+        assert_eq!(tree.depth(), Some(2));
+        assert_eq!(MerkleTree::new(None).depth(), None);
+    }
+
+    #[test]
+    fn build_rejects_empty_and_oversized_data() {
+        assert_eq!(
+            MerkleTree::new(None).build(Vec::new()).unwrap_err(),
+            MerkleError::EmptyTree
+        );
+        let limited = MerkleTree::new(None).with_limits(Limits {
+            max_leaves: 2,
+            ..Limits::default()
+        });
+        assert_eq!(
+            limited.build(vec!["a", "b", "c"]).unwrap_err(),
+            MerkleError::TooManyLeaves {
+                limit: 2,
+                actual: 3
+            }
+        );
+    }
+}
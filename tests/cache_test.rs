@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::transcript::Transcript;
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn cached_rebuilds_match_and_skip_hashing() {
+        let data: Vec<String> = (0..100).map(|i| format!("row {}", i)).collect();
+        let mut changed = data.clone();
+        changed[42] = String::from("row 42 updated");
+        changed.push(String::from("row 100"));
+        for new in [MerkleTree::new, MerkleTree::new_legacy] {
+            let mut cached = new(None).with_leaf_cache();
+            for (round, rows) in [&data, &changed, &changed].into_iter().enumerate() {
+                let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+                if round == 2 {
+                    cached.rebuild(rows.clone());
+                } else {
+                    cached.build_tree(rows.clone());
+                }
+                let mut plain = new(None);
+                plain.build_tree(rows);
+                assert_eq!(cached.root_node(), plain.root_node());
+            }
+            let cache = cached.leaf_cache().unwrap();
+            assert_eq!(cache.misses(), 100 + 2);
+            assert_eq!(cache.hits(), 99 + 101);
+            // "row 42" is no longer in the data and was evicted.
+            assert_eq!(cache.len(), 101);
+        }
+        assert!(MerkleTree::new(None).leaf_cache().is_none());
+    }
+
+    #[test]
+    fn recorded_builds_bypass_the_cache() {
+        let mut tree = MerkleTree::new(None).with_leaf_cache();
+        tree.build_tree(vec!["a", "b", "c"]);
+        let mut transcript = Transcript::new(Vec::new());
+        tree.build_tree_recorded(vec!["a", "b", "d"], &mut transcript)
+            .unwrap();
+        let cache = tree.leaf_cache().unwrap();
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 3, 3));
+
+        let mut plain = MerkleTree::new(None);
+        plain.build_tree(vec!["a", "b", "d"]);
+        assert_eq!(tree.root_node(), plain.root_node());
+    }
+}
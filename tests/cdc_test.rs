@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::cdc::FastCdc;
+    use rs_merkletree::file::FileTree;
+
+    fn pseudo_random(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chunks_respect_bounds_and_reassemble() {
+        let data = pseudo_random(200_000, 7);
+        let cdc = FastCdc::new(512, 2048, 8192);
+        let chunks: Vec<&[u8]> = cdc.chunks(&data).collect();
+        assert_eq!(chunks.concat(), data);
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert!(chunk.len() >= 512 && chunk.len() <= 8192);
+        }
+        let average = data.len() / chunks.len();
+        assert!(average > 1024 && average < 4096, "average {}", average);
+
+        let tree = FileTree::from_reader_cdc(&data[..], &cdc).unwrap();
+        assert_eq!(tree.chunk_count(), chunks.len() as u64);
+        let root = tree.root();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let range = tree.chunk_range(index as u64).unwrap();
+            assert_eq!(&data[range.start as usize..range.end as usize], *chunk);
+            assert!(tree.prove(index as u64).unwrap().verify(&root, chunk));
+        }
+    }
+
+    #[test]
+    fn insertion_only_changes_nearby_chunks() {
+        let original = pseudo_random(200_000, 11);
+        let mut edited = original.clone();
+        edited.splice(100_000..100_000, b"inserted bytes".iter().copied());
+
+        let cdc = FastCdc::new(512, 2048, 8192);
+        let before = FileTree::from_reader_cdc(&original[..], &cdc).unwrap();
+        let after = FileTree::from_reader_cdc(&edited[..], &cdc).unwrap();
+        assert_ne!(before.root(), after.root());
+        let changed = after.changed_chunks(&before);
+        assert!(!changed.is_empty() && changed.len() <= 3, "{:?}", changed);
+
+        // Fixed-size chunks shift after the edit, so most of them change.
+        let fixed_before = FileTree::from_reader(&original[..], 2048).unwrap();
+        let fixed_after = FileTree::from_reader(&edited[..], 2048).unwrap();
+        assert!(fixed_after.changed_chunks(&fixed_before).len() > 40);
+    }
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "circom")]
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_ff::{BigInteger, PrimeField};
+    use rs_merkletree::arkworks::FieldMerkleTree;
+    use rs_merkletree::circom::{input_json, to_decimal, CircomPoseidon};
+
+    #[test]
+    fn matches_circomlib_poseidon() {
+        // poseidon([1, 2]) from circomlibjs.
+        let hash = CircomPoseidon::new().hash(Fr::from(1u64), Fr::from(2u64));
+        let expected = "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a";
+        let hex: String = hash
+            .into_bigint()
+            .to_bytes_be()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        assert_eq!(hex, expected);
+    }
+
+    #[test]
+    fn exports_circuit_input() {
+        let leaves: Vec<Fr> = (10..15u64).map(Fr::from).collect();
+        let hasher = CircomPoseidon::new();
+        let tree = FieldMerkleTree::new(hasher.clone(), &leaves, 3).unwrap();
+        let proof = tree.prove(4).unwrap();
+        assert!(proof.verify(&hasher, tree.root(), leaves[4]));
+
+        let input = input_json(&proof, tree.root(), leaves[4]);
+        assert_eq!(input["root"], to_decimal(tree.root()));
+        assert_eq!(input["leaf"], "14");
+        assert_eq!(input["pathElements"][0], "0");
+        assert_eq!(input["pathIndices"], serde_json::json!([0, 0, 1]));
+        let zeros = hasher.hash(Fr::from(0u64), Fr::from(0u64));
+        assert_eq!(input["pathElements"][1], to_decimal(zeros));
+    }
+}
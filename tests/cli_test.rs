@@ -0,0 +1,103 @@
+#![cfg(feature = "cli")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::cli::run;
+
+    fn rs_merkle(args: &[&str], input: &[u8]) -> Result<(bool, String), String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut out = Vec::new();
+        let status = run(&args, input, &mut out)?;
+        Ok((
+            status,
+            String::from_utf8(out).unwrap().trim_end().to_string(),
+        ))
+    }
+
+    #[test]
+    fn item_roots_and_proofs() {
+        let input = b"Hello\nWorld\nFrom\nRust\n";
+        let (_, root) = rs_merkle(&["root"], input).unwrap();
+        assert_eq!(
+            root,
+            "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
+        );
+        let (_, proof) = rs_merkle(&["prove", "--index", "2"], input).unwrap();
+        let verify = |leaf| {
+            rs_merkle(
+                &["verify", "--root", &root, "--proof", &proof, "--leaf", leaf],
+                b"",
+            )
+        };
+        assert_eq!(verify("From"), Ok((true, "valid".to_string())));
+        assert_eq!(verify("Rust"), Ok((false, "invalid".to_string())));
+        assert!(rs_merkle(&["prove", "--index", "4"], input).is_err());
+        assert!(rs_merkle(&["root"], b"").is_err());
+    }
+
+    #[test]
+    fn chunk_roots_and_proofs() {
+        let data: Vec<u8> = (0..100u8).collect();
+        let (_, root) = rs_merkle(&["root", "--chunk-size", "16"], &data).unwrap();
+        assert_eq!(root.len(), 64);
+        let (_, proof) =
+            rs_merkle(&["prove", "--chunk-size", "16", "--index", "6"], &data).unwrap();
+        assert!(proof.starts_with("chunk:6:7:"));
+
+        let dir = std::env::temp_dir().join(format!("rs-merkle-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let chunk = dir.join("chunk");
+        std::fs::write(&chunk, &data[96..]).unwrap();
+        let chunk = chunk.to_str().unwrap();
+        let (valid, _) = rs_merkle(
+            &[
+                "verify",
+                "--root",
+                &root,
+                "--proof",
+                &proof,
+                "--leaf-file",
+                chunk,
+            ],
+            b"",
+        )
+        .unwrap();
+        assert!(valid);
+        std::fs::write(dir.join("chunk"), &data[95..]).unwrap();
+        let (valid, _) = rs_merkle(
+            &[
+                "verify",
+                "--root",
+                &root,
+                "--proof",
+                &proof,
+                "--leaf-file",
+                chunk,
+            ],
+            b"",
+        )
+        .unwrap();
+        assert!(!valid);
+        std::fs::remove_dir_all(dir).unwrap();
+
+        assert!(rs_merkle(&["root", "--chunk-size", "0"], &data).is_err());
+        assert!(rs_merkle(&["frobnicate"], &data).is_err());
+    }
+
+    #[test]
+    fn diff_files() {
+        let dir = std::env::temp_dir().join(format!("rs-merkle-diff-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (a, b) = (dir.join("a.txt"), dir.join("b.txt"));
+        std::fs::write(&a, "one\ntwo\nthree\nfour\nfive\n").unwrap();
+        std::fs::write(&b, "one\nTWO\nthree\nfour\n").unwrap();
+        let (a, b) = (a.to_str().unwrap(), b.to_str().unwrap());
+        assert_eq!(
+            rs_merkle(&["diff", a, b], b""),
+            Ok((false, "1\ttwo\tTWO\n4\tfive".to_string()))
+        );
+        assert_eq!(rs_merkle(&["diff", a, a], b""), Ok((true, String::new())));
+        assert!(rs_merkle(&["diff", a], b"").is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
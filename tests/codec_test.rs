@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::codec::{from_base64, from_hex, to_base64, to_hex};
+    use rs_merkletree::proof::{IndexedProof, MerkleProof, SizedProof};
+    use rs_merkletree::{Hash, MerkleError, MerkleTree};
+
+    #[test]
+    fn codecs_round_trip_and_locate_errors() {
+        for len in 0..10 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37 + 200) as u8).collect();
+            assert_eq!(from_hex(&to_hex(&bytes)), Ok(bytes.clone()));
+            assert_eq!(from_hex(&to_hex(&bytes).to_uppercase()), Ok(bytes.clone()));
+            let text = to_base64(&bytes);
+            assert_eq!(text.len() % 4, 0);
+            assert_eq!(from_base64(&text), Ok(bytes.clone()));
+            assert_eq!(from_base64(text.trim_end_matches('=')), Ok(bytes));
+        }
+        assert_eq!(to_base64(b"hello"), "aGVsbG8=");
+        assert_eq!(
+            from_hex("0g"),
+            Err(MerkleError::InvalidEncoding { position: 1 })
+        );
+        assert_eq!(
+            from_hex("abc"),
+            Err(MerkleError::InvalidEncoding { position: 3 })
+        );
+        assert_eq!(
+            from_base64("aG*s"),
+            Err(MerkleError::InvalidEncoding { position: 2 })
+        );
+        assert_eq!(
+            from_base64("aGVsb"),
+            Err(MerkleError::InvalidEncoding { position: 5 })
+        );
+        assert_eq!(
+            from_base64("aG==="),
+            Err(MerkleError::InvalidEncoding { position: 4 })
+        );
+    }
+
+    #[test]
+    fn hashes_and_proofs_encode_as_text() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+        let root = tree.root().unwrap();
+        assert_eq!(root.to_hex(), root.to_string());
+        assert_eq!(Hash::from_hex(&root.to_hex()), Ok(root));
+        assert_eq!(Hash::from_base64(&root.to_base64()), Ok(root));
+        assert_eq!(root.to_base64().len(), 44);
+        assert_eq!(
+            Hash::from_base64("AAAA"),
+            Err(MerkleError::InvalidHashLength {
+                expected: 32,
+                actual: 3
+            })
+        );
+
+        let proof = tree.proof(2).unwrap();
+        assert_eq!(MerkleProof::from_hex(&proof.to_hex()), Some(proof.clone()));
+        assert_eq!(
+            MerkleProof::from_base64(&proof.to_base64()),
+            Some(proof.clone())
+        );
+        assert_eq!(MerkleProof::from_hex("zz"), None);
+        let sized = tree.sized_proof(2).unwrap();
+        assert_eq!(SizedProof::from_base64(&sized.to_base64()), Some(sized));
+        let indexed = tree.indexed_proof(2).unwrap();
+        assert_eq!(IndexedProof::from_hex(&indexed.to_hex()), Some(indexed));
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use rs_merkletree::commit_reveal::{commitment, BlindedTree, NONCE_LEN};
+    use rs_merkletree::MerkleTree;
+
+    fn votes() -> Vec<Vec<u8>> {
+        ["yes", "no", "yes", "yes", "no"]
+            .iter()
+            .map(|vote| vote.as_bytes().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn reveals_verify_against_the_root() {
+        let tree = BlindedTree::new(votes());
+        let root = tree.root().unwrap();
+        for index in 0..tree.len() {
+            let reveal = tree.reveal(index).unwrap();
+            assert!(reveal.verify(&root));
+
+            let mut forged = reveal.clone();
+            forged.value = if forged.value == b"yes" {
+                b"no".to_vec()
+            } else {
+                b"yes".to_vec()
+            };
+            assert!(!forged.verify(&root));
+            let mut forged = reveal;
+            forged.nonce[0] ^= 1;
+            assert!(!forged.verify(&root));
+        }
+        assert!(tree.reveal(5).is_none());
+
+        // Equal values get different commitments, and trees over the same values different roots.
+        let commitments = tree.commitments();
+        assert_ne!(commitments[0], commitments[2]);
+        assert_ne!(BlindedTree::new(votes()).root(), Some(root));
+    }
+
+    #[test]
+    fn trees_restore_from_nonces() {
+        let nonces: Vec<[u8; NONCE_LEN]> = (0..5u8).map(|i| [i; NONCE_LEN]).collect();
+        let tree = BlindedTree::with_nonces(votes(), nonces.clone()).unwrap();
+        let restored = BlindedTree::with_nonces(votes(), nonces.clone()).unwrap();
+        assert_eq!(tree.root(), restored.root());
+
+        // The leaves are the hex commitments, so the public tree can be rebuilt from them alone.
+        let commitments: Vec<String> = votes()
+            .iter()
+            .zip(&nonces)
+            .map(|(vote, nonce)| String::from_utf8(commitment(vote, nonce)).unwrap())
+            .collect();
+        let mut public = MerkleTree::new(None);
+        public.build_tree(commitments.iter().map(String::as_str).collect());
+        assert_eq!(public.root_node().map(|root| root.hash()), tree.root());
+        assert!(BlindedTree::with_nonces(Vec::new(), Vec::new())
+            .unwrap()
+            .root()
+            .is_none());
+        assert!(BlindedTree::with_nonces(votes(), nonces[1..].to_vec()).is_none());
+    }
+
+    #[test]
+    fn injected_rng_makes_nonces_reproducible() {
+        let tree = BlindedTree::new_with_rng(votes(), &mut ChaCha8Rng::seed_from_u64(0));
+        let again = BlindedTree::new_with_rng(votes(), &mut ChaCha8Rng::seed_from_u64(0));
+        assert_eq!(tree.root(), again.root());
+        assert_ne!(
+            BlindedTree::new_with_rng(votes(), &mut ChaCha8Rng::seed_from_u64(1)).root(),
+            tree.root()
+        );
+        assert!(tree.reveal(3).unwrap().verify(&tree.root().unwrap()));
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::concurrent::{
+        compute_root, proof_from_leaves, CmtError, ConcurrentMerkleTree,
+    };
+
+    fn leaf(i: u32) -> Vec<u8> {
+        let mut bytes = vec![0u8; 32];
+        bytes[..4].copy_from_slice(&(i + 1).to_be_bytes());
+        bytes
+    }
+
+    fn reference_root(leaves: &[Vec<u8>], depth: usize) -> Vec<u8> {
+        let proof = proof_from_leaves(leaves, 0, depth);
+        compute_root(leaves.first().unwrap_or(&vec![0u8; 32]), &proof, 0).unwrap()
+    }
+
+    #[test]
+    fn stale_proofs_are_fast_forwarded() {
+        let depth = 5;
+        let mut tree = ConcurrentMerkleTree::new(depth, 16);
+        let mut leaves = Vec::new();
+        for i in 0..13 {
+            leaves.push(leaf(i));
+            tree.append(&leaves[i as usize]).unwrap();
+            assert_eq!(tree.root(), reference_root(&leaves, depth));
+        }
+
+        // Every writer proves against the same snapshot, interleaved with appends.
+        let snapshot = tree.root();
+        let snapshot_leaves = leaves.clone();
+        for (n, index) in [3usize, 12, 0, 7, 8].iter().enumerate() {
+            let proof = proof_from_leaves(&snapshot_leaves, *index, depth);
+            let new_leaf = leaf(100 + n as u32);
+            tree.replace_leaf(
+                &snapshot,
+                &snapshot_leaves[*index],
+                &new_leaf,
+                &proof,
+                *index as u64,
+            )
+            .unwrap();
+            leaves[*index] = new_leaf;
+            leaves.push(leaf(200 + n as u32));
+            tree.append(leaves.last().unwrap()).unwrap();
+            assert_eq!(tree.root(), reference_root(&leaves, depth));
+        }
+
+        // A second stale update of the same leaf is rejected.
+        let proof = proof_from_leaves(&snapshot_leaves, 3, depth);
+        assert_eq!(
+            tree.replace_leaf(&snapshot, &snapshot_leaves[3], &leaf(999), &proof, 3),
+            Err(CmtError::LeafContentsModified)
+        );
+        assert_eq!(
+            tree.replace_leaf(&[1u8; 32], &snapshot_leaves[3], &leaf(999), &proof, 3),
+            Err(CmtError::RootNotFound)
+        );
+        assert_eq!(
+            tree.replace_leaf(&snapshot, &snapshot_leaves[4], &leaf(999), &proof, 4),
+            Err(CmtError::InvalidProof)
+        );
+        assert_eq!(
+            tree.replace_leaf(&snapshot, &snapshot_leaves[4], &leaf(999), &proof, 31),
+            Err(CmtError::LeafIndexOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn full_tree_and_buffer_eviction() {
+        let mut tree = ConcurrentMerkleTree::new(2, 2);
+        let first = tree.root();
+        for i in 0..4 {
+            tree.append(&leaf(i)).unwrap();
+        }
+        assert_eq!(tree.append(&leaf(4)), Err(CmtError::TreeFull));
+        let proof = proof_from_leaves(&[], 0, 2);
+        assert_eq!(
+            tree.replace_leaf(&first, &[0u8; 32], &leaf(9), &proof, 0),
+            Err(CmtError::RootNotFound)
+        );
+    }
+
+    #[test]
+    fn overlong_proofs_have_no_root() {
+        let proof = proof_from_leaves(&[leaf(0)], 0, 64);
+        assert!(compute_root(&leaf(0), &proof, u64::MAX).is_some());
+        let proof = vec![vec![0u8; 32]; 65];
+        assert_eq!(compute_root(&leaf(0), &proof, u64::MAX), None);
+    }
+}
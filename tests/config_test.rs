@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::codec::from_hex;
+    use rs_merkletree::config::{ConfigError, DuplicateLeaves, HashFunction, OddNode, TreeConfig};
+    use rs_merkletree::history::HistoryTree;
+
+    #[test]
+    fn merkletreejs_vectors() {
+        // Roots printed by merkletreejs for leaves `['a', 'b', 'c'].map(x => hash(x))`.
+        for (hash_function, sort_pairs, root) in [
+            // new MerkleTree(leaves, SHA256), from the library's README.
+            (
+                HashFunction::Sha256,
+                false,
+                "7075152d03a5cd92104887b476862778ec0c87be5c2fa1c0a90f87c49fad6eff",
+            ),
+            // new MerkleTree(leaves, keccak256, { sortPairs: true }), from its tests.
+            (
+                HashFunction::Keccak256,
+                true,
+                "5842148bc6ebeb52af882a317c765fccd3ae80589b21a9b8cbf21abb630e46a7",
+            ),
+        ] {
+            let config = TreeConfig {
+                sort_pairs,
+                ..TreeConfig::merkletreejs(hash_function)
+            };
+            let leaves: Vec<Vec<u8>> = [b"a", b"b", b"c"]
+                .iter()
+                .map(|leaf| hash_function.digest(&[*leaf]))
+                .collect();
+            assert_eq!(config.root(&leaves), Some(from_hex(root).unwrap()));
+        }
+    }
+
+    #[test]
+    fn merkletreejs_options() {
+        let leaves: Vec<&[u8]> = vec![b"d", b"a", b"c", b"b", b"e"];
+        let base = TreeConfig {
+            hash_leaves: true,
+            ..TreeConfig::merkletreejs(HashFunction::Keccak256)
+        };
+        let h = |parts: &[&[u8]]| HashFunction::Keccak256.digest(parts);
+        let hashed: Vec<Vec<u8>> = leaves.iter().map(|leaf| h(&[leaf])).collect();
+        // The default promotes the odd fifth leaf.
+        let ab = h(&[&hashed[0], &hashed[1]]);
+        let cd = h(&[&hashed[2], &hashed[3]]);
+        assert_eq!(base.root(&leaves), Some(h(&[&h(&[&ab, &cd]), &hashed[4]])));
+
+        let duplicate = TreeConfig {
+            odd_node: OddNode::Duplicate,
+            ..base
+        };
+        let ee = h(&[&hashed[4], &hashed[4]]);
+        let eeee = h(&[&ee, &ee]);
+        assert_eq!(duplicate.root(&leaves), Some(h(&[&h(&[&ab, &cd]), &eeee])));
+
+        // sortLeaves makes the root independent of order; sortPairs makes it independent of sibling order.
+        let sorted = TreeConfig {
+            sort_leaves: true,
+            ..base
+        };
+        let mut reordered = leaves.clone();
+        reordered.reverse();
+        assert_eq!(sorted.root(&leaves), sorted.root(&reordered));
+        let pairs = TreeConfig {
+            sort_pairs: true,
+            ..base
+        };
+        assert_eq!(pairs.root(&[b"x", b"y"]), pairs.root(&[b"y", b"x"]));
+        assert_ne!(base.root(&[b"x", b"y"]), base.root(&[b"y", b"x"]));
+    }
+
+    #[test]
+    fn rfc6962_preset_matches_history_tree() {
+        let leaves: Vec<Vec<u8>> = (0..13u8).map(|i| vec![i]).collect();
+        let mut history = HistoryTree::new();
+        for leaf in &leaves {
+            history.append(leaf);
+        }
+        assert_eq!(TreeConfig::rfc6962().root(&leaves), Some(history.root()));
+    }
+
+    #[test]
+    fn duplicate_leaves() {
+        let leaves: Vec<&[u8]> = vec![b"a", b"b", b"a", b"c", b"b", b"a"];
+        let allow = TreeConfig::rfc6962();
+        assert_eq!(allow.find_duplicates(&leaves), vec![2, 4, 5]);
+        assert_eq!(allow.build_layers(&leaves)[0].len(), 6);
+
+        let reject = TreeConfig {
+            duplicate_leaves: DuplicateLeaves::Reject,
+            ..allow
+        };
+        assert_eq!(
+            reject.try_build_layers(&leaves),
+            Err(ConfigError::DuplicateLeaves(vec![2, 4, 5]))
+        );
+        assert_eq!(reject.root(&leaves), None);
+        assert_eq!(
+            reject.try_root(&leaves),
+            Err(ConfigError::DuplicateLeaves(vec![2, 4, 5]))
+        );
+        assert_eq!(reject.try_root(&leaves[..2]), Ok(reject.root(&leaves[..2])));
+        assert_eq!(
+            ConfigError::DuplicateLeaves(vec![2, 4, 5]).to_string(),
+            "leaves at indices [2, 4, 5] repeat an earlier leaf"
+        );
+        assert!(reject.try_build_layers(&leaves[..2]).is_ok());
+
+        let remove = TreeConfig {
+            duplicate_leaves: DuplicateLeaves::Remove,
+            ..allow
+        };
+        let unique: Vec<&[u8]> = vec![b"a", b"b", b"c"];
+        assert_eq!(remove.root(&leaves), allow.root(&unique));
+    }
+}
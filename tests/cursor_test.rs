@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::proof::Position;
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn cursor_visits_every_node() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a", "b", "c", "d", "e"]);
+        let mut cursor = tree.cursor().unwrap();
+        // Depth-first walk using only cursor moves.
+        let mut visited = 0;
+        loop {
+            visited += 1;
+            assert_eq!(
+                tree.node(cursor.depth(), cursor.index()).unwrap().hash(),
+                *cursor.current_hash()
+            );
+            if cursor.go_left() {
+                continue;
+            }
+            loop {
+                let came_from_left = cursor.path().last() == Some(Position::Left);
+                if !cursor.go_up() {
+                    assert_eq!(visited, tree.stats().nodes);
+                    return;
+                }
+                if came_from_left && cursor.go_right() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cursor_stops_at_edges() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a"]);
+        let mut cursor = tree.cursor().unwrap();
+        assert!(!cursor.go_up());
+        assert!(!cursor.go_right());
+        assert!(cursor.go_left());
+        assert!(cursor.is_leaf());
+        assert!(!cursor.go_left() && !cursor.go_right());
+        assert_eq!(cursor.depth(), 1);
+        assert!(cursor.go_up());
+        assert_eq!(*cursor.current_hash(), tree.root_node().unwrap().hash());
+        assert!(MerkleTree::new(None).cursor().is_none());
+    }
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "debug-validate")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::concurrent::{proof_from_leaves, ConcurrentMerkleTree};
+    use rs_merkletree::history::HistoryTree;
+    use rs_merkletree::incremental::IncrementalMerkleTree;
+    use rs_merkletree::indexed::IndexedMerkleTree;
+    use rs_merkletree::jmt::JellyfishMerkleTree;
+    use rs_merkletree::mmr::MerkleMountainRange;
+    use rs_merkletree::mst::MerkleSearchTree;
+    use rs_merkletree::smt::SparseMerkleTree;
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn builds_and_appends_pass_their_checks() {
+        for size in 1..=17 {
+            let data: Vec<String> = (0..size).map(|i| i.to_string()).collect();
+            let mut tree = MerkleTree::new(None);
+            tree.build_tree(data.iter().map(String::as_str).collect());
+        }
+        let mut history = HistoryTree::new();
+        for i in 0..33u32 {
+            history.append(&i.to_be_bytes());
+        }
+        assert_eq!(history.len(), 33);
+    }
+
+    #[test]
+    fn updates_pass_their_checks() {
+        let mut indexed = IndexedMerkleTree::new(8);
+        for value in [5u8, 1, 9, 3] {
+            indexed.insert([value; 32]);
+        }
+        assert!(indexed.contains(&[3; 32]));
+
+        let mut tree = ConcurrentMerkleTree::new(3, 8);
+        let mut leaves = Vec::new();
+        for i in 0..5u8 {
+            tree.append(&[i; 32]).unwrap();
+            leaves.push(vec![i; 32]);
+        }
+        let proof = proof_from_leaves(&leaves, 1, 3);
+        tree.replace_leaf(&tree.root(), &leaves[1], &[9; 32], &proof, 1)
+            .unwrap();
+    }
+
+    #[test]
+    fn appends_to_fixed_depth_trees_pass_their_checks() {
+        let mut incremental = IncrementalMerkleTree::new(6);
+        for i in 0..9u8 {
+            incremental.insert(&[i; 32]).unwrap();
+        }
+        incremental
+            .extend_leaves((9..40u8).map(|i| [i; 32]))
+            .unwrap();
+        assert_eq!(incremental.len(), 40);
+
+        let mut mmr = MerkleMountainRange::new();
+        for i in 0..33u32 {
+            mmr.append(&i.to_be_bytes());
+        }
+        assert_eq!(mmr.leaf_count(), 33);
+    }
+
+    #[test]
+    fn key_value_updates_pass_their_checks() {
+        let mut smt = SparseMerkleTree::new();
+        for key in ["a", "b", "c"] {
+            smt.insert(key.as_bytes(), b"value");
+        }
+        smt.remove(b"b");
+        smt.remove(b"missing");
+
+        let mut jmt = JellyfishMerkleTree::new();
+        let keys: Vec<[u8; 4]> = (0..40u32).map(u32::to_be_bytes).collect();
+        let puts: Vec<(&[u8], Option<&[u8]>)> =
+            keys.iter().map(|key| (&key[..], Some(&b"v"[..]))).collect();
+        jmt.put_value_set(1, &puts).unwrap();
+        let deletes: Vec<(&[u8], Option<&[u8]>)> =
+            keys[..20].iter().map(|key| (&key[..], None)).collect();
+        jmt.put_value_set(2, &deletes).unwrap();
+
+        let mut mst = MerkleSearchTree::new();
+        for key in &keys {
+            mst.insert(key);
+        }
+        for key in &keys[..20] {
+            mst.remove(key);
+        }
+        assert_eq!(mst.len(), 20);
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "rayon")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::determinism::{
+        check, check_config, check_tree, config_combinations, Divergence,
+    };
+    use rs_merkletree::proof::{leaf_hash, NodeHasher, TaggedHexSha256};
+    use rs_merkletree::MerkleTree;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn parallel_builds_match_for_every_configuration() {
+        let data: Vec<String> = (0..1000).map(|i| format!("leaf {}", i % 150)).collect();
+        let data: Vec<&str> = data.iter().map(String::as_str).collect();
+        let configs = config_combinations();
+        assert_eq!(configs.len(), 3 * 2 * 3 * 16);
+        for config in &configs {
+            for size in [0, 1, 2, 3, 5, 8, 13, 200] {
+                let root = check_config(config, &data[..size]).unwrap();
+                assert_eq!(root, config.root_parallel(&data[..size]));
+                assert_eq!(root, config.root(&data[..size]));
+            }
+        }
+
+        for tree in [
+            MerkleTree::new(None),
+            MerkleTree::new_legacy(None),
+            MerkleTree::new(None).with_sorted_leaves(),
+        ] {
+            for size in (0..=17).chain([1000]) {
+                let root = check_tree(&tree, &data[..size]).unwrap();
+                let mut parallel = tree.clone();
+                parallel.build_tree_parallel(data[..size].to_vec());
+                assert_eq!(root, parallel.root_node().map(|root| root.hash()));
+            }
+        }
+    }
+
+    /// Mixes a call counter into every hash, so the result depends on the order nodes are hashed in.
+    struct Stateful(AtomicU64);
+
+    impl NodeHasher for Stateful {
+        type Output = [u8; 64];
+
+        fn hash_nodes(&self, left: &[u8], right: &[u8]) -> [u8; 64] {
+            let calls = self.0.fetch_add(1, Ordering::Relaxed);
+            TaggedHexSha256.hash_nodes(left, &[right, &calls.to_be_bytes()].concat())
+        }
+    }
+
+    #[test]
+    fn harness_checks_custom_hashers() {
+        let data: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+        let leaves: Vec<[u8; 64]> = data
+            .iter()
+            .map(|data| leaf_hash(data.as_bytes()).to_hex_bytes())
+            .collect();
+        let leaves: Vec<&[u8]> = leaves.iter().map(|leaf| &leaf[..]).collect();
+
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.iter().map(String::as_str).collect());
+        let root = check(&TaggedHexSha256, &leaves).unwrap();
+        assert_eq!(root, tree.root().map(|root| root.to_hex_bytes().to_vec()));
+        assert_eq!(check(&TaggedHexSha256, &[]), Ok(None));
+
+        assert_eq!(
+            check(&Stateful(AtomicU64::new(0)), &leaves),
+            Err(Divergence { level: 1, index: 0 })
+        );
+    }
+}
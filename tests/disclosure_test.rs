@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use rs_merkletree::disclosure::{
+        field_commitment, verify_disclosures, Disclosable, DisclosureTree, SALT_LEN,
+    };
+
+    struct Credential {
+        subject: &'static str,
+        over_18: bool,
+        country: &'static str,
+    }
+
+    impl Disclosable for Credential {
+        fn fields(&self) -> Vec<(String, Vec<u8>)> {
+            vec![
+                ("subject".into(), self.subject.as_bytes().to_vec()),
+                ("over_18".into(), vec![self.over_18 as u8]),
+                ("country".into(), self.country.as_bytes().to_vec()),
+            ]
+        }
+    }
+
+    fn credential() -> Credential {
+        Credential {
+            subject: "did:example:alice",
+            over_18: true,
+            country: "NL",
+        }
+    }
+
+    #[test]
+    fn disclosed_fields_verify_and_cannot_be_altered() {
+        let tree = DisclosureTree::new(&credential()).unwrap();
+        let root = tree.root().unwrap();
+        assert_eq!(tree.len(), 3);
+
+        let disclosures = tree.disclose(&["over_18", "country"]).unwrap();
+        assert!(verify_disclosures(&root, &disclosures));
+        assert!(tree.disclose(&["email"]).is_none());
+
+        // A value cannot be claimed for another field, changed, or disclosed twice.
+        let mut renamed = disclosures[1].clone();
+        renamed.name = "subject".into();
+        assert!(!renamed.verify(&root));
+        let mut changed = disclosures[0].clone();
+        changed.value = vec![0];
+        assert!(!changed.verify(&root));
+        let twice = vec![disclosures[0].clone(), disclosures[0].clone()];
+        assert!(!verify_disclosures(&root, &twice));
+
+        // Fresh salts give the same document a different root.
+        assert_ne!(
+            DisclosureTree::new(&credential()).unwrap().root(),
+            Some(root)
+        );
+    }
+
+    #[test]
+    fn trees_restore_from_salts() {
+        let tree = DisclosureTree::new(&credential()).unwrap();
+        let fields = credential().fields();
+        let restored = DisclosureTree::with_salts(fields.clone(), tree.salts().to_vec()).unwrap();
+        assert_eq!(restored.root(), tree.root());
+        let proof = restored.tree().proof(2).unwrap();
+        let commitment = field_commitment("country", b"NL", &tree.salts()[2]);
+        assert!(proof.verify_data(&restored.root().unwrap(), &commitment));
+
+        let salts = vec![[0u8; SALT_LEN]; 3];
+        let duplicated = vec![fields[0].clone(), fields[1].clone(), fields[0].clone()];
+        assert!(DisclosureTree::with_salts(duplicated, salts.clone()).is_none());
+        assert!(DisclosureTree::with_salts(fields, salts[..2].to_vec()).is_none());
+    }
+
+    #[test]
+    fn injected_rng_makes_salts_reproducible() {
+        let tree =
+            DisclosureTree::new_with_rng(&credential(), &mut ChaCha8Rng::seed_from_u64(0)).unwrap();
+        let again =
+            DisclosureTree::new_with_rng(&credential(), &mut ChaCha8Rng::seed_from_u64(0)).unwrap();
+        assert_eq!(tree.salts(), again.salts());
+        assert_eq!(tree.root(), again.root());
+        let other =
+            DisclosureTree::new_with_rng(&credential(), &mut ChaCha8Rng::seed_from_u64(1)).unwrap();
+        assert_ne!(other.root(), tree.root());
+
+        let root = tree.root().unwrap();
+        let disclosures = tree.disclose(&["country"]).unwrap();
+        assert!(verify_disclosures(&root, &disclosures));
+    }
+}
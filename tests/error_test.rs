@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::proof::{leaf_hash, validate_hash};
+    use rs_merkletree::{Hash, MerkleError, MerkleTree};
+    use std::error::Error;
+
+    #[test]
+    fn hex_hashes_reject_malformed_input() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a", "b", "c"]);
+        let leaf = leaf_hash(b"b").to_hex_bytes();
+        assert_eq!(
+            Hash::from_hex_bytes(&leaf).map(|leaf| tree.includes(&leaf)),
+            Ok(true)
+        );
+        assert_eq!(
+            Hash::from_hex_bytes(&leaf[..63]),
+            Err(MerkleError::InvalidHashLength {
+                expected: 64,
+                actual: 63
+            })
+        );
+        let upper = leaf.to_ascii_uppercase();
+        let position = upper.iter().position(u8::is_ascii_uppercase).unwrap();
+        assert_eq!(
+            Hash::from_hex_bytes(&upper),
+            Err(MerkleError::InvalidEncoding { position })
+        );
+        assert_eq!(
+            validate_hash(&[b'g'; 64]),
+            Err(MerkleError::InvalidEncoding { position: 0 })
+        );
+    }
+
+    #[test]
+    fn try_verify_checks_every_hash() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a", "b", "c"]);
+        let root = tree.root_node().unwrap().hash().to_hex_bytes();
+        let proof = tree.proof(1).unwrap();
+        let mut leaf = leaf_hash(b"b").to_hex_bytes();
+        assert_eq!(proof.try_verify(&root, &leaf), Ok(true));
+        assert_eq!(
+            proof.try_verify(&root, &leaf_hash(b"c").to_hex_bytes()),
+            Ok(false)
+        );
+        assert_eq!(
+            proof.try_verify(b"root", &leaf),
+            Err(MerkleError::InvalidHashLength {
+                expected: 64,
+                actual: 4
+            })
+        );
+        leaf[10] = b'X';
+        assert_eq!(
+            proof.try_verify(&root, &leaf),
+            Err(MerkleError::InvalidEncoding { position: 10 })
+        );
+    }
+
+    #[test]
+    fn errors_have_stable_codes_and_messages() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a", "b", "c"]);
+        let root = tree.root_node().unwrap().hash();
+        let leaf = leaf_hash(b"c");
+
+        let missing = tree.try_proof(3).unwrap_err();
+        assert_eq!(
+            missing,
+            MerkleError::LeafNotFound {
+                index: 3,
+                leaves: 3
+            }
+        );
+        assert_eq!(tree.try_proof(2).ok(), tree.proof(2));
+        assert_eq!(
+            MerkleTree::new(None).try_proof(0),
+            Err(MerkleError::LeafNotFound {
+                index: 0,
+                leaves: 0
+            })
+        );
+
+        let proof = tree.sized_proof(2).unwrap();
+        assert_eq!(proof.check(&root, &leaf, 3), Ok(()));
+        let mismatch = proof.check(&root, &leaf, 4).unwrap_err();
+        assert_eq!(
+            mismatch,
+            MerkleError::SizeMismatch {
+                expected: 4,
+                actual: 3
+            }
+        );
+        let invalid = proof.check(&root, &leaf_hash(b"a"), 3).unwrap_err();
+        assert_eq!(invalid, MerkleError::InvalidProof);
+
+        let codes: Vec<u16> = [
+            MerkleError::InvalidHashLength {
+                expected: 64,
+                actual: 3,
+            },
+            MerkleError::InvalidEncoding { position: 0 },
+            MerkleError::HashMismatch { depth: 0, index: 0 },
+            MerkleError::MalformedTree { depth: 0, index: 0 },
+            MerkleError::TooManyLeaves {
+                limit: 1,
+                actual: 2,
+            },
+            MerkleError::LeafTooLarge {
+                index: 0,
+                limit: 1,
+                actual: 2,
+            },
+            MerkleError::ProofTooLong {
+                limit: 1,
+                actual: 2,
+            },
+            MerkleError::MalformedProof,
+            missing.clone(),
+            invalid,
+            mismatch.clone(),
+            MerkleError::EmptyTree,
+        ]
+        .iter()
+        .map(MerkleError::code)
+        .collect();
+        assert_eq!(codes, (1..=12).collect::<Vec<u16>>());
+
+        let boxed: Box<dyn Error> = Box::new(mismatch);
+        assert_eq!(
+            boxed.to_string(),
+            "proof is for a tree of 3 leaves, expected 4"
+        );
+        assert_eq!(
+            missing.to_string(),
+            "no leaf at index 3 in a tree of 3 leaves"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "ffi")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::ffi::*;
+    use rs_merkletree::MerkleTree;
+    use std::ptr;
+
+    fn build(data: &[&str]) -> *mut MerkleTree {
+        let ptrs: Vec<*const u8> = data.iter().map(|leaf| leaf.as_ptr()).collect();
+        let lens: Vec<usize> = data.iter().map(|leaf| leaf.len()).collect();
+        unsafe { merkle_build(ptrs.as_ptr(), lens.as_ptr(), data.len()) }
+    }
+
+    #[test]
+    fn build_prove_and_verify_through_handles() {
+        let data = ["Hello", "World", "From", "Rust"];
+        let tree = build(&data);
+        unsafe {
+            assert_eq!(merkle_root(tree, ptr::null_mut(), 0), 64);
+            let mut root = [0u8; 64];
+            merkle_root(tree, root.as_mut_ptr(), root.len());
+            assert_eq!(
+                &root[..],
+                b"a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
+            );
+
+            for (i, leaf) in data.iter().enumerate() {
+                let proof = merkle_proof(tree, i);
+                let mut bytes = vec![0u8; merkle_proof_serialize(proof, ptr::null_mut(), 0)];
+                merkle_proof_serialize(proof, bytes.as_mut_ptr(), bytes.len());
+                merkle_proof_free(proof);
+
+                let parsed = merkle_proof_parse(bytes.as_ptr(), bytes.len());
+                assert!(merkle_verify(
+                    parsed,
+                    root.as_ptr(),
+                    64,
+                    leaf.as_ptr(),
+                    leaf.len()
+                ));
+                assert!(!merkle_verify(parsed, root.as_ptr(), 64, b"x".as_ptr(), 1));
+                merkle_proof_free(parsed);
+            }
+            assert!(merkle_proof(tree, 4).is_null());
+            merkle_free(tree);
+        }
+    }
+
+    #[test]
+    fn invalid_input_returns_null() {
+        assert!(build(&[]).is_null());
+        let leaf = [0xffu8];
+        let ptrs = [leaf.as_ptr()];
+        unsafe {
+            assert!(merkle_build(ptrs.as_ptr(), [1].as_ptr(), 1).is_null());
+            assert!(merkle_proof_parse([7u8].as_ptr(), 1).is_null());
+            assert!(!merkle_verify(ptr::null(), ptr::null(), 0, ptr::null(), 0));
+            merkle_free(ptr::null_mut());
+            merkle_proof_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "tokio")]
+
+#[cfg(test)]
+mod tests {
+    use futures_core::Stream;
+    use rs_merkletree::file::FileTree;
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    /// Yields the given buffers one at a time.
+    struct Buffers(Vec<Vec<u8>>);
+
+    impl Stream for Buffers {
+        type Item = io::Result<Vec<u8>>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            if self.0.is_empty() {
+                return Poll::Ready(None);
+            }
+            Poll::Ready(Some(Ok(self.0.remove(0))))
+        }
+    }
+
+    #[tokio::test]
+    async fn async_reader_matches_blocking_build() {
+        let contents: Vec<u8> = (0..10_000u32).map(|i| (i % 199) as u8).collect();
+        for len in [0, 1, 512, 10_000] {
+            let expected = FileTree::from_reader(&contents[..len], 512).unwrap();
+            let built = FileTree::from_async_reader(&contents[..len], 512)
+                .await
+                .unwrap();
+            assert_eq!(built.root(), expected.root());
+            assert_eq!(built.chunk_count(), expected.chunk_count());
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_of_uneven_buffers_is_regrouped() {
+        let contents: Vec<u8> = (0..3_000u32).map(|i| (i % 97) as u8).collect();
+        let buffers = vec![
+            contents[..1].to_vec(),
+            contents[1..700].to_vec(),
+            Vec::new(),
+            contents[700..2049].to_vec(),
+            contents[2049..].to_vec(),
+        ];
+        let built = FileTree::from_stream(Buffers(buffers), 512).await.unwrap();
+        let expected = FileTree::from_reader(&contents[..], 512).unwrap();
+        assert_eq!(built.root(), expected.root());
+        assert_eq!(built.len(), 3_000);
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::file::{FileHasher, FileTree};
+    use std::io::Write;
+
+    #[test]
+    fn chunk_proofs_verify_and_reject_tampering() {
+        let contents: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let tree = FileTree::from_reader(&contents[..], 1000).unwrap();
+        assert_eq!(tree.chunk_count(), 10);
+        assert_eq!(tree.len(), 10_000);
+
+        let root = tree.root();
+        let chunks: Vec<&[u8]> = contents.chunks(1000).collect();
+        for (proof, chunk) in tree.proofs().iter().zip(&chunks) {
+            assert!(proof.verify(&root, chunk));
+        }
+        let proof = tree.prove(3).unwrap();
+        assert!(!proof.verify(&root, chunks[4]));
+        let mut tampered = chunks[3].to_vec();
+        tampered[0] ^= 1;
+        assert!(!proof.verify(&root, &tampered));
+        assert!(tree.prove(10).is_none());
+    }
+
+    #[test]
+    fn file_matches_reader_and_handles_short_tail() {
+        let contents = b"the quick brown fox jumps over the lazy dog";
+        let path = std::env::temp_dir().join("rs_merkletree_file_test.bin");
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(contents)
+            .unwrap();
+        let from_file = FileTree::open(&path, 8).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let from_reader = FileTree::from_reader(&contents[..], 8).unwrap();
+        assert_eq!(from_file.root(), from_reader.root());
+        assert_eq!(from_file.chunk_count(), 6);
+        let tail = from_file.prove(5).unwrap();
+        assert!(tail.verify(&from_file.root(), b"dog"));
+
+        let empty = FileTree::from_reader(&b""[..], 8).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.chunk_count(), 0);
+    }
+
+    #[test]
+    fn hasher_resumes_from_saved_state() {
+        let contents: Vec<u8> = (0..5_000u32).map(|i| (i * 7 % 256) as u8).collect();
+        for len in [0, 1, 100, 1000, 1001, 4096, 5000] {
+            let expected = FileTree::from_reader(&contents[..len], 100).unwrap().root();
+
+            let mut hasher = FileHasher::new(100);
+            let mut offset = 0;
+            for step in [1, 99, 150, 333, 5000] {
+                let end = (offset + step).min(len);
+                hasher.update(&contents[offset..end]);
+                offset = end;
+                // Simulate a crash after every piece.
+                hasher = FileHasher::resume(&hasher.save_state()).unwrap();
+                assert_eq!(hasher.position(), offset as u64);
+            }
+            assert_eq!(hasher.finalize(), expected, "len={}", len);
+        }
+    }
+
+    #[test]
+    fn hasher_rejects_malformed_state() {
+        let mut hasher = FileHasher::new(64);
+        hasher.update_reader(&[5u8; 1000][..]).unwrap();
+        let state = hasher.save_state();
+        assert!(FileHasher::resume(&state[..state.len() - 1]).is_none());
+        assert!(FileHasher::resume(&state[1..]).is_none());
+        let mut extra = state.clone();
+        extra.push(0);
+        assert!(FileHasher::resume(&extra).is_none());
+    }
+}
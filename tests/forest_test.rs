@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::forest::ForestBuilder;
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn combined_root_matches_monolithic_build() {
+        for shard_height in 1..4 {
+            let builder = ForestBuilder::new(shard_height);
+            for n in [1, 2, 3, 5, 8, 9, 16, 17, 23, 33] {
+                let owned: Vec<String> = (0..n).map(|i| format!("leaf-{}", i)).collect();
+                let data: Vec<&str> = owned.iter().map(String::as_str).collect();
+
+                let mut tree = MerkleTree::new(None);
+                tree.build_tree(data.clone());
+                let expected = tree.root_node().unwrap().hash();
+
+                let mut shards: Vec<_> = data
+                    .chunks(builder.shard_size())
+                    .enumerate()
+                    .map(|(index, chunk)| builder.build_shard(index, chunk))
+                    .collect();
+                shards.reverse();
+                assert_eq!(
+                    builder.combine(&shards),
+                    Some(expected),
+                    "n={} height={}",
+                    n,
+                    shard_height
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_gaps_and_partial_middle_shards() {
+        let builder = ForestBuilder::new(1);
+        let a = builder.build_shard(0, &["a", "b"]);
+        let b = builder.build_shard(1, &["c"]);
+        let c = builder.build_shard(2, &["d", "e"]);
+        assert!(builder.combine(&[a.clone(), c.clone()]).is_none());
+        assert!(builder.combine(&[a, b, c]).is_none());
+        assert!(builder.combine(&[]).is_none());
+    }
+}
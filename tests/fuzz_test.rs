@@ -0,0 +1,46 @@
+#![cfg(feature = "arbitrary")]
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use rs_merkletree::config::TreeConfig;
+    use rs_merkletree::fuzz::{LeafSet, MAX_FUZZ_LEAVES};
+    use rs_merkletree::proof::{MerkleProof, SizedProof};
+
+    fn noise(len: usize) -> Vec<u8> {
+        (0..len as u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn generated_leaf_sets_build() {
+        let data = noise(4096);
+        let mut u = Unstructured::new(&data);
+        for _ in 0..8 {
+            let Ok(set) = LeafSet::arbitrary(&mut u) else {
+                break;
+            };
+            assert!(!set.leaves.is_empty() && set.leaves.len() <= MAX_FUZZ_LEAVES);
+            let tree = set.tree();
+            let root = tree.root_node().unwrap().hash();
+            let last = set.leaves.len() - 1;
+            assert!(tree
+                .proof(last)
+                .unwrap()
+                .verify_data(&root, set.leaves[last].as_bytes()));
+        }
+    }
+
+    #[test]
+    fn generated_proofs_round_trip() {
+        let data = noise(2048);
+        let mut u = Unstructured::new(&data);
+        let config = TreeConfig::arbitrary(&mut u).unwrap();
+        let _ = config.root(&[b"a", b"b", b"c"]);
+        let proof = MerkleProof::arbitrary(&mut u).unwrap();
+        assert_eq!(MerkleProof::from_bytes(&proof.to_bytes()), Some(proof));
+        let sized = SizedProof::arbitrary(&mut u).unwrap();
+        assert_eq!(SizedProof::from_bytes(&sized.to_bytes()), Some(sized));
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::git::{
+        blob_id, dir_tree_id, to_hex, tree_id, GitHash, TreeEntry, FILE_MODE, TREE_MODE,
+    };
+    use std::fs;
+
+    #[test]
+    fn matches_well_known_object_ids() {
+        assert_eq!(
+            to_hex(&blob_id(GitHash::Sha1, b"")),
+            "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+        assert_eq!(
+            to_hex(&tree_id(GitHash::Sha1, &[])),
+            "4b825dc642cb6eb9a060e54bf8d69288fbee4904"
+        );
+        assert_eq!(
+            to_hex(&blob_id(GitHash::Sha256, b"")),
+            "473a0f4c3be8a93681a267e3b1e9a7dcda1185436fe141f7749120a303721813"
+        );
+        assert_eq!(
+            to_hex(&tree_id(GitHash::Sha256, &[])),
+            "6ef19b41225c5369f1c104d45d8d85efa9b057b53b14b4b9b939dd74decc5321"
+        );
+    }
+
+    #[test]
+    fn directory_tree_matches_manual_entries() {
+        let dir = std::env::temp_dir().join("rs_merkletree_git_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("empty")).unwrap();
+        fs::write(dir.join("src.txt"), b"file\n").unwrap();
+        fs::write(dir.join("src/lib.rs"), b"// lib\n").unwrap();
+
+        let sub = tree_id(
+            GitHash::Sha1,
+            &[TreeEntry::new(
+                FILE_MODE,
+                "lib.rs",
+                blob_id(GitHash::Sha1, b"// lib\n"),
+            )],
+        );
+        // "src.txt" sorts before the directory "src", compared as "src/".
+        let expected = tree_id(
+            GitHash::Sha1,
+            &[
+                TreeEntry::new(TREE_MODE, "src", sub),
+                TreeEntry::new(FILE_MODE, "src.txt", blob_id(GitHash::Sha1, b"file\n")),
+            ],
+        );
+        assert_eq!(dir_tree_id(GitHash::Sha1, &dir).unwrap(), Some(expected));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::hash::DIGEST_LEN;
+    use rs_merkletree::proof::{leaf_hash, legacy_leaf_hash};
+    use rs_merkletree::{Hash, MerkleError, MerkleTree};
+    use std::collections::HashSet;
+
+    #[test]
+    fn hashes_convert_between_forms() {
+        let hex = leaf_hash(b"Hello").to_hex_bytes();
+        let hash = Hash::from_hex_bytes(&hex).unwrap();
+        assert_eq!(hash, Hash::leaf(b"Hello"));
+        assert_eq!(hash.to_hex_bytes(), hex);
+        assert_eq!(hash.to_string().as_bytes(), &hex[..]);
+        assert_eq!(hash.to_string().parse::<Hash>(), Ok(hash));
+        assert_eq!(Hash::try_from(hash.as_ref()), Ok(hash));
+        assert_eq!(Hash::from(*hash.as_bytes()), hash);
+        assert_eq!(Hash::legacy_leaf(b"Hello"), legacy_leaf_hash(b"Hello"));
+        assert_eq!(format!("{:?}", hash), format!("Hash({})", hash));
+
+        // The hex form is not mistaken for a raw digest, nor the other way round.
+        assert_eq!(
+            Hash::try_from(&hex[..]),
+            Err(MerkleError::InvalidHashLength {
+                expected: DIGEST_LEN,
+                actual: 64
+            })
+        );
+        assert_eq!(
+            Hash::from_hex_bytes(hash.as_ref()),
+            Err(MerkleError::InvalidHashLength {
+                expected: 64,
+                actual: DIGEST_LEN
+            })
+        );
+        assert_eq!(
+            hash.to_string().to_uppercase().parse::<Hash>(),
+            Err(MerkleError::InvalidEncoding {
+                position: hash
+                    .to_string()
+                    .find(|c: char| c.is_ascii_alphabetic())
+                    .unwrap()
+            })
+        );
+        let set: HashSet<Hash> = [hash, hash, Hash::default()].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn trees_and_proofs_take_typed_hashes() {
+        let mut tree = MerkleTree::new(None);
+        assert_eq!(tree.root(), None);
+        tree.build_tree(vec!["a", "b", "c"]);
+        let root = tree.root().unwrap();
+        assert_eq!(tree.root_node().unwrap().hash(), root);
+        assert!(tree.includes(&Hash::leaf(b"b")));
+
+        let proof = tree.proof(1).unwrap();
+        assert!(proof.verify(&root, &Hash::leaf(b"b")));
+        assert!(!proof.verify(&root, &Hash::leaf(b"c")));
+        let step = proof.steps[0].hash;
+        assert_eq!(step, Hash::leaf(b"a"));
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::history::{leaf_hash, node_hash, HistoryTree};
+
+    /// Reference RFC 6962 root computed by plain recursion.
+    fn reference_root(leaves: &[Vec<u8>]) -> Vec<u8> {
+        if leaves.len() == 1 {
+            return leaves[0].clone();
+        }
+        let mut split = 1;
+        while split * 2 < leaves.len() {
+            split *= 2;
+        }
+        node_hash(
+            &reference_root(&leaves[..split]),
+            &reference_root(&leaves[split..]),
+        )
+    }
+
+    #[test]
+    fn membership_and_incremental_proofs() {
+        let mut log = HistoryTree::new();
+        let events: Vec<String> = (0..20).map(|i| format!("event-{}", i)).collect();
+        let mut hashes = Vec::new();
+        for event in &events {
+            log.append(event.as_bytes());
+            hashes.push(leaf_hash(event.as_bytes()));
+            assert_eq!(log.root(), reference_root(&hashes));
+        }
+
+        for size in 1..=20u64 {
+            let root = log.commitment(size).unwrap();
+            for index in 0..size {
+                let proof = log.membership_proof(index, size).unwrap();
+                assert!(proof.verify(&root, events[index as usize].as_bytes()));
+                assert!(!proof.verify(&root, b"forged"));
+            }
+            for old_size in 0..=size {
+                let old_root = log.commitment(old_size).unwrap();
+                let proof = log.incremental_proof(old_size, size).unwrap();
+                assert!(proof.verify(&old_root, &root), "{} -> {}", old_size, size);
+                if old_size > 0 && old_size < size {
+                    assert!(!proof.verify(&root, &root));
+                }
+            }
+        }
+        assert!(log.membership_proof(5, 21).is_none());
+        assert!(log.incremental_proof(6, 5).is_none());
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::incremental::IncrementalMerkleTree;
+
+    fn sha256(data: &[u8]) -> Vec<u8> {
+        use crypto::{digest::Digest, sha2::Sha256};
+        let mut hasher = Sha256::new();
+        hasher.input(data);
+        let mut out = vec![0u8; 32];
+        hasher.result(&mut out);
+        out
+    }
+
+    /// Builds the root of a zero-padded tree level by level.
+    fn naive_root(leaves: &[Vec<u8>], depth: usize) -> Vec<u8> {
+        let mut layer: Vec<Vec<u8>> = leaves.to_vec();
+        layer.resize(1 << depth, vec![0u8; 32]);
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(2)
+                .map(|pair| sha256(&[pair[0].clone(), pair[1].clone()].concat()))
+                .collect();
+        }
+        layer.remove(0)
+    }
+
+    #[test]
+    fn matches_zero_padded_tree() {
+        let mut tree = IncrementalMerkleTree::new(4);
+        let mut leaves = Vec::new();
+        assert_eq!(tree.root(), naive_root(&leaves, 4));
+        assert_eq!(tree.root(), tree.zero_hashes()[4]);
+
+        for i in 0..15u8 {
+            let leaf = sha256(&[i]);
+            assert_eq!(tree.insert(&leaf), Some(i as u64));
+            leaves.push(leaf);
+            assert_eq!(tree.root(), naive_root(&leaves, 4));
+        }
+        assert_eq!(tree.insert(&[0u8; 32]), None);
+    }
+    #[test]
+    fn extend_leaves_matches_single_inserts() {
+        let leaves: Vec<Vec<u8>> = (0..15u8).map(|i| sha256(&[i])).collect();
+        for split in 0..=leaves.len() {
+            let mut batched = IncrementalMerkleTree::new(4);
+            let mut single = IncrementalMerkleTree::new(4);
+            for leaf in &leaves[..split] {
+                batched.insert(leaf);
+            }
+            let changes = batched.subscribe_channel();
+            let root = batched.extend_leaves(&leaves[split..]).unwrap();
+            for leaf in &leaves {
+                single.insert(leaf);
+            }
+            assert_eq!(root, single.root(), "split {}", split);
+            assert_eq!(root, naive_root(&leaves, 4));
+            assert_eq!(batched.len(), 15);
+            // An empty batch changes nothing, so nobody is notified.
+            let expected = if split < leaves.len() {
+                vec![15]
+            } else {
+                vec![]
+            };
+            assert_eq!(
+                changes
+                    .try_iter()
+                    .map(|change| change.version)
+                    .collect::<Vec<_>>(),
+                expected
+            );
+        }
+
+        let mut tree = IncrementalMerkleTree::new(4);
+        tree.extend_leaves(&leaves[..3]).unwrap();
+        let root = tree.root();
+        assert_eq!(tree.extend_leaves(&leaves), None);
+        assert_eq!((tree.len(), tree.root()), (3, root));
+        tree.insert(&leaves[3]);
+        assert_eq!(tree.root(), naive_root(&leaves[..4], 4));
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::indexed::{IndexedMerkleTree, Value};
+
+    fn value(v: u64) -> Value {
+        let mut bytes = [0u8; 32];
+        bytes[24..].copy_from_slice(&v.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn linked_list_and_proofs() {
+        let mut tree = IndexedMerkleTree::new(4);
+        let empty_root = tree.root();
+        assert!(tree
+            .prove_non_membership(&value(5))
+            .unwrap()
+            .verify_non_membership(&empty_root, &value(5)));
+
+        for v in [50, 10, 30, 70, 20] {
+            tree.insert(value(v)).unwrap();
+        }
+        assert_eq!(tree.insert(value(30)), None);
+        assert_eq!(tree.insert(value(0)), None);
+        assert_eq!(tree.len(), 6);
+
+        // Walking the list from the zero leaf visits the values in order.
+        let mut visited = Vec::new();
+        let mut leaf = tree.leaf(0).unwrap();
+        while leaf.next_value != [0u8; 32] {
+            leaf = tree.leaf(leaf.next_index).unwrap();
+            visited.push(leaf.value);
+        }
+        assert_eq!(visited, [10, 20, 30, 50, 70].map(value).to_vec());
+
+        let root = tree.root();
+        for v in [10, 20, 30, 50, 70] {
+            let proof = tree.prove_membership(&value(v)).unwrap();
+            assert!(proof.verify_membership(&root, &value(v)));
+            assert!(tree.prove_non_membership(&value(v)).is_none());
+        }
+        for v in [5, 15, 40, 60, 100] {
+            let proof = tree.prove_non_membership(&value(v)).unwrap();
+            assert!(proof.verify_non_membership(&root, &value(v)));
+            assert!(!proof.verify_non_membership(&empty_root, &value(v)));
+        }
+        let proof = tree.prove_non_membership(&value(40)).unwrap();
+        assert!(!proof.verify_non_membership(&root, &value(50)));
+        assert!(!proof.verify_non_membership(&root, &value(25)));
+    }
+
+    #[test]
+    fn oversized_proofs_are_rejected() {
+        let mut tree = IndexedMerkleTree::new(4);
+        tree.insert(value(10)).unwrap();
+        let root = tree.root();
+        let proof = tree.prove_membership(&value(10)).unwrap();
+        assert!(proof.verify_membership(&root, &value(10)));
+
+        let mut too_deep = proof.clone();
+        too_deep.siblings.resize(100, vec![0; 32]);
+        assert!(!too_deep.verify_membership(&root, &value(10)));
+        let mut out_of_range = proof;
+        out_of_range.index |= 1 << 4;
+        assert!(!out_of_range.verify_membership(&root, &value(10)));
+    }
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "ipld")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::ipld::{decode_node, encode_tree, Cid};
+    use rs_merkletree::MerkleTree;
+    use std::collections::HashMap;
+
+    #[test]
+    fn cid_matches_ipfs_for_empty_map() {
+        assert_eq!(
+            Cid::for_dag_cbor(&[0xa0]).to_string(),
+            "bafyreigbtj4x7ip5legnfznufuopl4sg4knzc2cof6duas4b3q2fy6swua"
+        );
+    }
+
+    #[test]
+    fn tree_round_trips_through_blocks() {
+        let data = vec!["a", "b", "c", "d", "e"];
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data);
+        let (root, blocks) = encode_tree(&tree).unwrap();
+        let store: HashMap<Cid, Vec<u8>> = blocks
+            .into_iter()
+            .map(|block| (block.cid, block.data))
+            .collect();
+
+        // Walk the DAG from the root, checking every block against its CID.
+        let mut leaves = 0;
+        let mut pending = vec![root];
+        while let Some(cid) = pending.pop() {
+            let data = &store[&cid];
+            assert!(cid.verify(data));
+            let node = decode_node(data).unwrap();
+            match (node.left, node.right) {
+                (None, None) => leaves += 1,
+                (left, right) => pending.extend(left.into_iter().chain(right)),
+            }
+        }
+        assert_eq!(leaves, tree.count_leaves());
+        assert!(decode_node(&[0xa0]).is_none());
+    }
+}
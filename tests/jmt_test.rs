@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::jmt::{JellyfishMerkleTree, JmtProof, PLACEHOLDER_HASH};
+    use std::thread;
+
+    #[test]
+    fn versions_and_proofs() {
+        let mut tree = JellyfishMerkleTree::new();
+        let keys: Vec<String> = (0..100).map(|i| format!("account-{}", i)).collect();
+
+        let batch: Vec<(&[u8], Option<&[u8]>)> = keys
+            .iter()
+            .map(|key| (key.as_bytes(), Some(key.as_bytes())))
+            .collect();
+        let root_v1 = tree.put_value_set(1, &batch).unwrap();
+
+        let root_v2 = tree
+            .put_value_set(2, &[(keys[0].as_bytes(), None), (b"new", Some(b"value"))])
+            .unwrap();
+        assert_ne!(root_v1, root_v2);
+        assert_eq!(tree.put_value_set(2, &[]), None);
+        assert_eq!(tree.latest_version(), Some(2));
+        assert_eq!(tree.root(1), Some(root_v1.clone()));
+
+        assert_eq!(tree.get(keys[0].as_bytes(), 1), Some(keys[0].as_bytes()));
+        assert_eq!(tree.get(keys[0].as_bytes(), 2), None);
+
+        for key in &keys[1..] {
+            let proof = tree.prove(key.as_bytes(), 2).unwrap();
+            assert!(proof.verify_membership(&root_v2, key.as_bytes(), key.as_bytes()));
+            assert!(!proof.verify_membership(&root_v2, key.as_bytes(), b"wrong"));
+            assert!(!proof.verify_non_membership(&root_v2, key.as_bytes()));
+        }
+        let proof = tree.prove(keys[0].as_bytes(), 2).unwrap();
+        assert!(proof.verify_non_membership(&root_v2, keys[0].as_bytes()));
+        let proof = tree.prove(keys[0].as_bytes(), 1).unwrap();
+        assert!(proof.verify_membership(&root_v1, keys[0].as_bytes(), keys[0].as_bytes()));
+        let proof = tree.prove(b"new", 1).unwrap();
+        assert!(proof.verify_non_membership(&root_v1, b"new"));
+    }
+
+    #[test]
+    fn malformed_proofs_are_rejected() {
+        let mut tree = JellyfishMerkleTree::new();
+        let root = tree.put_value_set(1, &[(b"x", Some(b"1"))]).unwrap();
+        let short_leaf = JmtProof {
+            leaf: Some((vec![], vec![])),
+            siblings: vec![vec![0; 32]],
+        };
+        assert!(!short_leaf.verify_non_membership(&root, b"y"));
+        let too_deep = JmtProof {
+            leaf: None,
+            siblings: vec![PLACEHOLDER_HASH.to_vec(); 300],
+        };
+        assert!(!too_deep.verify_non_membership(&root, b"y"));
+        assert!(!too_deep.verify_membership(&root, b"x", b"1"));
+    }
+
+    #[test]
+    fn root_is_independent_of_history() {
+        let mut a = JellyfishMerkleTree::new();
+        a.put_value_set(1, &[(b"x", Some(b"1")), (b"y", Some(b"2"))]);
+        a.put_value_set(2, &[(b"z", Some(b"3")), (b"x", None)]);
+
+        let mut b = JellyfishMerkleTree::new();
+        b.put_value_set(7, &[(b"z", Some(b"3")), (b"y", Some(b"2"))]);
+        assert_eq!(a.root(2), b.root(7));
+
+        b.put_value_set(8, &[(b"z", None), (b"y", None)]);
+        assert_eq!(b.root(8), Some(PLACEHOLDER_HASH.to_vec()));
+    }
+    #[test]
+    fn versions_share_unchanged_subtrees() {
+        let keys: Vec<String> = (0..1000).map(|i| format!("account {}", i)).collect();
+        let mut tree = JellyfishMerkleTree::new();
+        let initial: Vec<(&[u8], Option<&[u8]>)> = keys
+            .iter()
+            .map(|key| (key.as_bytes(), Some(&b"0"[..])))
+            .collect();
+        tree.put_value_set(0, &initial).unwrap();
+        let base = tree.node_count();
+
+        for version in 1..=1000u64 {
+            let key = keys[version as usize % keys.len()].as_bytes();
+            let value = version.to_string();
+            tree.put_value_set(version, &[(key, Some(value.as_bytes()))])
+                .unwrap();
+        }
+        // Each version adds one path of a few nodes instead of copying the whole tree.
+        assert!(
+            tree.node_count() < base + 1000 * 5,
+            "{} nodes",
+            tree.node_count()
+        );
+        assert_eq!(tree.get(keys[7].as_bytes(), 6), Some(&b"0"[..]));
+        assert_eq!(tree.get(keys[7].as_bytes(), 7), Some(&b"7"[..]));
+        assert_eq!(tree.get(keys[7].as_bytes(), 1000), Some(&b"7"[..]));
+
+        // Removing an absent key changes nothing and shares the whole tree.
+        let before = tree.node_count();
+        let root = tree.put_value_set(1001, &[(b"missing", None)]).unwrap();
+        assert_eq!(Some(root), tree.root(1000));
+        assert_eq!(tree.node_count(), before);
+    }
+    #[test]
+    fn pruning_frees_nodes_of_old_versions_only() {
+        let mut tree = JellyfishMerkleTree::new();
+        assert_eq!(tree.prune_before(10), 0);
+        for version in 0..100u64 {
+            let key = format!("key {}", version % 10);
+            let value = version.to_string();
+            tree.put_value_set(version, &[(key.as_bytes(), Some(value.as_bytes()))])
+                .unwrap();
+        }
+        let root = tree.root(99);
+        let before = tree.node_count();
+
+        assert_eq!(tree.prune_before(90), 90);
+        assert_eq!(tree.oldest_version(), Some(90));
+        assert!(tree.node_count() < before);
+        assert_eq!((tree.root(89), tree.root(99)), (None, root));
+        assert_eq!(tree.get(b"key 3", 93), Some(&b"93"[..]));
+        assert_eq!(tree.get(b"key 3", 89), None);
+        assert!(tree.prove(b"key 3", 89).is_none());
+
+        // The latest version is always kept, so commits can continue.
+        assert_eq!(tree.prune_before(1000), 9);
+        assert_eq!(tree.oldest_version(), Some(99));
+        assert_eq!(tree.get(b"key 0", 99), Some(&b"90"[..]));
+        assert!(tree.put_value_set(100, &[(b"key 0", Some(b"x"))]).is_some());
+        assert_eq!(tree.get(b"key 1", 100), Some(&b"91"[..]));
+    }
+
+    #[test]
+    fn snapshots_stay_consistent_while_the_tree_moves_on() {
+        let mut tree = JellyfishMerkleTree::new();
+        let root = tree
+            .put_value_set(1, &[(b"alice", Some(b"100")), (b"bob", Some(b"10"))])
+            .unwrap();
+        let snapshot = tree.latest_snapshot().unwrap();
+        assert_eq!(snapshot.version(), 1);
+        assert_eq!(snapshot.root(), root);
+        assert!(tree.snapshot(2).is_none());
+
+        let reader = thread::spawn(move || {
+            let proof = snapshot.prove(b"alice");
+            assert!(proof.verify_membership(&snapshot.root(), b"alice", b"100"));
+            snapshot
+        });
+        for version in 2..10u64 {
+            let value = version.to_string();
+            tree.put_value_set(
+                version,
+                &[(b"alice", Some(value.as_bytes())), (b"bob", None)],
+            )
+            .unwrap();
+        }
+        tree.prune_before(9);
+        assert_eq!(tree.oldest_version(), Some(9));
+
+        let snapshot = reader.join().unwrap();
+        assert_eq!(snapshot.root(), root);
+        assert_eq!(snapshot.get(b"alice"), Some(&b"100"[..]));
+        assert_eq!(snapshot.get(b"bob"), Some(&b"10"[..]));
+        assert_eq!(tree.get(b"alice", 9), Some(&b"9"[..]));
+        assert!(snapshot
+            .prove(b"carol")
+            .verify_non_membership(&root, b"carol"));
+    }
+}
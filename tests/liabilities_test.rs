@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use rs_merkletree::liabilities::{part_id, LiabilitiesTree};
+
+    fn accounts() -> Vec<(Vec<u8>, u64)> {
+        (0..20u64)
+            .map(|i| (format!("user {}", i).into_bytes(), i * 1_000 + 7))
+            .collect()
+    }
+
+    #[test]
+    fn every_user_verifies_their_balance() {
+        let accounts = accounts();
+        let tree = LiabilitiesTree::new(accounts.clone(), 4).unwrap();
+        let root = tree.root().unwrap();
+        assert_eq!(
+            root.sum,
+            accounts.iter().map(|(_, balance)| balance).sum::<u64>()
+        );
+        assert_eq!(tree.tree().len(), 80);
+        for (user_id, balance) in &accounts {
+            let proof = tree.proof(user_id).unwrap();
+            assert_eq!(proof.parts.len(), 4);
+            assert!(proof.verify(root, user_id, *balance));
+            assert!(!proof.verify(root, user_id, balance - 1));
+            assert!(!proof.verify(root, b"someone else", *balance));
+        }
+        assert!(tree.proof(b"unknown").is_none());
+
+        // A zero balance and a single part still verify.
+        let tree = LiabilitiesTree::new(vec![(b"a".to_vec(), 0), (b"b".to_vec(), 5)], 0).unwrap();
+        assert!(tree
+            .proof(b"a")
+            .unwrap()
+            .verify(tree.root().unwrap(), b"a", 0));
+    }
+
+    #[test]
+    fn leaves_hide_owners_and_balances() {
+        let accounts = accounts();
+        let tree = LiabilitiesTree::new(accounts.clone(), 3).unwrap();
+        let root = tree.root().unwrap();
+        let proof = tree.proof(b"user 19").unwrap();
+
+        // Leaves only carry salted IDs, which differ per part and per tree.
+        let ids: Vec<Vec<u8>> = (0..3)
+            .map(|part| part_id(b"user 19", &proof.salt, part))
+            .collect();
+        assert!(ids[0] != ids[1] && ids[1] != ids[2]);
+        let other = LiabilitiesTree::new(accounts.clone(), 3).unwrap();
+        assert_ne!(other.proof(b"user 19").unwrap().salt, proof.salt);
+
+        // A proof leaving out a part no longer adds up to the user's balance.
+        let mut partial = proof.clone();
+        let (dropped, _) = partial.parts.pop().unwrap();
+        assert_eq!(partial.verify(root, b"user 19", 19_007), dropped == 0);
+
+        assert!(LiabilitiesTree::new(vec![(b"a".to_vec(), 1), (b"a".to_vec(), 2)], 2).is_none());
+        assert!(
+            LiabilitiesTree::new(vec![(b"a".to_vec(), u64::MAX), (b"b".to_vec(), 1)], 2).is_none()
+        );
+    }
+
+    #[test]
+    fn injected_rng_makes_trees_reproducible() {
+        let tree = LiabilitiesTree::new_with_rng(accounts(), 3, &mut ChaCha8Rng::seed_from_u64(0))
+            .unwrap();
+        let again = LiabilitiesTree::new_with_rng(accounts(), 3, &mut ChaCha8Rng::seed_from_u64(0))
+            .unwrap();
+        assert_eq!(tree.root(), again.root());
+        assert_eq!(tree.proof(b"user 4"), again.proof(b"user 4"));
+        let other = LiabilitiesTree::new_with_rng(accounts(), 3, &mut ChaCha8Rng::seed_from_u64(9))
+            .unwrap();
+        assert_ne!(other.root(), tree.root());
+        let root = tree.root().unwrap();
+        assert!(tree
+            .proof(b"user 4")
+            .unwrap()
+            .verify(root, b"user 4", 4_007));
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::limits::Limits;
+    use rs_merkletree::proof::MerkleProof;
+    use rs_merkletree::{MerkleError, MerkleTree};
+
+    #[test]
+    fn build_limits() {
+        let limits = Limits {
+            max_leaves: 4,
+            max_leaf_size: 3,
+            ..Limits::UNLIMITED
+        };
+        let mut tree = MerkleTree::new(None).with_limits(limits);
+        assert_eq!(tree.limits(), &limits);
+        assert_eq!(
+            tree.try_build_tree(vec!["a", "bb", "long", "c"]).err(),
+            Some(MerkleError::LeafTooLarge {
+                index: 2,
+                limit: 3,
+                actual: 4
+            })
+        );
+        assert!(tree.root_node().is_none());
+        assert_eq!(
+            tree.try_build_tree(vec!["a"; 5]).err(),
+            Some(MerkleError::TooManyLeaves {
+                limit: 4,
+                actual: 5
+            })
+        );
+        assert!(tree.try_build_tree(vec!["a", "bb", "ccc"]).is_ok());
+        assert!(tree.root_node().is_some());
+    }
+
+    #[test]
+    fn proof_limits() {
+        let data: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.iter().map(String::as_str).collect());
+        let proof = tree.proof(7).unwrap();
+        let bytes = proof.to_bytes();
+
+        let limits = Limits::default();
+        assert_eq!(
+            MerkleProof::from_bytes_limited(&bytes, &limits),
+            Ok(proof.clone())
+        );
+        let tight = Limits {
+            max_proof_len: 4,
+            ..limits
+        };
+        assert_eq!(
+            MerkleProof::from_bytes_limited(&bytes, &tight),
+            Err(MerkleError::ProofTooLong {
+                limit: 4,
+                actual: 5
+            })
+        );
+        assert!(tight.check_proof(&proof).is_err());
+        assert_eq!(
+            MerkleProof::from_bytes_limited(&[0, 0xff, 0xff], &limits),
+            Err(MerkleError::MalformedProof)
+        );
+        assert_eq!(
+            MerkleProof::from_bytes_limited(&bytes[..bytes.len() - 1], &limits),
+            Err(MerkleError::MalformedProof)
+        );
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::log::TransparencyLog;
+
+    #[test]
+    fn tree_heads_and_proofs() {
+        let mut log = TransparencyLog::new(&[1u8; 32], 1_000);
+        assert!(log.tick(0).is_some());
+        for i in 0..5 {
+            log.append(format!("entry-{}", i).as_bytes());
+        }
+        assert!(log.tick(500).is_none());
+        let first = log.tick(1_000).unwrap();
+        assert_eq!(first.tree_size, 5);
+        assert!(first.verify(log.public_key()));
+
+        for i in 5..12 {
+            log.append(format!("entry-{}", i).as_bytes());
+        }
+        let second = log.tick(2_500).unwrap();
+        assert!(log.tick(9_000).is_none());
+        assert_eq!(log.tree_heads().len(), 3);
+
+        let proof = log
+            .consistency_proof(first.tree_size, second.tree_size)
+            .unwrap();
+        assert!(proof.verify(&first.root_hash, &second.root_hash));
+
+        let proof = log.inclusion_proof(3, first.tree_size).unwrap();
+        assert!(proof.verify(&first.root_hash, b"entry-3"));
+        assert_eq!(log.entry(3), Some(&b"entry-3"[..]));
+
+        let mut forged = second.clone();
+        forged.tree_size += 1;
+        assert!(!forged.verify(log.public_key()));
+        let other = TransparencyLog::new(&[2u8; 32], 1_000);
+        assert!(!second.verify(other.public_key()));
+    }
+}
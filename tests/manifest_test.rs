@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::manifest::{Manifest, Mismatch};
+    use std::fs;
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("docs/nested")).unwrap();
+        fs::write(dir.join("readme.md"), b"# readme").unwrap();
+        fs::write(dir.join("docs/guide.txt"), vec![3u8; 5000]).unwrap();
+        fs::write(dir.join("docs/nested/empty"), b"").unwrap();
+        dir
+    }
+
+    #[test]
+    fn identical_copy_verifies_and_roundtrips() {
+        let original = fixture("rs_merkletree_manifest_original");
+        let copy = fixture("rs_merkletree_manifest_copy");
+        let manifest = Manifest::build(&original, 1024).unwrap();
+        let paths: Vec<&str> = manifest.entries().iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["docs/guide.txt", "docs/nested/empty", "readme.md"]
+        );
+        assert_eq!(manifest.entry("docs/guide.txt").unwrap().len, 5000);
+
+        let parsed = Manifest::parse(&manifest.to_text()).unwrap();
+        assert_eq!(parsed.root(), manifest.root());
+        assert_eq!(parsed.root(), Manifest::build(&copy, 1024).unwrap().root());
+        assert!(parsed.verify(&copy).unwrap().is_empty());
+
+        fs::remove_dir_all(&original).unwrap();
+        fs::remove_dir_all(&copy).unwrap();
+    }
+
+    #[test]
+    fn reports_each_kind_of_difference() {
+        let dir = fixture("rs_merkletree_manifest_changed");
+        let manifest = Manifest::build(&dir, 1024).unwrap();
+        fs::remove_file(dir.join("readme.md")).unwrap();
+        fs::write(dir.join("docs/guide.txt"), vec![4u8; 5000]).unwrap();
+        fs::write(dir.join("extra.bin"), b"x").unwrap();
+
+        assert_eq!(
+            manifest.verify(&dir).unwrap(),
+            vec![
+                Mismatch::Modified("docs/guide.txt".to_string()),
+                Mismatch::Unexpected("extra.bin".to_string()),
+                Mismatch::Missing("readme.md".to_string()),
+            ]
+        );
+        assert!(Manifest::parse("not a manifest").is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
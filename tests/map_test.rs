@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::map::{Encode, MerkleMap};
+
+    #[test]
+    fn map_operations_and_proofs() {
+        let mut map: MerkleMap<u32, String> = MerkleMap::new();
+        let empty_root = map.root();
+        for i in 0..20 {
+            assert_eq!(map.insert(i, format!("value-{}", i)), None);
+        }
+        assert_eq!(
+            map.insert(3, "updated".to_string()),
+            Some("value-3".to_string())
+        );
+        assert_eq!(map.remove(&7), Some("value-7".to_string()));
+        assert_eq!(map.len(), 19);
+        assert_eq!(map.get(&3).map(String::as_str), Some("updated"));
+
+        let root = map.root();
+        let proof = map.prove(&3);
+        assert!(proof.verify(&root, &3u32, Some(&"updated")));
+        assert!(!proof.verify(&root, &3u32, Some(&"value-3")));
+        assert!(map.prove(&7).verify::<u32, String>(&root, &7, None));
+        assert!(!map.prove(&7).verify::<u32, String>(&empty_root, &8, None));
+
+        // The root depends on contents only.
+        let mut other: MerkleMap<u32, String> = MerkleMap::new();
+        for (key, value) in map.iter().rev() {
+            other.insert(*key, value.clone());
+        }
+        assert_eq!(other.root(), root);
+    }
+
+    #[test]
+    fn pointer_sized_integers_encode_as_eight_bytes() {
+        assert_eq!(7usize.encode(), 7u64.to_be_bytes());
+        assert_eq!((-7isize).encode(), (-7i64).to_be_bytes());
+        let mut map: MerkleMap<usize, isize> = MerkleMap::new();
+        let mut wide: MerkleMap<u64, i64> = MerkleMap::new();
+        map.insert(3, -3);
+        wide.insert(3, -3);
+        assert_eq!(map.root(), wide.root());
+    }
+}
@@ -1,7 +1,8 @@
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
-    use rs_merkletree::MerkleTree;
+    use rs_merkletree::proof::{leaf_hash, legacy_leaf_hash, MerkleProof};
+    use rs_merkletree::{Hash, MerkleTree};
 
     #[test]
     fn it_works() {
@@ -10,19 +11,195 @@ mod tests {
         let rootNode = tree.build_tree(data);
         let root_hash = rootNode.root_node().unwrap().hash();
         assert_eq!(
-            String::from_utf8(root_hash),
-            Ok(String::from(
-                "725367a8cee028cf3360c19d20c175733191562b01e60d093e81d8570e865f81"
-            ))
+            root_hash.to_string(),
+            "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
         );
 
         let path = tree.includes(
-            "d9aa89fdd15ad5c41d9c128feffe9e07dc828b83f85296f7f42bda506821300e".as_bytes(),
+            &"635fd701970453575a3f84cb82631929f1a96dbeb7c45f983ff648698a5ca8f4".parse().unwrap(),
         );
-        assert_eq!(path, true);
+        assert!(path);
 
-        println!("Depth:{}",tree.depth());
+        println!("Depth:{:?}",tree.depth());
 
         println!("Leaves:{}",tree.count_leaves());
     }
+
+    #[test]
+    fn diff_reports_differing_leaves() {
+        let build = |data: &[&str]| {
+            let mut tree = MerkleTree::new(None);
+            tree.build_tree(data.to_vec());
+            tree
+        };
+        let data: Vec<String> = (0..13).map(|i| format!("line {}", i)).collect();
+        let data: Vec<&str> = data.iter().map(String::as_str).collect();
+        let tree = build(&data);
+        assert!(tree.diff(&build(&data)).is_empty());
+
+        let mut changed = data.clone();
+        changed[2] = "changed";
+        changed[11] = "changed";
+        assert_eq!(tree.diff(&build(&changed)), vec![2, 11]);
+        assert_eq!(tree.diff(&build(&data[..9])), vec![9, 10, 11, 12]);
+        assert_eq!(build(&data[..3]).diff(&tree), (3..13).collect::<Vec<_>>());
+        assert_eq!(tree.diff(&MerkleTree::new(None)), (0..13).collect::<Vec<_>>());
+
+        // A lone leaf hashes like a pair of equal leaves, so counts are compared too.
+        assert_eq!(build(&["a", "b", "c"]).diff(&build(&["a", "b", "c", "c"])), vec![3]);
+    }
+
+    #[test]
+    fn inner_nodes_are_not_leaves() {
+        let data = vec!["Hello", "World", "From", "Rust"];
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.clone());
+        let root = tree.root_node().unwrap();
+        let inner = root.left_node().unwrap();
+        assert!(tree.includes(&leaf_hash(b"Hello")));
+        assert!(!tree.includes(&inner.hash()));
+
+        // Presenting an inner node as a leaf, with its children's hashes as the data, must fail.
+        let forged = MerkleProof { steps: tree.proof(0).unwrap().steps[1..].to_vec() };
+        let children = [inner.left_node().unwrap().hash().to_hex_bytes(), inner.right_node().unwrap().hash().to_hex_bytes()].concat();
+        assert!(!forged.verify_data(&root.hash(), &children));
+
+        let mut legacy = MerkleTree::new_legacy(None);
+        legacy.build_tree(data);
+        assert!(legacy.is_legacy() && !tree.is_legacy());
+        let root = legacy.root_node().unwrap();
+        assert_eq!(
+            root.hash().to_string(),
+            "725367a8cee028cf3360c19d20c175733191562b01e60d093e81d8570e865f81"
+        );
+        assert!(legacy.includes(&root.left_node().unwrap().hash()));
+        assert!(legacy.proof(3).unwrap().verify_legacy(&root.hash(), &legacy_leaf_hash(b"Rust")));
+        // Legacy trees are open to exactly that.
+        let inner = root.left_node().unwrap();
+        let forged = MerkleProof { steps: legacy.proof(0).unwrap().steps[1..].to_vec() };
+        let children = [inner.left_node().unwrap().hash().to_hex_bytes(), inner.right_node().unwrap().hash().to_hex_bytes()].concat();
+        assert!(forged.verify_legacy(&root.hash(), &legacy_leaf_hash(&children)));
+    }
+
+    #[test]
+    fn sorted_leaves_are_order_independent() {
+        let data = vec!["pear", "apple", "fig", "kiwi", "plum"];
+        let mut reversed = data.clone();
+        reversed.reverse();
+        let mut first = MerkleTree::new(None).with_sorted_leaves();
+        let mut second = MerkleTree::new(None).with_sorted_leaves();
+        first.build_tree(data.clone());
+        second.build_tree(reversed);
+        assert!(first.sorts_leaves());
+        let root = first.root_node().unwrap().hash();
+        assert_eq!(root, second.root_node().unwrap().hash());
+
+        let mut unsorted = MerkleTree::new(None);
+        unsorted.build_tree(data.clone());
+        assert_ne!(root, unsorted.root_node().unwrap().hash());
+
+        for item in data {
+            let index = first.leaf_index(&leaf_hash(item.as_bytes())).unwrap();
+            assert!(first.proof(index).unwrap().verify_data(&root, item.as_bytes()));
+        }
+        assert_eq!(first.leaf_index(&leaf_hash(b"grape")), None);
+    }
+
+    #[test]
+    fn upward_navigation_matches_proofs() {
+        let data = vec!["a", "b", "c", "d", "e"];
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.clone());
+        let leaf_depth = tree.depth().unwrap() - 1;
+        for index in 0..data.len() {
+            // Walk up from the leaf, collecting siblings as a proof would.
+            let mut siblings = Vec::new();
+            let (mut depth, mut position) = (leaf_depth, index);
+            while depth > 0 {
+                let node = tree.node(depth, position).unwrap();
+                siblings.push(tree.sibling_of(depth, position).unwrap_or(node).hash());
+                let parent = tree.parent_of(depth, position).unwrap();
+                assert_eq!(tree.node(depth - 1, position / 2), Some(parent));
+                depth -= 1;
+                position /= 2;
+            }
+            let proof: Vec<Hash> = tree.proof(index).unwrap().steps.into_iter().map(|step| step.hash).collect();
+            assert_eq!(siblings, proof);
+        }
+        assert_eq!(tree.node(leaf_depth, 5), None);
+        assert_eq!(tree.parent_of(leaf_depth, 5), None);
+        assert_eq!(tree.node(leaf_depth + 1, 0), None);
+        assert_eq!(MerkleTree::new(None).node(0, 0), None);
+    }
+
+    #[test]
+    fn leaf_metadata_is_not_hashed() {
+        let items = vec![("pear", b"t=3".to_vec()), ("apple", b"t=1".to_vec()), ("fig", b"t=2".to_vec())];
+        let mut plain = MerkleTree::new(None).with_sorted_leaves();
+        plain.build_tree(items.iter().map(|(leaf, _)| *leaf).collect());
+        let mut tree = MerkleTree::new(None).with_sorted_leaves();
+        tree.build_tree_with_meta(items.clone());
+        assert_eq!(tree.root_node(), plain.root_node());
+
+        // Metadata follows its leaf to the sorted position.
+        for (leaf, meta) in &items {
+            let index = tree.leaf_index(&leaf_hash(leaf.as_bytes())).unwrap();
+            assert_eq!(tree.leaf_meta(index), Some(meta.as_slice()));
+        }
+
+        assert!(tree.set_leaf_meta(0, b"edited".to_vec()));
+        assert!(!tree.set_leaf_meta(3, b"missing".to_vec()));
+        assert_eq!(tree.leaf_meta(0), Some(&b"edited"[..]));
+        assert_eq!(tree.leaf_meta(3), None);
+        assert_eq!(tree.root_node(), plain.root_node());
+
+        tree.build_tree(vec!["pear"]);
+        assert_eq!(tree.leaf_meta(0), None);
+    }
+    #[test]
+    fn rebuild_matches_fresh_build() {
+        let data: Vec<String> = (0..20).map(|i| format!("leaf {}", 19 - i)).collect();
+        let constructors: [fn() -> MerkleTree; 3] = [
+            || MerkleTree::new(None),
+            || MerkleTree::new_legacy(None),
+            || MerkleTree::new(None).with_sorted_leaves(),
+        ];
+        for new in constructors {
+            let mut tree = new();
+            for size in [5, 20, 0, 1, 2, 7, 19, 3] {
+                let leaves: Vec<&str> = data[..size].iter().map(String::as_str).collect();
+                tree.set_leaf_meta(0, b"stale".to_vec());
+                tree.rebuild(leaves.clone());
+                let mut fresh = new();
+                fresh.build_tree(leaves);
+                assert_eq!(tree.root_node(), fresh.root_node(), "size {}", size);
+                assert_eq!(tree.leaf_meta(0), None);
+            }
+        }
+    }
+    #[test]
+    fn same_as_compares_roots_sizes_and_settings() {
+        let data: Vec<String> = (0..13).map(|i| format!("leaf {}", i)).collect();
+        for size in 0..13 {
+            let leaves: Vec<&str> = data[..size].iter().map(String::as_str).collect();
+            let mut tree = MerkleTree::new(None);
+            tree.build_tree(leaves.clone());
+            let mut same = MerkleTree::new(None);
+            same.build_tree(leaves.clone());
+            assert!(tree.same_as(&same));
+            assert!(tree.diff(&same).is_empty());
+
+            let mut legacy = MerkleTree::new_legacy(None);
+            legacy.build_tree(leaves.clone());
+            assert!(!tree.same_as(&legacy));
+
+            // Padding with a copy of the last leaf keeps the root of odd trees but not the leaves.
+            if let Some(&last) = leaves.last() {
+                let mut padded = MerkleTree::new(None);
+                padded.build_tree([leaves.clone(), vec![last]].concat());
+                assert!(!tree.same_as(&padded));
+                assert_eq!(tree.diff(&padded), vec![size]);
+            }
+        }
+    }
 }
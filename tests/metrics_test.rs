@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::metrics::Metrics;
+    use rs_merkletree::MerkleTree;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Recorded {
+        hashes: Mutex<Vec<u64>>,
+        builds: Mutex<Vec<usize>>,
+        proofs: Mutex<Vec<Duration>>,
+    }
+
+    impl Metrics for Recorded {
+        fn hashes_computed(&self, count: u64) {
+            self.hashes.lock().unwrap().push(count);
+        }
+
+        fn build_completed(&self, leaves: usize, _duration: Duration) {
+            self.builds.lock().unwrap().push(leaves);
+        }
+
+        fn proof_generated(&self, duration: Duration) {
+            self.proofs.lock().unwrap().push(duration);
+        }
+    }
+
+    #[test]
+    fn builds_and_proofs_are_reported() {
+        let metrics = Arc::new(Recorded::default());
+        let mut tree = MerkleTree::new(None).with_metrics(metrics.clone());
+        tree.build_tree(vec!["a", "b", "c"]);
+        tree.build_tree(vec!["a"]);
+        tree.rebuild(vec!["a", "b", "c"]);
+        // 3 leaves + 2 parents + root, then 1 leaf + its padded parent; rebuilds count the same.
+        assert_eq!(*metrics.hashes.lock().unwrap(), vec![6, 2, 6]);
+        assert_eq!(*metrics.builds.lock().unwrap(), vec![3, 1, 3]);
+
+        assert!(tree.proof(0).is_some());
+        assert!(tree.clone().proof(5).is_none());
+        assert_eq!(metrics.proofs.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn metrics_are_optional() {
+        struct Nothing;
+        impl Metrics for Nothing {}
+
+        let mut with = MerkleTree::new(None).with_metrics(Arc::new(Nothing));
+        let mut without = MerkleTree::new(None);
+        with.build_tree(vec!["Hello", "World"]);
+        without.build_tree(vec!["Hello", "World"]);
+        assert_eq!(with.root_node(), without.root_node());
+        assert_eq!(with.proof(1), without.proof(1));
+    }
+}
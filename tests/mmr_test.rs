@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::mmr::MerkleMountainRange;
+
+    #[test]
+    fn append_and_prove() {
+        let mut mmr = MerkleMountainRange::new();
+        assert_eq!(mmr.root(), None);
+
+        let data: Vec<String> = (0..11).map(|i| format!("entry-{}", i)).collect();
+        let mut roots = Vec::new();
+        for (i, entry) in data.iter().enumerate() {
+            assert_eq!(mmr.append(entry.as_bytes()), i);
+            roots.push(mmr.root().unwrap());
+        }
+        // 11 = 8 + 2 + 1 leaves, 15 + 3 + 1 nodes
+        assert_eq!(mmr.peaks().len(), 3);
+        assert_eq!(mmr.size(), 19);
+        assert_eq!(mmr.leaf_count(), 11);
+
+        let root = mmr.root().unwrap();
+        for (i, entry) in data.iter().enumerate() {
+            let proof = mmr.prove(i).unwrap();
+            assert!(proof.verify(&root, entry.as_bytes()));
+            assert!(!proof.verify(&root, b"tampered"));
+            assert!(!proof.verify(&roots[5], entry.as_bytes()));
+        }
+        assert!(mmr.prove(11).is_none());
+    }
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "mpt")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::mpt::account::{encode_storage_value, Account, AccountProof, StorageProof};
+    use rs_merkletree::mpt::{keccak256, verify_proof, MerklePatriciaTrie, MptError, EMPTY_ROOT};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn matches_ethereum_vectors() {
+        let mut trie = MerklePatriciaTrie::new();
+        assert_eq!(trie.root(), EMPTY_ROOT.to_vec());
+
+        trie.insert(b"doe", b"reindeer");
+        trie.insert(b"dog", b"puppy");
+        trie.insert(b"dogglesworth", b"cat");
+        assert_eq!(
+            hex(&trie.root()),
+            "8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3"
+        );
+        assert_eq!(trie.get(b"dog"), Some(&b"puppy"[..]));
+        assert_eq!(trie.get(b"do"), None);
+
+        let mut trie = MerklePatriciaTrie::new();
+        trie.insert(b"foo", b"bar");
+        trie.insert(b"food", b"bass");
+        assert_eq!(
+            hex(&trie.root()),
+            "17beaa1648bafa633cda809c90c04af50fc8aed3cb40d16efbddee6fdf63c4c3"
+        );
+    }
+
+    #[test]
+    fn proofs() {
+        let mut trie = MerklePatriciaTrie::new();
+        let keys: Vec<String> = (0..64).map(|i| format!("key-{}", i * 7)).collect();
+        for key in &keys {
+            trie.insert(key.as_bytes(), format!("value of {}", key).as_bytes());
+        }
+        let root = trie.root();
+
+        for key in &keys {
+            let proof = trie.prove(key.as_bytes());
+            let expected = format!("value of {}", key).into_bytes();
+            assert_eq!(
+                verify_proof(&root, key.as_bytes(), &proof),
+                Ok(Some(expected))
+            );
+        }
+
+        let proof = trie.prove(b"missing");
+        assert_eq!(verify_proof(&root, b"missing", &proof), Ok(None));
+
+        let mut proof = trie.prove(keys[3].as_bytes());
+        let last = proof.len() - 1;
+        proof[last][5] ^= 1;
+        assert_eq!(
+            verify_proof(&root, keys[3].as_bytes(), &proof),
+            Err(MptError::HashMismatch)
+        );
+        assert_eq!(
+            verify_proof(&root, keys[3].as_bytes(), &proof[..1]),
+            Err(MptError::IncompleteProof)
+        );
+    }
+
+    #[test]
+    fn verifies_eth_get_proof_responses() {
+        let mut storage = MerklePatriciaTrie::new();
+        let slot = [0u8; 32];
+        let mut value = [0u8; 32];
+        value[31] = 0x2a;
+        storage.insert(&keccak256(&slot), &encode_storage_value(&value));
+
+        let address = [0x11u8; 20];
+        let mut account = Account::empty();
+        account.nonce = 3;
+        account.balance[31] = 100;
+        account.storage_root.copy_from_slice(&storage.root());
+
+        let mut state = MerklePatriciaTrie::new();
+        state.insert(&keccak256(&address), &account.encode());
+        state.insert(&keccak256(&[0x22u8; 20]), &Account::empty().encode());
+        let state_root = state.root();
+
+        let missing_slot = [1u8; 32];
+        let response = AccountProof {
+            address,
+            account: account.clone(),
+            account_proof: state.prove(&keccak256(&address)),
+            storage_proofs: vec![
+                StorageProof {
+                    key: slot,
+                    value,
+                    proof: storage.prove(&keccak256(&slot)),
+                },
+                StorageProof {
+                    key: missing_slot,
+                    value: [0; 32],
+                    proof: storage.prove(&keccak256(&missing_slot)),
+                },
+            ],
+        };
+        assert_eq!(response.verify(&state_root), Ok(()));
+
+        let mut inflated = response.clone();
+        inflated.account.balance[30] = 1;
+        assert_eq!(inflated.verify(&state_root), Err(MptError::ValueMismatch));
+        let mut forged = response.clone();
+        forged.storage_proofs[0].value[31] = 0x2b;
+        assert_eq!(forged.verify(&state_root), Err(MptError::ValueMismatch));
+
+        // An unused address proves as the empty account.
+        let unused = [0x33u8; 20];
+        let absent = AccountProof {
+            address: unused,
+            account: Account::empty(),
+            account_proof: state.prove(&keccak256(&unused)),
+            storage_proofs: Vec::new(),
+        };
+        assert_eq!(absent.verify(&state_root), Ok(()));
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::mst::MerkleSearchTree;
+
+    #[test]
+    fn shape_is_independent_of_insertion_order() {
+        let keys: Vec<String> = (0..200).map(|i| format!("key-{:04}", i)).collect();
+        let mut forward = MerkleSearchTree::new();
+        let mut backward = MerkleSearchTree::new();
+        for key in &keys {
+            forward.insert(key.as_bytes());
+        }
+        backward.extend(keys.iter().rev().map(|key| key.as_bytes()));
+        assert_eq!(forward.root(), backward.root());
+
+        forward.remove(keys[10].as_bytes());
+        assert_ne!(forward.root(), backward.root());
+        forward.insert(keys[10].as_bytes());
+        assert_eq!(forward.root(), backward.root());
+    }
+
+    #[test]
+    fn reconcile_fetches_only_diverging_subtrees() {
+        let keys: Vec<String> = (0..2000).map(|i| format!("key-{:04}", i)).collect();
+        let mut local = MerkleSearchTree::new();
+        let mut remote = MerkleSearchTree::new();
+        local.extend(keys.iter().map(|key| key.as_bytes()));
+        remote.extend(keys.iter().map(|key| key.as_bytes()));
+        local.remove(b"key-0042");
+        remote.remove(b"key-1500");
+        remote.insert(b"extra");
+
+        let mut fetched = 0;
+        let missing = local
+            .reconcile(&remote.root(), |hash| {
+                fetched += 1;
+                remote.node(hash).cloned()
+            })
+            .unwrap();
+        assert_eq!(missing, vec![b"extra".to_vec(), b"key-0042".to_vec()]);
+        assert!(fetched < 20);
+
+        let (only_local, only_remote) = local.diff(&remote);
+        assert_eq!(only_local, vec![b"key-1500".to_vec()]);
+        assert_eq!(only_remote, missing);
+        assert_eq!(local.diff(&local.clone()), (vec![], vec![]));
+    }
+
+    #[test]
+    fn updates_match_a_fresh_build() {
+        let keys: Vec<String> = (0..500).map(|i| format!("key-{:04}", i)).collect();
+        let mut tree = MerkleSearchTree::new();
+        let empty = tree.root();
+        for (i, key) in keys.iter().enumerate() {
+            tree.insert(key.as_bytes());
+            if i % 3 == 0 {
+                tree.remove(keys[i / 2].as_bytes());
+            }
+        }
+        let mut fresh = MerkleSearchTree::new();
+        fresh.extend(
+            tree.keys()
+                .map(<[u8]>::to_vec)
+                .collect::<Vec<_>>()
+                .iter()
+                .map(Vec::as_slice),
+        );
+        assert_eq!(tree.root(), fresh.root());
+
+        // Replaced nodes are dropped, so a subtree holding a removed key is not mistaken for a local one.
+        tree.remove(b"key-0499");
+        let missing = tree.reconcile(&fresh.root(), |hash| fresh.node(hash).cloned());
+        assert_eq!(missing, Some(vec![b"key-0499".to_vec()]));
+
+        for key in fresh.keys() {
+            tree.remove(key);
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.root(), empty);
+    }
+
+    #[test]
+    fn reconcile_rejects_forged_nodes() {
+        let mut local = MerkleSearchTree::new();
+        let mut remote = MerkleSearchTree::new();
+        local.extend([b"apple".as_slice()]);
+        remote.extend([b"apple".as_slice(), b"banana"]);
+
+        let mut forged = remote.node(&remote.root()).unwrap().clone();
+        forged.keys.push(b"injected".to_vec());
+        assert_eq!(
+            local.reconcile(&remote.root(), |_| Some(forged.clone())),
+            None
+        );
+
+        // A node claiming its own hash as a child would loop forever if it were trusted.
+        let mut cyclic = remote.node(&remote.root()).unwrap().clone();
+        cyclic.children[0] = Some(remote.root());
+        assert_eq!(
+            local.reconcile(&remote.root(), |_| Some(cyclic.clone())),
+            None
+        );
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::multiproof::{FrameDecoder, MultiproofError, MIN_FRAME_LEN};
+    use rs_merkletree::proof::{legacy_leaf_hash, HexSha256, NodeHasher, TaggedHexSha256};
+    use rs_merkletree::{Hash, MerkleTree};
+
+    fn data(size: usize) -> Vec<String> {
+        (0..size).map(|i| format!("leaf {}", i)).collect()
+    }
+
+    fn build(tree: &mut MerkleTree, data: &[String]) {
+        tree.build_tree(data.iter().map(String::as_str).collect());
+    }
+
+    /// Streams a multiproof of `indices` through a decoder, returning the proven leaves.
+    fn stream<H: NodeHasher>(
+        tree: &MerkleTree,
+        hasher: H,
+        indices: &[usize],
+        max_frame_len: usize,
+    ) -> Result<Vec<(usize, Hash)>, MultiproofError> {
+        let root = tree.root_node().unwrap().hash();
+        let mut decoder = FrameDecoder::new(hasher, &root, tree.count_leaves());
+        let mut proven = Vec::new();
+        for frame in tree.multiproof_frames(indices, max_frame_len).unwrap() {
+            assert!(frame.len() <= max_frame_len);
+            proven.extend(decoder.push_frame(&frame)?);
+        }
+        decoder.finish()?;
+        Ok(proven)
+    }
+
+    #[test]
+    fn streamed_multiproofs_prove_every_leaf() {
+        for size in [1, 2, 5, 13, 64, 300] {
+            let data = data(size);
+            let mut tree = MerkleTree::new(None);
+            build(&mut tree, &data);
+            let all: Vec<usize> = (0..size).collect();
+            for indices in [vec![0], vec![size - 1], vec![size - 1, 0, size / 2, 0], all] {
+                let mut expected = indices.clone();
+                expected.sort_unstable();
+                expected.dedup();
+                for max_frame_len in [MIN_FRAME_LEN, 1000, usize::MAX] {
+                    let proven = stream(&tree, TaggedHexSha256, &indices, max_frame_len).unwrap();
+                    let proven_indices: Vec<usize> =
+                        proven.iter().map(|(index, _)| *index).collect();
+                    assert_eq!(proven_indices, expected);
+                    for (index, leaf) in proven {
+                        assert_eq!(leaf, tree.node(tree.depth().unwrap() - 1, index).unwrap().hash());
+                    }
+                }
+            }
+            assert!(tree.multiproof_frames(&[size], 1000).is_none());
+            assert!(tree.multiproof_frames(&[], 1000).is_none());
+        }
+
+        let data = data(9);
+        let mut tree = MerkleTree::new_legacy(None);
+        build(&mut tree, &data);
+        let proven = stream(&tree, HexSha256, &[2, 8], MIN_FRAME_LEN).unwrap();
+        assert_eq!(proven[1], (8, legacy_leaf_hash(b"leaf 8")));
+    }
+
+    #[test]
+    fn tampered_streams_are_rejected() {
+        let data = data(100);
+        let mut tree = MerkleTree::new(None);
+        build(&mut tree, &data);
+        let root = tree.root_node().unwrap().hash();
+        let frames: Vec<Vec<u8>> = tree
+            .multiproof_frames(&[3, 50, 99], MIN_FRAME_LEN)
+            .unwrap()
+            .collect();
+        assert!(frames.len() > 1);
+
+        // Leaves from frames before the corrupted one stay proven.
+        let mut corrupted = frames.clone();
+        corrupted[1][10] ^= 1;
+        let mut decoder = FrameDecoder::new(TaggedHexSha256, &root, 100);
+        let mut proven = Vec::new();
+        let error = corrupted
+            .iter()
+            .find_map(|frame| match decoder.push_frame(frame) {
+                Ok(leaves) => {
+                    proven.extend(leaves);
+                    None
+                }
+                Err(error) => Some(error),
+            });
+        assert_eq!(error, Some(MultiproofError::HashMismatch));
+        assert!(proven.iter().all(|(index, _)| *index == 3));
+
+        let mut decoder = FrameDecoder::new(TaggedHexSha256, &root, 100);
+        decoder.push_frame(&frames[0]).unwrap();
+        assert_eq!(decoder.finish(), Err(MultiproofError::Incomplete));
+
+        let mut decoder = FrameDecoder::new(TaggedHexSha256, &root, 100);
+        let whole = frames.concat();
+        decoder.push_frame(&whole).unwrap();
+        assert_eq!(
+            decoder.push_frame(&frames[0]),
+            Err(MultiproofError::TrailingData)
+        );
+
+        let mut decoder = FrameDecoder::new(TaggedHexSha256, &root, 100);
+        assert_eq!(
+            decoder.push_frame(&whole[..whole.len() - 1]),
+            Err(MultiproofError::Malformed)
+        );
+
+        // The tree size decides which nodes have a single child, so a wrong one is caught.
+        let mut decoder = FrameDecoder::new(TaggedHexSha256, &root, 128);
+        assert!(frames
+            .iter()
+            .any(|frame| decoder.push_frame(frame).is_err()));
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::nmt::{NamespacedMerkleTree, NmtError};
+
+    #[test]
+    fn namespace_and_absence_proofs() {
+        let mut tree = NamespacedMerkleTree::new(2);
+        let namespaces: [u8; 9] = [1, 1, 3, 3, 3, 4, 7, 7, 9];
+        for (i, ns) in namespaces.iter().enumerate() {
+            tree.push(&[0, *ns], format!("leaf-{}", i).as_bytes())
+                .unwrap();
+        }
+        assert_eq!(tree.push(&[0, 2], b"late"), Err(NmtError::OutOfOrder));
+        assert_eq!(
+            tree.push(&[2], b"short"),
+            Err(NmtError::InvalidNamespaceSize)
+        );
+
+        let root = tree.root();
+        assert_eq!(&root[..4], &[0, 1, 0, 9]);
+
+        let data = |range: std::ops::Range<usize>| -> Vec<Vec<u8>> {
+            range.map(|i| format!("leaf-{}", i).into_bytes()).collect()
+        };
+        let proof = tree.prove_namespace(&[0, 3]);
+        assert_eq!(proof.range, 2..5);
+        assert!(proof.verify(&root, &[0, 3], &data(2..5)));
+        // Withholding a leaf of the namespace must fail.
+        let partial = tree.prove_range(2..4);
+        assert!(!partial.verify(&root, &[0, 3], &data(2..4)));
+
+        for absent in [[0, 5], [0, 2], [0, 8], [0, 0], [0, 10]] {
+            let proof = tree.prove_namespace(&absent);
+            assert!(proof.verify(&root, &absent, &[]), "{:?}", absent);
+        }
+        // An absence proof cannot be used for a namespace that has leaves.
+        let proof = tree.prove_namespace(&[0, 5]);
+        assert!(!proof.verify(&root, &[0, 4], &[]));
+        assert!(!proof.verify(&root, &[0, 7], &[]));
+
+        let empty = NamespacedMerkleTree::new(2);
+        assert!(empty
+            .prove_namespace(&[0, 1])
+            .verify(&empty.root(), &[0, 1], &[]));
+    }
+
+    #[test]
+    fn truncated_hashes_are_rejected() {
+        let mut tree = NamespacedMerkleTree::new(2);
+        for ns in [1, 3, 7, 9] {
+            tree.push(&[0, ns], b"leaf").unwrap();
+        }
+        let root = tree.root();
+        let proof = tree.prove_namespace(&[0, 5]);
+        assert!(proof.verify(&root, &[0, 5], &[]));
+
+        let mut short_leaf = proof.clone();
+        short_leaf.leaf_hash.as_mut().unwrap().truncate(3);
+        assert!(!short_leaf.verify(&root, &[0, 5], &[]));
+        let mut short_node = proof.clone();
+        short_node.nodes[0].truncate(3);
+        assert!(!short_node.verify(&root, &[0, 5], &[]));
+        let mut long_node = proof;
+        long_node.nodes[0].push(0);
+        assert!(!long_node.verify(&root, &[0, 5], &[]));
+    }
+}
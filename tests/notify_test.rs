@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::concurrent::ConcurrentMerkleTree;
+    use rs_merkletree::incremental::IncrementalMerkleTree;
+    use rs_merkletree::jmt::JellyfishMerkleTree;
+    use rs_merkletree::notify::RootChange;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn callbacks_see_consecutive_roots() {
+        let seen: Arc<Mutex<Vec<RootChange>>> = Arc::default();
+        let mut tree = IncrementalMerkleTree::new(4);
+        let empty_root = tree.root();
+        let sink = seen.clone();
+        tree.subscribe(move |change| sink.lock().unwrap().push(change.clone()));
+        for i in 0..3u8 {
+            tree.insert(&[i; 32]);
+        }
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[0].old_root, empty_root);
+        assert_eq!(seen[1].old_root, seen[0].new_root);
+        assert_eq!(seen[2].new_root, tree.root());
+        assert_eq!(
+            seen.iter().map(|change| change.version).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        // Clones do not inherit subscribers.
+        let mut copy = tree.clone();
+        copy.insert(&[9; 32]);
+        assert_eq!(seen.len(), 3);
+    }
+
+    #[test]
+    fn channels_receive_commits() {
+        let mut jmt = JellyfishMerkleTree::new();
+        let versions = jmt.subscribe_channel();
+        let first = jmt.put_value_set(1, &[(b"a", Some(b"1"))]).unwrap();
+        jmt.put_value_set(5, &[(b"a", None)]).unwrap();
+        assert!(jmt.put_value_set(2, &[]).is_none());
+        let changes: Vec<RootChange> = versions.try_iter().collect();
+        assert_eq!(changes.len(), 2);
+        assert_eq!((changes[0].version, changes[1].version), (1, 5));
+        assert_eq!(changes[1].old_root, first);
+        assert_eq!(changes[1].new_root, changes[0].old_root);
+
+        let mut tree = ConcurrentMerkleTree::new(3, 4);
+        let receiver = tree.subscribe_channel();
+        tree.append(&[1; 32]).unwrap();
+        drop(receiver);
+        // A dropped receiver is simply forgotten.
+        tree.append(&[2; 32]).unwrap();
+        assert_eq!(tree.sequence_number(), 2);
+    }
+}
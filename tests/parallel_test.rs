@@ -0,0 +1,60 @@
+#![cfg(feature = "rayon")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::config::TreeConfig;
+    use rs_merkletree::parallel::Parallelism;
+    use rs_merkletree::MerkleTree;
+    use std::sync::Arc;
+
+    #[test]
+    fn work_runs_on_the_configured_pool() {
+        let one = Parallelism::with_threads(1).unwrap();
+        assert_eq!(one.threads(), 1);
+        assert_eq!(one.install(rayon::current_num_threads), 1);
+
+        let pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(3)
+                .build()
+                .unwrap(),
+        );
+        let shared = Parallelism::with_pool(Arc::clone(&pool));
+        assert_eq!(shared.install(rayon::current_num_threads), 3);
+        assert!(shared.install(|| rayon::current_thread_index().is_some()));
+        assert_eq!(
+            Parallelism::global().threads(),
+            rayon::current_num_threads()
+        );
+    }
+
+    #[test]
+    fn results_do_not_depend_on_the_pool() {
+        let data: Vec<String> = (0..500).map(|i| i.to_string()).collect();
+        let data: Vec<&str> = data.iter().map(String::as_str).collect();
+        let mut sequential = MerkleTree::new(None);
+        sequential.build_tree(data.clone());
+        let indices: Vec<usize> = (0..510).step_by(7).collect();
+        let expected: Vec<_> = indices
+            .iter()
+            .map(|&index| sequential.proof(index))
+            .collect();
+
+        for parallelism in [
+            Parallelism::global(),
+            Parallelism::with_threads(1).unwrap(),
+            Parallelism::with_threads(4).unwrap(),
+        ] {
+            let mut tree = MerkleTree::new(None);
+            tree.build_tree_parallel_in(data.clone(), &parallelism);
+            assert_eq!(tree.root_node(), sequential.root_node());
+            assert_eq!(tree.proofs_parallel(&indices, &parallelism), expected);
+
+            let config = TreeConfig::rfc6962();
+            assert_eq!(
+                config.try_build_layers_parallel_in(&data, &parallelism),
+                config.try_build_layers(&data)
+            );
+        }
+    }
+}
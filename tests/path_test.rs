@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::limits::Limits;
+    use rs_merkletree::{MerkleError, MerkleTree};
+    use std::path::{Path, PathBuf};
+
+    fn write_temp(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "rs_merkletree_path_test_{}_{}",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn newline_delimited_file_matches_build_tree() {
+        let path = write_temp("lines", b"Hello\nWorld\nFrom\nRust\n");
+        let tree = MerkleTree::try_from(path.as_path()).unwrap();
+        let mut expected = MerkleTree::new(None);
+        expected.build_tree(vec!["Hello", "World", "From", "Rust"]);
+        assert_eq!(tree.root(), expected.root());
+        assert_eq!(tree.count_leaves(), 4);
+
+        // Without a trailing newline the last record is the same, and empty lines are leaves too.
+        let path = write_temp("no_trailing", b"Hello\n\nRust");
+        let tree = MerkleTree::try_from(path.as_path()).unwrap();
+        expected.build_tree(vec!["Hello", "", "Rust"]);
+        assert_eq!(tree.root(), expected.root());
+
+        // Records are taken as-is, so CRLF line endings keep their `\r`.
+        let path = write_temp("crlf", b"Hello\r\nWorld\r\n");
+        let tree = MerkleTree::try_from(path.as_path()).unwrap();
+        expected.build_tree(vec!["Hello\r", "World\r"]);
+        assert_eq!(tree.root(), expected.root());
+    }
+
+    #[test]
+    fn custom_delimiter() {
+        let path = write_temp("nul", b"a b\0c\nd\0e");
+        let tree = MerkleTree::from_path_delimited(&path, 0).unwrap();
+        let mut expected = MerkleTree::new(None);
+        expected.build_tree(vec!["a b", "c\nd", "e"]);
+        assert_eq!(tree.root(), expected.root());
+    }
+
+    #[test]
+    fn reader_respects_tree_settings() {
+        let data = b"c,a,b";
+        let mut tree = MerkleTree::new(None).with_sorted_leaves();
+        tree.build_tree_from_reader(&data[..], b',').unwrap();
+        let mut expected = MerkleTree::new(None).with_sorted_leaves();
+        expected.build_tree(vec!["c", "a", "b"]);
+        assert_eq!(tree.root(), expected.root());
+
+        let mut tree = MerkleTree::new_legacy(None);
+        tree.build_tree_from_reader(&data[..], b',').unwrap();
+        let mut expected = MerkleTree::new_legacy(None);
+        expected.build_tree(vec!["c", "a", "b"]);
+        assert_eq!(tree.root(), expected.root());
+
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree_from_reader(&b""[..], b'\n').unwrap();
+        assert_eq!(tree.root(), None);
+    }
+
+    #[test]
+    fn missing_file_is_an_io_error() {
+        let error =
+            MerkleTree::try_from(Path::new("/nonexistent/rs_merkletree/leaves.txt")).unwrap_err();
+        assert!(matches!(
+            error,
+            MerkleError::Io {
+                kind: std::io::ErrorKind::NotFound,
+                ..
+            }
+        ));
+        assert_eq!(error.code(), 13);
+        assert!(error.to_string().starts_with("I/O error: "));
+    }
+
+    #[test]
+    fn limits_are_checked_while_reading() {
+        let limits = Limits {
+            max_leaves: 2,
+            max_leaf_size: 4,
+            ..Limits::default()
+        };
+        let mut tree = MerkleTree::new(None).with_limits(limits);
+        tree.build_tree(vec!["kept"]);
+        let root = tree.root();
+
+        assert_eq!(
+            tree.build_tree_from_reader(&b"a\nb\nc\nd"[..], b'\n')
+                .unwrap_err(),
+            MerkleError::TooManyLeaves {
+                limit: 2,
+                actual: 3
+            }
+        );
+        assert_eq!(
+            tree.build_tree_from_reader(&b"a\ntoo long"[..], b'\n')
+                .unwrap_err(),
+            MerkleError::LeafTooLarge {
+                index: 1,
+                limit: 4,
+                actual: 5
+            }
+        );
+        assert_eq!(tree.root(), root);
+    }
+
+    #[test]
+    fn oversized_records_are_not_buffered() {
+        let limits = Limits {
+            max_leaf_size: 4,
+            ..Limits::default()
+        };
+        let mut tree = MerkleTree::new(None).with_limits(limits);
+        // An endless record without a delimiter is rejected after reading past the limit.
+        let endless = std::io::BufReader::new(std::io::repeat(b'a'));
+        assert_eq!(
+            tree.build_tree_from_reader(endless, b'\n').unwrap_err(),
+            MerkleError::LeafTooLarge {
+                index: 0,
+                limit: 4,
+                actual: 5
+            }
+        );
+        // A record of exactly the limit still fits, with or without its delimiter.
+        tree.build_tree_from_reader(&b"abcd\nefgh"[..], b'\n')
+            .unwrap();
+        let mut expected = MerkleTree::new(None);
+        expected.build_tree(vec!["abcd", "efgh"]);
+        assert_eq!(tree.root(), expected.root());
+    }
+
+    struct Failing;
+
+    impl std::io::Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disk on fire"))
+        }
+    }
+
+    #[test]
+    fn failed_reads_leave_the_leaf_cache_alone() {
+        use std::io::Read;
+        let mut tree = MerkleTree::new(None).with_leaf_cache();
+        tree.build_tree(vec!["Hello", "World"]);
+        let (len, hits, misses) = {
+            let cache = tree.leaf_cache().unwrap();
+            (cache.len(), cache.hits(), cache.misses())
+        };
+
+        let failing = std::io::BufReader::new((&b"Hello\nWorld\n"[..]).chain(Failing));
+        let error = tree.build_tree_from_reader(failing, b'\n').unwrap_err();
+        assert_eq!(
+            error,
+            MerkleError::Io {
+                kind: std::io::ErrorKind::Other,
+                message: "disk on fire".to_string()
+            }
+        );
+        let cache = tree.leaf_cache().unwrap();
+        assert_eq!(
+            (cache.len(), cache.hits(), cache.misses()),
+            (len, hits, misses)
+        );
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::progress::{CancellationToken, Cancelled, Progress};
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn reports_leaves_and_levels() {
+        let owned: Vec<String> = (0..10_000).map(|i| i.to_string()).collect();
+        let data: Vec<&str> = owned.iter().map(String::as_str).collect();
+        let mut reports: Vec<Progress> = Vec::new();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree_with_progress(data.clone(), |p| reports.push(p), &CancellationToken::new())
+            .unwrap();
+
+        let leaf_reports: Vec<usize> = reports
+            .iter()
+            .filter(|p| p.level == 0)
+            .map(|p| p.leaves_processed)
+            .collect();
+        assert_eq!(leaf_reports, vec![4096, 8192, 10_000]);
+        let levels: Vec<usize> = reports.iter().map(|p| p.level).skip(3).collect();
+        assert_eq!(levels, (1..=14).collect::<Vec<_>>());
+        assert!(reports.iter().all(|p| p.total_leaves == 10_000));
+
+        let mut plain = MerkleTree::new(None);
+        plain.build_tree(data);
+        assert_eq!(plain.root_node(), tree.root_node());
+    }
+
+    #[test]
+    fn cancelled_build_leaves_tree_unchanged() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["old"]);
+        let old = tree.root_node();
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = tree.build_tree_with_progress(vec!["a", "b"], |_| {}, &cancel);
+        assert_eq!(result.err(), Some(Cancelled));
+        assert_eq!(tree.root_node(), old);
+    }
+}
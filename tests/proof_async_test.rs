@@ -0,0 +1,40 @@
+#![cfg(feature = "tokio")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::MerkleTree;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn async_proofs_match_sync_proofs() {
+        let data: Vec<String> = (0..1000).map(|i| format!("entry {}", i)).collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.iter().map(String::as_str).collect());
+        let tree = Arc::new(tree);
+        let root = tree.root_node().unwrap().hash();
+
+        for index in [0, 1, 500, 999] {
+            let proof = tree.proof_async(index).await.unwrap();
+            assert!(proof.verify_data(&root, data[index].as_bytes()));
+            assert_eq!(Some(proof), tree.proof(index));
+        }
+        assert_eq!(tree.proof_async(1000).await, None);
+        assert_eq!(Arc::new(MerkleTree::new(None)).proof_async(0).await, None);
+    }
+
+    #[tokio::test]
+    async fn async_proofs_run_concurrently_with_other_tasks() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a", "b", "c"]);
+        let tree = Arc::new(tree);
+        let handles: Vec<_> = (0..3)
+            .map(|index| {
+                let tree = Arc::clone(&tree);
+                tokio::spawn(async move { tree.proof_async(index).await })
+            })
+            .collect();
+        for (index, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.await.unwrap(), tree.proof(index));
+        }
+    }
+}
@@ -0,0 +1,325 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::proof::{
+        ct_eq, fold_root, leaf_hash, proof_len, small_root, verify_multiproof, verify_steps,
+        HexSha256, IndexedProof, MerkleProof, NodeHasher, Position, SizedProof, TaggedHexSha256,
+        SMALL_TREE_LEAVES,
+    };
+    use rs_merkletree::{Hash, MerkleTree};
+
+    fn leaf_hashes(data: &[&str]) -> Vec<Hash> {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.to_vec());
+        (0..data.len())
+            .map(|i| {
+                let mut node = tree.root_node().unwrap();
+                let mut steps = tree.proof(i).unwrap().steps;
+                steps.reverse();
+                for step in steps {
+                    node = match step.position {
+                        Position::Right => node.left_node().unwrap(),
+                        Position::Left => node.right_node().unwrap(),
+                    };
+                }
+                node.hash()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn proofs_verify_for_every_leaf() {
+        for size in 1..=11 {
+            let data: Vec<String> = (0..size).map(|i| format!("leaf {}", i)).collect();
+            let data: Vec<&str> = data.iter().map(String::as_str).collect();
+            let mut tree = MerkleTree::new(None);
+            tree.build_tree(data.clone());
+            let root = tree.root_node().unwrap().hash();
+            let leaves = leaf_hashes(&data);
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(i).unwrap();
+                assert!(proof.verify(&root, leaf), "size {} index {}", size, i);
+                assert!(tree.includes(leaf));
+                let other = &leaves[(i + 1) % size];
+                assert_eq!(proof.verify(&root, other), size == 1);
+            }
+            assert!(tree.proof(size).is_none());
+        }
+    }
+
+    #[test]
+    fn proof_bytes_round_trip() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a", "b", "c"]);
+        let proof = tree.proof(2).unwrap();
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), 2 * (3 + 64));
+        assert_eq!(MerkleProof::from_bytes(&bytes), Some(proof));
+        assert_eq!(MerkleProof::from_bytes(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(
+            MerkleProof::from_bytes(&[]),
+            Some(MerkleProof { steps: Vec::new() })
+        );
+    }
+
+    #[test]
+    fn verify_steps_without_allocation() {
+        let left = [b'a'; 64];
+        let right = [b'b'; 64];
+        let root = HexSha256.hash_nodes(&left, &right);
+        let steps = [(&right[..], Position::Right)];
+        assert!(verify_steps(&HexSha256, &root, &left, steps));
+        let steps = [(&left[..], Position::Left)];
+        assert!(verify_steps(&HexSha256, &root, &right, steps));
+        assert!(!verify_steps(&HexSha256, &root, &left, steps));
+        assert!(verify_steps(
+            &HexSha256,
+            &root,
+            &root,
+            [(&[][..], Position::Left); 0]
+        ));
+    }
+
+    #[test]
+    fn constant_time_comparison() {
+        assert!(ct_eq(b"abc", b"abc"));
+        assert!(!ct_eq(b"abc", b"abd"));
+        assert!(!ct_eq(b"abc", b"ab"));
+        assert!(ct_eq(b"", b""));
+        assert!(!MerkleTree::new(None).includes(&Hash::default()));
+    }
+
+    #[test]
+    fn sized_proofs_reject_padding() {
+        let build = |data: Vec<&str>| {
+            let mut tree = MerkleTree::new(None);
+            tree.build_tree(data);
+            tree
+        };
+        let three = build(vec!["a", "b", "c"]);
+        let four = build(vec!["a", "b", "c", "c"]);
+        let root = three.root_node().unwrap().hash();
+        assert_eq!(root, four.root_node().unwrap().hash());
+        let leaf = leaf_hash(b"c");
+
+        // The padded copy of "c" verifies as a plain proof, but not as leaf 3 of a 3 leaf tree.
+        let padded = four.sized_proof(3).unwrap();
+        assert!(padded.proof.verify(&root, &leaf));
+        assert!(!padded.verify(&root, &leaf, 3));
+        assert!(!padded.proof.verify_sized(&root, &leaf, 3, 3));
+        assert!(padded.verify(&root, &leaf, 4));
+
+        let real = three.sized_proof(2).unwrap();
+        assert!(real.verify(&root, &leaf, 3));
+        assert_eq!(SizedProof::from_bytes(&real.to_bytes()), Some(real.clone()));
+        // Claiming another position or size fails.
+        assert!(!real.proof.verify_sized(&root, &leaf, 0, 3));
+        assert!(!real.proof.verify_sized(&root, &leaf, 2, 5));
+
+        for size in 1..=9u64 {
+            let data: Vec<String> = (0..size).map(|i| i.to_string()).collect();
+            let tree = build(data.iter().map(String::as_str).collect());
+            let root = tree.root_node().unwrap().hash();
+            for index in 0..size {
+                let proof = tree.sized_proof(index as usize).unwrap();
+                assert_eq!(proof.proof.steps.len(), proof_len(size));
+                let leaf = leaf_hash(data[index as usize].as_bytes());
+                assert!(
+                    proof.verify(&root, &leaf, size),
+                    "size {} index {}",
+                    size,
+                    index
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn range_iterator_is_lazy_and_bounded() {
+        let data: Vec<String> = (0..11).map(|i| i.to_string()).collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.iter().map(String::as_str).collect());
+        let root = tree.root_node().unwrap().hash();
+        let expected = leaf_hashes(&data.iter().map(String::as_str).collect::<Vec<_>>());
+
+        let mut slice = tree.iter_range_with_proofs(3..7);
+        let (index, leaf, proof) = slice.next().unwrap();
+        assert_eq!((index, &leaf), (3, &expected[3]));
+        assert!(proof.verify_data(&root, data[3].as_bytes()));
+        assert_eq!(slice.count(), 3);
+
+        let tail: Vec<usize> = tree
+            .iter_range_with_proofs(9..usize::MAX)
+            .map(|(index, _, _)| index)
+            .collect();
+        assert_eq!(tail, vec![9, 10]);
+        assert_eq!(tree.iter_range_with_proofs(11..20).count(), 0);
+        assert_eq!(
+            MerkleTree::new(None).iter_range_with_proofs(0..4).count(),
+            0
+        );
+    }
+    #[test]
+    fn multiproofs_verify_for_every_subset() {
+        for size in 1..=9usize {
+            let data: Vec<String> = (0..size).map(|i| format!("leaf {}", i)).collect();
+            let mut tree = MerkleTree::new(None);
+            tree.build_tree(data.iter().map(String::as_str).collect());
+            let root = tree.root_node().unwrap().hash();
+            for subset in 1..(1u32 << size) {
+                let indices: Vec<usize> = (0..size).filter(|i| subset >> i & 1 == 1).collect();
+                let proof = tree.multiproof(&indices).unwrap();
+                let leaves: Vec<Hash> = indices
+                    .iter()
+                    .map(|&i| leaf_hash(data[i].as_bytes()))
+                    .collect();
+                assert!(
+                    proof.verify(&root, &leaves),
+                    "size {} subset {:b}",
+                    size,
+                    subset
+                );
+                assert!(proof.hashes.len() <= proof_len(size as u64) * indices.len());
+                if indices.len() == size {
+                    assert!(proof.hashes.is_empty());
+                }
+            }
+        }
+        let tree = MerkleTree::new(None);
+        assert_eq!(tree.multiproof(&[0]), None);
+    }
+
+    #[test]
+    fn multiproof_rejects_tampering() {
+        let data: Vec<String> = (0..11).map(|i| i.to_string()).collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.iter().map(String::as_str).collect());
+        let root = tree.root_node().unwrap().hash();
+        assert_eq!(tree.multiproof(&[3, 11]), None);
+
+        let proof = tree.multiproof(&[7, 2, 9, 2]).unwrap();
+        assert_eq!(proof.indices, vec![2, 7, 9]);
+        let leaves: Vec<Hash> = [2, 7, 9]
+            .iter()
+            .map(|&i| leaf_hash(data[i].as_bytes()))
+            .collect();
+        assert!(proof.verify(&root, &leaves));
+        assert!(!proof.verify(&root, &leaves[..2]));
+        assert!(!proof.verify(&root, &[leaves[0], leaves[2], leaves[1]]));
+
+        // The byte-level verifier takes the hex forms the tree hashes.
+        let root = root.to_hex_bytes();
+        let hashes: Vec<[u8; 64]> = leaves.iter().map(Hash::to_hex_bytes).collect();
+        let leaves: Vec<&[u8]> = hashes.iter().map(|leaf| &leaf[..]).collect();
+        let siblings: Vec<[u8; 64]> = proof.hashes.iter().map(Hash::to_hex_bytes).collect();
+        let siblings = || siblings.iter().map(|hash| &hash[..]);
+        let pairs = |order: [usize; 3]| order.map(|k| (proof.indices[k], leaves[k]));
+        assert!(verify_multiproof(
+            &TaggedHexSha256,
+            &root,
+            11,
+            pairs([0, 1, 2]),
+            siblings()
+        ));
+        assert!(!verify_multiproof(
+            &TaggedHexSha256,
+            &root,
+            11,
+            pairs([1, 0, 2]),
+            siblings()
+        ));
+        assert!(!verify_multiproof(
+            &TaggedHexSha256,
+            &root,
+            10,
+            pairs([0, 1, 2]),
+            siblings()
+        ));
+        assert!(!verify_multiproof(
+            &TaggedHexSha256,
+            &root,
+            11,
+            pairs([0, 1, 2]),
+            siblings().skip(1)
+        ));
+        assert!(!verify_multiproof(
+            &TaggedHexSha256,
+            &root,
+            11,
+            pairs([0, 1, 2]),
+            siblings().chain([&root[..]])
+        ));
+        assert!(!verify_multiproof(
+            &TaggedHexSha256,
+            &root,
+            11,
+            [(11, leaves[0])],
+            siblings()
+        ));
+        assert!(!verify_multiproof(
+            &TaggedHexSha256,
+            &root,
+            0,
+            pairs([0, 1, 2]),
+            siblings()
+        ));
+    }
+    #[test]
+    fn small_roots_match_built_trees() {
+        let data: Vec<String> = (0..100).map(|i| format!("leaf {}", 99 - i)).collect();
+        let data: Vec<&str> = data.iter().map(String::as_str).collect();
+        for size in (0..=SMALL_TREE_LEAVES + 1).chain([100]) {
+            let data = &data[..size];
+            for tree in [
+                MerkleTree::new(None),
+                MerkleTree::new_legacy(None),
+                MerkleTree::new(None).with_sorted_leaves(),
+            ] {
+                let mut built = tree.clone();
+                built.build_tree(data.to_vec());
+                let expected = built.root_node().map(|root| root.hash());
+                assert_eq!(tree.root_of(data), expected, "size {}", size);
+                if !tree.is_legacy() && !tree.sorts_leaves() {
+                    let small = small_root(data);
+                    assert_eq!(small, expected.filter(|_| size <= SMALL_TREE_LEAVES));
+                }
+            }
+        }
+        assert_eq!(fold_root(&TaggedHexSha256, &mut []), None);
+    }
+
+    #[test]
+    fn indexed_proofs_only_verify_at_their_index() {
+        let data: Vec<String> = (0..7).map(|i| format!("leaf {}", i)).collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.iter().map(String::as_str).collect());
+        let root = tree.root_node().unwrap().hash();
+        for index in 0..7u64 {
+            let proof = tree.indexed_proof(index as usize).unwrap();
+            let leaf = leaf_hash(data[index as usize].as_bytes());
+            for claimed in 0..16u64 {
+                assert_eq!(proof.verify(&root, &leaf, claimed, 7), claimed == index);
+            }
+            assert!(!proof.verify(&root, &leaf, index + (1 << 40), 7));
+            assert_eq!(IndexedProof::from_bytes(&proof.to_bytes()), Some(proof));
+        }
+        assert_eq!(tree.indexed_proof(7), None);
+        assert_eq!(IndexedProof::from_bytes(&[0, 5, 1]), None);
+    }
+
+    #[test]
+    fn indexed_proofs_reject_padded_positions() {
+        for (size, index) in [(3usize, 2u64), (5, 4)] {
+            let data: Vec<String> = (0..size).map(|i| format!("leaf {}", i)).collect();
+            let mut tree = MerkleTree::new(None);
+            tree.build_tree(data.iter().map(String::as_str).collect());
+            let root = tree.root_node().unwrap().hash();
+            let leaf = leaf_hash(data[index as usize].as_bytes());
+            let proof = tree.indexed_proof(index as usize).unwrap();
+            assert!(proof.verify(&root, &leaf, index, size as u64));
+            // Every padded position past the last leaf hashes to the same root.
+            for padded in index + 1..1 << proof.siblings.len() {
+                assert!(!proof.verify(&root, &leaf, padded, size as u64));
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "python")]
+
+#[cfg(test)]
+mod tests {
+    use pyo3::prelude::*;
+    use pyo3::types::PyDict;
+    use rs_merkletree::python::{self, verify_proof, PyMerkleTree};
+    use std::ffi::CString;
+
+    #[test]
+    fn class_matches_the_tree() {
+        let tree = PyMerkleTree::new(
+            ["Hello", "World", "From", "Rust"]
+                .map(String::from)
+                .to_vec(),
+        );
+        let root = tree.root().unwrap();
+        assert_eq!(
+            root,
+            "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
+        );
+        assert_eq!(tree.count_leaves(), 4);
+        assert!(tree
+            .includes("635fd701970453575a3f84cb82631929f1a96dbeb7c45f983ff648698a5ca8f4")
+            .unwrap());
+        let proof = tree.proof(3).unwrap();
+        assert!(verify_proof(&root, "Rust", &proof));
+        assert!(!verify_proof(&root, "From", &proof));
+        assert!(tree.proof(4).is_none());
+        assert_eq!(PyMerkleTree::new(Vec::new()).root(), None);
+    }
+
+    #[test]
+    fn module_is_usable_from_python() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "rs_merkletree").unwrap();
+            python::rs_merkletree(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("rs_merkletree", module).unwrap();
+            let code = CString::new(
+                "tree = rs_merkletree.MerkleTree(['a', 'b', 'c'])\n\
+                 assert len(tree) == 3\n\
+                 proof = tree.proof(2)\n\
+                 assert isinstance(proof, bytes)\n\
+                 assert rs_merkletree.verify_proof(tree.root(), 'c', proof)\n\
+                 assert not rs_merkletree.verify_proof(tree.root(), 'a', proof)\n\
+                 assert tree.proof(3) is None\n",
+            )
+            .unwrap();
+            py.run(&code, Some(&globals), None).unwrap();
+        });
+    }
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "rekor")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::rekor::{Checkpoint, LogEntry, RekorError};
+
+    /// A five-entry RFC 6962 log in Rekor's JSON format, with the proof for entry 3. Its checkpoint signature is a placeholder.
+    const ENTRY: &str = include_str!("data/rekor_entry.json");
+
+    #[test]
+    fn verifies_entry_and_checkpoint() {
+        let entry = LogEntry::from_json(ENTRY).unwrap();
+        assert_eq!(entry.verify(), Ok(()));
+        let checkpoint =
+            Checkpoint::parse(entry.inclusion_proof.checkpoint.as_ref().unwrap()).unwrap();
+        assert_eq!(checkpoint.origin, "rekor.example - 1");
+        assert_eq!(checkpoint.tree_size, 5);
+        assert_eq!(checkpoint.signatures[0].0, "rekor.example");
+        assert!(
+            Checkpoint::signed_body(entry.inclusion_proof.checkpoint.as_ref().unwrap())
+                .unwrap()
+                .ends_with("\n")
+        );
+    }
+
+    #[test]
+    fn rejects_tampering() {
+        let entry = LogEntry::from_json(ENTRY).unwrap();
+
+        let mut body = entry.clone();
+        body.body.push(b' ');
+        body.uuid = None;
+        assert_eq!(body.verify(), Err(RekorError::InvalidProof));
+
+        let mut index = entry.clone();
+        index.inclusion_proof.log_index = 2;
+        assert_eq!(index.verify(), Err(RekorError::InvalidProof));
+
+        let mut size = entry.clone();
+        size.inclusion_proof.tree_size = 6;
+        assert_eq!(size.verify(), Err(RekorError::CheckpointMismatch));
+
+        assert_eq!(LogEntry::from_json("{"), Err(RekorError::InvalidJson));
+        assert_eq!(
+            LogEntry::from_json("{\"body\":\"\"}"),
+            Err(RekorError::MissingField("verification.inclusionProof"))
+        );
+    }
+}
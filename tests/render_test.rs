@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::render::RenderOptions;
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn mermaid_has_every_node_and_edge() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+        let chart = tree.to_mermaid();
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(lines[0], "flowchart TD");
+        assert_eq!(lines[1], "    n0[\"a67134ab\"]");
+        // Seven nodes and six edges.
+        assert_eq!(lines.iter().filter(|line| line.ends_with("\"]")).count(), 7);
+        assert_eq!(lines.iter().filter(|line| line.contains("-->")).count(), 6);
+        assert!(chart.contains("    n2 --> n5\n    n2 --> n6\n"));
+        assert!(chart.contains("    n6[\"635fd701\"]\n"));
+    }
+
+    #[test]
+    fn mermaid_of_padded_and_empty_trees() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a"]);
+        let chart = tree.to_mermaid();
+        // The lone leaf's parent has a single edge.
+        assert_eq!(chart.matches("-->").count(), 1);
+        assert_eq!(MerkleTree::new(None).to_mermaid(), "flowchart TD\n");
+    }
+
+    #[test]
+    fn render_options() {
+        let data = vec!["Hello", "World", "From", "Rust"];
+        let mut tree = MerkleTree::new(None).with_sorted_leaves();
+        tree.build_tree(data.clone());
+
+        let text = tree.render(&RenderOptions {
+            leaf_data: Some(&data),
+            ..RenderOptions::default()
+        });
+        assert_eq!(text.lines().count(), 7);
+        let root = tree.root_node().unwrap().hash().to_string();
+        assert!(text.starts_with(&format!("{}…\n", &root[..8])));
+        assert!(text.contains("635fd701… \"Rust\"\n"));
+        for leaf in &data {
+            assert!(text.contains(&format!("{:?}", leaf)));
+        }
+
+        let full = tree.render(&RenderOptions {
+            hash_len: 64,
+            max_depth: Some(1),
+            leaf_data: None,
+        });
+        let lines: Vec<&str> = full.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].len(), 64);
+        assert!(lines[1].starts_with("├── ") && lines[1].ends_with(" …"));
+        assert!(lines[2].starts_with("└── "));
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::log::TransparencyLog;
+    use rs_merkletree::root_chain::{link, RootChain, GENESIS};
+
+    #[test]
+    fn chain_detects_rewritten_history() {
+        let roots: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 32]).collect();
+        let chain = RootChain::from_roots(&roots);
+        assert_eq!(chain.len(), 5);
+        assert!(RootChain::verify(&roots, chain.head()));
+        assert!(RootChain::new().is_empty());
+        assert_eq!(RootChain::new().head(), GENESIS);
+
+        let mut resumed = RootChain::from_roots(&roots[..3]);
+        resumed = RootChain::resume(resumed.head().to_vec(), resumed.len());
+        resumed.push(&roots[3]);
+        assert_eq!(resumed.push(&roots[4]), chain.head());
+        assert_eq!(
+            link(RootChain::from_roots(&roots[..4]).head(), &roots[4]),
+            chain.head()
+        );
+
+        let mut changed = roots.clone();
+        changed[1][0] = 9;
+        assert!(!RootChain::verify(&changed, chain.head()));
+        let mut reordered = roots.clone();
+        reordered.swap(0, 1);
+        assert!(!RootChain::verify(&reordered, chain.head()));
+        assert!(!RootChain::verify(&roots[1..], chain.head()));
+    }
+
+    #[test]
+    fn log_chains_its_tree_heads() {
+        let mut log = TransparencyLog::new(&[1u8; 32], 1_000);
+        assert_eq!(log.chain_head(), GENESIS);
+        for i in 0..4u64 {
+            log.append(format!("entry-{}", i).as_bytes());
+            log.sign_tree_head(i);
+        }
+        let roots = log.tree_heads().iter().map(|head| &head.root_hash);
+        assert!(RootChain::verify(roots, log.chain_head()));
+        let roots = log.tree_heads()[1..].iter().map(|head| &head.root_hash);
+        assert!(!RootChain::verify(roots, log.chain_head()));
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::concurrent::proof_from_leaves;
+    use rs_merkletree::history::HistoryTree;
+    use rs_merkletree::incremental::IncrementalMerkleTree;
+    use rs_merkletree::root_history::RootHistory;
+
+    #[test]
+    fn proofs_expire_when_their_root_leaves_the_window() {
+        let mut tree = IncrementalMerkleTree::new(8);
+        let mut history = RootHistory::new(3);
+        let mut leaves = Vec::new();
+        let mut proofs = Vec::new();
+        for i in 0..6u8 {
+            leaves.push(vec![i; 32]);
+            tree.insert(&leaves[i as usize]);
+            history.push(tree.root());
+            proofs.push(proof_from_leaves(&leaves, i as usize, 8));
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.latest(), Some(&tree.root()[..]));
+        assert_eq!(history.verify_path(&leaves[5], &proofs[5], 5), Some(0));
+        assert_eq!(history.verify_path(&leaves[3], &proofs[3], 3), Some(2));
+        assert_eq!(history.verify_path(&leaves[2], &proofs[2], 2), None);
+        assert_eq!(history.verify_path(&leaves[4], &proofs[4], 3), None);
+    }
+
+    #[test]
+    fn generic_verification_over_window() {
+        let mut log = HistoryTree::new();
+        let mut history = RootHistory::new(4);
+        for event in [b"a", b"b", b"c"] {
+            log.append(event);
+            history.push(log.root());
+        }
+        let proof = log.membership_proof(0, 2).unwrap();
+        assert_eq!(history.verify(|root| proof.verify(root, b"a")), Some(1));
+        assert!(history.contains(&log.commitment(1).unwrap()));
+        assert!(RootHistory::new(1).verify(|_| true).is_none());
+    }
+}
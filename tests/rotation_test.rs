@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::history::HistoryTree;
+    use rs_merkletree::rotation::{RotatingTree, RotationPolicy};
+
+    #[test]
+    fn epochs_seal_by_size_and_interval() {
+        let policy = RotationPolicy {
+            interval: Some(100),
+            max_leaves: Some(3),
+        };
+        let mut log = RotatingTree::new(policy, 0);
+        for i in 0..7u64 {
+            log.append(&i.to_be_bytes(), i);
+        }
+        assert_eq!(log.epoch(), 2);
+        assert_eq!(log.current().len(), 1);
+        // Nothing is sealed while the interval runs, and empty epochs stay open.
+        assert!(log.tick(104).is_none());
+        assert_eq!(log.tick(105).unwrap().size, 1);
+        assert!(log.tick(1_000).is_none());
+        assert_eq!(log.append(b"late", 1_000), (3, 0));
+
+        let sizes: Vec<u64> = log.sealed().iter().map(|epoch| epoch.size).collect();
+        assert_eq!(sizes, vec![3, 3, 1]);
+        let mut expected = HistoryTree::new();
+        for i in 3..6u64 {
+            expected.append(&i.to_be_bytes());
+        }
+        let epoch = &log.sealed()[1];
+        assert_eq!(epoch.root, expected.root());
+        assert_eq!((epoch.started_at, epoch.sealed_at), (2, 5));
+        assert_eq!(log.sealed_root(1), Some(expected.root().as_slice()));
+        let proof = log.epoch_tree(1).unwrap().membership_proof(2, 3).unwrap();
+        assert!(proof.verify(&epoch.root, &5u64.to_be_bytes()));
+        assert!(log.sealed_root(3).is_none());
+    }
+
+    #[test]
+    fn manual_sealing() {
+        let mut log = RotatingTree::new(RotationPolicy::default(), 10);
+        assert!(log.seal(20).is_none());
+        log.append(b"a", 30);
+        assert!(log.tick(u64::MAX).is_none());
+        let epoch = log.seal(40).unwrap().clone();
+        assert_eq!(
+            (epoch.number, epoch.started_at, epoch.sealed_at),
+            (0, 10, 40)
+        );
+        assert_eq!(log.append(b"b", 50), (1, 0));
+        assert_eq!(log.seal(60).unwrap().started_at, 40);
+    }
+
+    #[test]
+    fn cross_tree_proofs_chain_epochs() {
+        let policy = RotationPolicy {
+            interval: None,
+            max_leaves: Some(4),
+        };
+        let mut log = RotatingTree::new(policy, 0);
+        for i in 0..10u64 {
+            log.append(&i.to_be_bytes(), i);
+        }
+        assert_eq!(log.meta_tree().len(), 2);
+        let old_meta_root = log.meta_tree().commitment(1).unwrap();
+        log.seal(10);
+        let meta_root = log.meta_root();
+
+        let proof = log.cross_tree_proof(1, 2, 3).unwrap();
+        assert_eq!(proof.epoch(), 1);
+        assert!(proof.verify(&meta_root, &6u64.to_be_bytes()));
+        assert!(!proof.verify(&meta_root, &7u64.to_be_bytes()));
+        assert!(!proof.verify(&old_meta_root, &6u64.to_be_bytes()));
+
+        // Proofs against an earlier meta-tree root, for clients that have not caught up.
+        let proof = log.cross_tree_proof(0, 3, 1).unwrap();
+        assert!(proof.verify(&old_meta_root, &3u64.to_be_bytes()));
+        assert!(log.cross_tree_proof(1, 0, 1).is_none());
+        assert!(log.cross_tree_proof(2, 2, 3).is_none());
+        assert!(log.cross_tree_proof(3, 0, 3).is_none());
+    }
+
+    #[test]
+    fn pruned_epochs_keep_their_records() {
+        let policy = RotationPolicy {
+            interval: None,
+            max_leaves: Some(2),
+        };
+        let mut log = RotatingTree::new(policy, 0);
+        for i in 0..7u64 {
+            log.append(&i.to_be_bytes(), i);
+        }
+        let meta_root = log.meta_root();
+        log.prune_before(2);
+        assert!(log.epoch_tree(0).is_none());
+        assert!(log.epoch_tree(1).is_none());
+        assert!(log.cross_tree_proof(1, 0, 3).is_none());
+        assert_eq!(log.sealed().len(), 3);
+        assert_eq!(log.meta_root(), meta_root);
+        let root = log.sealed_root(0).unwrap().to_vec();
+        let meta_proof = log.meta_tree().membership_proof(0, 3).unwrap();
+        assert!(meta_proof.verify(&meta_root, &root));
+
+        let proof = log.cross_tree_proof(2, 1, 3).unwrap();
+        assert!(proof.verify(&meta_root, &5u64.to_be_bytes()));
+        // Pruning past the sealed epochs leaves the open one alone.
+        log.prune_before(u64::MAX);
+        assert!(log.epoch_tree(2).is_none());
+        assert_eq!(log.append(b"next", 7), (3, 1));
+    }
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "server")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::server::ProofServer;
+    use rs_merkletree::MerkleTree;
+    use serde_json::{json, Value};
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    fn server() -> ProofServer {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+        ProofServer::new(tree)
+    }
+
+    #[test]
+    fn routes() {
+        let server = server();
+        let root = server.handle("GET", "/root", b"");
+        assert_eq!(root.status, 200);
+        assert_eq!(
+            root.body,
+            json!({
+                "root": "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb",
+                "leaves": 4,
+            })
+        );
+
+        let proof = server.handle("GET", "/proof/1", b"");
+        assert_eq!(proof.status, 200);
+        let request = json!({
+            "root": root.body["root"],
+            "leaf": "World",
+            "proof": proof.body["proof"],
+        });
+        let verify = server.handle("POST", "/verify", request.to_string().as_bytes());
+        assert_eq!(verify.body, json!({ "valid": true }));
+        let request = json!({
+            "root": root.body["root"],
+            "leaf": "Rust",
+            "proof": proof.body["proof"],
+        });
+        let verify = server.handle("POST", "/verify", request.to_string().as_bytes());
+        assert_eq!(verify.body, json!({ "valid": false }));
+    }
+
+    #[test]
+    fn errors_and_sockets() {
+        let server = server();
+        assert_eq!(server.handle("GET", "/proof/4", b"").status, 404);
+        assert_eq!(server.handle("GET", "/proof/x", b"").status, 400);
+        assert_eq!(server.handle("POST", "/verify", b"{").status, 400);
+        assert_eq!(server.handle("DELETE", "/root", b"").status, 404);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || server.serve(listener));
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream
+            .write_all(b"GET /proof/3 HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["index"], 3);
+    }
+}
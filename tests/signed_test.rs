@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::SigningKey;
+    use rs_merkletree::signed::{RootSigner, RootVerifier, SignedRoot};
+    use rs_merkletree::{Hash, MerkleTree};
+
+    #[test]
+    fn sign_and_verify() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a", "b", "c"]);
+        let key = SigningKey::from_bytes(&[3u8; 32]);
+        let signed = SignedRoot::for_tree(&tree, &key, 42).unwrap();
+        assert_eq!(signed.root, tree.root_node().unwrap().hash());
+        assert_eq!(signed.tree_size, 3);
+        assert!(signed.verify(&key.verifying_key()));
+
+        let other = SigningKey::from_bytes(&[4u8; 32]);
+        assert!(!signed.verify(&other.verifying_key()));
+        assert!(!signed.verify_ed25519(&[0u8; 31]));
+
+        let mut tampered = signed.clone();
+        tampered.tree_size = 2;
+        assert!(!tampered.verify(&key.verifying_key()));
+        let mut tampered = signed;
+        tampered.timestamp += 1;
+        assert!(!tampered.verify_ed25519(key.verifying_key().as_bytes()));
+
+        assert!(SignedRoot::for_tree(&MerkleTree::new(None), &key, 0).is_none());
+    }
+
+    struct SharedSecret(Vec<u8>);
+
+    impl RootSigner for SharedSecret {
+        fn sign_root(&self, message: &[u8]) -> Vec<u8> {
+            message
+                .iter()
+                .chain(&self.0)
+                .map(|byte| byte ^ 0x5a)
+                .collect()
+        }
+    }
+
+    impl RootVerifier for SharedSecret {
+        fn verify_root(&self, message: &[u8], signature: &[u8]) -> bool {
+            self.sign_root(message) == signature
+        }
+    }
+
+    #[test]
+    fn pluggable_scheme() {
+        let secret = SharedSecret(b"secret".to_vec());
+        let signed = SignedRoot::sign(&secret, Hash::new([1; 32]), 1, 7);
+        assert!(signed.verify(&secret));
+        assert!(!signed.verify(&SharedSecret(b"other".to_vec())));
+    }
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::smt::SparseMerkleTree;
+
+    #[test]
+    fn membership_and_non_membership() {
+        let mut tree = SparseMerkleTree::new();
+        let empty_root = tree.root();
+        assert!(tree
+            .prove(b"alice")
+            .verify_non_membership(&empty_root, b"alice"));
+
+        tree.insert(b"alice", b"100");
+        tree.insert(b"bob", b"42");
+        tree.insert(b"carol", b"7");
+        let root = tree.root();
+        assert_ne!(root, empty_root);
+
+        let proof = tree.prove(b"bob");
+        assert!(proof.verify_membership(&root, b"bob", b"42"));
+        assert!(!proof.verify_membership(&root, b"bob", b"43"));
+        assert!(!proof.verify_non_membership(&root, b"bob"));
+
+        let proof = tree.prove(b"dave");
+        assert!(proof.verify_non_membership(&root, b"dave"));
+        assert!(!proof.verify_membership(&root, b"dave", b""));
+        assert!(!proof.verify_non_membership(&empty_root, b"dave"));
+
+        assert_eq!(tree.remove(b"bob"), Some(b"42".to_vec()));
+        let root = tree.root();
+        assert!(tree.prove(b"bob").verify_non_membership(&root, b"bob"));
+        assert_eq!(tree.get(b"alice"), Some(&b"100"[..]));
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::ssz::{merkleize, mix_in_length, Bitlist, Bitvector, HashTreeRoot, List};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn basic_types_and_vectors() {
+        assert_eq!(0u64.hash_tree_root(), [0u8; 32]);
+        let mut expected = [0u8; 32];
+        expected[..8].copy_from_slice(&0x0102030405060708u64.to_le_bytes());
+        assert_eq!(0x0102030405060708u64.hash_tree_root(), expected);
+        // Bytes32 is its own root.
+        assert_eq!([7u8; 32].hash_tree_root(), [7u8; 32]);
+        // Vector[uint64, 4] fits in one chunk.
+        let packed = [1u64, 2, 3, 4];
+        let mut chunk = [0u8; 32];
+        for (i, value) in packed.iter().enumerate() {
+            chunk[i * 8..i * 8 + 8].copy_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(packed.hash_tree_root(), chunk);
+        // Two zero chunks hash to the first zero hash.
+        assert_eq!(
+            hex(&[[0u8; 32], [0u8; 32]].hash_tree_root()),
+            "f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b"
+        );
+    }
+
+    #[test]
+    fn lists_pad_to_limit_and_mix_in_length() {
+        // An empty List[uint64, 1024] is the zero tree of 256 chunks mixed with length 0.
+        let empty: List<u64, 1024> = List(Vec::new());
+        let zero_tree = merkleize(&[], 256);
+        assert_eq!(empty.hash_tree_root(), mix_in_length(&zero_tree, 0));
+
+        let list: List<u64, 1024> = List(vec![5, 6]);
+        let mut chunk = [0u8; 32];
+        chunk[..8].copy_from_slice(&5u64.to_le_bytes());
+        chunk[8..16].copy_from_slice(&6u64.to_le_bytes());
+        assert_eq!(
+            list.hash_tree_root(),
+            mix_in_length(&merkleize(&[chunk], 256), 2)
+        );
+
+        let roots: List<[u8; 32], 8> = List(vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        let expected = mix_in_length(&merkleize(&[[1u8; 32], [2u8; 32], [3u8; 32]], 8), 3);
+        assert_eq!(roots.hash_tree_root(), expected);
+
+        let bits = vec![true, false, true];
+        let mut byte_chunk = [0u8; 32];
+        byte_chunk[0] = 0b101;
+        assert_eq!(Bitvector::<3>(bits.clone()).hash_tree_root(), byte_chunk);
+        assert_eq!(
+            Bitlist::<2048>(bits).hash_tree_root(),
+            mix_in_length(&merkleize(&[byte_chunk], 8), 3)
+        );
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn stats_track_tree_growth() {
+        let empty = MerkleTree::new(None).stats();
+        assert_eq!((empty.nodes, empty.leaves, empty.depth), (0, 0, 0));
+
+        let mut previous = empty.memory_bytes;
+        for size in [1, 2, 5, 8, 100] {
+            let data: Vec<String> = (0..size).map(|i| i.to_string()).collect();
+            let mut tree = MerkleTree::new(None);
+            tree.build_tree(data.iter().map(String::as_str).collect());
+            let stats = tree.stats();
+            assert_eq!(stats.leaves, size);
+            assert_eq!(stats.leaves, tree.count_leaves());
+            assert_eq!(Some(stats.depth), tree.depth());
+            assert!(stats.memory_bytes > previous);
+            previous = stats.memory_bytes;
+        }
+    }
+
+    #[test]
+    fn padding_and_algorithm() {
+        let mut tree = MerkleTree::new_legacy(None);
+        tree.build_tree(vec!["a"]);
+        let stats = tree.stats();
+        // A lone leaf is still hashed once, with itself.
+        assert_eq!((stats.nodes, stats.padding_nodes), (2, 1));
+        assert_eq!(stats.hash_algorithm, "sha256-legacy");
+
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["a", "b", "c", "d", "e"]);
+        // "e" is padded on both levels below the root.
+        assert_eq!(tree.stats().padding_nodes, 2);
+        assert_eq!(tree.stats().nodes, 5 + 3 + 2 + 1);
+    }
+}
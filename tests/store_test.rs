@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::store::NodeStore;
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn shared_trees_match_merkle_tree_and_dedup() {
+        let owned: Vec<String> = (0..100).map(|i| format!("value-{}", i % 4)).collect();
+        let data: Vec<&str> = owned.iter().map(String::as_str).collect();
+
+        let mut store = NodeStore::new();
+        let shared = store.build(data.clone());
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.clone());
+        let root = tree.root_node().unwrap();
+
+        assert_eq!(shared.root_hash(), Some(root.hash()));
+        assert_eq!(shared.root().unwrap().to_node(), root);
+        assert_eq!(shared.count_leaves(), 100);
+        assert!(store.len() < shared.count_nodes() / 4);
+
+        // A second, overlapping tree adds only its new nodes.
+        let before = store.len();
+        let again = store.build(data[..64].to_vec());
+        assert!(store.len() - before <= 2);
+        assert_eq!(store.insert(&root), shared);
+        assert_eq!(again.count_leaves(), 64);
+    }
+
+    #[test]
+    fn lone_and_paired_nodes_stay_distinct() {
+        let mut store = NodeStore::new();
+        // ["a"] and ["a", "a"] share a root hash but not a shape.
+        let one = store.build(vec!["a"]);
+        let two = store.build(vec!["a", "a"]);
+        assert_eq!(one.root_hash(), two.root_hash());
+        assert_eq!(one.count_leaves(), 1);
+        assert_eq!(two.count_leaves(), 2);
+
+        drop(two);
+        assert_eq!(store.collect_garbage(), 1);
+        drop(one);
+        assert_eq!(store.collect_garbage(), 2);
+        assert!(store.is_empty());
+    }
+}
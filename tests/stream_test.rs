@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::file::FileTree;
+    use rs_merkletree::stream::{encode, Decoder};
+    use std::io::Read;
+
+    #[test]
+    fn decodes_to_original_with_file_tree_root() {
+        for len in [0usize, 1, 7, 8, 9, 100, 1000] {
+            let contents: Vec<u8> = (0..len).map(|i| (i * 31 % 256) as u8).collect();
+            let (root, encoded) = encode(&contents, 8);
+            assert_eq!(
+                root,
+                FileTree::from_reader(&contents[..], 8).unwrap().root()
+            );
+
+            let mut decoder = Decoder::new(&encoded[..], &root, 8);
+            let mut decoded = Vec::new();
+            decoder.read_to_end(&mut decoded).unwrap();
+            assert_eq!(decoded, contents);
+            assert_eq!(decoder.len(), Some(len as u64));
+        }
+    }
+
+    #[test]
+    fn stops_at_first_corrupted_chunk() {
+        let contents: Vec<u8> = (0..64u8).collect();
+        let (root, mut encoded) = encode(&contents, 8);
+        // Flip a byte of the last chunk: everything before it still decodes.
+        let last = encoded.len() - 1;
+        encoded[last] ^= 0xff;
+
+        let mut decoder = Decoder::new(&encoded[..], &root, 8);
+        let mut buffer = [0u8; 8];
+        for chunk in contents.chunks(8).take(7) {
+            decoder.read_exact(&mut buffer).unwrap();
+            assert_eq!(&buffer[..], chunk);
+        }
+        assert!(decoder.read(&mut buffer).is_err());
+        assert!(decoder.read(&mut buffer).is_err());
+
+        let (_, encoded) = encode(&contents, 8);
+        let wrong_root = vec![0u8; 32];
+        assert!(Decoder::new(&encoded[..], &wrong_root, 8)
+            .read_to_end(&mut Vec::new())
+            .is_err());
+    }
+}
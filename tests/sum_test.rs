@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::proof::Position;
+    use rs_merkletree::sum::{SumNode, SumTree};
+
+    fn accounts(size: u64) -> Vec<(Vec<u8>, u64)> {
+        (0..size)
+            .map(|i| (format!("user {}", i).into_bytes(), i * 10 + 1))
+            .collect()
+    }
+
+    #[test]
+    fn every_leaf_is_counted_once() {
+        for size in [1, 2, 3, 7, 16, 33] {
+            let leaves = accounts(size);
+            let tree = SumTree::new(leaves.clone()).unwrap();
+            let root = tree.root().unwrap();
+            assert_eq!(root.sum, leaves.iter().map(|(_, value)| value).sum::<u64>());
+            for (index, (data, value)) in leaves.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(proof.verify(root, data, *value));
+                assert!(!proof.verify(root, data, value + 1));
+            }
+            assert!(tree.proof(size as usize).is_none());
+        }
+        assert_eq!(SumTree::new(Vec::new()).unwrap().total(), 0);
+        assert!(SumTree::new(vec![(b"a".to_vec(), u64::MAX), (b"b".to_vec(), 1)]).is_none());
+    }
+
+    #[test]
+    fn sums_cannot_be_shifted_between_siblings() {
+        let tree = SumTree::new(accounts(4)).unwrap();
+        let root = tree.root().unwrap().clone();
+        let mut proof = tree.proof(0).unwrap();
+        assert_eq!(proof.steps[0].position, Position::Right);
+
+        // Lowering a sibling's sum to hide liabilities changes the root.
+        proof.steps[1].sibling.sum -= 1;
+        assert!(!proof.verify(&root, b"user 0", 1));
+
+        // A sibling whose sum would overflow is rejected rather than wrapped.
+        let mut proof = tree.proof(0).unwrap();
+        proof.steps[0].sibling = SumNode::leaf(b"user 1", u64::MAX);
+        assert!(!proof.verify(&root, b"user 0", 1));
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::sync::{
+        respond, ReconciliationPlanner, SyncError, SyncRequest, SyncResponse, SyncSession,
+    };
+    use rs_merkletree::MerkleTree;
+
+    /// Runs a session against `remote` over encoded messages, returning the local leaves and the number of round trips.
+    fn sync(local: &[String], remote: &[String]) -> (SyncSession, usize) {
+        sync_with_fan_out(local, remote, 1)
+    }
+
+    fn sync_with_fan_out(
+        local: &[String],
+        remote: &[String],
+        fan_out: usize,
+    ) -> (SyncSession, usize) {
+        let remote_data: Vec<&str> = remote.iter().map(String::as_str).collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(remote_data.clone());
+        let mut session = SyncSession::with_fan_out(local.to_vec(), fan_out);
+        let mut request = Some(session.start());
+        let mut rounds = 0;
+        while let Some(next) = request {
+            rounds += 1;
+            let next = SyncRequest::from_bytes(&next.to_bytes()).unwrap();
+            let response = respond(&tree, &remote_data, &next);
+            let response = SyncResponse::from_bytes(&response.to_bytes()).unwrap();
+            request = session.handle(&response).unwrap();
+        }
+        (session, rounds)
+    }
+
+    fn data(size: usize) -> Vec<String> {
+        (0..size).map(|i| format!("leaf {}", i)).collect()
+    }
+
+    #[test]
+    fn peers_converge() {
+        let remote = data(100);
+        let mut changed = remote.clone();
+        changed[17] = "stale".to_string();
+        changed[64] = "stale".to_string();
+        for local in [remote.clone(), changed, data(60), data(130), Vec::new()] {
+            let (session, _) = sync(&local, &remote);
+            assert_eq!(session.leaves(), remote.as_slice());
+        }
+
+        let mut changed = remote.clone();
+        changed[42] = "stale".to_string();
+        let (session, rounds) = sync(&changed, &remote);
+        assert_eq!(session.fetched(), 1);
+        // Root, one round per level below the root of 128 leaves, then the leaf.
+        assert_eq!(rounds, 1 + 7 + 1);
+
+        let (session, rounds) = sync(&remote, &remote);
+        assert_eq!((session.fetched(), rounds), (0, 1));
+        let (session, _) = sync(&data(5), &[]);
+        assert!(session.leaves().is_empty());
+    }
+
+    #[test]
+    fn bad_responses_are_rejected() {
+        let mut session = SyncSession::new(data(4));
+        assert_eq!(
+            session.handle(&SyncResponse::Leaves(Vec::new())),
+            Err(SyncError::UnexpectedResponse)
+        );
+
+        let remote = data(4);
+        let remote_data: Vec<&str> = remote.iter().map(String::as_str).collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(remote_data.clone());
+        let mut session = SyncSession::new(vec!["x".to_string(); 4]);
+        let mut request = session.start();
+        loop {
+            let mut response = respond(&tree, &remote_data, &request);
+            if let SyncResponse::Leaves(leaves) = &mut response {
+                leaves[0].1 = "forged".to_string();
+                assert_eq!(session.handle(&response), Err(SyncError::RootMismatch));
+                break;
+            }
+            request = session.handle(&response).unwrap().unwrap();
+        }
+        assert_eq!(SyncRequest::from_bytes(&[1, 0, 0, 0, 1]), None);
+    }
+
+    /// Runs a planner against `remote`, returning the ranges to fetch, the number of rounds and the number of hashes queried.
+    fn plan(
+        local: &[String],
+        remote: &MerkleTree,
+        fan_out: usize,
+    ) -> (Vec<std::ops::Range<usize>>, usize, usize) {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(local.iter().map(String::as_str).collect());
+        let height = remote.depth().unwrap() - 1;
+        let root = remote.root_node().map(|root| root.hash());
+        let mut planner = ReconciliationPlanner::new(
+            &tree,
+            root.as_ref(),
+            remote.count_leaves(),
+            height,
+            fan_out,
+        );
+        let (mut rounds, mut queried) = (0, 0);
+        while !planner.is_complete() {
+            let answers: Vec<_> = planner
+                .next_round()
+                .into_iter()
+                .map(|(h, i)| ((h, i), remote.node(height - h, i).map(|node| node.hash())))
+                .collect();
+            rounds += 1;
+            queried += answers.len();
+            planner.record(&tree, &answers);
+        }
+        (planner.ranges(), rounds, queried)
+    }
+
+    #[test]
+    fn planner_fan_out_reduces_rounds() {
+        let remote_data = data(100);
+        let mut remote = MerkleTree::new(None);
+        remote.build_tree(remote_data.iter().map(String::as_str).collect());
+        let mut local = remote_data.clone();
+        for i in [3, 4, 5, 70] {
+            local[i] = "stale".to_string();
+        }
+        let (ranges, rounds, _) = plan(&local, &remote, 1);
+        assert_eq!(ranges, vec![3..6, 70..71]);
+        assert_eq!(rounds, 7);
+        let (wide, wide_rounds, _) = plan(&local, &remote, 3);
+        assert_eq!(wide, ranges);
+        assert_eq!(wide_rounds, 3);
+
+        // Sessions plan the same way.
+        let (session, _) = sync_with_fan_out(&local, &remote_data, 4);
+        assert_eq!(
+            (session.leaves(), session.fetched()),
+            (remote_data.as_slice(), 4)
+        );
+
+        assert_eq!(plan(&remote_data, &remote, 2), (Vec::new(), 0, 0));
+    }
+
+    #[test]
+    fn planner_fetches_missing_tail_without_queries() {
+        let remote_data = data(100);
+        let mut remote = MerkleTree::new(None);
+        remote.build_tree(remote_data.iter().map(String::as_str).collect());
+        let (ranges, _, queried) = plan(&remote_data[..64], &remote, 1);
+        assert_eq!((ranges.len(), ranges[0].clone()), (1, 64..100));
+        // Only the left half is compared; the right half is beyond the local tree.
+        assert_eq!(queried, 1);
+        let (ranges, rounds, _) = plan(&[], &remote, 1);
+        assert_eq!((ranges.len(), ranges[0].clone()), (1, 0..100));
+        assert_eq!(rounds, 0);
+    }
+}
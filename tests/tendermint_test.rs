@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::history::HistoryTree;
+    use rs_merkletree::tendermint::{hash_from_byte_slices, prove};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn matches_tendermint_vectors() {
+        let empty: Vec<Vec<u8>> = Vec::new();
+        assert_eq!(
+            hex(&hash_from_byte_slices(&empty)),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex(&hash_from_byte_slices(&[vec![1u8, 2, 3]])),
+            "054edec1d0211f624fed0cbca9d4f9400b0e491c43742af2c5b0abebf0c990d8"
+        );
+        assert_eq!(
+            hex(&hash_from_byte_slices(&[vec![1u8, 2, 3], vec![4, 5, 6]])),
+            "82e6cfce00453804379b53962939eaa7906b39904be0813fcadd31b100773c4b"
+        );
+    }
+
+    #[test]
+    fn proofs_agree_with_rfc6962_history() {
+        for n in 1..20usize {
+            let items: Vec<Vec<u8>> = (0..n).map(|i| vec![i as u8; 3]).collect();
+            let root = hash_from_byte_slices(&items);
+            let mut history = HistoryTree::new();
+            for item in &items {
+                history.append(item);
+            }
+            assert_eq!(root, history.root());
+            for (index, item) in items.iter().enumerate() {
+                let proof = prove(&items, index).unwrap();
+                assert!(proof.verify(&root, item));
+                assert!(!proof.verify(&root, b"other"));
+            }
+        }
+    }
+}
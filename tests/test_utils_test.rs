@@ -0,0 +1,42 @@
+#![cfg(feature = "test-utils")]
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rs_merkletree::test_utils::{proof, test_vectors, tree_with_proof, vectors_json};
+
+    proptest! {
+        #[test]
+        fn generated_proofs_verify((leaves, tree, index, leaf_proof) in tree_with_proof()) {
+            let root = tree.root_node().unwrap().hash();
+            prop_assert!(leaf_proof.verify_data(&root, leaves[index].as_bytes()));
+        }
+
+        #[test]
+        fn random_proofs_are_well_formed(random in proof()) {
+            prop_assert!(random.steps.iter().all(|step| step.hash.to_string().len() == 64));
+        }
+    }
+
+    #[test]
+    fn known_answer_vectors() {
+        let vectors = test_vectors(&["Hello", "World", "From", "Rust"]);
+        let root = |preset: &str| {
+            vectors
+                .iter()
+                .find(|vector| vector.preset == preset)
+                .unwrap()
+                .root
+                .clone()
+        };
+        assert_eq!(
+            root("merkletree"),
+            "a67134ab2f9257f9ac07fe71dc32b3073129e7cff159d70cbed8f029547c36fb"
+        );
+        assert_eq!(vectors.len(), 8);
+        assert!(test_vectors(&[]).is_empty());
+        assert!(
+            vectors_json(&vectors).contains("\"leaves\":[\"Hello\",\"World\",\"From\",\"Rust\"]")
+        );
+    }
+}
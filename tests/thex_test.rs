@@ -0,0 +1,34 @@
+#![cfg(feature = "thex")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::thex::{node_hash, ThexTree, SEGMENT_SIZE};
+
+    #[test]
+    fn matches_published_tiger_tree_hashes() {
+        let vectors = [
+            (0, "LWPNACQDBZRYXW3VHJVCJ64QBZNGHOHHHZWCLNQ"),
+            (1024, "L66Q4YVNAFWVS23X2HJIRA5ZJ7WXR3F26RSASFA"),
+            (1025, "PZMRYHGY6LTBEH63ZWAHDORHSYTLO4LEFUIKHWY"),
+        ];
+        for (len, expected) in vectors {
+            let data = vec![b'A'; len];
+            let tree = ThexTree::from_reader(&data[..]).unwrap();
+            assert_eq!(tree.root_base32(), expected, "len={}", len);
+        }
+    }
+
+    #[test]
+    fn odd_nodes_are_promoted() {
+        let data = vec![7u8; 3 * SEGMENT_SIZE];
+        let tree = ThexTree::from_reader(&data[..]).unwrap();
+        let leaves = &tree.levels()[0];
+        assert_eq!(tree.leaf_count(), 3);
+        let expected = node_hash(&node_hash(&leaves[0], &leaves[1]), &leaves[2]);
+        assert_eq!(tree.root(), expected);
+
+        let serialized = tree.breadth_first();
+        assert_eq!(serialized.len(), (1 + 2 + 3) * 24);
+        assert_eq!(&serialized[..24], &tree.root()[..]);
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::transcript::{
+        replay, replay_build, verify_recorded, Transcript, TranscriptError,
+    };
+    use rs_merkletree::MerkleTree;
+
+    fn recorded(mut tree: MerkleTree, data: Vec<&str>) -> (MerkleTree, String) {
+        let mut transcript = Transcript::new(Vec::new());
+        tree.build_tree_recorded(data, &mut transcript).unwrap();
+        let text = String::from_utf8(transcript.into_inner().unwrap()).unwrap();
+        (tree, text)
+    }
+
+    #[test]
+    fn build_transcripts_replay_to_the_root() {
+        for tree in [MerkleTree::new(None), MerkleTree::new_legacy(None)] {
+            let (tree, text) = recorded(tree, vec!["Hello", "World", "From"]);
+            // Three leaves, two nodes on the first level, the root, then the root line.
+            assert_eq!(text.lines().count(), 1 + 3 + 2 + 1 + 1);
+            assert_eq!(replay_build(&text), Ok(tree.root_node().unwrap().hash()));
+        }
+
+        let (_, text) = recorded(MerkleTree::new(None), vec!["Hello", "World"]);
+        let tampered = text.replacen("leaf 00", "leaf 01", 1);
+        assert_eq!(
+            replay_build(&tampered),
+            Err(TranscriptError::HashMismatch { line: 2 })
+        );
+        let mut lines: Vec<&str> = text.lines().collect();
+        lines.remove(1);
+        assert_eq!(
+            replay_build(&lines.join("\n")),
+            Err(TranscriptError::UnknownChild { line: 3 })
+        );
+        assert_eq!(replay_build(""), Err(TranscriptError::MissingHeader));
+    }
+
+    #[test]
+    fn build_transcripts_cannot_take_siblings() {
+        // Passed off as a build transcript, its root would rest on a sibling not derived from any leaf.
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["Hello", "World"]);
+        let proof = tree.proof(0).unwrap();
+        let mut transcript = Transcript::new(Vec::new());
+        verify_recorded(
+            &proof,
+            &tree.root_node().unwrap().hash(),
+            b"Hello",
+            &mut transcript,
+        )
+        .unwrap();
+        let text = String::from_utf8(transcript.into_inner().unwrap()).unwrap();
+        assert!(replay(&text).is_ok());
+        assert_eq!(
+            replay_build(&text),
+            Err(TranscriptError::UnexpectedSibling { line: 2 })
+        );
+    }
+
+    #[test]
+    fn verification_transcripts() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["Hello", "World", "From", "Rust"]);
+        let root = tree.root_node().unwrap().hash();
+        let proof = tree.proof(3).unwrap();
+
+        let mut transcript = Transcript::new(Vec::new());
+        assert!(verify_recorded(&proof, &root, b"Rust", &mut transcript).unwrap());
+        let text = String::from_utf8(transcript.into_inner().unwrap()).unwrap();
+        assert_eq!(replay(&text), Ok(root));
+
+        let mut transcript = Transcript::new(Vec::new());
+        assert!(!verify_recorded(&proof, &root, b"Rest", &mut transcript).unwrap());
+        let text = String::from_utf8(transcript.into_inner().unwrap()).unwrap();
+        assert_eq!(
+            replay(&text),
+            Err(TranscriptError::RootMismatch { line: 7 })
+        );
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::history::HistoryTree;
+    use rs_merkletree::trillian::{HashStrategy, LogRootV1, Proof, SignedLogRoot, TrillianError};
+
+    #[test]
+    fn proofs_round_trip_through_trillian_shape() {
+        let mut log = HistoryTree::new();
+        for i in 0..11u8 {
+            log.append(&[i]);
+        }
+        let strategy = HashStrategy::from_name("RFC6962_SHA256").unwrap();
+        assert_eq!(strategy.hash_leaf(&[3]), log.leaf_hash(3).unwrap());
+
+        let proof = Proof::from(log.membership_proof(3, 11).unwrap());
+        assert_eq!(proof.leaf_index, 3);
+        assert_eq!(
+            proof.to_membership_proof(11).unwrap(),
+            log.membership_proof(3, 11).unwrap()
+        );
+        assert!(proof.verify_inclusion(&[3], 11, &log.root()));
+        assert!(!proof.verify_inclusion(&[4], 11, &log.root()));
+        let negative = Proof {
+            leaf_index: -1,
+            hashes: proof.hashes.clone(),
+        };
+        assert_eq!(
+            negative.to_membership_proof(11),
+            Err(TrillianError::InvalidIndex)
+        );
+
+        let old = log.commitment(6).unwrap();
+        let proof = Proof::from(log.incremental_proof(6, 11).unwrap());
+        assert_eq!(proof.leaf_index, 0);
+        assert!(proof.verify_consistency(6, 11, &old, &log.root()));
+        assert!(!proof.verify_consistency(6, 11, &log.root(), &old));
+    }
+
+    #[test]
+    fn log_root_v1_encoding() {
+        let root = LogRootV1 {
+            tree_size: 2,
+            root_hash: vec![0xab; 3],
+            timestamp_nanos: 5,
+            revision: 7,
+            metadata: vec![0xcd],
+        };
+        let encoded = root.encode().unwrap();
+        let mut expected = vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 3, 0xab, 0xab, 0xab];
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0, 7, 0, 1, 0xcd]);
+        assert_eq!(encoded, expected);
+
+        let signed = SignedLogRoot::from_log_root(&root).unwrap();
+        assert_eq!(signed.decode().unwrap(), root);
+        assert_eq!(
+            LogRootV1::decode(&encoded[..20]),
+            Err(TrillianError::Truncated)
+        );
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert_eq!(
+            LogRootV1::decode(&trailing),
+            Err(TrillianError::TrailingData)
+        );
+        let mut v2 = encoded;
+        v2[1] = 2;
+        assert_eq!(
+            LogRootV1::decode(&v2),
+            Err(TrillianError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            LogRootV1::new(1, vec![0; 129]).encode(),
+            Err(TrillianError::FieldTooLong("root_hash"))
+        );
+    }
+}
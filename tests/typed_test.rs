@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::typed::{CanonicalEncoder, TypedMerkleTree};
+    use rs_merkletree::MerkleTree;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Transfer {
+        from: &'static str,
+        amount: u64,
+    }
+
+    fn encode(transfer: &Transfer) -> Vec<u8> {
+        format!("{}:{}", transfer.from, transfer.amount).into_bytes()
+    }
+
+    #[test]
+    fn typed_tree_matches_plain_tree() {
+        let data = vec!["Hello", "World", "From", "Rust"];
+        let typed = TypedMerkleTree::new(data.clone(), CanonicalEncoder);
+        let mut plain = MerkleTree::new(None);
+        plain.build_tree(data.clone());
+        assert_eq!(typed.root(), plain.root_node().map(|root| root.hash()));
+        assert_eq!(typed.proof(3), plain.proof(3));
+        assert_eq!(typed.iter().copied().collect::<Vec<_>>(), data);
+
+        let empty: TypedMerkleTree<u64, _> = TypedMerkleTree::new(Vec::new(), CanonicalEncoder);
+        assert!(empty.is_empty() && empty.root().is_none());
+    }
+
+    #[test]
+    fn proofs_by_value() {
+        let transfers = vec![
+            Transfer {
+                from: "alice",
+                amount: 5,
+            },
+            Transfer {
+                from: "bob",
+                amount: 7,
+            },
+        ];
+        let mut tree = TypedMerkleTree::new(transfers, encode);
+        tree.push(Transfer {
+            from: "carol",
+            amount: 1,
+        });
+        let root = tree.root().unwrap();
+
+        let carol = Transfer {
+            from: "carol",
+            amount: 1,
+        };
+        let (index, proof) = tree.proof_of(&carol).unwrap();
+        assert_eq!((index, &tree[index]), (2, &carol));
+        assert!(tree.verify(&root, &carol, &proof));
+        assert!(proof.verify_data(&root, b"carol:1"));
+
+        let forged = Transfer {
+            from: "carol",
+            amount: 100,
+        };
+        assert!(!tree.verify(&root, &forged, &proof));
+        assert_eq!(tree.proof_of(&forged), None);
+        assert_eq!(tree.into_items().len(), 3);
+    }
+}
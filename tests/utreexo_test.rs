@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::utreexo::{Pollard, Stump, UtreexoProof};
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn stump_tracks_pollard() {
+        let mut pollard = Pollard::new();
+        let mut stump = Stump::new();
+        let items: Vec<String> = (0..37).map(|i| format!("utxo-{}", i)).collect();
+
+        for (round, chunk) in items.chunks(6).enumerate() {
+            for item in chunk {
+                pollard.add(item.as_bytes());
+                stump.add(item.as_bytes());
+            }
+            // Spend every third element added so far that is still unspent.
+            for item in items.iter().take((round + 1) * 6).step_by(3) {
+                if let Some(proof) = pollard.prove(item.as_bytes()) {
+                    assert!(stump.verify(&proof, item.as_bytes()));
+                    assert!(stump.delete(&proof, item.as_bytes()));
+                    assert!(!stump.verify(&proof, item.as_bytes()));
+                    assert!(pollard.delete(item.as_bytes()));
+                }
+            }
+            assert_eq!(stump.roots(), pollard.roots());
+            assert_eq!(stump, pollard.stump());
+        }
+
+        for (i, item) in items.iter().enumerate() {
+            assert_eq!(pollard.contains(item.as_bytes()), i % 3 != 0);
+            if let Some(proof) = pollard.prove(item.as_bytes()) {
+                assert!(stump.verify(&proof, item.as_bytes()));
+            }
+        }
+        assert!(!pollard.delete(items[0].as_bytes()));
+    }
+
+    #[test]
+    fn duplicates_are_separate_copies() {
+        let mut pollard = Pollard::new();
+        let mut stump = Stump::new();
+        for utxo in ["a", "b", "a"] {
+            pollard.add(utxo.as_bytes());
+            stump.add(utxo.as_bytes());
+        }
+        assert_eq!(pollard.len(), 3);
+        for _ in 0..2 {
+            let proof = pollard.prove(b"a").unwrap();
+            assert!(stump.delete(&proof, b"a"));
+            assert!(pollard.delete(b"a"));
+            assert_eq!(stump, pollard.stump());
+        }
+        assert!(!pollard.contains(b"a"));
+        assert_eq!(pollard.len(), 1);
+    }
+
+    #[test]
+    fn forged_proofs_are_rejected() {
+        let mut stump = Stump::new();
+        stump.add(b"a");
+        stump.add(b"b");
+        // The concatenated leaf hashes of the height-1 tree are not an element.
+        let leaf = |data: &[u8]| {
+            Sha256::new()
+                .chain_update([0])
+                .chain_update(data)
+                .finalize()
+        };
+        let pair = [leaf(b"a"), leaf(b"b")].concat();
+        let empty = UtreexoProof {
+            siblings: vec![],
+            directions: 0,
+            row: 0,
+        };
+        assert!(!stump.verify(&empty, &pair));
+        assert!(!stump.clone().delete(&empty, &pair));
+
+        // A proof must lead to the root of the height it claims.
+        let proof = UtreexoProof {
+            siblings: vec![leaf(b"b").to_vec()],
+            directions: 0,
+            row: 0,
+        };
+        assert!(stump.verify(&proof, b"a"));
+        assert!(!stump.verify(
+            &UtreexoProof {
+                row: 1,
+                ..proof.clone()
+            },
+            b"a"
+        ));
+        assert!(!stump.verify(
+            &UtreexoProof {
+                row: usize::MAX,
+                ..proof
+            },
+            b"a"
+        ));
+        let long = UtreexoProof {
+            siblings: vec![vec![0; 32]; 65],
+            directions: u64::MAX,
+            row: 0,
+        };
+        assert!(!stump.verify(&long, b"a"));
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::proof::{leaf_hash, node_hash};
+    use rs_merkletree::{Hash, MerkleError, MerkleTree, Node};
+
+    fn build(data: Vec<&str>) -> MerkleTree {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data);
+        tree
+    }
+
+    #[test]
+    fn built_trees_are_valid() {
+        assert_eq!(MerkleTree::new(None).validate(), Ok(()));
+        for size in 1..=9 {
+            let data: Vec<String> = (0..size).map(|i| i.to_string()).collect();
+            let tree = build(data.iter().map(String::as_str).collect());
+            assert_eq!(tree.validate(), Ok(()), "size {}", size);
+        }
+        let mut legacy = MerkleTree::new_legacy(None);
+        legacy.build_tree(vec!["a", "b", "c"]);
+        assert_eq!(legacy.validate(), Ok(()));
+    }
+
+    #[test]
+    fn corrupted_trees_are_rejected() {
+        let tree = build(vec!["a", "b", "c"]);
+        let root = tree.root_node().unwrap();
+        let left = root.left_node().unwrap();
+        let right = root.right_node().unwrap();
+
+        // Swapping the leaves below the left inner node breaks its hash.
+        let swapped = Node::new(
+            left.hash(),
+            left.right_node().map(Box::new),
+            left.left_node().map(Box::new),
+        );
+        let corrupted = Node::new(
+            root.hash(),
+            Some(Box::new(swapped)),
+            Some(Box::new(right.clone())),
+        );
+        assert_eq!(
+            MerkleTree::new(Some(Box::new(corrupted))).validate(),
+            Err(MerkleError::HashMismatch { depth: 1, index: 0 })
+        );
+
+        // A leaf placed one level too high, under a root rehashed to match it.
+        let leaf = right.left_node().unwrap();
+        let short = Node::new(
+            node_hash(&left.hash(), &leaf.hash()),
+            Some(Box::new(left)),
+            Some(Box::new(leaf)),
+        );
+        assert_eq!(
+            MerkleTree::new(Some(Box::new(short))).validate(),
+            Err(MerkleError::MalformedTree { depth: 1, index: 1 })
+        );
+
+        // A right child without a left child.
+        let lopsided = Node::new(root.hash(), None, Some(Box::new(right)));
+        assert_eq!(
+            MerkleTree::new(Some(Box::new(lopsided))).validate(),
+            Err(MerkleError::MalformedTree { depth: 0, index: 0 })
+        );
+    }
+
+    #[test]
+    fn audit_yields_leaves_until_the_first_bad_node() {
+        for size in 0..=9 {
+            let data: Vec<String> = (0..size).map(|i| i.to_string()).collect();
+            let tree = build(data.iter().map(String::as_str).collect());
+            let audited: Vec<(usize, Hash)> = tree.audit().collect::<Result<_, _>>().unwrap();
+            let expected: Vec<(usize, Hash)> = data
+                .iter()
+                .enumerate()
+                .map(|(index, data)| (index, leaf_hash(data.as_bytes())))
+                .collect();
+            assert_eq!(audited, expected);
+        }
+
+        // A rewritten leaf in the right half: the left half is yielded, then the mismatch.
+        let tree = build(vec!["a", "b", "c", "d"]);
+        let root = tree.root_node().unwrap();
+        let right = root.right_node().unwrap();
+        let forged = Node::new(
+            right.hash(),
+            right.left_node().map(Box::new),
+            Some(Box::new(Node::new(leaf_hash(b"x"), None, None))),
+        );
+        let forged = MerkleTree::new(Some(Box::new(Node::new(
+            root.hash(),
+            root.left_node().map(Box::new),
+            Some(Box::new(forged)),
+        ))));
+        let audit: Vec<_> = forged.audit().collect();
+        assert_eq!(audit.len(), 3);
+        assert_eq!(audit[1].as_ref().map(|(index, _)| *index), Ok(1));
+        assert_eq!(
+            audit[2],
+            Err(MerkleError::HashMismatch { depth: 1, index: 1 })
+        );
+    }
+
+    #[test]
+    fn overly_tall_trees_are_rejected_without_overflow() {
+        use rs_merkletree::proof::MAX_MULTIPROOF_HEIGHT;
+        let leaf = Node::new(leaf_hash(b"a"), None, None);
+        let mut node = leaf.clone();
+        for _ in 0..MAX_MULTIPROOF_HEIGHT + 36 {
+            node = Node::new(
+                node.hash(),
+                Some(Box::new(node)),
+                Some(Box::new(leaf.clone())),
+            );
+        }
+        let tree = MerkleTree::new(Some(Box::new(node)));
+        let malformed = MerkleError::MalformedTree {
+            depth: MAX_MULTIPROOF_HEIGHT + 1,
+            index: 0,
+        };
+        assert_eq!(tree.validate(), Err(malformed.clone()));
+        assert_eq!(tree.audit().collect::<Vec<_>>(), vec![Err(malformed)]);
+        assert!(tree.node(0, usize::MAX).is_none());
+        assert!(tree.node(0, 0).is_some());
+        assert!(matches!(
+            tree.try_proof(usize::MAX),
+            Err(MerkleError::LeafNotFound {
+                leaves: usize::MAX,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "wasm")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::proof::MerkleProof;
+    use rs_merkletree::wasm::{build_root, generate_proof, verify_proof};
+    use rs_merkletree::MerkleTree;
+
+    #[test]
+    fn bindings_match_the_tree() {
+        let data = vec!["a", "b", "c", "d", "e"];
+        let leaves: Vec<String> = data.iter().map(|s| s.to_string()).collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.clone());
+        let root = build_root(leaves.clone()).unwrap();
+        assert_eq!(root, tree.root_node().unwrap().hash().to_string());
+
+        for (i, leaf) in data.iter().enumerate() {
+            let proof = generate_proof(leaves.clone(), i).unwrap();
+            assert_eq!(MerkleProof::from_bytes(&proof), tree.proof(i));
+            assert!(verify_proof(&root, leaf, &proof));
+            assert!(!verify_proof(&root, "z", &proof));
+        }
+    }
+
+    #[test]
+    fn empty_and_malformed_input() {
+        assert_eq!(build_root(Vec::new()), None);
+        assert_eq!(generate_proof(vec!["a".to_string()], 1), None);
+        let root = build_root(vec!["a".to_string()]).unwrap();
+        assert!(!verify_proof(&root, "a", &[2, 0, 0]));
+        assert!(!verify_proof(&root, "a", &[1, 0, 64, 0]));
+    }
+}
@@ -0,0 +1,163 @@
+#![cfg(feature = "zeroize")]
+
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::commit_reveal::{BlindedTree, NONCE_LEN};
+    use rs_merkletree::disclosure::{DisclosureTree, SALT_LEN};
+    use rs_merkletree::history::{self, HistoryTree};
+    use rs_merkletree::liabilities::LiabilitiesTree;
+    use rs_merkletree::log::TransparencyLog;
+    use rs_merkletree::map::MerkleMap;
+    use rs_merkletree::smt::SparseMerkleTree;
+    use rs_merkletree::{Hash, MerkleTree};
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+    const PATTERN_LEN: usize = 16;
+    const MAX_PATTERNS: usize = 4;
+
+    thread_local! {
+        static PATTERNS: Cell<[[u8; PATTERN_LEN]; MAX_PATTERNS]> = const { Cell::new([[0; PATTERN_LEN]; MAX_PATTERNS]) };
+        static ARMED: Cell<usize> = const { Cell::new(0) };
+        static FOUND: Cell<bool> = const { Cell::new(false) };
+    }
+
+    /// Scans every block freed while armed on the current thread for the armed patterns, without allocating.
+    struct ScanningAllocator;
+
+    unsafe impl GlobalAlloc for ScanningAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            let armed = ARMED.try_with(Cell::get).unwrap_or(0);
+            if armed > 0 {
+                let block = std::slice::from_raw_parts(ptr, layout.size());
+                let patterns = PATTERNS.with(Cell::get);
+                let found = block
+                    .windows(PATTERN_LEN)
+                    .any(|window| patterns[..armed].iter().any(|pattern| window == pattern));
+                if found {
+                    FOUND.with(|cell| cell.set(true));
+                }
+            }
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: ScanningAllocator = ScanningAllocator;
+
+    /// Drops `value` and reports whether none of the freed memory still held a prefix of any of `secrets`.
+    fn leaves_no_trace<T>(value: T, secrets: &[&[u8]]) -> bool {
+        assert!(secrets.len() <= MAX_PATTERNS);
+        let mut patterns = [[0; PATTERN_LEN]; MAX_PATTERNS];
+        for (pattern, secret) in patterns.iter_mut().zip(secrets) {
+            pattern.copy_from_slice(&secret[..PATTERN_LEN]);
+        }
+        PATTERNS.with(|cell| cell.set(patterns));
+        FOUND.with(|cell| cell.set(false));
+        ARMED.with(|cell| cell.set(secrets.len()));
+        drop(value);
+        ARMED.with(|cell| cell.set(0));
+        !FOUND.with(Cell::get)
+    }
+
+    fn zeroized_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+
+    #[test]
+    fn trees_are_wiped() {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec!["secret-1", "secret-2", "secret-3"]);
+        tree.zeroize();
+        assert!(tree.root_node().is_none());
+
+        let mut map = MerkleMap::new();
+        map.insert(1u64, b"secret".to_vec());
+        map.zeroize();
+        assert!(map.get(&1).is_none());
+        assert_eq!(map.root(), MerkleMap::<u64, Vec<u8>>::new().root());
+    }
+
+    #[test]
+    fn scanner_finds_secrets_left_in_memory() {
+        let secret = b"an unwiped secret value".to_vec();
+        assert!(!leaves_no_trace(secret.clone(), &[&secret]));
+        assert!(leaves_no_trace(Zeroizing::new(secret.clone()), &[&secret]));
+    }
+
+    #[test]
+    fn trees_and_caches_are_overwritten_on_drop() {
+        let data = "secret leaf number one";
+        let leaf = Hash::leaf(data.as_bytes());
+        let mut tree = MerkleTree::new(None).with_leaf_cache();
+        tree.build_tree(vec![data, "secret leaf number two"]);
+        assert_eq!(tree.leaf_cache().map(|cache| cache.len()), Some(2));
+        zeroized_on_drop(&tree);
+        assert!(leaves_no_trace(tree, &[data.as_bytes(), leaf.as_bytes()]));
+
+        let value = b"sparse secret value";
+        let mut smt = SparseMerkleTree::new();
+        smt.insert(b"key", value);
+        zeroized_on_drop(&smt);
+        assert!(leaves_no_trace(smt, &[value]));
+
+        let hash = history::leaf_hash(b"history secret");
+        let mut log = HistoryTree::new();
+        log.append(b"history secret");
+        log.append(b"another entry");
+        zeroized_on_drop(&log);
+        assert!(leaves_no_trace(log, &[&hash]));
+    }
+
+    #[test]
+    fn maps_of_zeroizing_values_are_overwritten_on_drop() {
+        let value = b"map secret value";
+        let mut map = MerkleMap::new();
+        map.insert(1u64, Zeroizing::new(value.to_vec()));
+        zeroized_on_drop(&map);
+        assert!(leaves_no_trace(map, &[value]));
+    }
+
+    #[test]
+    fn commitments_are_overwritten_on_drop() {
+        let value = b"committed secret value";
+        let nonce = [0x5a; NONCE_LEN];
+        let blinded = BlindedTree::with_nonces(vec![value.to_vec()], vec![nonce]).unwrap();
+        zeroized_on_drop(&blinded);
+        assert!(leaves_no_trace(blinded, &[value, &nonce]));
+
+        let field = b"disclosed secret field";
+        let salt = [0xa5; SALT_LEN];
+        let disclosure =
+            DisclosureTree::with_salts(vec![("name".into(), field.to_vec())], vec![salt]).unwrap();
+        zeroized_on_drop(&disclosure);
+        assert!(leaves_no_trace(disclosure, &[field, &salt]));
+    }
+
+    #[test]
+    fn liability_salts_are_wiped() {
+        let user = b"customer with a long id";
+        let mut tree =
+            LiabilitiesTree::new(vec![(user.to_vec(), 100), (b"bob".to_vec(), 50)], 2).unwrap();
+        let salt = tree.proof(user).unwrap().salt;
+        zeroized_on_drop(&tree);
+        assert!(leaves_no_trace(tree.clone(), &[&salt, user]));
+
+        tree.zeroize();
+        assert!(tree.proof(user).is_none());
+        assert_eq!(tree.root().map(|root| root.sum), Some(150));
+    }
+
+    #[test]
+    fn log_entries_are_wiped() {
+        let mut log = TransparencyLog::new(&[1u8; 32], 1_000);
+        log.append(b"secret");
+        zeroized_on_drop(&log);
+        log.zeroize();
+        assert!(log.is_empty());
+        assert_eq!(log.entry(0), None);
+    }
+}
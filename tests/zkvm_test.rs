@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use rs_merkletree::proof::{MerkleProof, Position, ProofStep};
+    use rs_merkletree::zkvm::{verify_compact, CompactProof, MAX_DEPTH};
+    use rs_merkletree::{Hash, MerkleTree};
+
+    #[test]
+    fn compact_proofs_match_merkle_proofs() {
+        let data: Vec<String> = (0..7).map(|i| format!("item {}", i)).collect();
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(data.iter().map(String::as_str).collect());
+        let root = tree.root_node().unwrap().hash().to_hex_bytes();
+        for (i, item) in data.iter().enumerate() {
+            let proof = tree.proof(i).unwrap();
+            let compact = CompactProof::from_proof(&proof).unwrap();
+            assert_eq!(compact.siblings.len(), proof.steps.len());
+            // The leaf is the left child of its parent exactly when the first sibling is on the right.
+            assert_eq!(compact.left_bits & 1 == 1, i % 2 == 1);
+            let hash = hex_sha256(item);
+            assert!(compact.verify(&root, &hash));
+            assert!(!compact.verify(&root, &hex_sha256("other")));
+            assert!(!verify_compact(
+                &root,
+                &hash,
+                &compact.siblings,
+                !compact.left_bits
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_proofs() {
+        let step = ProofStep {
+            hash: Hash::default(),
+            position: Position::Right,
+        };
+        let proof = MerkleProof {
+            steps: vec![step.clone(); MAX_DEPTH + 1],
+        };
+        assert_eq!(CompactProof::from_proof(&proof), None);
+        let deepest = MerkleProof {
+            steps: vec![step; MAX_DEPTH],
+        };
+        assert!(CompactProof::from_proof(&deepest).is_some());
+        let siblings = vec![[b'0'; 64]; MAX_DEPTH + 1];
+        assert!(!verify_compact(&[0; 64], &[0; 64], &siblings, 0));
+    }
+
+    fn hex_sha256(data: &str) -> [u8; 64] {
+        let mut tree = MerkleTree::new(None);
+        tree.build_tree(vec![data]);
+        tree.root_node()
+            .unwrap()
+            .left_node()
+            .unwrap()
+            .hash()
+            .to_hex_bytes()
+    }
+}